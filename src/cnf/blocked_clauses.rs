@@ -0,0 +1,108 @@
+//! Blocked clause elimination (BCE).
+//!
+//! A clause `C` is _blocked_ on one of its literals `l` if every resolvent of `C` with a clause
+//! containing `(-l)` is a tautology. Removing a blocked clause preserves satisfiability, so blocked
+//! clauses can be removed until none are left.
+
+use super::{Clause, CnfFormula};
+
+use log::debug;
+
+/// Statistics collected during blocked clause elimination.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BlockedClauseStats {
+    /// Number of blocked clauses removed.
+    pub removed_clauses: usize,
+    /// Number of clauses before elimination.
+    pub clauses_before: usize,
+    /// Number of clauses after elimination.
+    pub clauses_after: usize,
+}
+
+/// Remove blocked clauses from the `CnfFormula` until no blocked clauses remain.
+///
+/// Removing a clause can make other clauses blocked, so we repeat until a fixpoint is reached.
+pub fn eliminate_blocked_clauses(cnf: &mut CnfFormula) -> BlockedClauseStats {
+    let mut stats = BlockedClauseStats {
+        clauses_before: cnf.len(),
+        ..BlockedClauseStats::default()
+    };
+
+    while let Some(index) = (0..cnf.len()).find(|&i| is_blocked(cnf, i)) {
+        let clause = cnf.remove(index);
+        debug!("removed blocked clause {:?}", &clause);
+        stats.removed_clauses += 1;
+    }
+
+    stats.clauses_after = cnf.len();
+    stats
+}
+
+/// Checks if the clause at `index` is blocked on any of its literals.
+fn is_blocked(cnf: &CnfFormula, index: usize) -> bool {
+    // PANIC: cannot panic because `index` is always in bounds.
+    let clause: &Clause = cnf.clauses().nth(index).unwrap();
+
+    clause.literals().any(|literal| {
+        let complement = literal.complement();
+
+        cnf.clauses()
+            .enumerate()
+            .filter(|(i, other)| *i != index && other.contains(&complement))
+            .all(|(_, other)| clause.resolve(other, literal).is_none())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cnf::Literal;
+    use crate::formula::Variable;
+    use assert2::check;
+
+    fn literal(name: &str) -> Literal {
+        Literal::positive(Variable::new(name))
+    }
+
+    #[test]
+    fn test_pure_literal_clauses_are_blocked() {
+        // { (a|b), (a|(-b)) }: `a` never occurs negated, so both clauses are blocked on `a`.
+        let mut cnf = CnfFormula::new();
+        cnf.add(Clause::new(vec![literal("a"), literal("b")]));
+        cnf.add(Clause::new(vec![literal("a"), literal("b").complement()]));
+
+        let stats = eliminate_blocked_clauses(&mut cnf);
+
+        check!(cnf.is_empty());
+        check!(stats.removed_clauses == 2);
+    }
+
+    #[test]
+    fn test_tautological_resolvents_are_blocked() {
+        // { (a|b), ((-a)|(-b)) }: the only resolvent on `a` is (b|(-b)), a tautology.
+        let mut cnf = CnfFormula::new();
+        cnf.add(Clause::new(vec![literal("a"), literal("b")]));
+        cnf.add(Clause::new(vec![
+            literal("a").complement(),
+            literal("b").complement(),
+        ]));
+
+        let stats = eliminate_blocked_clauses(&mut cnf);
+
+        check!(stats.removed_clauses == 2);
+        check!(stats.clauses_after == 0);
+    }
+
+    #[test]
+    fn test_unsatisfiable_clauses_are_not_blocked() {
+        // { a, (-a) } is unsatisfiable, so neither clause may be removed.
+        let mut cnf = CnfFormula::new();
+        cnf.add(Clause::new(vec![literal("a")]));
+        cnf.add(Clause::new(vec![literal("a").complement()]));
+
+        let stats = eliminate_blocked_clauses(&mut cnf);
+
+        check!(stats.removed_clauses == 0);
+        check!(cnf.len() == 2);
+    }
+}
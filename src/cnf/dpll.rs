@@ -0,0 +1,318 @@
+//! DPLL satisfiability checking of `CnfFormula`s with two-watched-literal unit propagation.
+//!
+//! Each clause with at least two literals watches two of its literals which are not false. Only
+//! when a watched literal becomes false is the clause visited, to look for another literal to
+//! watch instead; if there is none, the clause has become unit (or conflicting). Unit propagation
+//! thus only touches the clauses watching the falsified literals rather than scanning every clause
+//! per assignment, and backtracking never needs to update the watches.
+
+use std::collections::HashMap;
+
+use log::debug;
+
+use super::{CnfFormula, Literal};
+
+/// Checks if the `CnfFormula` is _satisfiable_ with the DPLL procedure.
+///
+/// Decisions pick the unassigned variable with the most occurrences, assigned the polarity in
+/// which it occurs most often first, and conflicts backtrack chronologically to the last decision
+/// whose other polarity has not been tried yet.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::cnf::{dpll, Clause, CnfFormula, Literal};
+/// use libprop_sat_solver::formula::Variable;
+/// let a = Variable::new("a");
+/// let b = Variable::new("b");
+///
+/// let mut cnf = CnfFormula::new();
+/// cnf.add(Clause::new(vec![Literal::positive(a.clone()), Literal::positive(b.clone())]));
+/// cnf.add(Clause::new(vec![Literal::negative(a.clone())]));
+/// assert!(dpll::is_satisfiable(&cnf));
+///
+/// cnf.add(Clause::new(vec![Literal::negative(b)]));
+/// assert!(!dpll::is_satisfiable(&cnf));
+/// ```
+pub fn is_satisfiable(cnf: &CnfFormula) -> bool {
+    match Solver::new(cnf) {
+        Some(mut solver) => solver.solve(),
+        None => false,
+    }
+}
+
+/// A literal encoded as `2 * variable + negated`, so that its complement is `literal ^ 1`.
+type Lit = usize;
+
+fn variable_of(literal: Lit) -> usize {
+    literal >> 1
+}
+
+/// Truth value of the literal under the values of the variables, if assigned.
+fn value_of(values: &[Option<bool>], literal: Lit) -> Option<bool> {
+    values[variable_of(literal)].map(|value| value != (literal & 1 == 1))
+}
+
+/// A decision: the length of the trail before it, the decided literal, and whether the other
+/// polarity has been tried already.
+#[derive(Debug, Clone, Copy)]
+struct Decision {
+    trail_len: usize,
+    literal: Lit,
+    flipped: bool,
+}
+
+#[derive(Debug)]
+struct Solver {
+    /// Clauses with at least two literals; the first two literals of each are watched.
+    clauses: Vec<Vec<Lit>>,
+    /// For each literal, the indices of the clauses watching it.
+    watches: Vec<Vec<usize>>,
+    /// Truth value of each variable, if assigned.
+    values: Vec<Option<bool>>,
+    /// Literals made true, in assignment order.
+    trail: Vec<Lit>,
+    /// Index of the next literal on the trail to propagate.
+    propagated: usize,
+    decisions: Vec<Decision>,
+    /// Decision literals, most frequent first.
+    order: Vec<Lit>,
+}
+
+impl Solver {
+    /// Set up the solver, or `None` if the CNF is unsatisfiable by its empty or unit clauses alone.
+    fn new(cnf: &CnfFormula) -> Option<Self> {
+        let mut variables: HashMap<_, usize> = HashMap::new();
+        let mut encode = |literal: &Literal| {
+            let next = variables.len();
+            let variable = *variables.entry(literal.variable().clone()).or_insert(next);
+            2 * variable + literal.is_negated() as usize
+        };
+
+        let encoded: Vec<Vec<Lit>> = cnf
+            .clauses()
+            .map(|clause| clause.literals().map(&mut encode).collect())
+            .collect();
+
+        let variable_count = variables.len();
+        let mut solver = Self {
+            clauses: Vec::new(),
+            watches: vec![Vec::new(); 2 * variable_count],
+            values: vec![None; variable_count],
+            trail: Vec::new(),
+            propagated: 0,
+            decisions: Vec::new(),
+            order: Vec::new(),
+        };
+
+        let mut occurrences = vec![0usize; 2 * variable_count];
+        for literal in encoded.iter().flatten() {
+            occurrences[*literal] += 1;
+        }
+        solver.order = (0..variable_count)
+            .map(|variable| {
+                let (positive, negative) = (2 * variable, 2 * variable + 1);
+                if occurrences[negative] > occurrences[positive] {
+                    negative
+                } else {
+                    positive
+                }
+            })
+            .collect();
+        solver.order.sort_by_key(|&literal| {
+            let variable = variable_of(literal);
+            std::cmp::Reverse(occurrences[2 * variable] + occurrences[2 * variable + 1])
+        });
+
+        for clause in encoded {
+            match clause.len() {
+                0 => return None,
+                1 => {
+                    if !solver.assign(clause[0]) {
+                        return None;
+                    }
+                }
+                _ => {
+                    let index = solver.clauses.len();
+                    solver.watches[clause[0]].push(index);
+                    solver.watches[clause[1]].push(index);
+                    solver.clauses.push(clause);
+                }
+            }
+        }
+
+        Some(solver)
+    }
+
+    /// Make the literal true, or return `false` if it is already false.
+    fn assign(&mut self, literal: Lit) -> bool {
+        match value_of(&self.values, literal) {
+            Some(value) => value,
+            None => {
+                self.values[variable_of(literal)] = Some(literal & 1 == 0);
+                self.trail.push(literal);
+                true
+            }
+        }
+    }
+
+    /// Propagate the literals on the trail, returning `false` on a conflict.
+    fn propagate(&mut self) -> bool {
+        while self.propagated < self.trail.len() {
+            let falsified = self.trail[self.propagated] ^ 1;
+            self.propagated += 1;
+
+            let mut watching = std::mem::take(&mut self.watches[falsified]);
+            let mut i = 0;
+
+            while i < watching.len() {
+                let index = watching[i];
+                let clause = &mut self.clauses[index];
+
+                // Keep the falsified literal in the second watch.
+                if clause[0] == falsified {
+                    clause.swap(0, 1);
+                }
+                let other = clause[0];
+
+                if value_of(&self.values, other) == Some(true) {
+                    i += 1;
+                    continue;
+                }
+
+                let values = &self.values;
+                let replacement =
+                    (2..clause.len()).find(|&k| value_of(values, clause[k]) != Some(false));
+
+                match replacement {
+                    Some(k) => {
+                        clause.swap(1, k);
+                        self.watches[clause[1]].push(index);
+                        watching.swap_remove(i);
+                    }
+                    None => {
+                        i += 1;
+                        if !self.assign(other) {
+                            self.watches[falsified] = watching;
+                            return false;
+                        }
+                    }
+                }
+            }
+
+            self.watches[falsified] = watching;
+        }
+
+        true
+    }
+
+    /// Undo the assignments after the first `trail_len` literals of the trail.
+    fn backtrack(&mut self, trail_len: usize) {
+        for literal in self.trail.drain(trail_len..) {
+            self.values[variable_of(literal)] = None;
+        }
+        self.propagated = trail_len;
+    }
+
+    fn solve(&mut self) -> bool {
+        loop {
+            if !self.propagate() {
+                loop {
+                    let decision = match self.decisions.pop() {
+                        Some(decision) => decision,
+                        None => return false,
+                    };
+
+                    self.backtrack(decision.trail_len);
+
+                    if !decision.flipped {
+                        let literal = decision.literal ^ 1;
+                        self.decisions.push(Decision {
+                            literal,
+                            flipped: true,
+                            ..decision
+                        });
+                        self.assign(literal);
+                        break;
+                    }
+                }
+
+                continue;
+            }
+
+            let values = &self.values;
+            let literal = match self
+                .order
+                .iter()
+                .find(|&&literal| values[variable_of(literal)].is_none())
+            {
+                Some(&literal) => literal,
+                None => {
+                    debug!("all {} variables assigned", self.values.len());
+                    return true;
+                }
+            };
+
+            self.decisions.push(Decision {
+                trail_len: self.trail.len(),
+                literal,
+                flipped: false,
+            });
+            self.assign(literal);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cnf::Clause;
+    use crate::formula::Variable;
+    use crate::testing::{assert_agrees_with_truth_table, formulas};
+    use assert2::check;
+
+    fn clause(literals: &[i32]) -> Clause {
+        Clause::new(literals.iter().map(|&n| {
+            let variable = Variable::new(format!("x{}", n.abs()));
+            if n < 0 {
+                Literal::negative(variable)
+            } else {
+                Literal::positive(variable)
+            }
+        }))
+    }
+
+    #[test]
+    fn test_trivial_instances() {
+        check!(is_satisfiable(&CnfFormula::new()));
+
+        let mut cnf = CnfFormula::new();
+        cnf.add(clause(&[]));
+        check!(!is_satisfiable(&cnf));
+    }
+
+    #[test]
+    fn test_pigeonhole() {
+        // Three pigeons in two holes: `x(2p+h)` means pigeon `p` sits in hole `h`.
+        let mut cnf = CnfFormula::new();
+        for pigeon in 0..3 {
+            cnf.add(clause(&[2 * pigeon + 1, 2 * pigeon + 2]));
+        }
+        for hole in 1..=2 {
+            for p in 0..3 {
+                for q in p + 1..3 {
+                    cnf.add(clause(&[-(2 * p + hole), -(2 * q + hole)]));
+                }
+            }
+        }
+
+        check!(!is_satisfiable(&cnf));
+    }
+
+    #[test]
+    fn test_agrees_with_truth_table() {
+        assert_agrees_with_truth_table(formulas(4, 5, 1, 200), |formula| {
+            is_satisfiable(&CnfFormula::from_formula(formula))
+        });
+    }
+}
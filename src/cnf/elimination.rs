@@ -0,0 +1,157 @@
+//! Bounded variable elimination (BVE), also known as Davis-Putnam (DP) resolution.
+//!
+//! Eliminating a variable `p` replaces every clause containing `p` or `(-p)` by all
+//! non-tautological resolvents on `p`. The result is _equisatisfiable_ (but in general not
+//! equivalent) to the original CNF. Since this can grow the number of clauses quadratically, we
+//! only eliminate a variable when it occurs in few clauses and when doing so does not increase the
+//! number of clauses, which is the same bound used by modern SAT preprocessors.
+
+use super::{Clause, CnfFormula, Literal};
+
+use log::debug;
+
+/// Statistics collected during variable elimination.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EliminationStats {
+    /// Number of variables eliminated.
+    pub eliminated_variables: usize,
+    /// Number of clauses before elimination.
+    pub clauses_before: usize,
+    /// Number of clauses after elimination.
+    pub clauses_after: usize,
+}
+
+/// Eliminate variables from the `CnfFormula` by resolution until no more variables can be
+/// eliminated.
+///
+/// A variable is only considered for elimination if it occurs in at most `max_occurrences`
+/// clauses, and it is only eliminated if the number of non-tautological resolvents does not exceed
+/// the number of clauses removed. Pure literals (variables occurring with only one polarity) are
+/// eliminated as a special case, since they have no resolvents at all.
+pub fn eliminate_variables(cnf: &mut CnfFormula, max_occurrences: usize) -> EliminationStats {
+    let mut stats = EliminationStats {
+        clauses_before: cnf.len(),
+        ..EliminationStats::default()
+    };
+
+    let mut changed = true;
+    while changed && !cnf.has_empty_clause() {
+        changed = false;
+
+        let variables: Vec<_> = cnf.variables().into_iter().cloned().collect();
+        for variable in variables {
+            let positive = Literal::positive(variable);
+            if try_eliminate(cnf, &positive, max_occurrences) {
+                debug!("eliminated variable {:?}", positive.variable());
+                stats.eliminated_variables += 1;
+                changed = true;
+            }
+        }
+    }
+
+    stats.clauses_after = cnf.len();
+    stats
+}
+
+/// Try to eliminate the variable of the (positive) literal `pivot`, returning `true` on success.
+fn try_eliminate(cnf: &mut CnfFormula, pivot: &Literal, max_occurrences: usize) -> bool {
+    let complement = pivot.complement();
+
+    let positive: Vec<&Clause> = cnf.clauses().filter(|c| c.contains(pivot)).collect();
+    let negative: Vec<&Clause> = cnf.clauses().filter(|c| c.contains(&complement)).collect();
+
+    let occurrences = positive.len() + negative.len();
+    if occurrences == 0 || occurrences > max_occurrences {
+        return false;
+    }
+
+    let mut resolvents = Vec::new();
+    for c in &positive {
+        for d in &negative {
+            if let Some(resolvent) = c.resolve(d, pivot) {
+                resolvents.push(resolvent);
+
+                if resolvents.len() > occurrences {
+                    // Eliminating this variable would grow the CNF.
+                    return false;
+                }
+            }
+        }
+    }
+
+    cnf.retain(|c| !c.contains(pivot) && !c.contains(&complement));
+    for resolvent in resolvents {
+        cnf.add(resolvent);
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formula::Variable;
+    use assert2::check;
+
+    fn literal(name: &str) -> Literal {
+        Literal::positive(Variable::new(name))
+    }
+
+    #[test]
+    fn test_pure_literal_elimination() {
+        // { (a|b), (a|c) }: `a` is pure, so both clauses go away.
+        let mut cnf = CnfFormula::new();
+        cnf.add(Clause::new(vec![literal("a"), literal("b")]));
+        cnf.add(Clause::new(vec![literal("a"), literal("c")]));
+
+        let stats = eliminate_variables(&mut cnf, 10);
+
+        check!(cnf.is_empty());
+        check!(stats.clauses_before == 2);
+        check!(stats.clauses_after == 0);
+    }
+
+    #[test]
+    fn test_resolution() {
+        // { (a|b), ((-a)|c), ((-b)|(-c)) }: eliminating `a` gives { (b|c), ((-b)|(-c)) } and so on
+        // until nothing is left, since the CNF is satisfiable.
+        let mut cnf = CnfFormula::new();
+        cnf.add(Clause::new(vec![literal("a"), literal("b")]));
+        cnf.add(Clause::new(vec![literal("a").complement(), literal("c")]));
+        cnf.add(Clause::new(vec![
+            literal("b").complement(),
+            literal("c").complement(),
+        ]));
+
+        eliminate_variables(&mut cnf, 10);
+
+        check!(!cnf.has_empty_clause());
+        check!(cnf.is_empty());
+    }
+
+    #[test]
+    fn test_contradiction_produces_empty_clause() {
+        // { a, (-a) } resolves to the empty clause.
+        let mut cnf = CnfFormula::new();
+        cnf.add(Clause::new(vec![literal("a")]));
+        cnf.add(Clause::new(vec![literal("a").complement()]));
+
+        eliminate_variables(&mut cnf, 10);
+
+        check!(cnf.has_empty_clause());
+    }
+
+    #[test]
+    fn test_occurrence_bound() {
+        // `a` occurs in three clauses, which exceeds the bound of two.
+        let mut cnf = CnfFormula::new();
+        cnf.add(Clause::new(vec![literal("a"), literal("b")]));
+        cnf.add(Clause::new(vec![literal("a").complement(), literal("b")]));
+        cnf.add(Clause::new(vec![literal("a"), literal("b").complement()]));
+
+        let stats = eliminate_variables(&mut cnf, 2);
+
+        check!(stats.eliminated_variables == 0);
+        check!(cnf.len() == 3);
+    }
+}
@@ -0,0 +1,130 @@
+//! Linear-time satisfiability checking of Horn formulas by forward chaining.
+//!
+//! A Horn clause has at most one positive literal, so it reads as the rule "if all of its negated
+//! variables are true, then its positive variable is true" (or, without a positive literal, "they
+//! are not all true"). Starting from all variables false, only the variables forced by some rule
+//! are made true; the formula is unsatisfiable exactly if a rule without a positive literal fires.
+
+use std::collections::{HashMap, HashSet};
+
+use super::CnfFormula;
+
+/// Checks if the `CnfFormula`, whose clauses have at most one positive literal each, is
+/// _satisfiable_.
+///
+/// # Panics
+///
+/// Panics if a clause has more than one positive literal.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::cnf::{horn, Clause, CnfFormula, Literal};
+/// use libprop_sat_solver::formula::Variable;
+/// let a = Variable::new("a");
+/// let b = Variable::new("b");
+///
+/// let mut cnf = CnfFormula::new();
+/// cnf.add(Clause::new(vec![Literal::positive(a.clone())]));
+/// cnf.add(Clause::new(vec![Literal::negative(a.clone()), Literal::positive(b.clone())]));
+/// assert!(horn::is_satisfiable(&cnf));
+///
+/// cnf.add(Clause::new(vec![Literal::negative(a), Literal::negative(b)]));
+/// assert!(!horn::is_satisfiable(&cnf));
+/// ```
+pub fn is_satisfiable(cnf: &CnfFormula) -> bool {
+    let clauses: Vec<_> = cnf.clauses().collect();
+
+    // For each clause, its positive variable and the number of its negative literals whose
+    // variable is not yet true.
+    let mut heads = Vec::with_capacity(clauses.len());
+    let mut pending = Vec::with_capacity(clauses.len());
+    // For each variable, the clauses in which it occurs negatively (once per occurrence).
+    let mut occurrences: HashMap<_, Vec<usize>> = HashMap::new();
+
+    let mut fired = Vec::new();
+    for (i, clause) in clauses.iter().enumerate() {
+        let mut positives = clause.literals().filter(|literal| !literal.is_negated());
+        heads.push(positives.next().map(|literal| literal.variable()));
+        assert!(positives.next().is_none(), "non-Horn clause {:?}", clause);
+
+        let mut negatives = 0;
+        for literal in clause.literals().filter(|literal| literal.is_negated()) {
+            negatives += 1;
+            occurrences.entry(literal.variable()).or_default().push(i);
+        }
+        pending.push(negatives);
+        if negatives == 0 {
+            fired.push(i);
+        }
+    }
+
+    let mut is_true = HashSet::new();
+    while let Some(i) = fired.pop() {
+        let head = match heads[i] {
+            Some(head) => head,
+            None => return false,
+        };
+        if !is_true.insert(head) {
+            continue;
+        }
+
+        for &j in occurrences.get(head).into_iter().flatten() {
+            pending[j] -= 1;
+            if pending[j] == 0 {
+                fired.push(j);
+            }
+        }
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cnf::{dpll, Clause, Literal};
+    use crate::formula::Variable;
+    use assert2::check;
+
+    fn clause(literals: &[i32]) -> Clause {
+        Clause::new(literals.iter().map(|&n| {
+            let variable = Variable::new(format!("x{}", n.abs()));
+            if n < 0 {
+                Literal::negative(variable)
+            } else {
+                Literal::positive(variable)
+            }
+        }))
+    }
+
+    #[test]
+    fn test_agrees_with_dpll() {
+        // Every set of clauses drawn from a fixed pool of Horn clauses over four variables.
+        let pool: [&[i32]; 8] = [
+            &[1],
+            &[-1, 2],
+            &[-1, -2, 3],
+            &[-3, -4],
+            &[-2, 4],
+            &[-4, 1],
+            &[-3, 3],
+            &[-2, -3],
+        ];
+
+        for subset in 0..(1 << pool.len()) {
+            let mut cnf = CnfFormula::new();
+            for (i, literals) in pool.iter().enumerate() {
+                if subset & (1 << i) != 0 {
+                    cnf.add(clause(literals));
+                }
+            }
+
+            check!(is_satisfiable(&cnf) == dpll::is_satisfiable(&cnf));
+        }
+
+        let mut cnf = CnfFormula::new();
+        cnf.add(clause(&[]));
+        check!(!is_satisfiable(&cnf));
+    }
+}
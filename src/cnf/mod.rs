@@ -0,0 +1,470 @@
+//! Conjunctive normal form (CNF) representation of propositional formulas.
+//!
+//! A formula in CNF is a conjunction of _clauses_, where each clause is a disjunction of
+//! _literals_, e.g. `((a|(-b))^(b|c))`. Preprocessing techniques such as variable elimination work
+//! directly on this clausal representation.
+
+pub mod blocked_clauses;
+pub mod dpll;
+pub mod elimination;
+pub mod horn;
+pub mod two_sat;
+
+pub use blocked_clauses::{eliminate_blocked_clauses, BlockedClauseStats};
+pub use elimination::{eliminate_variables, EliminationStats};
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+pub use crate::formula::Literal;
+
+use crate::formula::{PropositionalFormula, Variable};
+
+/// A clause is a disjunction of literals. The empty clause is unsatisfiable.
+///
+/// Duplicate literals are removed on construction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Clause {
+    literals: Vec<Literal>,
+}
+
+impl Clause {
+    /// Construct a clause from the given literals, dropping duplicates.
+    pub fn new<I>(literals: I) -> Self
+    where
+        I: IntoIterator<Item = Literal>,
+    {
+        let mut seen = HashSet::new();
+        let literals = literals
+            .into_iter()
+            .filter(|literal| seen.insert(literal.clone()))
+            .collect();
+
+        Self { literals }
+    }
+
+    /// Get the literals of the clause.
+    pub fn literals(&self) -> impl Iterator<Item = &Literal> {
+        self.literals.iter()
+    }
+
+    /// Number of literals in the clause.
+    pub fn len(&self) -> usize {
+        self.literals.len()
+    }
+
+    /// Checks if the clause is the empty (unsatisfiable) clause.
+    pub fn is_empty(&self) -> bool {
+        self.literals.is_empty()
+    }
+
+    /// Checks if the clause contains the given literal.
+    pub fn contains(&self, literal: &Literal) -> bool {
+        self.literals.contains(literal)
+    }
+
+    /// Checks if the clause is a tautology, i.e. it contains both `p` and `(-p)` for some `p`.
+    pub fn is_tautology(&self) -> bool {
+        self.literals
+            .iter()
+            .any(|literal| !literal.is_negated() && self.contains(&literal.complement()))
+    }
+
+    /// Resolve this clause (which must contain `pivot`) with `other` (which must contain the
+    /// complement of `pivot`), producing the resolvent `(C \ {pivot}) ∪ (D \ {-pivot})`.
+    ///
+    /// Returns `None` if the resolvent is a tautology, since such resolvents carry no information.
+    pub fn resolve(&self, other: &Clause, pivot: &Literal) -> Option<Clause> {
+        let complement = pivot.complement();
+        let resolvent = Clause::new(
+            self.literals
+                .iter()
+                .filter(|literal| *literal != pivot)
+                .chain(
+                    other
+                        .literals
+                        .iter()
+                        .filter(|literal| **literal != complement),
+                )
+                .cloned(),
+        );
+
+        if resolvent.is_tautology() {
+            None
+        } else {
+            Some(resolvent)
+        }
+    }
+
+    /// Convert the clause into a (right-nested) disjunction of its literals.
+    ///
+    /// Returns `None` for the empty clause, which has no propositional formula representation.
+    pub fn to_formula(&self) -> Option<PropositionalFormula> {
+        let (last, init) = self.literals.split_last()?;
+
+        Some(
+            init.iter()
+                .rev()
+                .fold(last.to_formula(), |clause, literal| {
+                    PropositionalFormula::disjunction(
+                        Box::new(literal.to_formula()),
+                        Box::new(clause),
+                    )
+                }),
+        )
+    }
+}
+
+/// A propositional formula in conjunctive normal form, i.e. a conjunction of clauses.
+///
+/// A `CnfFormula` with no clauses is trivially satisfiable, while a `CnfFormula` containing the
+/// empty clause is trivially unsatisfiable.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CnfFormula {
+    clauses: Vec<Clause>,
+}
+
+impl CnfFormula {
+    /// Construct an empty `CnfFormula` with no clauses.
+    pub fn new() -> Self {
+        Self {
+            clauses: Vec::new(),
+        }
+    }
+
+    /// Convert a propositional formula into an _equisatisfiable_ `CnfFormula` using the Tseitin
+    /// transformation.
+    ///
+    /// Each binary sub-formula is named by a fresh propositional variable `t`, and clauses are
+    /// added which force `t` to be equivalent to the sub-formula, so the resulting CNF is only
+    /// linear in the size of the input formula. The fresh variables are named `_t0`, `_t1`, ... and
+    /// are guaranteed not to clash with the variables of `formula`.
+    pub fn from_formula(formula: &PropositionalFormula) -> Self {
+        let mut encoder = TseitinEncoder::new(formula);
+        let root = encoder.encode(formula);
+        encoder.cnf.add(Clause::new(vec![root]));
+        encoder.cnf
+    }
+
+    /// Read a formula which is already syntactically in CNF, i.e. a conjunction of disjunctions of
+    /// literals `p` and `(-p)`, clause by clause without introducing fresh variables.
+    ///
+    /// Returns `None` if the formula is not in CNF.
+    pub fn from_clausal_formula(formula: &PropositionalFormula) -> Option<Self> {
+        let mut cnf = Self::new();
+        for clause in formula.conjuncts() {
+            let literals = clause.disjuncts().into_iter().map(Literal::from_formula);
+            cnf.add(Clause::new(literals.collect::<Option<Vec<_>>>()?));
+        }
+        Some(cnf)
+    }
+
+    /// Get the clauses.
+    pub fn clauses(&self) -> impl Iterator<Item = &Clause> {
+        self.clauses.iter()
+    }
+
+    /// Number of clauses.
+    pub fn len(&self) -> usize {
+        self.clauses.len()
+    }
+
+    /// Checks if the `CnfFormula` has no clauses.
+    pub fn is_empty(&self) -> bool {
+        self.clauses.is_empty()
+    }
+
+    /// Add a clause to the `CnfFormula`.
+    pub fn add(&mut self, clause: Clause) {
+        self.clauses.push(clause);
+    }
+
+    /// Checks if the `CnfFormula` contains the empty clause, and is thus unsatisfiable.
+    pub fn has_empty_clause(&self) -> bool {
+        self.clauses.iter().any(Clause::is_empty)
+    }
+
+    /// Get the distinct propositional variables occurring in the clauses, in order of first
+    /// occurrence.
+    pub fn variables(&self) -> Vec<&Variable> {
+        let mut seen = HashSet::new();
+
+        self.clauses
+            .iter()
+            .flat_map(Clause::literals)
+            .map(Literal::variable)
+            .filter(|variable| seen.insert(*variable))
+            .collect()
+    }
+
+    /// Convert the `CnfFormula` into a (right-nested) conjunction of its clauses.
+    ///
+    /// Returns `None` if there are no clauses or if there is an empty clause, because neither
+    /// trivially satisfiable nor trivially unsatisfiable CNFs have a propositional formula
+    /// representation.
+    pub fn to_formula(&self) -> Option<PropositionalFormula> {
+        let clauses = self
+            .clauses
+            .iter()
+            .map(Clause::to_formula)
+            .collect::<Option<Vec<_>>>()?;
+        let (last, init) = clauses.split_last()?;
+
+        Some(init.iter().rev().fold(last.clone(), |cnf, clause| {
+            PropositionalFormula::conjunction(Box::new(clause.clone()), Box::new(cnf))
+        }))
+    }
+
+    /// Render the `CnfFormula` in the DIMACS CNF format.
+    ///
+    /// Variables are numbered `1, 2, ...` in order of first occurrence, and a `c <number> <name>`
+    /// comment line records the original name of each variable.
+    pub fn to_dimacs(&self) -> String {
+        let variables = self.variables();
+        let numbers: HashMap<&Variable, usize> = variables
+            .iter()
+            .enumerate()
+            .map(|(i, &v)| (v, i + 1))
+            .collect();
+
+        let mut dimacs = String::new();
+        for (i, variable) in variables.iter().enumerate() {
+            // PANIC: writing into a `String` cannot fail.
+            writeln!(dimacs, "c {} {}", i + 1, variable).unwrap();
+        }
+        writeln!(dimacs, "p cnf {} {}", variables.len(), self.len()).unwrap();
+
+        for clause in &self.clauses {
+            for literal in clause.literals() {
+                let number = numbers[literal.variable()];
+                if literal.is_negated() {
+                    write!(dimacs, "-{} ", number).unwrap();
+                } else {
+                    write!(dimacs, "{} ", number).unwrap();
+                }
+            }
+            dimacs.push_str("0\n");
+        }
+
+        dimacs
+    }
+
+    pub(crate) fn remove(&mut self, index: usize) -> Clause {
+        self.clauses.remove(index)
+    }
+
+    pub(crate) fn retain<F>(&mut self, predicate: F)
+    where
+        F: FnMut(&Clause) -> bool,
+    {
+        self.clauses.retain(predicate)
+    }
+}
+
+/// Bookkeeping for the Tseitin transformation.
+struct TseitinEncoder {
+    cnf: CnfFormula,
+    used_names: HashSet<String>,
+    next_fresh: usize,
+}
+
+impl TseitinEncoder {
+    fn new(formula: &PropositionalFormula) -> Self {
+        let used_names = formula
+            .variables()
+            .into_iter()
+            .map(|v| v.name().to_string())
+            .collect();
+
+        Self {
+            cnf: CnfFormula::new(),
+            used_names,
+            next_fresh: 0,
+        }
+    }
+
+    fn fresh_variable(&mut self) -> Variable {
+        loop {
+            let name = format!("_t{}", self.next_fresh);
+            self.next_fresh += 1;
+
+            if self.used_names.insert(name.clone()) {
+                return Variable::new(name);
+            }
+        }
+    }
+
+    /// Encode the formula, returning the literal which is equivalent to it.
+    fn encode(&mut self, formula: &PropositionalFormula) -> Literal {
+        let (a, b) = match formula {
+            PropositionalFormula::Variable(v) => return Literal::positive(v.clone()),
+            PropositionalFormula::Negation(f) => return self.encode(f).complement(),
+            // t <-> 1, resp. t <-> 0
+            PropositionalFormula::Top | PropositionalFormula::Bottom => {
+                let t = Literal::positive(self.fresh_variable());
+                let unit = match formula {
+                    PropositionalFormula::Top => t.clone(),
+                    _ => t.complement(),
+                };
+                self.cnf.add(Clause::new(vec![unit]));
+                return t;
+            }
+            PropositionalFormula::Conjunction(a, b)
+            | PropositionalFormula::Disjunction(a, b)
+            | PropositionalFormula::Implication(a, b)
+            | PropositionalFormula::Biimplication(a, b) => (self.encode(a), self.encode(b)),
+        };
+
+        let t = Literal::positive(self.fresh_variable());
+        let (not_t, not_a, not_b) = (t.complement(), a.complement(), b.complement());
+
+        let clauses = match formula {
+            // t <-> (a^b)
+            PropositionalFormula::Conjunction(_, _) => vec![
+                vec![not_t.clone(), a.clone()],
+                vec![not_t, b.clone()],
+                vec![t.clone(), not_a, not_b],
+            ],
+            // t <-> (a|b)
+            PropositionalFormula::Disjunction(_, _) => vec![
+                vec![not_t, a.clone(), b.clone()],
+                vec![t.clone(), not_a],
+                vec![t.clone(), not_b],
+            ],
+            // t <-> (a->b)
+            PropositionalFormula::Implication(_, _) => vec![
+                vec![not_t, not_a, b.clone()],
+                vec![t.clone(), a.clone()],
+                vec![t.clone(), not_b],
+            ],
+            // t <-> (a<->b)
+            _ => vec![
+                vec![not_t.clone(), not_a.clone(), b.clone()],
+                vec![not_t, a.clone(), not_b.clone()],
+                vec![t.clone(), a, b],
+                vec![t.clone(), not_a, not_b],
+            ],
+        };
+
+        for clause in clauses {
+            self.cnf.add(Clause::new(clause));
+        }
+
+        t
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert2::check;
+
+    fn literal(name: &str) -> Literal {
+        Literal::positive(Variable::new(name))
+    }
+
+    #[test]
+    fn test_complement() {
+        check!(literal("a").complement() == Literal::negative(Variable::new("a")));
+        check!(literal("a").complement().complement() == literal("a"));
+    }
+
+    #[test]
+    fn test_clause_drops_duplicates() {
+        let clause = Clause::new(vec![literal("a"), literal("b"), literal("a")]);
+        check!(clause.len() == 2);
+    }
+
+    #[test]
+    fn test_clause_tautology() {
+        let clause = Clause::new(vec![literal("a"), literal("b"), literal("a").complement()]);
+        check!(clause.is_tautology());
+    }
+
+    #[test]
+    fn test_resolve() {
+        // (a|b) and ((-a)|c) resolve on a to (b|c).
+        let c = Clause::new(vec![literal("a"), literal("b")]);
+        let d = Clause::new(vec![literal("a").complement(), literal("c")]);
+
+        check!(c.resolve(&d, &literal("a")) == Some(Clause::new(vec![literal("b"), literal("c")])));
+    }
+
+    #[test]
+    fn test_resolve_tautology() {
+        // (a|b) and ((-a)|(-b)) resolve on a to the tautology (b|(-b)).
+        let c = Clause::new(vec![literal("a"), literal("b")]);
+        let d = Clause::new(vec![literal("a").complement(), literal("b").complement()]);
+
+        check!(c.resolve(&d, &literal("a")) == None);
+    }
+
+    #[test]
+    fn test_tseitin_literal() {
+        // (-a) needs no fresh variables.
+        let formula = PropositionalFormula::negated(Box::new(PropositionalFormula::variable(
+            Variable::new("a"),
+        )));
+        let cnf = CnfFormula::from_formula(&formula);
+
+        check!(cnf.len() == 1);
+        let a = Variable::new("a");
+        check!(cnf.variables() == vec![&a]);
+    }
+
+    #[test]
+    fn test_tseitin_binary() {
+        // (a^b) needs one fresh variable and three defining clauses plus the root unit clause.
+        let formula = PropositionalFormula::conjunction(
+            Box::new(PropositionalFormula::variable(Variable::new("a"))),
+            Box::new(PropositionalFormula::variable(Variable::new("b"))),
+        );
+        let cnf = CnfFormula::from_formula(&formula);
+
+        check!(cnf.len() == 4);
+        check!(cnf.variables().len() == 3);
+    }
+
+    #[test]
+    fn test_tseitin_fresh_variables_do_not_clash() {
+        // (_t0|b) must not reuse `_t0` as a fresh variable.
+        let formula = PropositionalFormula::disjunction(
+            Box::new(PropositionalFormula::variable(Variable::new("_t0"))),
+            Box::new(PropositionalFormula::variable(Variable::new("b"))),
+        );
+        let cnf = CnfFormula::from_formula(&formula);
+
+        check!(cnf.variables().len() == 3);
+    }
+
+    #[test]
+    fn test_to_formula() {
+        let mut cnf = CnfFormula::new();
+        check!(cnf.to_formula() == None);
+
+        cnf.add(Clause::new(vec![literal("a"), literal("b")]));
+        cnf.add(Clause::new(vec![literal("c")]));
+
+        let expected = PropositionalFormula::conjunction(
+            Box::new(PropositionalFormula::disjunction(
+                Box::new(PropositionalFormula::variable(Variable::new("a"))),
+                Box::new(PropositionalFormula::variable(Variable::new("b"))),
+            )),
+            Box::new(PropositionalFormula::variable(Variable::new("c"))),
+        );
+        check!(cnf.to_formula() == Some(expected));
+
+        cnf.add(Clause::new(vec![]));
+        check!(cnf.has_empty_clause());
+        check!(cnf.to_formula() == None);
+    }
+
+    #[test]
+    fn test_to_dimacs() {
+        let mut cnf = CnfFormula::new();
+        cnf.add(Clause::new(vec![literal("a"), literal("b").complement()]));
+        cnf.add(Clause::new(vec![literal("b")]));
+
+        check!(cnf.to_dimacs() == "c 1 a\nc 2 b\np cnf 2 2\n1 -2 0\n2 0\n");
+    }
+}
@@ -0,0 +1,173 @@
+//! Linear-time satisfiability checking of 2-CNF formulas via strongly connected components.
+//!
+//! Each clause `(a|b)` is read as the two implications `((-a)->b)` and `((-b)->a)` between
+//! literals. The formula is unsatisfiable exactly if some variable `p` implies `(-p)` and vice
+//! versa, i.e. if `p` and `(-p)` lie in the same strongly connected component of this implication
+//! graph.
+
+use std::collections::HashMap;
+
+use super::{CnfFormula, Literal};
+
+/// Checks if the `CnfFormula`, whose clauses have at most two literals each, is _satisfiable_.
+///
+/// # Panics
+///
+/// Panics if a clause has more than two literals.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::cnf::{two_sat, Clause, CnfFormula, Literal};
+/// use libprop_sat_solver::formula::Variable;
+/// let a = Variable::new("a");
+/// let b = Variable::new("b");
+///
+/// let mut cnf = CnfFormula::new();
+/// cnf.add(Clause::new(vec![Literal::positive(a.clone()), Literal::positive(b.clone())]));
+/// cnf.add(Clause::new(vec![Literal::negative(a.clone()), Literal::positive(b.clone())]));
+/// assert!(two_sat::is_satisfiable(&cnf));
+///
+/// cnf.add(Clause::new(vec![Literal::negative(b)]));
+/// assert!(!two_sat::is_satisfiable(&cnf));
+/// ```
+pub fn is_satisfiable(cnf: &CnfFormula) -> bool {
+    let mut indices: HashMap<_, usize> = HashMap::new();
+    for variable in cnf.variables() {
+        let index = indices.len();
+        indices.insert(variable, index);
+    }
+    // A literal is encoded as `2 * variable + negated`, so that its complement is `literal ^ 1`.
+    let encode =
+        |literal: &Literal| 2 * indices[literal.variable()] + literal.is_negated() as usize;
+
+    let nodes = 2 * indices.len();
+    let mut successors = vec![Vec::new(); nodes];
+    let mut predecessors = vec![Vec::new(); nodes];
+    let mut add_edge = |from: usize, to: usize| {
+        successors[from].push(to);
+        predecessors[to].push(from);
+    };
+
+    for clause in cnf.clauses() {
+        let literals: Vec<usize> = clause.literals().map(encode).collect();
+        match literals[..] {
+            [] => return false,
+            [a] => add_edge(a ^ 1, a),
+            [a, b] => {
+                add_edge(a ^ 1, b);
+                add_edge(b ^ 1, a);
+            }
+            _ => panic!("2-SAT clause with {} literals", literals.len()),
+        }
+    }
+
+    let components = strongly_connected_components(&successors, &predecessors);
+    (0..nodes)
+        .step_by(2)
+        .all(|literal| components[literal] != components[literal + 1])
+}
+
+/// Label each node of the graph with its strongly connected component by Kosaraju's algorithm:
+/// nodes are ordered by decreasing finishing time of a depth-first search, and each search of the
+/// reversed graph in that order visits exactly one component.
+fn strongly_connected_components(
+    successors: &[Vec<usize>],
+    predecessors: &[Vec<usize>],
+) -> Vec<usize> {
+    let nodes = successors.len();
+
+    let mut visited = vec![false; nodes];
+    let mut finished = Vec::with_capacity(nodes);
+    for root in 0..nodes {
+        if visited[root] {
+            continue;
+        }
+        visited[root] = true;
+        // Each entry is a node along with the index of its next successor to visit.
+        let mut stack = vec![(root, 0)];
+        while let Some((node, next)) = stack.pop() {
+            match successors[node].get(next) {
+                Some(&successor) => {
+                    stack.push((node, next + 1));
+                    if !visited[successor] {
+                        visited[successor] = true;
+                        stack.push((successor, 0));
+                    }
+                }
+                None => finished.push(node),
+            }
+        }
+    }
+
+    let mut components = vec![usize::MAX; nodes];
+    let mut component = 0;
+    for &root in finished.iter().rev() {
+        if components[root] != usize::MAX {
+            continue;
+        }
+        components[root] = component;
+        let mut stack = vec![root];
+        while let Some(node) = stack.pop() {
+            for &predecessor in &predecessors[node] {
+                if components[predecessor] == usize::MAX {
+                    components[predecessor] = component;
+                    stack.push(predecessor);
+                }
+            }
+        }
+        component += 1;
+    }
+
+    components
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cnf::{dpll, Clause};
+    use crate::formula::Variable;
+    use assert2::check;
+
+    fn clause(literals: &[i32]) -> Clause {
+        Clause::new(literals.iter().map(|&n| {
+            let variable = Variable::new(format!("x{}", n.abs()));
+            if n < 0 {
+                Literal::negative(variable)
+            } else {
+                Literal::positive(variable)
+            }
+        }))
+    }
+
+    #[test]
+    fn test_agrees_with_dpll() {
+        // Every set of clauses drawn from a fixed pool of 2-clauses over four variables.
+        let pool = [
+            [1, 2],
+            [-1, 3],
+            [-3, -2],
+            [2, 4],
+            [-4, 1],
+            [-2, -1],
+            [3, -4],
+            [-3, 4],
+        ];
+
+        for subset in 0..(1 << pool.len()) {
+            let mut cnf = CnfFormula::new();
+            for (i, literals) in pool.iter().enumerate() {
+                if subset & (1 << i) != 0 {
+                    cnf.add(clause(literals));
+                }
+            }
+
+            check!(is_satisfiable(&cnf) == dpll::is_satisfiable(&cnf));
+        }
+
+        let mut cnf = CnfFormula::new();
+        cnf.add(clause(&[1]));
+        cnf.add(clause(&[-1]));
+        check!(!is_satisfiable(&cnf));
+    }
+}
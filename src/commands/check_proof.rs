@@ -0,0 +1,57 @@
+//! `check-proof` subcommand.
+
+use std::fs;
+use std::io::{self, prelude::*};
+use std::path::PathBuf;
+
+use log::error;
+
+use libprop_sat_solver::tableaux_solver::proof::{check_proof, Claim, Proof};
+
+/// Check each proof file, without searching for proofs of the formulas again.
+///
+/// The program aborts with exit code `5` if a file cannot be read, and with exit code `1` after all
+/// proofs are checked if any of them is malformed or invalid.
+pub fn run(paths: &[PathBuf]) -> io::Result<()> {
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+
+    let mut all_valid = true;
+
+    for path in paths {
+        let input = match fs::read_to_string(path) {
+            Ok(input) => input,
+            Err(e) => {
+                error!("failed to read proof from {:?}: {}", path, e);
+                std::process::exit(5);
+            }
+        };
+
+        match Proof::from_json(&input).and_then(|proof| check_proof(&proof).map(|_| proof)) {
+            Ok(proof) => {
+                let claim = match proof.claim {
+                    Claim::Unsatisfiable => "unsatisfiable",
+                    Claim::Valid => "valid",
+                };
+                stdout.write_fmt(format_args!(
+                    "{}: proves {} {}\n",
+                    path.display(),
+                    proof.formula,
+                    claim
+                ))?;
+            }
+            Err(e) => {
+                all_valid = false;
+                stdout.write_fmt(format_args!("{}: invalid proof: {}\n", path.display(), e))?;
+            }
+        }
+    }
+
+    stdout.flush()?;
+
+    if !all_valid {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
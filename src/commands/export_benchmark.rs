@@ -0,0 +1,96 @@
+//! `export-benchmark` subcommand.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Instant;
+
+use log::{error, info};
+
+use libprop_sat_solver::formula::{analysis::FormulaMetrics, obfuscate, PropositionalFormula};
+use libprop_sat_solver::solver::{self, SolverConfig};
+
+use crate::header;
+
+/// Obfuscate the formulas (see [`obfuscate`]) into a reproduction package in `dir`, which can be
+/// attached to performance bug reports without giving the formulas away:
+///
+/// - `benchmark.txt`: the obfuscated formulas, after a header with the solver `config`.
+/// - `report.json`: the structure metrics of each formula, and the observed behavior of the
+///   solver in satisfiability mode on both the formula and its obfuscation.
+///
+/// Formula `i` (from `0`) is obfuscated with `seed + i`.
+///
+/// # Errors
+///
+/// If the package cannot be written then the program aborts with a non-zero exit code.
+///
+/// [`obfuscate`]: ../../../libprop_sat_solver/formula/obfuscation/fn.obfuscate.html
+pub fn run(
+    formulas: &[PropositionalFormula],
+    dir: &Path,
+    seed: u64,
+    config: &SolverConfig,
+) -> io::Result<()> {
+    let header = header::render(config);
+    let mut benchmark = format!("{}\n", header);
+    let mut entries = Vec::with_capacity(formulas.len());
+
+    for (i, formula) in formulas.iter().enumerate() {
+        let obfuscated = obfuscate(formula, seed.wrapping_add(i as u64));
+        benchmark.push_str(&format!("{}\n", obfuscated));
+
+        let metrics = FormulaMetrics::of(formula);
+        entries.push(format!(
+            "    {{\"line\": {}, \"size\": {}, \"depth\": {}, \"variables\": {}, \
+             \"connectives\": {}, \"original\": {}, \"obfuscated\": {}}}",
+            // The header is the first line of the benchmark.
+            i + 2,
+            metrics.size,
+            metrics.depth,
+            metrics.variables,
+            metrics.connectives.total(),
+            behavior(formula, config),
+            behavior(&obfuscated, config),
+        ));
+    }
+
+    let report = format!(
+        "{{\n  \"version\": \"{}\",\n  \"seed\": {},\n  \"header\": \"{}\",\n  \
+         \"formulas\": [\n{}\n  ]\n}}\n",
+        crate::VERSION.unwrap_or("unknown"),
+        seed,
+        header,
+        entries.join(",\n"),
+    );
+
+    info!("writing reproduction package to {:?}", dir);
+    write(&dir.join("benchmark.txt"), &benchmark, dir);
+    write(&dir.join("report.json"), &report, dir);
+
+    Ok(())
+}
+
+/// Solve the formula, rendering the result and the resources it took as a JSON object.
+fn behavior(formula: &PropositionalFormula, config: &SolverConfig) -> String {
+    let start = Instant::now();
+    let (result, stats) = solver::is_satisfiable_with_stats(formula, config);
+    let time = start.elapsed();
+
+    let optional =
+        |value: Option<usize>| value.map_or_else(|| "null".to_string(), |v| v.to_string());
+    format!(
+        "{{\"result\": {}, \"time_us\": {}, \"expansions\": {}, \"peak_queue\": {}}}",
+        result,
+        time.as_micros(),
+        optional(stats.map(|s| s.expansions)),
+        optional(stats.map(|s| s.peak_queue)),
+    )
+}
+
+fn write(path: &Path, contents: &str, dir: &Path) {
+    if let Err(e) = fs::create_dir_all(dir).and_then(|_| fs::write(path, contents)) {
+        error!("failed to write {:?}: {}", path, e);
+        std::process::exit(5);
+    }
+}
@@ -0,0 +1,158 @@
+//! Subcommands of the CLI, which do something other than computing the satisfiability/validity of
+//! the given formula(s).
+
+pub mod check_proof;
+pub mod export_benchmark;
+pub mod qbf;
+pub mod random;
+pub mod regress;
+pub mod simplify;
+pub mod smt;
+pub mod stats;
+pub mod tptp;
+pub mod verify_model;
+pub mod why_unsat;
+
+use std::io;
+use std::path::PathBuf;
+
+use libprop_sat_solver::parser::ParserOptions;
+use libprop_sat_solver::solver::SolverConfig;
+
+/// Subcommands of the CLI.
+#[derive(Debug, Clone, PartialEq, structopt::StructOpt)]
+pub enum Command {
+    /// Simplify the given formula(s) and print the simplified formulas, one per line.
+    Simplify {
+        /// Prove that each simplified formula is equivalent to its input formula, failing with a
+        /// non-zero exit code after all formulas are printed if any of them is not.
+        #[structopt(short = "e", long)]
+        check_equivalence: bool,
+    },
+    /// Decide the truth of the given quantified Boolean formula(s), e.g. `forall x. exists y.
+    /// (x<->y)`, one per line.
+    Qbf {
+        /// Read the whole input as a single QDIMACS instance instead.
+        #[structopt(short = "q", long)]
+        qdimacs: bool,
+    },
+    /// Answer each `(check-sat)` of the input, read as a single SMT-LIB 2 script over the `Bool`
+    /// sort, with `sat` or `unsat`; the query of each is the conjunction of the preceding asserts.
+    Smt,
+    /// Decide the input, read as a single propositional TPTP problem of `fof` and `cnf` formulas,
+    /// and print its status like a TPTP prover, e.g. `% SZS status Theorem`.
+    Tptp,
+    /// Print pseudo-random formulas, one per line, without reading any input.
+    Random {
+        /// Number of propositional variables `x1, ..., xn` to draw from.
+        #[structopt(long, default_value = "5")]
+        vars: usize,
+        /// Maximum nesting depth of connectives.
+        #[structopt(long, default_value = "4")]
+        depth: usize,
+        /// Number of formulas to generate.
+        #[structopt(long, default_value = "10")]
+        count: usize,
+        /// Seed of the pseudo-random generator; the same seed always gives the same formulas.
+        #[structopt(long, default_value = "0")]
+        seed: u64,
+        /// Print each formula as a DIMACS CNF instance (via the Tseitin transformation) instead
+        /// of in the input syntax. Instances are separated by a `c formula <n>` comment line.
+        #[structopt(long)]
+        dimacs: bool,
+        /// Only print formulas whose complete tableau has at least this many branches, e.g. to
+        /// pick exercises of a given difficulty.
+        #[structopt(long)]
+        min_branches: Option<usize>,
+    },
+    /// Print metrics (size, depth, variables, connectives, Horn/2-SAT classification) of the given
+    /// formula(s) and aggregate statistics, without solving them.
+    Stats {
+        /// List the sub-formulas which occur more than once, most frequent first.
+        #[structopt(long)]
+        shared: bool,
+        /// Maximum number of shared sub-formulas to list per formula.
+        #[structopt(long, default_value = "10")]
+        top: usize,
+    },
+    /// Check whether an assignment satisfies the given formula(s), pointing out a falsified
+    /// sub-formula of each formula it does not satisfy.
+    VerifyModel {
+        /// File assigning truth values to variables, one `name=1` or `name=0` per line.
+        #[structopt(short = "a", long)]
+        assignment: PathBuf,
+    },
+    /// Check a corpus of annotated formulas, one `<formula> ; <status>` per line with status
+    /// `sat`, `unsat`, `valid` or `invalid`, and report the formulas the solver disagrees with.
+    Regress,
+    /// Check recorded tableau proofs (see `--proof-dir`) without solving their formulas again.
+    CheckProof {
+        /// Proof files to check.
+        #[structopt(required = true)]
+        proofs: Vec<PathBuf>,
+    },
+    /// Write a shareable reproduction package for performance bug reports: the given formula(s)
+    /// with variables renamed and commutative operands shuffled, the solver options as a file
+    /// header, and the structure metrics and observed solver behavior of each formula.
+    ExportBenchmark {
+        /// Directory to write `benchmark.txt` and `report.json` to.
+        #[structopt(long)]
+        dir: PathBuf,
+        /// Seed of the obfuscation; the same seed always gives the same package formulas.
+        #[structopt(long, default_value = "0")]
+        seed: u64,
+    },
+    /// Drill down into an unsatisfiable conjunction of the given formula(s): relax (remove) and
+    /// restore its top-level conjuncts with commands read from `stdin`, seeing after each command
+    /// whether satisfiability is restored. Pass the formulas with `--input` or `--formula` to keep
+    /// `stdin` free for the commands.
+    WhyUnsat,
+}
+
+impl Command {
+    /// Checks if the command needs the input formula(s).
+    pub fn reads_input(&self) -> bool {
+        !matches!(self, Command::Random { .. } | Command::CheckProof { .. })
+    }
+}
+
+/// Run the `command` over the raw input lines, parsing formulas in the grammar chosen by the
+/// `options` and solving with the given `config` where needed.
+pub fn run(
+    command: &Command,
+    inputs: &[String],
+    config: &SolverConfig,
+    options: &ParserOptions,
+) -> io::Result<()> {
+    match command {
+        Command::Simplify { check_equivalence } => simplify::run(
+            &crate::parse_formulas(inputs, options),
+            *check_equivalence,
+            config,
+        ),
+        Command::Qbf { qdimacs } => qbf::run(inputs, *qdimacs),
+        Command::Smt => smt::run(inputs, config),
+        Command::Tptp => tptp::run(inputs, config),
+        Command::Random {
+            vars,
+            depth,
+            count,
+            seed,
+            dimacs,
+            min_branches,
+        } => random::run(*vars, *depth, *count, *seed, *dimacs, *min_branches),
+        Command::Stats { shared, top } => {
+            stats::run(&crate::parse_formulas(inputs, options), *shared, *top)
+        }
+        Command::VerifyModel { assignment } => verify_model::run(
+            &crate::parse_formulas(inputs, options),
+            &crate::read_assignment(assignment)?,
+        ),
+        Command::Regress => regress::run(inputs, config),
+        Command::CheckProof { proofs } => check_proof::run(proofs),
+        Command::ExportBenchmark { dir, seed } => {
+            export_benchmark::run(&crate::parse_formulas(inputs, options), dir, *seed, config)
+        }
+        Command::WhyUnsat => why_unsat::run(&crate::parse_formulas(inputs, options), config),
+    }
+}
@@ -0,0 +1,50 @@
+//! `qbf` subcommand.
+
+use std::io::{self, prelude::*};
+
+use log::{debug, error};
+
+use libprop_sat_solver::qbf::{is_true, QuantifiedFormula};
+
+use libprop_sat_solver::parser::{parse_qdimacs, parse_quantified};
+
+/// Print the truth value of each quantified formula.
+///
+/// If `qdimacs` is set, the input lines are read as a single QDIMACS instance, otherwise each
+/// line is a quantified formula in the native syntax.
+///
+/// # Errors
+///
+/// If any formula is not well-formed then the program aborts with a non-zero exit code.
+pub fn run(inputs: &[String], qdimacs: bool) -> io::Result<()> {
+    let formulas: Vec<QuantifiedFormula> = if qdimacs {
+        match parse_qdimacs(&inputs.join("\n")) {
+            Ok(formula) => vec![formula],
+            Err(e) => {
+                error!("ill-formed QDIMACS instance: {}", e);
+                std::process::exit(22);
+            }
+        }
+    } else {
+        inputs
+            .iter()
+            .map(|f| match parse_quantified(f) {
+                Ok(f) => f,
+                Err(e) => {
+                    error!("ill-formed formula: {}", e.diagnostic());
+                    std::process::exit(22);
+                }
+            })
+            .collect()
+    };
+
+    debug!("parsed formulas:\n{:#?}", &formulas);
+
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+    for formula in &formulas {
+        stdout.write_fmt(format_args!("{:?}\n", is_true(formula)))?;
+    }
+
+    Ok(())
+}
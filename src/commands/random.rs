@@ -0,0 +1,74 @@
+//! `random` subcommand.
+
+use std::io::{self, prelude::*};
+
+use log::error;
+
+use libprop_sat_solver::cnf::CnfFormula;
+use libprop_sat_solver::formula::FormulaGenerator;
+use libprop_sat_solver::tableaux_solver::proof;
+
+/// Number of formulas drawn per requested formula before giving up on `--min-branches`.
+const ATTEMPTS_PER_FORMULA: usize = 1000;
+
+/// Print `count` pseudo-random formulas over `vars` variables with connectives nested at most
+/// `depth` deep, either in the input syntax or, if `dimacs` is set, as DIMACS CNF instances.
+///
+/// If `min_branches` is given, formulas whose complete tableau has fewer branches are skipped.
+///
+/// # Errors
+///
+/// If `vars` is zero, or if not enough formulas with `min_branches` branches turn up, then the
+/// program aborts with a non-zero exit code.
+pub fn run(
+    vars: usize,
+    depth: usize,
+    count: usize,
+    seed: u64,
+    dimacs: bool,
+    min_branches: Option<usize>,
+) -> io::Result<()> {
+    if vars == 0 {
+        error!("cannot generate formulas without variables");
+        std::process::exit(22);
+    }
+
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+
+    let attempts = match min_branches {
+        Some(_) => count.saturating_mul(ATTEMPTS_PER_FORMULA),
+        None => count,
+    };
+
+    let mut found = 0;
+    for (i, formula) in FormulaGenerator::new(vars, depth, seed)
+        .take(attempts)
+        .filter(|formula| {
+            min_branches.is_none_or(|min| proof::tableau_shape(formula).branches >= min)
+        })
+        .take(count)
+        .enumerate()
+    {
+        found += 1;
+        if dimacs {
+            stdout.write_fmt(format_args!("c formula {}\n", i + 1))?;
+            stdout.write_all(CnfFormula::from_formula(&formula).to_dimacs().as_bytes())?;
+        } else {
+            stdout.write_fmt(format_args!("{}\n", &formula))?;
+        }
+    }
+
+    if found < count {
+        error!(
+            "found only {} of {} formulas with at least {} branches in {} attempts",
+            found,
+            count,
+            min_branches.unwrap_or_default(),
+            attempts
+        );
+        std::process::exit(22);
+    }
+
+    Ok(())
+}
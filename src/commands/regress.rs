@@ -0,0 +1,153 @@
+//! `regress` subcommand.
+
+use std::io::{self, prelude::*};
+
+use log::error;
+
+use libprop_sat_solver::formula::PropositionalFormula;
+use libprop_sat_solver::parser;
+use libprop_sat_solver::solver::{self, SolverConfig};
+
+/// Expected status of a formula in a regression corpus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Satisfiable,
+    Unsatisfiable,
+    Valid,
+    Invalid,
+}
+
+impl Status {
+    fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "sat" | "satisfiable" => Some(Status::Satisfiable),
+            "unsat" | "unsatisfiable" => Some(Status::Unsatisfiable),
+            "valid" => Some(Status::Valid),
+            "invalid" => Some(Status::Invalid),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Status::Satisfiable => "sat",
+            Status::Unsatisfiable => "unsat",
+            Status::Valid => "valid",
+            Status::Invalid => "invalid",
+        }
+    }
+
+    /// The status of the `formula` in the same mode (satisfiability or validity) as `self`.
+    fn of(self, formula: &PropositionalFormula, config: &SolverConfig) -> Self {
+        match self {
+            Status::Satisfiable | Status::Unsatisfiable => {
+                if solver::is_satisfiable(formula, config) {
+                    Status::Satisfiable
+                } else {
+                    Status::Unsatisfiable
+                }
+            }
+            Status::Valid | Status::Invalid => {
+                if solver::is_valid(formula, config) {
+                    Status::Valid
+                } else {
+                    Status::Invalid
+                }
+            }
+        }
+    }
+}
+
+/// Split a `formula ; status` line at its last `;`. Blank lines and `#` comments give `Ok(None)`.
+fn parse_entry(line: &str) -> Result<Option<(PropositionalFormula, Status)>, String> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return Ok(None);
+    }
+
+    let separator = line
+        .rfind(';')
+        .ok_or_else(|| "missing `; <status>` annotation".to_string())?;
+    let (formula, status) = (&line[..separator], &line[separator + 1..]);
+
+    let status = Status::parse(status.trim()).ok_or_else(|| {
+        format!(
+            "unknown status {:?}, expected one of sat, unsat, valid, invalid",
+            status.trim()
+        )
+    })?;
+    let formula = parser::parse(formula.trim())?;
+
+    Ok(Some((formula, status)))
+}
+
+/// Solve each annotated formula of the corpus and report those whose status differs from the
+/// expected one, followed by a summary.
+///
+/// The program aborts with exit code `22` on an ill-formed line, and with exit code `1` after all
+/// formulas are solved if any of them does not have its expected status.
+pub fn run(inputs: &[String], config: &SolverConfig) -> io::Result<()> {
+    let entries: Vec<(usize, PropositionalFormula, Status)> = inputs
+        .iter()
+        .enumerate()
+        .filter_map(|(i, line)| match parse_entry(line) {
+            Ok(entry) => entry.map(|(formula, status)| (i + 1, formula, status)),
+            Err(e) => {
+                error!("ill-formed regression entry on line {}: {}", i + 1, e);
+                std::process::exit(22);
+            }
+        })
+        .collect();
+
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+
+    let mut failed = 0;
+
+    for (line, formula, expected) in &entries {
+        let actual = expected.of(formula, config);
+        if actual != *expected {
+            failed += 1;
+            stdout.write_fmt(format_args!(
+                "line {}: expected {}, got {}: {}\n",
+                line,
+                expected.name(),
+                actual.name(),
+                formula
+            ))?;
+        }
+    }
+
+    stdout.write_fmt(format_args!(
+        "regress: {} passed, {} failed\n",
+        entries.len() - failed,
+        failed
+    ))?;
+    stdout.flush()?;
+
+    if failed > 0 {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert2::check;
+
+    #[test]
+    fn test_parse_entry() {
+        let (formula, status) = parse_entry(" (a|(-a)) ; VALID ").unwrap().unwrap();
+        check!(formula.to_string() == "(a|(-a))");
+        check!(status == Status::Valid);
+        check!(status.of(&formula, &SolverConfig::default()) == Status::Valid);
+
+        check!(parse_entry("").unwrap().is_none());
+        check!(parse_entry("# comment; sat").unwrap().is_none());
+        check!(parse_entry("(a^b)").is_err());
+        check!(parse_entry("(a^b) ; maybe").is_err());
+        check!(parse_entry("(a^ ; sat").is_err());
+    }
+}
@@ -0,0 +1,53 @@
+//! `simplify` subcommand.
+
+use std::io::{self, prelude::*};
+
+use log::{error, info};
+
+use libprop_sat_solver::formula::{simplify, PropositionalFormula};
+use libprop_sat_solver::solver::{equivalent, SolverConfig};
+
+/// Print the simplified form of each formula.
+///
+/// If `check_equivalence` is set, each simplified formula is proven equivalent to its input with
+/// the solver configured by `config`.
+///
+/// # Errors
+///
+/// Returns an error after all formulas are printed if any simplified formula is not equivalent to
+/// its input.
+pub fn run(
+    formulas: &[PropositionalFormula],
+    check_equivalence: bool,
+    config: &SolverConfig,
+) -> io::Result<()> {
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+
+    let mut inequivalent = 0;
+
+    for formula in formulas {
+        let simplified = simplify(formula);
+        stdout.write_fmt(format_args!("{}\n", &simplified))?;
+
+        if check_equivalence {
+            if equivalent(formula, &simplified, config) {
+                info!("proved {} equivalent to {}", &simplified, formula);
+            } else {
+                error!("{} is not equivalent to {}", &simplified, formula);
+                inequivalent += 1;
+            }
+        }
+    }
+
+    stdout.flush()?;
+
+    if inequivalent > 0 {
+        return Err(io::Error::other(format!(
+            "{} simplified formula(s) not equivalent to their input",
+            inequivalent
+        )));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,40 @@
+//! `smt` subcommand.
+
+use std::io::{self, prelude::*};
+
+use log::{debug, error};
+
+use libprop_sat_solver::parser::parse_smtlib;
+use libprop_sat_solver::solver::{self, SolverConfig};
+
+/// Answer each `(check-sat)` of the SMT-LIB 2 script made up of the input lines with `sat` or
+/// `unsat`, like an SMT solver would.
+///
+/// # Errors
+///
+/// If the script is ill-formed or uses anything beyond the Boolean fragment then the program
+/// aborts with a non-zero exit code.
+pub fn run(inputs: &[String], config: &SolverConfig) -> io::Result<()> {
+    let queries = match parse_smtlib(&inputs.join("\n")) {
+        Ok(queries) => queries,
+        Err(e) => {
+            error!("ill-formed SMT-LIB script: {}", e);
+            std::process::exit(22);
+        }
+    };
+
+    debug!("parsed queries:\n{:#?}", &queries);
+
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+    for query in &queries {
+        let answer = if solver::is_satisfiable(query, config) {
+            "sat"
+        } else {
+            "unsat"
+        };
+        stdout.write_fmt(format_args!("{}\n", answer))?;
+    }
+
+    Ok(())
+}
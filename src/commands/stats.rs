@@ -0,0 +1,93 @@
+//! `stats` subcommand.
+
+use std::io::{self, prelude::*};
+
+use std::collections::HashSet;
+
+use libprop_sat_solver::formula::analysis::{shared_subformulas, ConnectiveCounts, FormulaMetrics};
+use libprop_sat_solver::formula::PropositionalFormula;
+
+/// Print the metrics of each formula followed by aggregate statistics over all of them. None of
+/// the formulas is solved.
+///
+/// If `shared` is set, at most `top` of the sub-formulas which occur more than once are listed
+/// along with their number of occurrences and their size, which helps spotting candidates for
+/// factoring out of large specifications.
+pub fn run(formulas: &[PropositionalFormula], shared: bool, top: usize) -> io::Result<()> {
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+
+    let mut total_size = 0;
+    let mut max_depth = 0;
+    let mut variables = HashSet::new();
+    let mut connectives = ConnectiveCounts::default();
+    let mut horn = 0;
+    let mut two_sat = 0;
+
+    for (i, formula) in formulas.iter().enumerate() {
+        let metrics = FormulaMetrics::of(formula);
+
+        stdout.write_fmt(format_args!("formula {}: {}\n", i + 1, formula))?;
+        write_metrics(&mut stdout, &metrics)?;
+
+        total_size += metrics.size;
+        max_depth = max_depth.max(metrics.depth);
+        variables.extend(formula.variables());
+        connectives += metrics.connectives;
+        horn += metrics.is_horn as usize;
+        two_sat += metrics.is_two_sat as usize;
+
+        if shared {
+            let shared = shared_subformulas(formula);
+            stdout.write_fmt(format_args!("  shared sub-formulas: {}\n", shared.len()))?;
+
+            for subformula in shared.iter().take(top) {
+                stdout.write_fmt(format_args!(
+                    "    {}x, size {}: {}\n",
+                    subformula.count, subformula.size, subformula.formula
+                ))?;
+            }
+        }
+    }
+
+    stdout.write_fmt(format_args!("total: {} formulas\n", formulas.len()))?;
+    stdout.write_fmt(format_args!("  size: {}\n", total_size))?;
+    stdout.write_fmt(format_args!("  max depth: {}\n", max_depth))?;
+    stdout.write_fmt(format_args!("  variables: {}\n", variables.len()))?;
+    write_connectives(&mut stdout, &connectives)?;
+    stdout.write_fmt(format_args!("  horn: {}, 2-sat: {}\n", horn, two_sat))?;
+
+    Ok(())
+}
+
+fn write_metrics(out: &mut impl Write, metrics: &FormulaMetrics) -> io::Result<()> {
+    out.write_fmt(format_args!("  size: {}\n", metrics.size))?;
+    out.write_fmt(format_args!("  depth: {}\n", metrics.depth))?;
+    out.write_fmt(format_args!("  variables: {}\n", metrics.variables))?;
+    write_connectives(out, &metrics.connectives)?;
+    out.write_fmt(format_args!(
+        "  cnf: {}, horn: {}, 2-sat: {}\n",
+        yes_no(metrics.is_cnf),
+        yes_no(metrics.is_horn),
+        yes_no(metrics.is_two_sat)
+    ))
+}
+
+fn write_connectives(out: &mut impl Write, connectives: &ConnectiveCounts) -> io::Result<()> {
+    out.write_fmt(format_args!(
+        "  connectives: - {}, ^ {}, | {}, -> {}, <-> {}\n",
+        connectives.negations,
+        connectives.conjunctions,
+        connectives.disjunctions,
+        connectives.implications,
+        connectives.biimplications
+    ))
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
@@ -0,0 +1,33 @@
+//! `tptp` subcommand.
+
+use std::io::{self, prelude::*};
+
+use log::{debug, error};
+
+use libprop_sat_solver::parser::parse_tptp;
+use libprop_sat_solver::solver::{self, SolverConfig};
+
+/// Decide the TPTP problem made up of the input lines and print its status as a TPTP prover would,
+/// e.g. `% SZS status Theorem`.
+///
+/// # Errors
+///
+/// If the problem is ill-formed or not propositional then the program aborts with a non-zero exit
+/// code.
+pub fn run(inputs: &[String], config: &SolverConfig) -> io::Result<()> {
+    let problem = match parse_tptp(&inputs.join("\n")) {
+        Ok(problem) => problem,
+        Err(e) => {
+            error!("ill-formed TPTP problem: {}", e);
+            std::process::exit(22);
+        }
+    };
+
+    debug!("parsed problem:\n{:#?}", &problem);
+
+    let satisfiable = solver::is_satisfiable(&problem.refutation_formula(), config);
+    io::stdout().write_fmt(format_args!(
+        "% SZS status {:?}\n",
+        problem.status(satisfiable)
+    ))
+}
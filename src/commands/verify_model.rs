@@ -0,0 +1,130 @@
+//! `verify-model` subcommand.
+
+use std::io::{self, prelude::*};
+use std::ops::Range;
+
+use libprop_sat_solver::formula::{Assignment, PropositionalFormula, Variable};
+
+/// Outcome of evaluating a formula under an assignment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Verdict {
+    Satisfied,
+    /// The falsified sub-formula and its span in the rendered formula.
+    Falsified(PropositionalFormula, Range<usize>),
+    /// The variables left unassigned, in order of first occurrence.
+    Unassigned(Vec<Variable>),
+}
+
+/// Check whether the `assignment` satisfies each formula.
+///
+/// For a falsified formula, the innermost falsified conjunct is reported along with its (0-based,
+/// half-open) character span in the formula as printed, and marked on the line below it. The
+/// program aborts with exit code `1` after all formulas are checked if any of them is not
+/// satisfied, including when the assignment misses some of its variables.
+pub fn run(formulas: &[PropositionalFormula], assignment: &Assignment) -> io::Result<()> {
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+
+    let mut all_satisfied = true;
+
+    for (i, formula) in formulas.iter().enumerate() {
+        match verify(formula, assignment) {
+            Verdict::Satisfied => {
+                stdout.write_fmt(format_args!("formula {}: satisfied\n", i + 1))?;
+            }
+            Verdict::Falsified(subformula, span) => {
+                all_satisfied = false;
+                stdout.write_fmt(format_args!("formula {}: falsified\n", i + 1))?;
+                stdout.write_fmt(format_args!(
+                    "  falsified sub-formula at {}..{}: {}\n",
+                    span.start, span.end, subformula
+                ))?;
+                stdout.write_fmt(format_args!("    {}\n", formula))?;
+                stdout.write_fmt(format_args!(
+                    "    {}{}\n",
+                    " ".repeat(span.start),
+                    "^".repeat(span.end - span.start)
+                ))?;
+            }
+            Verdict::Unassigned(variables) => {
+                all_satisfied = false;
+                let names: Vec<_> = variables.iter().map(Variable::name).collect();
+                stdout.write_fmt(format_args!(
+                    "formula {}: unassigned variables: {}\n",
+                    i + 1,
+                    names.join(", ")
+                ))?;
+            }
+        }
+    }
+
+    stdout.flush()?;
+
+    if !all_satisfied {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+fn verify(formula: &PropositionalFormula, assignment: &Assignment) -> Verdict {
+    let unassigned: Vec<Variable> = formula
+        .variables()
+        .into_iter()
+        .filter(|v| !assignment.contains_key(v))
+        .cloned()
+        .collect();
+    if !unassigned.is_empty() {
+        return Verdict::Unassigned(unassigned);
+    }
+
+    if is_true(formula, assignment) {
+        return Verdict::Satisfied;
+    }
+
+    // Descend into the leftmost falsified conjunct, keeping track of where the current
+    // sub-formula starts in the fully parenthesized rendering `(A^B)`.
+    let mut falsified = formula;
+    let mut start = 0;
+    while let PropositionalFormula::Conjunction(a, b) = falsified {
+        if !is_true(a, assignment) {
+            falsified = a;
+            start += 1;
+        } else {
+            start += 1 + a.to_string().len() + 1;
+            falsified = b;
+        }
+    }
+
+    let end = start + falsified.to_string().len();
+    Verdict::Falsified(falsified.clone(), start..end)
+}
+
+/// Truth value of a formula whose variables are all assigned.
+fn is_true(formula: &PropositionalFormula, assignment: &Assignment) -> bool {
+    formula.evaluate(assignment) == Some(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert2::check;
+
+    #[test]
+    fn test_falsified_conjunct_span() {
+        let formula = libprop_sat_solver::parser::parse("((a|b)^((a->b)^c))").unwrap();
+        let mut assignment = Assignment::new();
+        assignment.insert(Variable::new("a"), true);
+        assignment.insert(Variable::new("b"), false);
+        assignment.insert(Variable::new("c"), true);
+
+        let implication = libprop_sat_solver::parser::parse("(a->b)").unwrap();
+        check!(verify(&formula, &assignment) == Verdict::Falsified(implication, 8..14));
+
+        assignment.insert(Variable::new("b"), true);
+        check!(verify(&formula, &assignment) == Verdict::Satisfied);
+
+        assignment.remove(&Variable::new("c"));
+        check!(verify(&formula, &assignment) == Verdict::Unassigned(vec![Variable::new("c")]));
+    }
+}
@@ -0,0 +1,224 @@
+//! `why-unsat` subcommand.
+
+use std::collections::BTreeSet;
+use std::io::{self, prelude::*};
+
+use libprop_sat_solver::formula::PropositionalFormula;
+use libprop_sat_solver::models;
+use libprop_sat_solver::solver::{self, SolverConfig};
+
+/// Help text of the drill-down session.
+const HELP: &str = "\
+commands:
+  list              list the conjuncts, marking those in the core and those relaxed
+  relax <n>...      remove conjuncts and check whether the rest is satisfiable
+  restore <n>...    add relaxed conjuncts back
+  core              show a minimal unsatisfiable subset of the remaining conjuncts
+  help              show this help
+  quit              end the session";
+
+/// Split the formulas into their top-level conjuncts and, if their conjunction is unsatisfiable,
+/// let the user relax conjuncts with commands read from `stdin`, reporting after each one whether
+/// satisfiability is restored.
+///
+/// Prompts go to `stderr`, so that the session can also be scripted by piping commands in.
+pub fn run(formulas: &[PropositionalFormula], config: &SolverConfig) -> io::Result<()> {
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+
+    let mut session = Session::new(formulas, config);
+    writeln!(stdout, "{}", session.status())?;
+    if session.core.is_none() {
+        return Ok(());
+    }
+    writeln!(stdout, "type `help` for the commands")?;
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        eprint!("> ");
+        let line = match lines.next() {
+            Some(line) => line?,
+            None => return Ok(()),
+        };
+
+        match session.execute(&line) {
+            Some(output) => writeln!(stdout, "{}", output)?,
+            None => return Ok(()),
+        }
+    }
+}
+
+/// State of a drill-down session over the conjuncts of an unsatisfiable conjunction.
+#[derive(Debug)]
+struct Session<'a> {
+    conjuncts: Vec<&'a PropositionalFormula>,
+    /// Indices of the removed conjuncts.
+    relaxed: BTreeSet<usize>,
+    /// Minimal unsatisfiable subset of the remaining conjuncts, or `None` if they are satisfiable.
+    core: Option<Vec<usize>>,
+    config: &'a SolverConfig,
+}
+
+impl<'a> Session<'a> {
+    fn new(formulas: &'a [PropositionalFormula], config: &'a SolverConfig) -> Self {
+        let mut session = Self {
+            conjuncts: formulas.iter().flat_map(|f| f.conjuncts()).collect(),
+            relaxed: BTreeSet::new(),
+            core: None,
+            config,
+        };
+        session.solve();
+        session
+    }
+
+    /// Execute one command line, returning its output, or `None` if the session ends.
+    fn execute(&mut self, line: &str) -> Option<String> {
+        let mut words = line.split_whitespace();
+        let command = match words.next() {
+            Some(command) => command,
+            None => return Some(String::new()),
+        };
+        let arguments: Vec<&str> = words.collect();
+
+        let output = match command {
+            "list" | "l" => self.list(),
+            "relax" | "r" => self.update(&arguments, true),
+            "restore" => self.update(&arguments, false),
+            "core" | "c" => self.status(),
+            "help" | "h" | "?" => HELP.to_string(),
+            "quit" | "q" | "exit" => return None,
+            _ => format!(
+                "unknown command {:?}, type `help` for the commands",
+                command
+            ),
+        };
+        Some(output)
+    }
+
+    fn list(&self) -> String {
+        let lines: Vec<String> = self
+            .conjuncts
+            .iter()
+            .enumerate()
+            .map(|(i, conjunct)| {
+                let mark = if self.relaxed.contains(&i) {
+                    " [relaxed]"
+                } else if self.core.as_ref().is_some_and(|core| core.contains(&i)) {
+                    " [core]"
+                } else {
+                    ""
+                };
+                format!("{}: {}{}", i + 1, conjunct, mark)
+            })
+            .collect();
+        lines.join("\n")
+    }
+
+    /// Relax (or, if `relax` is not set, restore) the conjuncts with the given 1-based numbers.
+    fn update(&mut self, arguments: &[&str], relax: bool) -> String {
+        if arguments.is_empty() {
+            return "expected the numbers of conjuncts, see `list`".to_string();
+        }
+
+        let mut indices = Vec::with_capacity(arguments.len());
+        for argument in arguments {
+            match argument.parse::<usize>() {
+                Ok(n) if (1..=self.conjuncts.len()).contains(&n) => indices.push(n - 1),
+                _ => {
+                    return format!(
+                        "expected a conjunct number from 1 to {}, found {:?}",
+                        self.conjuncts.len(),
+                        argument
+                    )
+                }
+            }
+        }
+
+        for i in indices {
+            if relax {
+                self.relaxed.insert(i);
+            } else {
+                self.relaxed.remove(&i);
+            }
+        }
+
+        self.solve();
+        self.status()
+    }
+
+    /// Decide the remaining conjuncts and find their core if they are unsatisfiable.
+    fn solve(&mut self) {
+        let remaining: Vec<usize> = (0..self.conjuncts.len())
+            .filter(|i| !self.relaxed.contains(i))
+            .collect();
+        let formulas: Vec<PropositionalFormula> = remaining
+            .iter()
+            .map(|&i| self.conjuncts[i].clone())
+            .collect();
+
+        self.core = solver::unsatisfiable_core(&formulas, self.config)
+            .map(|core| core.into_iter().map(|i| remaining[i]).collect());
+    }
+
+    /// Whether the remaining conjuncts are satisfiable, with a model if they are and the core if
+    /// they are not.
+    fn status(&self) -> String {
+        match &self.core {
+            Some(core) => {
+                let numbers: Vec<String> = core.iter().map(|i| (i + 1).to_string()).collect();
+                format!("unsatisfiable, core: {}", numbers.join(", "))
+            }
+            None => {
+                let remaining = PropositionalFormula::conjoin(
+                    (0..self.conjuncts.len())
+                        .filter(|i| !self.relaxed.contains(i))
+                        .map(|i| self.conjuncts[i].clone()),
+                );
+                let remaining = match remaining {
+                    Some(remaining) => remaining,
+                    None => return "satisfiable, no conjuncts left".to_string(),
+                };
+
+                let variables = remaining.variables();
+                match models::enumerate_models_bounded(&remaining, 1).first() {
+                    Some(model) => {
+                        let values: Vec<String> = variables
+                            .iter()
+                            .map(|&v| format!("{}={}", v, u8::from(model[v])))
+                            .collect();
+                        format!("satisfiable, e.g. {}", values.join(", "))
+                    }
+                    None => "satisfiable".to_string(),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert2::check;
+
+    #[test]
+    fn test_session() {
+        let formulas: Vec<PropositionalFormula> =
+            vec!["((a->b)^c)".parse().unwrap(), "(a^(-b))".parse().unwrap()];
+        let config = SolverConfig::default();
+        let mut session = Session::new(&formulas, &config);
+
+        check!(session.status() == "unsatisfiable, core: 1, 3, 4");
+        let list = session.execute("list").unwrap();
+        check!(list.lines().nth(1) == Some("2: c"));
+        check!(list.lines().next() == Some("1: (a->b) [core]"));
+        check!(session.execute("relax 4").unwrap() == "satisfiable, e.g. a=1, b=1, c=1");
+        check!(session
+            .execute("list")
+            .unwrap()
+            .ends_with("4: (-b) [relaxed]"));
+        check!(session.execute("restore 4").unwrap() == "unsatisfiable, core: 1, 3, 4");
+        check!(session.execute("relax 5").unwrap().starts_with("expected"));
+        check!(session.execute("quit").is_none());
+    }
+}
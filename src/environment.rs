@@ -0,0 +1,129 @@
+//! Solver options from `PROP_SAT_*` environment variables, so that containerized deployments can
+//! configure the solver without changing its command line:
+//!
+//! ```text
+//! PROP_SAT_ENGINE=dpll PROP_SAT_TIMEOUT=2s PROP_SAT_COLOR=never prop-sat-solver -i formulas.txt
+//! ```
+//!
+//! Environment variables have the lowest precedence: they are overridden by the header of the input
+//! file (see [`header`]), which is in turn overridden by explicit command line flags.
+//!
+//! [`header`]: ../header/index.html
+
+use std::env;
+use std::time::Duration;
+
+use libprop_sat_solver::solver::Engine;
+
+use crate::header;
+use crate::records::OutputFormat;
+
+/// Prefix of the environment variables read by the solver.
+pub const PREFIX: &str = "PROP_SAT_";
+
+/// Solver options set by environment variables. Options without a variable are `None`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EnvOptions {
+    /// `PROP_SAT_ENGINE`, see `--engine`.
+    pub engine: Option<Engine>,
+    /// `PROP_SAT_FORMAT`, see `--format`.
+    pub format: Option<OutputFormat>,
+    /// `PROP_SAT_COLOR`: `always` or `never` to force colored log output on or off, or `auto` to
+    /// only color it on terminals.
+    pub color: Option<bool>,
+    /// `PROP_SAT_TIMEOUT`, see `--timeout`.
+    pub timeout: Option<Duration>,
+}
+
+/// Parse the options of the `PROP_SAT_*` variables among the given `(name, value)` pairs,
+/// ignoring all other variables.
+///
+/// # Errors
+///
+/// Fails on unknown `PROP_SAT_*` variables and malformed values.
+pub fn parse<I>(vars: I) -> Result<EnvOptions, String>
+where
+    I: IntoIterator<Item = (String, String)>,
+{
+    let mut options = EnvOptions::default();
+    for (name, value) in vars {
+        let option = match name.strip_prefix(PREFIX) {
+            Some(option) => option,
+            None => continue,
+        };
+
+        match option {
+            "ENGINE" => options.engine = Some(value.parse()?),
+            "FORMAT" => options.format = Some(value.parse()?),
+            "COLOR" => options.color = parse_color(&value)?,
+            "TIMEOUT" => options.timeout = Some(header::parse_duration(&value)?),
+            _ => return Err(format!("unknown environment variable {}", name)),
+        }
+    }
+
+    Ok(options)
+}
+
+fn parse_color(value: &str) -> Result<Option<bool>, String> {
+    match value.to_ascii_lowercase().as_ref() {
+        "always" => Ok(Some(true)),
+        "never" => Ok(Some(false)),
+        "auto" => Ok(None),
+        _ => Err(format!("unknown color choice: {:?}", value)),
+    }
+}
+
+/// Read the options of the `PROP_SAT_*` variables of the environment of the process.
+///
+/// # Errors
+///
+/// See [`parse`]; variables which are not valid unicode are malformed.
+///
+/// [`parse`]: fn.parse.html
+pub fn read() -> Result<EnvOptions, String> {
+    parse(env::vars_os().filter_map(|(name, value)| {
+        let name = name.into_string().ok()?;
+        let value = value
+            .into_string()
+            .unwrap_or_else(|value| value.to_string_lossy().into_owned());
+        Some((name, value))
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert2::check;
+
+    fn vars(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+        vars.iter()
+            .map(|&(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let options = parse(vars(&[
+            ("PATH", "/usr/bin"),
+            ("PROP_SAT_ENGINE", "dpll"),
+            ("PROP_SAT_FORMAT", "JSON"),
+            ("PROP_SAT_COLOR", "never"),
+            ("PROP_SAT_TIMEOUT", "2s"),
+        ]))
+        .unwrap();
+        check!(
+            options
+                == EnvOptions {
+                    engine: Some(Engine::Dpll),
+                    format: Some(OutputFormat::Json),
+                    color: Some(false),
+                    timeout: Some(Duration::from_secs(2)),
+                }
+        );
+
+        check!(parse(vars(&[("PROP_SAT_COLOR", "auto")])).unwrap() == EnvOptions::default());
+        check!(parse(vars(&[("PROP_SAT_JOBS", "2")])).is_err());
+        check!(parse(vars(&[("PROP_SAT_ENGINE", "cdcl")])).is_err());
+        check!(parse(vars(&[("PROP_SAT_COLOR", "sometimes")])).is_err());
+    }
+}
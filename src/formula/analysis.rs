@@ -0,0 +1,622 @@
+//! Structural analyses of propositional formulas.
+
+use std::collections::HashMap;
+use std::ops::AddAssign;
+
+use super::iter::operands;
+use super::{PropositionalFormula, Variable};
+
+/// Number of occurrences of each connective in a formula.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ConnectiveCounts {
+    /// Number of `-`.
+    pub negations: usize,
+    /// Number of `^`.
+    pub conjunctions: usize,
+    /// Number of `|`.
+    pub disjunctions: usize,
+    /// Number of `->`.
+    pub implications: usize,
+    /// Number of `<->`.
+    pub biimplications: usize,
+}
+
+impl ConnectiveCounts {
+    /// Total number of connectives.
+    pub fn total(&self) -> usize {
+        self.negations
+            + self.conjunctions
+            + self.disjunctions
+            + self.implications
+            + self.biimplications
+    }
+}
+
+impl AddAssign for ConnectiveCounts {
+    fn add_assign(&mut self, other: Self) {
+        self.negations += other.negations;
+        self.conjunctions += other.conjunctions;
+        self.disjunctions += other.disjunctions;
+        self.implications += other.implications;
+        self.biimplications += other.biimplications;
+    }
+}
+
+/// Complexity metrics of a formula, computed without solving it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormulaMetrics {
+    /// Number of variable occurrences and connectives.
+    pub size: usize,
+    /// Maximum nesting depth of connectives; a single variable has depth `0`.
+    pub depth: usize,
+    /// Number of distinct propositional variables.
+    pub variables: usize,
+    /// Histogram of connectives.
+    pub connectives: ConnectiveCounts,
+    /// Whether the formula is syntactically in conjunctive normal form, i.e. a conjunction of
+    /// disjunctions of literals `p` and `(-p)`.
+    pub is_cnf: bool,
+    /// Whether the formula is in CNF and each clause contains at most one positive literal.
+    pub is_horn: bool,
+    /// Whether the formula is in CNF and each clause contains at most two literals.
+    pub is_two_sat: bool,
+}
+
+impl FormulaMetrics {
+    /// Compute the metrics of the given formula.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libprop_sat_solver::formula::{analysis::FormulaMetrics, PropositionalFormula, Variable};
+    /// let formula = PropositionalFormula::disjunction(
+    ///     Box::new(PropositionalFormula::negated(Box::new(PropositionalFormula::variable(
+    ///         Variable::new("a"),
+    ///     )))),
+    ///     Box::new(PropositionalFormula::variable(Variable::new("b"))),
+    /// );
+    ///
+    /// let metrics = FormulaMetrics::of(&formula);
+    /// assert_eq!(4, metrics.size);
+    /// assert_eq!(2, metrics.depth);
+    /// assert_eq!(2, metrics.variables);
+    /// assert!(metrics.is_horn && metrics.is_two_sat);
+    /// ```
+    pub fn of(formula: &PropositionalFormula) -> Self {
+        let clauses = cnf_clauses(formula);
+        let is_cnf = clauses.is_some();
+        let clauses = clauses.unwrap_or_default();
+
+        Self {
+            size: formula.size(),
+            depth: formula.depth(),
+            variables: formula.variables().len(),
+            connectives: formula.connective_counts(),
+            is_cnf,
+            is_horn: is_cnf
+                && clauses
+                    .iter()
+                    .all(|clause| clause.iter().filter(|&&positive| positive).count() <= 1),
+            is_two_sat: is_cnf && clauses.iter().all(|clause| clause.len() <= 2),
+        }
+    }
+}
+
+impl PropositionalFormula {
+    /// Number of variable occurrences, constants and connectives in the formula.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libprop_sat_solver::formula::PropositionalFormula;
+    /// let formula: PropositionalFormula = "((a^(-b))->a)".parse().unwrap();
+    ///
+    /// assert_eq!(6, formula.size());
+    /// assert_eq!(3, formula.depth());
+    /// assert_eq!(1, formula.connective_counts().negations);
+    /// assert_eq!(3, formula.connective_counts().total());
+    /// ```
+    pub fn size(&self) -> usize {
+        self.iter_preorder().count()
+    }
+
+    /// Maximum nesting depth of connectives; a single variable or constant has depth `0`.
+    pub fn depth(&self) -> usize {
+        // Depths of the sub-formulas visited so far whose parent has not been visited yet.
+        let mut depths = Vec::new();
+
+        for formula in self.iter_postorder() {
+            let depth = match formula {
+                PropositionalFormula::Variable(_)
+                | PropositionalFormula::Top
+                | PropositionalFormula::Bottom => 0,
+                _ => {
+                    // The operands were visited right before, so their depths are on top.
+                    let arity = operands(formula).iter().flatten().count();
+                    depths.drain(depths.len() - arity..).max().unwrap_or(0) + 1
+                }
+            };
+            depths.push(depth);
+        }
+
+        depths.pop().unwrap_or(0)
+    }
+
+    /// Number of occurrences of each connective in the formula.
+    pub fn connective_counts(&self) -> ConnectiveCounts {
+        let mut connectives = ConnectiveCounts::default();
+
+        for formula in self.iter_preorder() {
+            match formula {
+                PropositionalFormula::Variable(_)
+                | PropositionalFormula::Top
+                | PropositionalFormula::Bottom => {}
+                PropositionalFormula::Negation(_) => connectives.negations += 1,
+                PropositionalFormula::Conjunction(..) => connectives.conjunctions += 1,
+                PropositionalFormula::Disjunction(..) => connectives.disjunctions += 1,
+                PropositionalFormula::Implication(..) => connectives.implications += 1,
+                PropositionalFormula::Biimplication(..) => connectives.biimplications += 1,
+            }
+        }
+
+        connectives
+    }
+}
+
+/// If the formula is in CNF, get the polarities of the literals of each clause (`true` for `p`,
+/// `false` for `(-p)`).
+fn cnf_clauses(formula: &PropositionalFormula) -> Option<Vec<Vec<bool>>> {
+    let polarity = |literal: &PropositionalFormula| match literal {
+        PropositionalFormula::Variable(_) => Some(true),
+        PropositionalFormula::Negation(a) => match &**a {
+            PropositionalFormula::Variable(_) => Some(false),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    formula
+        .conjuncts()
+        .into_iter()
+        .map(|clause| clause.disjuncts().into_iter().map(polarity).collect())
+        .collect()
+}
+
+/// A sub-formula which occurs more than once in a formula, see [`shared_subformulas`].
+///
+/// [`shared_subformulas`]: fn.shared_subformulas.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SharedSubformula<'a> {
+    /// The repeated sub-formula.
+    pub formula: &'a PropositionalFormula,
+    /// Number of occurrences of the sub-formula.
+    pub count: usize,
+    /// Size of (number of variables and connectives in) the sub-formula.
+    pub size: usize,
+}
+
+/// Hash-consing key of a sub-formula: structurally equal sub-formulas have equal keys, and the
+/// keys of compound formulas refer to the ids of their (already hash-consed) operands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Node<'a> {
+    Variable(&'a Variable),
+    Negation(usize),
+    Conjunction(usize, usize),
+    Disjunction(usize, usize),
+    Implication(usize, usize),
+    Biimplication(usize, usize),
+    /// Occurrence of a constant, which is never shared.
+    Constant(*const PropositionalFormula),
+}
+
+/// Find the non-variable sub-formulas which occur more than once in the given formula, most
+/// frequent first (ties are broken by larger size first, then by order of first occurrence).
+///
+/// Sub-formulas are hash-consed bottom-up, so each sub-formula is hashed in constant time and the
+/// whole analysis is linear in the size of the formula. Occurrences inside a repeated sub-formula
+/// count too, e.g. in `(((a^b)|c)^((a^b)|c))` both `((a^b)|c)` and `(a^b)` occur twice.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::formula::{analysis::shared_subformulas, PropositionalFormula, Variable};
+/// let a_and_b = PropositionalFormula::conjunction(
+///     Box::new(PropositionalFormula::variable(Variable::new("a"))),
+///     Box::new(PropositionalFormula::variable(Variable::new("b"))),
+/// );
+/// let formula =
+///     PropositionalFormula::disjunction(Box::new(a_and_b.clone()), Box::new(a_and_b.clone()));
+///
+/// let shared = shared_subformulas(&formula);
+/// assert_eq!(1, shared.len());
+/// assert_eq!(&a_and_b, shared[0].formula);
+/// assert_eq!(2, shared[0].count);
+/// assert_eq!(3, shared[0].size);
+/// ```
+pub fn shared_subformulas(formula: &PropositionalFormula) -> Vec<SharedSubformula<'_>> {
+    let mut table = HashConsTable::default();
+    table.intern(formula);
+
+    let mut shared: Vec<_> = table
+        .entries
+        .into_iter()
+        .enumerate()
+        .filter(|(_, entry)| {
+            entry.count > 1 && !matches!(entry.formula, PropositionalFormula::Variable(_))
+        })
+        .collect();
+
+    // Sort by descending count, then descending size, then ascending id (i.e. first occurrence).
+    shared.sort_by(|(i, a), (j, b)| {
+        b.count
+            .cmp(&a.count)
+            .then(b.size.cmp(&a.size))
+            .then(i.cmp(j))
+    });
+
+    shared.into_iter().map(|(_, entry)| entry).collect()
+}
+
+#[derive(Debug, Default)]
+struct HashConsTable<'a> {
+    ids: HashMap<Node<'a>, usize>,
+    entries: Vec<SharedSubformula<'a>>,
+}
+
+impl<'a> HashConsTable<'a> {
+    /// Intern the formula (and its sub-formulas), returning its id.
+    fn intern(&mut self, formula: &'a PropositionalFormula) -> usize {
+        let node = match formula {
+            PropositionalFormula::Variable(v) => Node::Variable(v),
+            PropositionalFormula::Negation(a) => Node::Negation(self.intern(a)),
+            PropositionalFormula::Conjunction(a, b) => {
+                Node::Conjunction(self.intern(a), self.intern(b))
+            }
+            PropositionalFormula::Disjunction(a, b) => {
+                Node::Disjunction(self.intern(a), self.intern(b))
+            }
+            PropositionalFormula::Implication(a, b) => {
+                Node::Implication(self.intern(a), self.intern(b))
+            }
+            PropositionalFormula::Biimplication(a, b) => {
+                Node::Biimplication(self.intern(a), self.intern(b))
+            }
+            PropositionalFormula::Top | PropositionalFormula::Bottom => Node::Constant(formula),
+        };
+
+        if let Some(&id) = self.ids.get(&node) {
+            self.entries[id].count += 1;
+            return id;
+        }
+
+        let size = match node {
+            Node::Variable(_) | Node::Constant(_) => 1,
+            Node::Negation(a) => 1 + self.entries[a].size,
+            Node::Conjunction(a, b)
+            | Node::Disjunction(a, b)
+            | Node::Implication(a, b)
+            | Node::Biimplication(a, b) => 1 + self.entries[a].size + self.entries[b].size,
+        };
+
+        let id = self.entries.len();
+        self.ids.insert(node, id);
+        self.entries.push(SharedSubformula {
+            formula,
+            count: 1,
+            size,
+        });
+
+        id
+    }
+}
+
+/// Polarity of the occurrences of a sub-formula: whether making it true can only help satisfy the
+/// whole formula (positive), only hurt (negative), or either (both).
+///
+/// An occurrence is positive at the top of the formula, and switches polarity under a negation and
+/// in the antecedent of an implication; both operands of a biimplication occur with both
+/// polarities.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Polarity {
+    Positive,
+    Negative,
+    Both,
+}
+
+impl Polarity {
+    /// The polarity of an occurrence under a negation.
+    pub fn flip(self) -> Self {
+        match self {
+            Polarity::Positive => Polarity::Negative,
+            Polarity::Negative => Polarity::Positive,
+            Polarity::Both => Polarity::Both,
+        }
+    }
+
+    /// The polarity of a sub-formula occurring with both polarities `self` and `other`.
+    pub fn join(self, other: Self) -> Self {
+        if self == other {
+            self
+        } else {
+            Polarity::Both
+        }
+    }
+}
+
+/// Get the distinct sub-formulas of the formula (including itself), in order of first occurrence
+/// from left to right, each with the polarity of all of its occurrences (see [`Polarity`]).
+///
+/// A variable which is not [`Polarity::Both`] is _pure_: the formula is satisfiable iff it is
+/// satisfiable with the variable set to true if positive, resp. false if negative.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::formula::analysis::{subformulas_with_polarity, Polarity};
+/// use libprop_sat_solver::formula::PropositionalFormula;
+/// let formula: PropositionalFormula = "((a->b)^(-(b<->c)))".parse().unwrap();
+///
+/// let polarities: Vec<_> = subformulas_with_polarity(&formula)
+///     .into_iter()
+///     .map(|(f, polarity)| (f.to_string(), polarity))
+///     .collect();
+/// assert_eq!(
+///     vec![
+///         ("((a->b)^(-(b<->c)))".to_string(), Polarity::Positive),
+///         ("(a->b)".to_string(), Polarity::Positive),
+///         ("a".to_string(), Polarity::Negative),
+///         ("b".to_string(), Polarity::Both),
+///         ("(-(b<->c))".to_string(), Polarity::Positive),
+///         ("(b<->c)".to_string(), Polarity::Negative),
+///         ("c".to_string(), Polarity::Both),
+///     ],
+///     polarities
+/// );
+/// ```
+///
+/// [`Polarity`]: enum.Polarity.html
+/// [`Polarity::Both`]: enum.Polarity.html#variant.Both
+pub fn subformulas_with_polarity(
+    formula: &PropositionalFormula,
+) -> Vec<(&PropositionalFormula, Polarity)> {
+    let mut polarities: Vec<(&PropositionalFormula, Polarity)> = Vec::new();
+    let mut indices: HashMap<&PropositionalFormula, usize> = HashMap::new();
+    let mut stack = vec![(formula, Polarity::Positive)];
+
+    while let Some((formula, polarity)) = stack.pop() {
+        match indices.get(formula) {
+            Some(&i) => polarities[i].1 = polarities[i].1.join(polarity),
+            None => {
+                indices.insert(formula, polarities.len());
+                polarities.push((formula, polarity));
+            }
+        }
+
+        // Operands are pushed right to left so that they are visited left to right.
+        match formula {
+            PropositionalFormula::Variable(_)
+            | PropositionalFormula::Top
+            | PropositionalFormula::Bottom => {}
+            PropositionalFormula::Negation(a) => stack.push((a, polarity.flip())),
+            PropositionalFormula::Conjunction(a, b) | PropositionalFormula::Disjunction(a, b) => {
+                stack.push((b, polarity));
+                stack.push((a, polarity));
+            }
+            PropositionalFormula::Implication(a, b) => {
+                stack.push((b, polarity));
+                stack.push((a, polarity.flip()));
+            }
+            PropositionalFormula::Biimplication(a, b) => {
+                stack.push((b, Polarity::Both));
+                stack.push((a, Polarity::Both));
+            }
+        }
+    }
+
+    polarities
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert2::check;
+
+    #[test]
+    fn test_no_shared_subformulas() {
+        // ((a^b)|(b^a))
+        let formula = PropositionalFormula::disjunction(
+            Box::new(PropositionalFormula::conjunction(
+                Box::new(PropositionalFormula::variable(Variable::new("a"))),
+                Box::new(PropositionalFormula::variable(Variable::new("b"))),
+            )),
+            Box::new(PropositionalFormula::conjunction(
+                Box::new(PropositionalFormula::variable(Variable::new("b"))),
+                Box::new(PropositionalFormula::variable(Variable::new("a"))),
+            )),
+        );
+
+        check!(shared_subformulas(&formula).is_empty());
+    }
+
+    #[test]
+    fn test_nested_shared_subformulas() {
+        // ((((-a)^b)|c)^(((-a)^b)|c))
+        let inner = PropositionalFormula::conjunction(
+            Box::new(PropositionalFormula::negated(Box::new(
+                PropositionalFormula::variable(Variable::new("a")),
+            ))),
+            Box::new(PropositionalFormula::variable(Variable::new("b"))),
+        );
+        let outer = PropositionalFormula::disjunction(
+            Box::new(inner.clone()),
+            Box::new(PropositionalFormula::variable(Variable::new("c"))),
+        );
+        let formula =
+            PropositionalFormula::conjunction(Box::new(outer.clone()), Box::new(outer.clone()));
+
+        let shared = shared_subformulas(&formula);
+
+        check!(shared.len() == 3);
+        check!(
+            shared[0]
+                == SharedSubformula {
+                    formula: &outer,
+                    count: 2,
+                    size: 6
+                }
+        );
+        check!(
+            shared[1]
+                == SharedSubformula {
+                    formula: &inner,
+                    count: 2,
+                    size: 4
+                }
+        );
+        check!(shared[2].size == 2);
+    }
+
+    #[test]
+    fn test_most_frequent_first() {
+        // ((a|b)^((-c)^((-c)^((-c)^(a|b)))))
+        let a_or_b = PropositionalFormula::disjunction(
+            Box::new(PropositionalFormula::variable(Variable::new("a"))),
+            Box::new(PropositionalFormula::variable(Variable::new("b"))),
+        );
+        let not_c = PropositionalFormula::negated(Box::new(PropositionalFormula::variable(
+            Variable::new("c"),
+        )));
+        let formula = PropositionalFormula::conjunction(
+            Box::new(a_or_b.clone()),
+            Box::new(PropositionalFormula::conjunction(
+                Box::new(not_c.clone()),
+                Box::new(PropositionalFormula::conjunction(
+                    Box::new(not_c.clone()),
+                    Box::new(PropositionalFormula::conjunction(
+                        Box::new(not_c.clone()),
+                        Box::new(a_or_b.clone()),
+                    )),
+                )),
+            )),
+        );
+
+        let shared = shared_subformulas(&formula);
+
+        check!(shared.len() == 2);
+        check!(shared[0].formula == &not_c);
+        check!(shared[0].count == 3);
+        check!(shared[1].formula == &a_or_b);
+        check!(shared[1].count == 2);
+    }
+
+    #[test]
+    fn test_metrics() {
+        // ((a<->(-b))^(-(-a)))
+        let formula = PropositionalFormula::conjunction(
+            Box::new(PropositionalFormula::biimplication(
+                Box::new(PropositionalFormula::variable(Variable::new("a"))),
+                Box::new(PropositionalFormula::negated(Box::new(
+                    PropositionalFormula::variable(Variable::new("b")),
+                ))),
+            )),
+            Box::new(PropositionalFormula::negated(Box::new(
+                PropositionalFormula::negated(Box::new(PropositionalFormula::variable(
+                    Variable::new("a"),
+                ))),
+            ))),
+        );
+
+        let metrics = FormulaMetrics::of(&formula);
+
+        check!(metrics.size == 8);
+        check!(metrics.depth == 3);
+        check!(metrics.variables == 2);
+        check!(
+            metrics.connectives
+                == ConnectiveCounts {
+                    negations: 3,
+                    conjunctions: 1,
+                    disjunctions: 0,
+                    implications: 0,
+                    biimplications: 1,
+                }
+        );
+        check!(metrics.connectives.total() == 5);
+        check!(!metrics.is_cnf);
+        check!(!metrics.is_horn);
+        check!(!metrics.is_two_sat);
+    }
+
+    #[test]
+    fn test_cnf_classification() {
+        // ((a|(b|c))^((-a)|(-b)))
+        let formula = PropositionalFormula::conjunction(
+            Box::new(PropositionalFormula::disjunction(
+                Box::new(PropositionalFormula::variable(Variable::new("a"))),
+                Box::new(PropositionalFormula::disjunction(
+                    Box::new(PropositionalFormula::variable(Variable::new("b"))),
+                    Box::new(PropositionalFormula::variable(Variable::new("c"))),
+                )),
+            )),
+            Box::new(PropositionalFormula::disjunction(
+                Box::new(PropositionalFormula::negated(Box::new(
+                    PropositionalFormula::variable(Variable::new("a")),
+                ))),
+                Box::new(PropositionalFormula::negated(Box::new(
+                    PropositionalFormula::variable(Variable::new("b")),
+                ))),
+            )),
+        );
+
+        let metrics = FormulaMetrics::of(&formula);
+
+        check!(metrics.is_cnf);
+        check!(!metrics.is_horn);
+        check!(!metrics.is_two_sat);
+
+        // ((a|(-b))^c)
+        let formula = PropositionalFormula::conjunction(
+            Box::new(PropositionalFormula::disjunction(
+                Box::new(PropositionalFormula::variable(Variable::new("a"))),
+                Box::new(PropositionalFormula::negated(Box::new(
+                    PropositionalFormula::variable(Variable::new("b")),
+                ))),
+            )),
+            Box::new(PropositionalFormula::variable(Variable::new("c"))),
+        );
+
+        let metrics = FormulaMetrics::of(&formula);
+
+        check!(metrics.is_cnf);
+        check!(metrics.is_horn);
+        check!(metrics.is_two_sat);
+    }
+
+    #[test]
+    fn test_pure_variables_can_be_fixed() {
+        use crate::formula::{substitute, Assignment, Residual};
+        use crate::testing::formulas;
+        use crate::truth_table;
+
+        for formula in formulas(4, 5, 2, 200) {
+            let pure: Assignment = subformulas_with_polarity(&formula)
+                .into_iter()
+                .filter_map(|(f, polarity)| match (f, polarity) {
+                    (PropositionalFormula::Variable(v), Polarity::Positive) => {
+                        Some((v.clone(), true))
+                    }
+                    (PropositionalFormula::Variable(v), Polarity::Negative) => {
+                        Some((v.clone(), false))
+                    }
+                    _ => None,
+                })
+                .collect();
+
+            let satisfiable = match substitute(&formula, &pure) {
+                Residual::Constant(value) => value,
+                Residual::Formula(residual) => truth_table::is_satisfiable(&residual),
+            };
+            check!(satisfiable == truth_table::is_satisfiable(&formula));
+        }
+    }
+}
@@ -0,0 +1,130 @@
+//! Arbitrary propositional formulas for property-based testing with [`proptest`], available with
+//! the `proptest` feature.
+//!
+//! ```
+//! use libprop_sat_solver::formula::PropositionalFormula;
+//! use proptest::prelude::*;
+//!
+//! proptest! {
+//!     fn printing_round_trips(formula: PropositionalFormula) {
+//!         prop_assert_eq!(formula.to_string().parse::<PropositionalFormula>(), Ok(formula));
+//!     }
+//! }
+//! # printing_round_trips();
+//! ```
+//!
+//! The shape of the formulas is set by [`FormulaParameters`], e.g.
+//! `any_with::<PropositionalFormula>(FormulaParameters { depth: 3, ..Default::default() })`.
+//!
+//! [`proptest`]: https://docs.rs/proptest
+//! [`FormulaParameters`]: struct.FormulaParameters.html
+
+use proptest::prelude::*;
+
+use super::{PropositionalFormula, Variable};
+
+/// Shape of arbitrary formulas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormulaParameters {
+    /// Largest number of connectives nested in each other.
+    pub depth: u32,
+    /// Number of propositional variables `x1, ..., xn` to draw from.
+    pub variables: usize,
+    /// Number of nodes the formulas aim for, see [`Strategy::prop_recursive`][1].
+    ///
+    /// [1]: https://docs.rs/proptest/1/proptest/strategy/trait.Strategy.html#method.prop_recursive
+    pub size: u32,
+    /// Whether the constants `1` and `0` may occur.
+    pub constants: bool,
+}
+
+impl Default for FormulaParameters {
+    /// Formulas over `x1, ..., x4` nested at most `6` deep, of about `32` nodes, with constants.
+    fn default() -> Self {
+        Self {
+            depth: 6,
+            variables: 4,
+            size: 32,
+            constants: true,
+        }
+    }
+}
+
+impl Arbitrary for PropositionalFormula {
+    type Parameters = FormulaParameters;
+    type Strategy = BoxedStrategy<Self>;
+
+    /// # Panics
+    ///
+    /// Panics if `parameters.variables` is zero.
+    fn arbitrary_with(parameters: Self::Parameters) -> Self::Strategy {
+        assert!(
+            parameters.variables > 0,
+            "cannot generate formulas without variables"
+        );
+
+        let variable = (1..=parameters.variables)
+            .prop_map(|index| PropositionalFormula::variable(Variable::new(format!("x{}", index))));
+        let leaf = if parameters.constants {
+            prop_oneof![
+                8 => variable,
+                1 => Just(PropositionalFormula::Top),
+                1 => Just(PropositionalFormula::Bottom),
+            ]
+            .boxed()
+        } else {
+            variable.boxed()
+        };
+
+        leaf.prop_recursive(parameters.depth, parameters.size, 2, |inner| {
+            let pair = (inner.clone(), inner.clone()).prop_map(|(a, b)| (Box::new(a), Box::new(b)));
+
+            prop_oneof![
+                inner.prop_map(|a| PropositionalFormula::negated(Box::new(a))),
+                pair.clone()
+                    .prop_map(|(a, b)| PropositionalFormula::conjunction(a, b)),
+                pair.clone()
+                    .prop_map(|(a, b)| PropositionalFormula::disjunction(a, b)),
+                pair.clone()
+                    .prop_map(|(a, b)| PropositionalFormula::implication(a, b)),
+                pair.prop_map(|(a, b)| PropositionalFormula::biimplication(a, b)),
+            ]
+        })
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tableaux_solver::{is_satisfiable, is_valid};
+
+    proptest! {
+        #[test]
+        fn test_printing_round_trips(formula: PropositionalFormula) {
+            prop_assert_eq!(formula.to_string().parse::<PropositionalFormula>(), Ok(formula));
+        }
+
+        #[test]
+        fn test_valid_formulas_are_satisfiable(formula: PropositionalFormula) {
+            prop_assert!(!is_valid(&formula) || is_satisfiable(&formula));
+        }
+
+        #[test]
+        fn test_parameters_are_respected(
+            formula in any_with::<PropositionalFormula>(FormulaParameters {
+                depth: 3,
+                variables: 2,
+                size: 8,
+                constants: false,
+            })
+        ) {
+            prop_assert!(formula.depth() <= 3);
+            prop_assert!(formula.variables().iter().all(|v| ["x1", "x2"].contains(&v.name())));
+            let constant = |f: &PropositionalFormula| {
+                matches!(f, PropositionalFormula::Top | PropositionalFormula::Bottom)
+            };
+            prop_assert!(!formula.iter_preorder().any(constant));
+        }
+    }
+}
@@ -0,0 +1,223 @@
+//! Hash-consed storage of propositional formulas.
+//!
+//! A [`FormulaArena`] stores every distinct sub-formula once, as a [`Node`] whose operands are
+//! [`FormulaId`] handles to other nodes. Structurally equal formulas get the same id, so they are
+//! compared and hashed by id, and building a formula out of existing ones (e.g. negating one)
+//! never copies them.
+//!
+//! [`FormulaArena`]: struct.FormulaArena.html
+//! [`Node`]: enum.Node.html
+//! [`FormulaId`]: struct.FormulaId.html
+
+use std::collections::HashMap;
+
+use super::{PropositionalFormula, Variable};
+
+/// Handle to a formula stored in a [`FormulaArena`]. Ids are only meaningful for the arena which
+/// handed them out.
+///
+/// [`FormulaArena`]: struct.FormulaArena.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FormulaId(u32);
+
+impl FormulaId {
+    /// Position of the formula in its arena, counting from `0` in order of insertion.
+    pub(crate) fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// A formula whose operands are stored in the arena, see [`PropositionalFormula`].
+///
+/// [`PropositionalFormula`]: ../propositional_formula/enum.PropositionalFormula.html
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Node {
+    Variable(Variable),
+    Top,
+    Bottom,
+    Negation(FormulaId),
+    Conjunction(FormulaId, FormulaId),
+    Disjunction(FormulaId, FormulaId),
+    Implication(FormulaId, FormulaId),
+    Biimplication(FormulaId, FormulaId),
+}
+
+impl Node {
+    /// The operands of the node from left to right.
+    pub fn operands(&self) -> impl Iterator<Item = FormulaId> {
+        let operands = match *self {
+            Node::Variable(_) | Node::Top | Node::Bottom => [None, None],
+            Node::Negation(a) => [Some(a), None],
+            Node::Conjunction(a, b)
+            | Node::Disjunction(a, b)
+            | Node::Implication(a, b)
+            | Node::Biimplication(a, b) => [Some(a), Some(b)],
+        };
+        IntoIterator::into_iter(operands).flatten()
+    }
+}
+
+/// An interning pool of formulas, in which structurally equal formulas are stored once.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::formula::arena::{FormulaArena, Node};
+/// let mut arena = FormulaArena::new();
+/// let formula = arena.intern(&"((a^b)|(-(a^b)))".parse().unwrap());
+///
+/// // `a`, `b`, `(a^b)`, `(-(a^b))` and the disjunction itself.
+/// assert_eq!(5, arena.len());
+/// match *arena.node(formula) {
+///     Node::Disjunction(left, right) => assert_eq!(Node::Negation(left), *arena.node(right)),
+///     _ => unreachable!(),
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FormulaArena {
+    nodes: Vec<Node>,
+    ids: HashMap<Node, FormulaId>,
+}
+
+impl FormulaArena {
+    /// Construct an empty arena.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store the node, or return the id of the equal node stored already.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an operand of the node is not an id of this arena.
+    pub fn insert(&mut self, node: Node) -> FormulaId {
+        if let Some(&id) = self.ids.get(&node) {
+            return id;
+        }
+
+        match node {
+            Node::Negation(a) => assert!(self.contains(a), "operand not in the arena"),
+            Node::Conjunction(a, b)
+            | Node::Disjunction(a, b)
+            | Node::Implication(a, b)
+            | Node::Biimplication(a, b) => {
+                assert!(
+                    self.contains(a) && self.contains(b),
+                    "operand not in the arena"
+                )
+            }
+            Node::Variable(_) | Node::Top | Node::Bottom => {}
+        }
+
+        let id = FormulaId(self.nodes.len() as u32);
+        self.nodes.push(node.clone());
+        self.ids.insert(node, id);
+        id
+    }
+
+    /// Store the formula and all of its sub-formulas, returning the id of the formula.
+    pub fn intern(&mut self, formula: &PropositionalFormula) -> FormulaId {
+        let node = match formula {
+            PropositionalFormula::Variable(v) => Node::Variable(v.clone()),
+            PropositionalFormula::Top => Node::Top,
+            PropositionalFormula::Bottom => Node::Bottom,
+            PropositionalFormula::Negation(a) => Node::Negation(self.intern(a)),
+            PropositionalFormula::Conjunction(a, b) => {
+                Node::Conjunction(self.intern(a), self.intern(b))
+            }
+            PropositionalFormula::Disjunction(a, b) => {
+                Node::Disjunction(self.intern(a), self.intern(b))
+            }
+            PropositionalFormula::Implication(a, b) => {
+                Node::Implication(self.intern(a), self.intern(b))
+            }
+            PropositionalFormula::Biimplication(a, b) => {
+                Node::Biimplication(self.intern(a), self.intern(b))
+            }
+        };
+
+        self.insert(node)
+    }
+
+    /// The id of the negation of the formula.
+    pub fn negation(&mut self, id: FormulaId) -> FormulaId {
+        self.insert(Node::Negation(id))
+    }
+
+    /// Get the node of the formula.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` is not an id of this arena.
+    pub fn node(&self, id: FormulaId) -> &Node {
+        &self.nodes[id.index()]
+    }
+
+    /// Checks if `id` is an id of this arena.
+    pub fn contains(&self, id: FormulaId) -> bool {
+        id.index() < self.nodes.len()
+    }
+
+    /// Rebuild the formula as a tree.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` is not an id of this arena.
+    pub fn to_formula(&self, id: FormulaId) -> PropositionalFormula {
+        let operand = |id| Box::new(self.to_formula(id));
+
+        match *self.node(id) {
+            Node::Variable(ref v) => PropositionalFormula::variable(v.clone()),
+            Node::Top => PropositionalFormula::Top,
+            Node::Bottom => PropositionalFormula::Bottom,
+            Node::Negation(a) => PropositionalFormula::negated(operand(a)),
+            Node::Conjunction(a, b) => PropositionalFormula::conjunction(operand(a), operand(b)),
+            Node::Disjunction(a, b) => PropositionalFormula::disjunction(operand(a), operand(b)),
+            Node::Implication(a, b) => PropositionalFormula::implication(operand(a), operand(b)),
+            Node::Biimplication(a, b) => {
+                PropositionalFormula::biimplication(operand(a), operand(b))
+            }
+        }
+    }
+
+    /// The ids of the stored formulas in order of insertion, so that the operands of each formula
+    /// come before it.
+    pub fn ids(&self) -> impl Iterator<Item = FormulaId> {
+        (0..self.nodes.len() as u32).map(FormulaId)
+    }
+
+    /// Number of distinct formulas stored.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Checks if the arena stores no formulas.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::formulas;
+    use assert2::check;
+
+    #[test]
+    fn test_interning() {
+        let mut arena = FormulaArena::new();
+
+        for formula in formulas(4, 5, 3, 50) {
+            let id = arena.intern(&formula);
+            let len = arena.len();
+
+            check!(arena.intern(&formula.clone()) == id);
+            check!(arena.len() == len);
+            check!(arena.to_formula(id) == formula);
+        }
+
+        let a = arena.intern(&"a".parse().unwrap());
+        let not_a = arena.negation(a);
+        check!(arena.intern(&"(-a)".parse().unwrap()) == not_a);
+    }
+}
@@ -0,0 +1,149 @@
+//! Canonical forms of formulas modulo commutativity, see
+//! [`PropositionalFormula::canonicalize`][canonicalize], and stable hashes of formulas, see
+//! [`PropositionalFormula::content_hash`][content-hash].
+//!
+//! [canonicalize]: ../propositional_formula/enum.PropositionalFormula.html#method.canonicalize
+//! [content-hash]: ../propositional_formula/enum.PropositionalFormula.html#method.content_hash
+
+use std::cmp::Ordering;
+
+use super::PropositionalFormula;
+
+impl PropositionalFormula {
+    /// Rewrite the formula into a canonical form, in which the operands of the commutative
+    /// connectives `^`, `|` and `<->` are sorted and double negations `(-(-A))` are removed, so
+    /// that formulas which only differ in these respects become equal.
+    ///
+    /// The canonical form is equivalent to the formula.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libprop_sat_solver::formula::PropositionalFormula;
+    /// let formula: PropositionalFormula = "((b^a)|(-(-c)))".parse().unwrap();
+    /// let other: PropositionalFormula = "(c|(a^b))".parse().unwrap();
+    ///
+    /// assert_eq!(formula.canonicalize(), other.canonicalize());
+    /// ```
+    pub fn canonicalize(&self) -> Self {
+        let canonical = |f: &Self| Box::new(f.canonicalize());
+        let sorted = |a: &Self, b: &Self| {
+            let (a, b) = (canonical(a), canonical(b));
+            match a.partial_cmp(&b) {
+                Some(Ordering::Greater) => (b, a),
+                _ => (a, b),
+            }
+        };
+
+        match self {
+            Self::Variable(_) | Self::Top | Self::Bottom => self.clone(),
+            Self::Negation(a) => match &**a {
+                Self::Negation(inner) => inner.canonicalize(),
+                _ => Self::negated(Box::new(a.canonicalize())),
+            },
+            Self::Conjunction(a, b) => {
+                let (a, b) = sorted(a, b);
+                Self::Conjunction(a, b)
+            }
+            Self::Disjunction(a, b) => {
+                let (a, b) = sorted(a, b);
+                Self::Disjunction(a, b)
+            }
+            Self::Implication(a, b) => Self::Implication(canonical(a), canonical(b)),
+            Self::Biimplication(a, b) => {
+                let (a, b) = sorted(a, b);
+                Self::Biimplication(a, b)
+            }
+        }
+    }
+
+    /// A 64-bit hash of the structure of the formula which is the same across runs, platforms and
+    /// versions of Rust (unlike [`Hash`] with the standard hashers), so that it can key on-disk
+    /// caches of results.
+    ///
+    /// Equal formulas have equal hashes. To also identify formulas which only differ in the order
+    /// of commutative operands, hash their [`canonicalize`]d forms instead.
+    ///
+    /// The hash is 64-bit FNV-1a over the connectives and variable names in preorder, which
+    /// identifies the formula since every connective has a fixed number of operands.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libprop_sat_solver::formula::PropositionalFormula;
+    /// let formula: PropositionalFormula = "((b^a)|(-(-c)))".parse().unwrap();
+    /// let other: PropositionalFormula = "(c|(a^b))".parse().unwrap();
+    ///
+    /// assert_ne!(formula.content_hash(), other.content_hash());
+    /// assert_eq!(formula.canonicalize().content_hash(), other.canonicalize().content_hash());
+    /// ```
+    ///
+    /// [`Hash`]: https://doc.rust-lang.org/std/hash/trait.Hash.html
+    /// [`canonicalize`]: #method.canonicalize
+    pub fn content_hash(&self) -> u64 {
+        const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+        const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+        let write = |hash: u64, bytes: &[u8]| {
+            bytes.iter().fold(hash, |hash, &byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+            })
+        };
+
+        self.iter_preorder()
+            .fold(OFFSET_BASIS, |hash, formula| match formula {
+                Self::Variable(v) => {
+                    // Prefix the name with its length so that consecutive names cannot run
+                    // together.
+                    let hash = write(hash, &[0]);
+                    let hash = write(hash, &(v.name().len() as u64).to_le_bytes());
+                    write(hash, v.name().as_bytes())
+                }
+                Self::Top => write(hash, &[1]),
+                Self::Bottom => write(hash, &[2]),
+                Self::Negation(_) => write(hash, &[3]),
+                Self::Conjunction(..) => write(hash, &[4]),
+                Self::Disjunction(..) => write(hash, &[5]),
+                Self::Implication(..) => write(hash, &[6]),
+                Self::Biimplication(..) => write(hash, &[7]),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{equivalence_counterexample, SolverConfig};
+    use crate::testing::formulas;
+    use assert2::check;
+
+    #[test]
+    fn test_canonical_form() {
+        for formula in formulas(4, 5, 4, 100) {
+            let canonical = formula.canonicalize();
+
+            check!(canonical.canonicalize() == canonical.clone());
+            check!(
+                equivalence_counterexample(&formula, &canonical, &SolverConfig::default()) == None
+            );
+        }
+
+        let formula: PropositionalFormula = "((a<->b)^(-(-(a->b))))".parse().unwrap();
+        let other: PropositionalFormula = "((a->b)^(b<->a))".parse().unwrap();
+        check!(formula.canonicalize() == other.canonicalize());
+    }
+
+    #[test]
+    fn test_content_hash() {
+        // Pinned, since the hash must not change between versions.
+        let formula: PropositionalFormula = "((a^b)->(-c))".parse().unwrap();
+        check!(formula.content_hash() == 0xaa9e_2363_b618_8fc9);
+
+        let formulas: Vec<_> = formulas(4, 5, 5, 200).collect();
+        for a in &formulas {
+            for b in &formulas {
+                check!((a.content_hash() == b.content_hash()) == (a == b));
+            }
+        }
+    }
+}
@@ -0,0 +1,159 @@
+//! A formula as a directed acyclic graph of its distinct sub-formulas, see [`FormulaDag`].
+//!
+//! [`FormulaDag`]: struct.FormulaDag.html
+
+use super::arena::{FormulaArena, FormulaId, Node};
+use super::PropositionalFormula;
+
+/// A formula in which structurally equal sub-formulas are stored once, with navigation from each
+/// sub-formula to its operands (children) and to the sub-formulas it is an operand of (parents).
+///
+/// The nodes are those of a [`FormulaArena`], which the `interned` tableau engine expands
+/// against, so formulas which repeat large sub-formulas (e.g. biimplication chains) take space
+/// linear in the number of _distinct_ sub-formulas rather than in the size of the tree.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::formula::dag::FormulaDag;
+/// let dag = FormulaDag::new(&"((a^b)<->(-(a^b)))".parse().unwrap());
+///
+/// // `a`, `b`, `(a^b)`, `(-(a^b))` and the biimplication itself.
+/// assert_eq!(5, dag.len());
+/// let children = dag.children(dag.root());
+/// assert_eq!(vec![children[1], dag.root()], dag.parents(children[0]));
+/// ```
+///
+/// [`FormulaArena`]: ../arena/struct.FormulaArena.html
+#[derive(Debug, Clone)]
+pub struct FormulaDag {
+    arena: FormulaArena,
+    root: FormulaId,
+    /// Distinct parents of each node, indexed by id, in ascending order.
+    parents: Vec<Vec<FormulaId>>,
+}
+
+impl FormulaDag {
+    /// Build the DAG of the formula.
+    pub fn new(formula: &PropositionalFormula) -> Self {
+        let mut arena = FormulaArena::new();
+        let root = arena.intern(formula);
+
+        // Ids are handed out in insertion order, so they range over `0..arena.len()` and every
+        // operand has a smaller id than the formulas it occurs in.
+        let ids: Vec<FormulaId> = arena.ids().collect();
+        let mut parents = vec![Vec::new(); ids.len()];
+        for &id in &ids {
+            for child in arena.node(id).operands() {
+                let siblings: &mut Vec<FormulaId> = &mut parents[child.index()];
+                if siblings.last() != Some(&id) {
+                    siblings.push(id);
+                }
+            }
+        }
+
+        Self {
+            arena,
+            root,
+            parents,
+        }
+    }
+
+    /// The id of the whole formula.
+    pub fn root(&self) -> FormulaId {
+        self.root
+    }
+
+    /// The arena storing the nodes.
+    pub fn arena(&self) -> &FormulaArena {
+        &self.arena
+    }
+
+    /// Get the node of the sub-formula.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` is not a node of the DAG.
+    pub fn node(&self, id: FormulaId) -> &Node {
+        self.arena.node(id)
+    }
+
+    /// The operands of the sub-formula from left to right, with repetitions, e.g. `[a, a]` for
+    /// `(a^a)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` is not a node of the DAG.
+    pub fn children(&self, id: FormulaId) -> Vec<FormulaId> {
+        self.node(id).operands().collect()
+    }
+
+    /// The distinct sub-formulas which have the sub-formula as an operand, in ascending order of
+    /// id; the root has none.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` is not a node of the DAG.
+    pub fn parents(&self, id: FormulaId) -> &[FormulaId] {
+        &self.parents[id.index()]
+    }
+
+    /// Rebuild the sub-formula as a tree.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` is not a node of the DAG.
+    pub fn to_formula(&self, id: FormulaId) -> PropositionalFormula {
+        self.arena.to_formula(id)
+    }
+
+    /// Number of distinct sub-formulas.
+    pub fn len(&self) -> usize {
+        self.arena.len()
+    }
+
+    /// Checks if the DAG has no nodes, which never happens since it has a root.
+    pub fn is_empty(&self) -> bool {
+        self.arena.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::formulas;
+    use assert2::check;
+
+    #[test]
+    fn test_navigation() {
+        for formula in formulas(4, 5, 6, 50) {
+            let dag = FormulaDag::new(&formula);
+
+            check!(dag.to_formula(dag.root()) == formula);
+            check!(dag.parents(dag.root()).is_empty());
+            for id in dag.arena().ids() {
+                for &parent in dag.parents(id) {
+                    check!(dag.children(parent).contains(&id));
+                }
+                for child in dag.children(id) {
+                    check!(dag.parents(child).contains(&id));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_shared_subformulas_are_stored_once() {
+        // Each level repeats the previous one twice, so the tree doubles in size per level.
+        let mut formula: PropositionalFormula = "a".parse().unwrap();
+        for _ in 0..15 {
+            formula = PropositionalFormula::biimplication(
+                Box::new(formula.clone()),
+                Box::new(PropositionalFormula::negated(Box::new(formula))),
+            );
+        }
+
+        let dag = FormulaDag::new(&formula);
+        check!(dag.len() == 31);
+    }
+}
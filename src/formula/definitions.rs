@@ -0,0 +1,194 @@
+//! Elimination of top-level definitions `(x<->A)` by substitution.
+
+use std::collections::HashMap;
+
+use super::template::replace;
+use super::{substitute, Assignment, PropositionalFormula, Residual, Variable};
+
+/// Definitions eliminated from a formula by [`eliminate_definitions`], in the order they were
+/// eliminated.
+///
+/// [`eliminate_definitions`]: fn.eliminate_definitions.html
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Definitions {
+    definitions: Vec<(Variable, PropositionalFormula)>,
+}
+
+impl Definitions {
+    /// Number of eliminated definitions.
+    pub fn len(&self) -> usize {
+        self.definitions.len()
+    }
+
+    /// Check if no definitions were eliminated.
+    pub fn is_empty(&self) -> bool {
+        self.definitions.is_empty()
+    }
+
+    /// Get the defined variables along with their definitions, in the order they were eliminated.
+    pub fn iter(&self) -> impl Iterator<Item = &(Variable, PropositionalFormula)> {
+        self.definitions.iter()
+    }
+
+    /// Extend a model of the formula left by [`eliminate_definitions`] to a model of the original
+    /// formula, by assigning each defined variable the value of its definition.
+    ///
+    /// Variables of the original formula which are missing from the model are taken to be
+    /// `false`, like variables which were eliminated altogether.
+    ///
+    /// [`eliminate_definitions`]: fn.eliminate_definitions.html
+    pub fn extend_model(&self, model: &mut Assignment) {
+        // A definition may only mention variables defined after it, so go in reverse.
+        for (variable, definition) in self.definitions.iter().rev() {
+            let mut assignment = model.clone();
+            for v in definition.variables() {
+                assignment.entry(v.clone()).or_insert(false);
+            }
+
+            let value = match substitute(definition, &assignment) {
+                Residual::Constant(value) => value,
+                Residual::Formula(_) => unreachable!("a complete assignment leaves a constant"),
+            };
+            model.insert(variable.clone(), value);
+        }
+    }
+}
+
+/// Eliminate the definitions `(x<->A)` (or `(A<->x)`) among the top-level conjuncts of the
+/// formula, where `x` is a variable which does not occur in `A`, by replacing `x` by `A` in the
+/// remaining conjuncts. Replacements can turn further conjuncts into definitions, which are then
+/// eliminated as well.
+///
+/// Returns the conjunction of the remaining conjuncts, or `None` if there are none left, along with
+/// the eliminated definitions. The result is satisfiable iff the formula is, and models of the
+/// result are extended to models of the formula by [`Definitions::extend_model`].
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::formula::{eliminate_definitions, PropositionalFormula, Variable};
+/// let x = PropositionalFormula::variable(Variable::new("x"));
+/// let a = PropositionalFormula::variable(Variable::new("a"));
+/// let b = PropositionalFormula::variable(Variable::new("b"));
+/// let a_and_b = PropositionalFormula::conjunction(Box::new(a), Box::new(b));
+///
+/// // ((x<->(a^b))^(-x)) becomes (-(a^b))
+/// let definition =
+///     PropositionalFormula::biimplication(Box::new(x.clone()), Box::new(a_and_b.clone()));
+/// let formula = PropositionalFormula::conjunction(
+///     Box::new(definition),
+///     Box::new(PropositionalFormula::negated(Box::new(x))),
+/// );
+/// let (remaining, definitions) = eliminate_definitions(&formula);
+///
+/// assert_eq!(Some(PropositionalFormula::negated(Box::new(a_and_b))), remaining);
+/// assert_eq!(1, definitions.len());
+/// ```
+///
+/// [`Definitions::extend_model`]: struct.Definitions.html#method.extend_model
+pub fn eliminate_definitions(
+    formula: &PropositionalFormula,
+) -> (Option<PropositionalFormula>, Definitions) {
+    let mut conjuncts: Vec<_> = formula.conjuncts().into_iter().cloned().collect();
+
+    let mut definitions = Definitions::default();
+
+    while let Some((index, variable, definition)) =
+        conjuncts.iter().enumerate().find_map(|(index, conjunct)| {
+            as_definition(conjunct).map(|(variable, definition)| (index, variable, definition))
+        })
+    {
+        conjuncts.remove(index);
+
+        let mut bindings = HashMap::new();
+        bindings.insert(variable.clone(), definition.clone());
+        for conjunct in conjuncts.iter_mut() {
+            *conjunct = replace(conjunct, &bindings);
+        }
+
+        definitions.definitions.push((variable, definition));
+    }
+
+    (PropositionalFormula::conjoin(conjuncts), definitions)
+}
+
+/// Split a definition `(x<->A)` or `(A<->x)` into `x` and `A`.
+fn as_definition(formula: &PropositionalFormula) -> Option<(Variable, PropositionalFormula)> {
+    let (a, b) = match formula {
+        PropositionalFormula::Biimplication(a, b) => (a, b),
+        _ => return None,
+    };
+
+    for (defined, definition) in [(a, b), (b, a)].iter() {
+        if let PropositionalFormula::Variable(variable) = &***defined {
+            if !definition.variables().contains(&variable) {
+                return Some((variable.clone(), (***definition).clone()));
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::formulas;
+    use crate::truth_table;
+    use assert2::check;
+
+    fn var(name: &str) -> PropositionalFormula {
+        PropositionalFormula::variable(Variable::new(name))
+    }
+
+    fn iff(a: PropositionalFormula, b: PropositionalFormula) -> PropositionalFormula {
+        PropositionalFormula::biimplication(Box::new(a), Box::new(b))
+    }
+
+    #[test]
+    fn test_chained_definitions() {
+        // ((((x<->(a|b))^(y<->(-x)))^y)^(-b)) leaves ((-(a|b))^(-b)) once `x` and then `y` are
+        // replaced by their definitions.
+        let formula = PropositionalFormula::conjoin(vec![
+            iff(
+                var("x"),
+                PropositionalFormula::disjunction(Box::new(var("a")), Box::new(var("b"))),
+            ),
+            iff(var("y"), PropositionalFormula::negated(Box::new(var("x")))),
+            var("y"),
+            PropositionalFormula::negated(Box::new(var("b"))),
+        ])
+        .unwrap();
+
+        let (remaining, definitions) = eliminate_definitions(&formula);
+        let remaining = remaining.unwrap();
+        check!(definitions.len() == 2);
+        let (a, b) = (Variable::new("a"), Variable::new("b"));
+        check!(remaining.variables() == vec![&a, &b]);
+
+        let mut model = HashMap::new();
+        model.insert(Variable::new("a"), false);
+        model.insert(Variable::new("b"), false);
+        check!(substitute(&remaining, &model) == Residual::Constant(true));
+
+        definitions.extend_model(&mut model);
+        check!(model[&Variable::new("x")] == false);
+        check!(model[&Variable::new("y")] == true);
+        check!(substitute(&formula, &model) == Residual::Constant(true));
+    }
+
+    #[test]
+    fn test_preserves_satisfiability() {
+        let mut generator = formulas(4, 3, 7, 200);
+        for _ in 0..100 {
+            let a = generator.next().unwrap();
+            let b = generator.next().unwrap();
+            let formula =
+                PropositionalFormula::conjunction(Box::new(iff(var("x1"), a)), Box::new(b));
+
+            let (remaining, _) = eliminate_definitions(&formula);
+            let satisfiable = remaining.is_none_or(|f| truth_table::is_satisfiable(&f));
+            check!(satisfiable == truth_table::is_satisfiable(&formula));
+        }
+    }
+}
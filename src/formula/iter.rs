@@ -0,0 +1,134 @@
+//! Iterators over the sub-formulas of a formula, see
+//! [`PropositionalFormula::iter_preorder`][iter-preorder] and
+//! [`PropositionalFormula::iter_postorder`][iter-postorder].
+//!
+//! The iterators keep their own stack on the heap instead of recursing, so they walk formulas of
+//! any depth without overflowing the call stack.
+//!
+//! [iter-preorder]: ../propositional_formula/enum.PropositionalFormula.html#method.iter_preorder
+//! [iter-postorder]: ../propositional_formula/enum.PropositionalFormula.html#method.iter_postorder
+
+use super::PropositionalFormula;
+
+impl PropositionalFormula {
+    /// Iterate over the formula and its sub-formulas, each formula before its operands, from left
+    /// to right.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libprop_sat_solver::formula::PropositionalFormula;
+    /// let formula: PropositionalFormula = "((-a)^b)".parse().unwrap();
+    ///
+    /// let visited: Vec<_> = formula.iter_preorder().map(ToString::to_string).collect();
+    /// assert_eq!(vec!["((-a)^b)", "(-a)", "a", "b"], visited);
+    /// ```
+    pub fn iter_preorder(&self) -> Preorder<'_> {
+        Preorder { stack: vec![self] }
+    }
+
+    /// Iterate over the formula and its sub-formulas, each formula after its operands, from left
+    /// to right.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libprop_sat_solver::formula::PropositionalFormula;
+    /// let formula: PropositionalFormula = "((-a)^b)".parse().unwrap();
+    ///
+    /// let visited: Vec<_> = formula.iter_postorder().map(ToString::to_string).collect();
+    /// assert_eq!(vec!["a", "(-a)", "b", "((-a)^b)"], visited);
+    /// ```
+    pub fn iter_postorder(&self) -> Postorder<'_> {
+        Postorder {
+            stack: vec![(self, false)],
+        }
+    }
+}
+
+/// Pre-order iterator over the sub-formulas of a formula, see
+/// [`PropositionalFormula::iter_preorder`][iter-preorder].
+///
+/// [iter-preorder]: ../propositional_formula/enum.PropositionalFormula.html#method.iter_preorder
+#[derive(Debug, Clone)]
+pub struct Preorder<'a> {
+    stack: Vec<&'a PropositionalFormula>,
+}
+
+impl<'a> Iterator for Preorder<'a> {
+    type Item = &'a PropositionalFormula;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let formula = self.stack.pop()?;
+        self.stack
+            .extend(operands(formula).iter().rev().flatten().copied());
+        Some(formula)
+    }
+}
+
+/// Post-order iterator over the sub-formulas of a formula, see
+/// [`PropositionalFormula::iter_postorder`][iter-postorder].
+///
+/// [iter-postorder]: ../propositional_formula/enum.PropositionalFormula.html#method.iter_postorder
+#[derive(Debug, Clone)]
+pub struct Postorder<'a> {
+    /// Formulas still to visit, along with whether their operands have been pushed already.
+    stack: Vec<(&'a PropositionalFormula, bool)>,
+}
+
+impl<'a> Iterator for Postorder<'a> {
+    type Item = &'a PropositionalFormula;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (formula, expanded) = self.stack.pop()?;
+            if expanded {
+                return Some(formula);
+            }
+
+            self.stack.push((formula, true));
+            self.stack.extend(
+                operands(formula)
+                    .iter()
+                    .rev()
+                    .flatten()
+                    .map(|&operand| (operand, false)),
+            );
+        }
+    }
+}
+
+/// The operands of the formula from left to right, `None` for absent ones.
+pub(crate) fn operands(formula: &PropositionalFormula) -> [Option<&PropositionalFormula>; 2] {
+    match formula {
+        PropositionalFormula::Variable(_)
+        | PropositionalFormula::Top
+        | PropositionalFormula::Bottom => [None, None],
+        PropositionalFormula::Negation(a) => [Some(a), None],
+        PropositionalFormula::Conjunction(a, b)
+        | PropositionalFormula::Disjunction(a, b)
+        | PropositionalFormula::Implication(a, b)
+        | PropositionalFormula::Biimplication(a, b) => [Some(a), Some(b)],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formula::Variable;
+    use assert2::check;
+
+    #[test]
+    fn test_deep_formula() {
+        // Deep enough to overflow the stack of a recursive walk in a test thread.
+        let depth = 200_000;
+        let mut formula = PropositionalFormula::variable(Variable::new("a"));
+        for _ in 0..depth {
+            formula = PropositionalFormula::negated(Box::new(formula));
+        }
+
+        check!(formula.iter_preorder().count() == depth + 1);
+        check!(formula.iter_postorder().count() == depth + 1);
+        check!(formula.iter_postorder().next() == Some(&"a".parse().unwrap()));
+    }
+}
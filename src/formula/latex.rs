@@ -0,0 +1,73 @@
+//! LaTeX rendering of formulas, see [`PropositionalFormula::to_latex`][to-latex].
+//!
+//! [to-latex]: ../propositional_formula/enum.PropositionalFormula.html#method.to_latex
+
+use super::{PropositionalFormula, Variable};
+
+impl PropositionalFormula {
+    /// Render the formula as LaTeX math, with the connectives `\neg`, `\land`, `\lor`,
+    /// `\rightarrow` and `\leftrightarrow` and the constants `\top` and `\bot`.
+    ///
+    /// Binary connectives are parenthesized except for the main connective of the formula, and
+    /// trailing digits of variable names become subscripts, e.g. `x12` is rendered as `x_{12}`.
+    /// Variable names of several letters are set in `\mathit`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libprop_sat_solver::formula::PropositionalFormula;
+    /// let formula: PropositionalFormula = "((p1^(-q))->(rain|1))".parse().unwrap();
+    /// assert_eq!(
+    ///     r"(p_{1} \land \neg q) \rightarrow (\mathit{rain} \lor \top)",
+    ///     formula.to_latex()
+    /// );
+    /// ```
+    pub fn to_latex(&self) -> String {
+        let mut latex = String::new();
+        write_latex(&mut latex, self, true);
+        latex
+    }
+}
+
+fn write_latex(latex: &mut String, formula: &PropositionalFormula, top: bool) {
+    let (a, connective, b) = match formula {
+        PropositionalFormula::Variable(v) => return write_variable(latex, v),
+        PropositionalFormula::Top => return latex.push_str(r"\top"),
+        PropositionalFormula::Bottom => return latex.push_str(r"\bot"),
+        PropositionalFormula::Negation(a) => {
+            latex.push_str(r"\neg ");
+            return write_latex(latex, a, false);
+        }
+        PropositionalFormula::Conjunction(a, b) => (a, r"\land", b),
+        PropositionalFormula::Disjunction(a, b) => (a, r"\lor", b),
+        PropositionalFormula::Implication(a, b) => (a, r"\rightarrow", b),
+        PropositionalFormula::Biimplication(a, b) => (a, r"\leftrightarrow", b),
+    };
+
+    if !top {
+        latex.push('(');
+    }
+    write_latex(latex, a, false);
+    latex.push(' ');
+    latex.push_str(connective);
+    latex.push(' ');
+    write_latex(latex, b, false);
+    if !top {
+        latex.push(')');
+    }
+}
+
+fn write_variable(latex: &mut String, variable: &Variable) {
+    let name = variable.name();
+    let (letters, digits) =
+        name.split_at(name.trim_end_matches(|c: char| c.is_ascii_digit()).len());
+
+    if letters.chars().count() > 1 {
+        latex.push_str(&format!(r"\mathit{{{}}}", letters));
+    } else {
+        latex.push_str(letters);
+    }
+    if !digits.is_empty() {
+        latex.push_str(&format!("_{{{}}}", digits));
+    }
+}
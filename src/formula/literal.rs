@@ -0,0 +1,137 @@
+//! Literals: propositional variables and their negations.
+
+use std::fmt;
+
+use super::{Assignment, PropositionalFormula, Variable};
+
+/// A propositional variable or its negation.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Literal {
+    variable: Variable,
+    negated: bool,
+}
+
+impl Literal {
+    /// Construct the positive literal `p` for the variable `p`.
+    pub fn positive(variable: Variable) -> Self {
+        Self {
+            variable,
+            negated: false,
+        }
+    }
+
+    /// Construct the negative literal `(-p)` for the variable `p`.
+    pub fn negative(variable: Variable) -> Self {
+        Self {
+            variable,
+            negated: true,
+        }
+    }
+
+    /// Construct the literal which is true iff the variable has the given `value`, i.e. `p` for
+    /// `true` and `(-p)` for `false`.
+    pub fn with_value(variable: Variable, value: bool) -> Self {
+        Self {
+            variable,
+            negated: !value,
+        }
+    }
+
+    /// Read a literal off a formula, stripping (possibly nested) negations such as `(-(-p))`.
+    ///
+    /// Returns `None` if the formula is not a literal over a variable, which includes the
+    /// constants.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libprop_sat_solver::formula::{Literal, Variable};
+    /// let literal = Literal::from_formula(&"(-(-(-p)))".parse().unwrap());
+    ///
+    /// assert_eq!(Some(Literal::negative(Variable::new("p"))), literal);
+    /// assert_eq!(None, Literal::from_formula(&"(-(p^q))".parse().unwrap()));
+    /// ```
+    pub fn from_formula(formula: &PropositionalFormula) -> Option<Self> {
+        match strip_negations(formula) {
+            (PropositionalFormula::Variable(v), value) => Some(Self::with_value(v.clone(), value)),
+            _ => None,
+        }
+    }
+
+    /// Get the propositional variable of the literal.
+    pub fn variable(&self) -> &Variable {
+        &self.variable
+    }
+
+    /// Checks if the literal is a negated propositional variable.
+    pub fn is_negated(&self) -> bool {
+        self.negated
+    }
+
+    /// Get the complementary literal, i.e. `(-p)` for `p` and `p` for `(-p)`.
+    pub fn complement(&self) -> Self {
+        Self {
+            variable: self.variable.clone(),
+            negated: !self.negated,
+        }
+    }
+
+    /// Truth value of the literal under the assignment, or `None` if its variable is unassigned.
+    pub fn value(&self, assignment: &Assignment) -> Option<bool> {
+        assignment
+            .get(&self.variable)
+            .map(|&value| value != self.negated)
+    }
+
+    /// Convert the literal back into a `PropositionalFormula`.
+    pub fn to_formula(&self) -> PropositionalFormula {
+        let variable = PropositionalFormula::variable(self.variable.clone());
+
+        if self.negated {
+            PropositionalFormula::negated(Box::new(variable))
+        } else {
+            variable
+        }
+    }
+}
+
+impl fmt::Display for Literal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.negated {
+            write!(f, "(-{})", self.variable)
+        } else {
+            write!(f, "{}", self.variable)
+        }
+    }
+}
+
+/// Strip (possibly nested) negations off the formula, returning what is under them and whether
+/// there is an even number of them, i.e. whether the formula is true iff what is under them is.
+pub(crate) fn strip_negations(mut formula: &PropositionalFormula) -> (&PropositionalFormula, bool) {
+    let mut value = true;
+    while let PropositionalFormula::Negation(inner) = formula {
+        formula = inner;
+        value = !value;
+    }
+
+    (formula, value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert2::check;
+
+    #[test]
+    fn test_value() {
+        let p = Variable::new("p");
+        let mut assignment = Assignment::new();
+        assignment.insert(p.clone(), false);
+
+        check!(Literal::positive(p.clone()).value(&assignment) == Some(false));
+        check!(Literal::negative(p.clone()).value(&assignment) == Some(true));
+        check!(Literal::positive(Variable::new("q")).value(&assignment) == None);
+        check!(Literal::with_value(p.clone(), false) == Literal::negative(p.clone()));
+        check!(Literal::negative(p.clone()).complement() == Literal::positive(p));
+    }
+}
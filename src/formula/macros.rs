@@ -0,0 +1,69 @@
+//! The `formula!` macro for building `PropositionalFormula`s in the input syntax.
+
+/// Construct a [`PropositionalFormula`] written in the (fully-parenthesized) input syntax, without
+/// nesting `Box::new` calls.
+///
+/// Variables are written as identifiers, the constants as `1` and `0`, and `{ <expr> }` splices in
+/// an existing formula. As in the input syntax, every unary and binary operation must be wrapped in
+/// parentheses, except for the outermost one.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::formula;
+/// use libprop_sat_solver::formula::{PropositionalFormula, Variable};
+/// let formula = formula!((a ^ (-b)) -> c);
+/// assert_eq!("((a^(-b))->c)", formula.to_string());
+///
+/// let b = PropositionalFormula::variable(Variable::new("b"));
+/// assert_eq!(formula!(a <-> b), formula!(a <-> {b}));
+/// ```
+///
+/// [`PropositionalFormula`]: formula/propositional_formula/enum.PropositionalFormula.html
+#[macro_export]
+macro_rules! formula {
+    (($($inner:tt)*)) => {
+        $crate::formula!($($inner)*)
+    };
+    ({ $formula:expr }) => {
+        $formula
+    };
+    (- $a:tt) => {
+        $crate::formula::PropositionalFormula::negated(::std::boxed::Box::new($crate::formula!($a)))
+    };
+    ($a:tt ^ $b:tt) => {
+        $crate::formula::PropositionalFormula::conjunction(
+            ::std::boxed::Box::new($crate::formula!($a)),
+            ::std::boxed::Box::new($crate::formula!($b)),
+        )
+    };
+    ($a:tt | $b:tt) => {
+        $crate::formula::PropositionalFormula::disjunction(
+            ::std::boxed::Box::new($crate::formula!($a)),
+            ::std::boxed::Box::new($crate::formula!($b)),
+        )
+    };
+    ($a:tt -> $b:tt) => {
+        $crate::formula::PropositionalFormula::implication(
+            ::std::boxed::Box::new($crate::formula!($a)),
+            ::std::boxed::Box::new($crate::formula!($b)),
+        )
+    };
+    ($a:tt <-> $b:tt) => {
+        $crate::formula::PropositionalFormula::biimplication(
+            ::std::boxed::Box::new($crate::formula!($a)),
+            ::std::boxed::Box::new($crate::formula!($b)),
+        )
+    };
+    (1) => {
+        $crate::formula::PropositionalFormula::Top
+    };
+    (0) => {
+        $crate::formula::PropositionalFormula::Bottom
+    };
+    ($variable:ident) => {
+        $crate::formula::PropositionalFormula::variable($crate::formula::Variable::new(
+            stringify!($variable),
+        ))
+    };
+}
@@ -1,10 +1,40 @@
 //! Abstract syntax tree representation of a well-formed propositional formula.
 
+pub mod analysis;
+#[cfg(any(test, feature = "proptest"))]
+pub mod arbitrary;
+pub mod arena;
+pub mod canonical;
+pub mod dag;
+pub mod definitions;
+pub mod generator;
+pub mod iter;
+pub mod latex;
+pub mod literal;
+mod macros;
+pub mod obfuscation;
 pub mod operators;
+pub mod prefix;
+pub mod projection;
 pub mod propositional_formula;
+pub mod rewrite;
+pub mod simplify;
+pub mod substitution;
+pub mod symbol;
+pub mod template;
+pub mod transform;
 pub mod variable;
 
-// Re-export propositional formula operators and variables.
+// Re-export propositional formula operators, variables and transformations.
+pub use definitions::{eliminate_definitions, Definitions};
+pub use generator::FormulaGenerator;
+pub use literal::Literal;
+pub use obfuscation::obfuscate;
 pub use operators::{BinaryOperator, Operator, UnaryOperator};
+pub use projection::project;
 pub use propositional_formula::PropositionalFormula;
-pub use variable::Variable;
+pub use simplify::simplify;
+pub use substitution::{substitute, Residual};
+pub use template::Template;
+pub use transform::push_negations;
+pub use variable::{Assignment, Variable};
@@ -0,0 +1,176 @@
+//! Obfuscation and renaming of formulas, so that formulas which cannot be shared as they are can
+//! still be shared as benchmarks or bug reports.
+
+use std::collections::HashMap;
+
+use super::generator::SplitMix64;
+use super::{PropositionalFormula, Variable};
+
+impl PropositionalFormula {
+    /// Rename each variable `v` of the formula to `rename(v)`, keeping everything else.
+    ///
+    /// The formula stays equisatisfiable as long as `rename` maps distinct variables of the
+    /// formula to distinct variables.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libprop_sat_solver::formula::{PropositionalFormula, Variable};
+    /// let formula: PropositionalFormula = "(p->(q^p))".parse().unwrap();
+    /// let upper_case = |v: &Variable| Variable::new(v.name().to_uppercase());
+    /// let renamed = formula.rename_variables(&upper_case);
+    /// assert_eq!("(P->(Q^P))", renamed.to_string());
+    /// ```
+    pub fn rename_variables(&self, rename: &impl Fn(&Variable) -> Variable) -> Self {
+        let operand = |a: &PropositionalFormula| Box::new(a.rename_variables(rename));
+
+        match self {
+            Self::Variable(v) => Self::variable(rename(v)),
+            Self::Top | Self::Bottom => self.clone(),
+            Self::Negation(a) => Self::negated(operand(a)),
+            Self::Conjunction(a, b) => Self::conjunction(operand(a), operand(b)),
+            Self::Disjunction(a, b) => Self::disjunction(operand(a), operand(b)),
+            Self::Implication(a, b) => Self::implication(operand(a), operand(b)),
+            Self::Biimplication(a, b) => Self::biimplication(operand(a), operand(b)),
+        }
+    }
+
+    /// Rename the variables of the formula to `v1, ..., vn` in order of first occurrence from left
+    /// to right, so that it can be shared without giving away its identifiers. Unlike
+    /// [`obfuscate`], the formula is otherwise left as it is.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libprop_sat_solver::formula::PropositionalFormula;
+    /// let formula: PropositionalFormula = "((reactorHot^valveOpen)->reactorHot)".parse().unwrap();
+    /// assert_eq!("((v1^v2)->v1)", formula.anonymize().to_string());
+    /// ```
+    ///
+    /// [`obfuscate`]: fn.obfuscate.html
+    pub fn anonymize(&self) -> Self {
+        let names: HashMap<&Variable, Variable> = self
+            .variables()
+            .into_iter()
+            .enumerate()
+            .map(|(i, v)| (v, Variable::new(format!("v{}", i + 1))))
+            .collect();
+
+        self.rename_variables(&|v: &Variable| names[v].clone())
+    }
+}
+
+/// Rewrite the formula into an isomorphic one which gives away nothing but its structure.
+///
+/// The variables are renamed to `x1, ..., xn` in a shuffled order and the operands of the
+/// commutative connectives `^`, `|` and `<->` are swapped at random, while the shape of the formula
+/// is kept: the obfuscated formula has the same size, depth, number of variables and connectives
+/// (see [`FormulaMetrics`]), and it is satisfiable iff the formula is. The same `seed` always gives
+/// the same obfuscation.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::formula::{analysis::FormulaMetrics, obfuscate};
+/// let formula = "((reactorHot^valveOpen)->(-alarmOff))".parse().unwrap();
+/// let obfuscated = obfuscate(&formula, 7);
+///
+/// assert!(!obfuscated.to_string().contains("reactorHot"));
+/// assert_eq!(FormulaMetrics::of(&formula), FormulaMetrics::of(&obfuscated));
+/// ```
+///
+/// [`FormulaMetrics`]: analysis/struct.FormulaMetrics.html
+pub fn obfuscate(formula: &PropositionalFormula, seed: u64) -> PropositionalFormula {
+    let mut rng = SplitMix64(seed);
+
+    // Fisher-Yates shuffle of the new names over the variables in order of first occurrence.
+    let variables = formula.variables();
+    let mut names: Vec<usize> = (1..=variables.len()).collect();
+    for i in (1..names.len()).rev() {
+        names.swap(i, rng.below(i as u64 + 1) as usize);
+    }
+    let renaming: HashMap<&Variable, Variable> = variables
+        .into_iter()
+        .zip(names)
+        .map(|(variable, name)| (variable, Variable::new(format!("x{}", name))))
+        .collect();
+
+    rewrite(formula, &renaming, &mut rng)
+}
+
+fn rewrite(
+    formula: &PropositionalFormula,
+    renaming: &HashMap<&Variable, Variable>,
+    rng: &mut SplitMix64,
+) -> PropositionalFormula {
+    let mut operands = |a: &PropositionalFormula, b: &PropositionalFormula, commutative: bool| {
+        let (a, b) = (rewrite(a, renaming, rng), rewrite(b, renaming, rng));
+        if commutative && rng.below(2) == 0 {
+            (Box::new(b), Box::new(a))
+        } else {
+            (Box::new(a), Box::new(b))
+        }
+    };
+
+    match formula {
+        PropositionalFormula::Variable(v) => PropositionalFormula::variable(renaming[v].clone()),
+        PropositionalFormula::Top => PropositionalFormula::Top,
+        PropositionalFormula::Bottom => PropositionalFormula::Bottom,
+        PropositionalFormula::Negation(a) => {
+            PropositionalFormula::negated(Box::new(rewrite(a, renaming, rng)))
+        }
+        PropositionalFormula::Conjunction(a, b) => {
+            let (a, b) = operands(a, b, true);
+            PropositionalFormula::conjunction(a, b)
+        }
+        PropositionalFormula::Disjunction(a, b) => {
+            let (a, b) = operands(a, b, true);
+            PropositionalFormula::disjunction(a, b)
+        }
+        PropositionalFormula::Implication(a, b) => {
+            let (a, b) = operands(a, b, false);
+            PropositionalFormula::implication(a, b)
+        }
+        PropositionalFormula::Biimplication(a, b) => {
+            let (a, b) = operands(a, b, true);
+            PropositionalFormula::biimplication(a, b)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formula::analysis::FormulaMetrics;
+    use crate::testing::formulas;
+    use crate::truth_table;
+    use assert2::check;
+
+    #[test]
+    fn test_obfuscation_preserves_structure() {
+        for (seed, formula) in formulas(4, 5, 8, 100).enumerate() {
+            let obfuscated = obfuscate(&formula, seed as u64);
+
+            check!(FormulaMetrics::of(&obfuscated) == FormulaMetrics::of(&formula));
+            check!(
+                truth_table::is_satisfiable(&obfuscated) == truth_table::is_satisfiable(&formula)
+            );
+            check!(obfuscate(&formula, seed as u64) == obfuscated);
+        }
+    }
+
+    #[test]
+    fn test_anonymize() {
+        for formula in formulas(4, 5, 9, 100) {
+            let anonymized = formula.anonymize();
+
+            check!(FormulaMetrics::of(&anonymized) == FormulaMetrics::of(&formula));
+            check!(
+                truth_table::is_satisfiable(&anonymized) == truth_table::is_satisfiable(&formula)
+            );
+            let names: Vec<_> = anonymized.variables().iter().map(|v| v.name()).collect();
+            let expected: Vec<_> = (1..=names.len()).map(|i| format!("v{}", i)).collect();
+            check!(names == expected);
+        }
+    }
+}
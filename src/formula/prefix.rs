@@ -0,0 +1,44 @@
+//! Polish (prefix) notation of formulas, see [`PropositionalFormula::to_prefix_string`][prefix].
+//!
+//! [prefix]: ../propositional_formula/enum.PropositionalFormula.html#method.to_prefix_string
+
+use super::PropositionalFormula;
+
+impl PropositionalFormula {
+    /// Format the formula in Polish (prefix) notation, with each connective written before its
+    /// operands and tokens separated by single spaces, e.g. `-> ^ a b c` for `((a^b)->c)`. The
+    /// notation needs no parentheses, and [`parse_prefix`] reads it back.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libprop_sat_solver::formula::PropositionalFormula;
+    /// let formula: PropositionalFormula = "((a^(-b))<->(c|1))".parse().unwrap();
+    /// assert_eq!("<-> ^ a - b | c 1", formula.to_prefix_string());
+    /// ```
+    ///
+    /// [`parse_prefix`]: ../../parser/prefix/fn.parse_prefix.html
+    pub fn to_prefix_string(&self) -> String {
+        let mut tokens = Vec::new();
+        // Operands are pushed right to left so that they are popped left to right.
+        let mut stack = vec![self];
+
+        while let Some(formula) = stack.pop() {
+            let (token, operands) = match formula {
+                Self::Variable(v) => (v.quoted_name().into_owned(), [None, None]),
+                Self::Top => ("1".to_string(), [None, None]),
+                Self::Bottom => ("0".to_string(), [None, None]),
+                Self::Negation(a) => ("-".to_string(), [Some(a), None]),
+                Self::Conjunction(a, b) => ("^".to_string(), [Some(a), Some(b)]),
+                Self::Disjunction(a, b) => ("|".to_string(), [Some(a), Some(b)]),
+                Self::Implication(a, b) => ("->".to_string(), [Some(a), Some(b)]),
+                Self::Biimplication(a, b) => ("<->".to_string(), [Some(a), Some(b)]),
+            };
+
+            tokens.push(token);
+            stack.extend(operands.iter().rev().flatten().map(|f| &***f));
+        }
+
+        tokens.join(" ")
+    }
+}
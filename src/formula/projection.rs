@@ -0,0 +1,123 @@
+//! Projection of propositional formulas onto a subset of their variables.
+
+use std::collections::HashSet;
+
+use super::{substitute, Assignment, PropositionalFormula, Residual, Variable};
+
+/// Compute the strongest consequence of the formula over the variables in `keep`, i.e. the formula
+/// `∃x1 ... ∃xn. A` where `x1, ..., xn` are the variables of `A` not in `keep`.
+///
+/// The variables are eliminated one at a time in order of first occurrence by Shannon expansion,
+/// `∃x. A = (A[x:=T] | A[x:=F])`, simplifying constants away with [`substitute`]. The result is a
+/// [`Residual`] since the projection may well be a constant, e.g. `true` for any satisfiable
+/// formula projected onto no variables. When both cofactors are equal the formula is not
+/// duplicated, but in general each eliminated variable may double the size of the formula.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashSet;
+/// use libprop_sat_solver::formula::{project, PropositionalFormula, Residual, Variable};
+/// // ((a->b)^(b->c)) projected onto {a, c} is (a->c).
+/// let formula: PropositionalFormula = "((a->b)^(b->c))".parse().unwrap();
+/// let keep: HashSet<_> = vec![Variable::new("a"), Variable::new("c")].into_iter().collect();
+///
+/// let projection = match project(&formula, &keep) {
+///     Residual::Formula(projection) => projection,
+///     Residual::Constant(_) => unreachable!(),
+/// };
+/// assert_eq!("(c|(-a))", projection.to_string());
+/// ```
+///
+/// [`substitute`]: ../substitution/fn.substitute.html
+/// [`Residual`]: ../substitution/enum.Residual.html
+pub fn project(formula: &PropositionalFormula, keep: &HashSet<Variable>) -> Residual {
+    let eliminated: Vec<Variable> = formula
+        .variables()
+        .into_iter()
+        .filter(|variable| !keep.contains(variable))
+        .cloned()
+        .collect();
+
+    eliminated
+        .into_iter()
+        .fold(
+            Residual::Formula(formula.clone()),
+            |residual, variable| match residual {
+                Residual::Formula(formula) => eliminate(&formula, variable),
+                constant => constant,
+            },
+        )
+}
+
+/// Existentially quantify the `variable` in the formula.
+fn eliminate(formula: &PropositionalFormula, variable: Variable) -> Residual {
+    let mut assignment = Assignment::new();
+    assignment.insert(variable.clone(), true);
+    let positive = substitute(formula, &assignment);
+    assignment.insert(variable, false);
+    let negative = substitute(formula, &assignment);
+
+    match (positive, negative) {
+        (Residual::Constant(true), _) | (_, Residual::Constant(true)) => Residual::Constant(true),
+        (Residual::Constant(false), other) | (other, Residual::Constant(false)) => other,
+        (Residual::Formula(a), Residual::Formula(b)) => {
+            if a == b {
+                Residual::Formula(a)
+            } else {
+                Residual::Formula(PropositionalFormula::disjunction(Box::new(a), Box::new(b)))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::formulas;
+    use crate::truth_table;
+    use assert2::check;
+
+    #[test]
+    fn test_projection_is_existential_quantification() {
+        let x1 = Variable::new("x1");
+        let x2 = Variable::new("x2");
+        let keep: HashSet<_> = vec![x1.clone(), x2.clone()].into_iter().collect();
+
+        for formula in formulas(4, 4, 10, 100) {
+            let projection = project(&formula, &keep);
+
+            for &(v1, v2) in &[(false, false), (false, true), (true, false), (true, true)] {
+                let mut assignment = Assignment::new();
+                assignment.insert(x1.clone(), v1);
+                assignment.insert(x2.clone(), v2);
+
+                // The formula is satisfiable under the assignment to the kept variables...
+                let expected = match substitute(&formula, &assignment) {
+                    Residual::Constant(value) => value,
+                    Residual::Formula(rest) => truth_table::is_satisfiable(&rest),
+                };
+                // ... exactly if the projection is true under it.
+                let actual = match &projection {
+                    Residual::Constant(value) => *value,
+                    Residual::Formula(projection) => {
+                        substitute(projection, &assignment) == Residual::Constant(true)
+                    }
+                };
+
+                check!(expected == actual);
+            }
+        }
+    }
+
+    #[test]
+    fn test_constant_projections() {
+        let keep = HashSet::new();
+
+        let satisfiable: PropositionalFormula = "(a^(b|c))".parse().unwrap();
+        check!(project(&satisfiable, &keep) == Residual::Constant(true));
+
+        let unsatisfiable: PropositionalFormula = "(a^(-a))".parse().unwrap();
+        check!(project(&unsatisfiable, &keep) == Residual::Constant(false));
+    }
+}
@@ -1,8 +1,11 @@
 //! A propositional formula.
 
+use std::collections::HashSet;
 use std::convert::{From, Into};
+use std::fmt;
+use std::str::FromStr;
 
-use super::Variable;
+use super::{Assignment, Variable};
 
 /// A propositional formula is defined inductively, conforming to the following BNF:
 ///
@@ -20,7 +23,7 @@ use super::Variable;
 /// eliminates the requirement for operator precedence due to grammar ambiguity at the cost of being
 /// more verbose.
 ///
-/// # Ownership, Interior Mutability and Optional Sub-formulas
+/// # Ownership, Interior Mutability and Sub-formulas
 ///
 /// Since we don't need any fancy multiple-threading or multi-owner business, we'll stick with the
 /// most trivial `Box` pointer indirection instead of the fancier alternatives:
@@ -32,39 +35,32 @@ use super::Variable;
 /// We do not support interior mutability as we do not need it for our use cases with respect to the
 /// propositional formula AST.
 ///
-/// We need sub-formulas to be wrapped in `Option` for construction purposes, so we can build a
-/// `PropPropositionalFormula` during parsing.
+/// Sub-formulas are always present: the parser only builds a formula once all of its operands
+/// have been parsed, so there is no partially-constructed state to represent and every consumer can
+/// be total over the variants.
 ///
 /// # No Default
 ///
 /// We cannot soundly define a sane default for a `PropositionalFormula` – even in the base case of
 /// a single propositional variable, what would the default propositional variable be?
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum PropositionalFormula {
     /// Base case: a single propositional variable.
     Variable(Variable),
+    /// Base case: the constant true (⊤), written `1` or `true`.
+    Top,
+    /// Base case: the constant false (⊥), written `0` or `false`.
+    Bottom,
     /// Unary case: negated formula.
-    Negation(Option<Box<PropositionalFormula>>),
+    Negation(Box<PropositionalFormula>),
     /// Binary formula with the main connective being the logical AND connective.
-    Conjunction(
-        Option<Box<PropositionalFormula>>,
-        Option<Box<PropositionalFormula>>,
-    ),
+    Conjunction(Box<PropositionalFormula>, Box<PropositionalFormula>),
     /// Binary formula with the main connective being the logical OR operator.
-    Disjunction(
-        Option<Box<PropositionalFormula>>,
-        Option<Box<PropositionalFormula>>,
-    ),
+    Disjunction(Box<PropositionalFormula>, Box<PropositionalFormula>),
     /// Binary formula with the main connective being the implication operator.
-    Implication(
-        Option<Box<PropositionalFormula>>,
-        Option<Box<PropositionalFormula>>,
-    ),
+    Implication(Box<PropositionalFormula>, Box<PropositionalFormula>),
     /// Binary formula with the main connective being the biimplication operator.
-    Biimplication(
-        Option<Box<PropositionalFormula>>,
-        Option<Box<PropositionalFormula>>,
-    ),
+    Biimplication(Box<PropositionalFormula>, Box<PropositionalFormula>),
 }
 
 // Convenience methods for constructing a `PropositionalFormula`.
@@ -97,7 +93,7 @@ impl PropositionalFormula {
     /// ```
     #[inline]
     pub fn negated(formula: Box<PropositionalFormula>) -> Self {
-        Self::Negation(Some(formula))
+        Self::Negation(formula)
     }
 
     /// Construct a new propositional formula from two propositional sub-formulas with a conjunction
@@ -116,7 +112,7 @@ impl PropositionalFormula {
         left_sub_formula: Box<PropositionalFormula>,
         right_sub_formula: Box<PropositionalFormula>,
     ) -> Self {
-        Self::Conjunction(Some(left_sub_formula), Some(right_sub_formula))
+        Self::Conjunction(left_sub_formula, right_sub_formula)
     }
 
     /// Construct a new propositional formula from two propositional sub-formulas with a disjunction
@@ -135,7 +131,7 @@ impl PropositionalFormula {
         left_sub_formula: Box<PropositionalFormula>,
         right_sub_formula: Box<PropositionalFormula>,
     ) -> Self {
-        Self::Disjunction(Some(left_sub_formula), Some(right_sub_formula))
+        Self::Disjunction(left_sub_formula, right_sub_formula)
     }
 
     /// Construct a new propositional formula from two propositional sub-formulas with an
@@ -154,7 +150,7 @@ impl PropositionalFormula {
         left_sub_formula: Box<PropositionalFormula>,
         right_sub_formula: Box<PropositionalFormula>,
     ) -> Self {
-        Self::Implication(Some(left_sub_formula), Some(right_sub_formula))
+        Self::Implication(left_sub_formula, right_sub_formula)
     }
 
     /// Construct a new propositional formula from two propositional sub-formulas with a
@@ -173,21 +169,252 @@ impl PropositionalFormula {
         left_sub_formula: Box<PropositionalFormula>,
         right_sub_formula: Box<PropositionalFormula>,
     ) -> Self {
-        Self::Biimplication(Some(left_sub_formula), Some(right_sub_formula))
+        Self::Biimplication(left_sub_formula, right_sub_formula)
+    }
+
+    /// Construct the conjunction `((A1^A2)^ ... ^An)` of the given formulas, or `None` if there
+    /// are none.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libprop_sat_solver::formula::{PropositionalFormula, Variable};
+    /// let a = PropositionalFormula::variable(Variable::new("a"));
+    /// let b = PropositionalFormula::variable(Variable::new("b"));
+    /// let formula = PropositionalFormula::conjoin(vec![a.clone(), b.clone()]);
+    /// assert_eq!(Some(PropositionalFormula::conjunction(Box::new(a), Box::new(b))), formula);
+    /// ```
+    pub fn conjoin<I>(formulas: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = PropositionalFormula>,
+    {
+        formulas
+            .into_iter()
+            .fold(None, |conjunction, formula| match conjunction {
+                Some(conjunction) => {
+                    Some(Self::conjunction(Box::new(conjunction), Box::new(formula)))
+                }
+                None => Some(formula),
+            })
+    }
+
+    /// Split nested conjunctions into their operands, from left to right, i.e. the inverse of
+    /// [`conjoin`]. A formula which is not a conjunction is its only conjunct.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libprop_sat_solver::formula::PropositionalFormula;
+    /// let formula: PropositionalFormula = "((a^(b|c))^(d^e))".parse().unwrap();
+    ///
+    /// let conjuncts: Vec<_> = formula.conjuncts().iter().map(ToString::to_string).collect();
+    /// assert_eq!(vec!["a", "(b|c)", "d", "e"], conjuncts);
+    /// ```
+    ///
+    /// [`conjoin`]: #method.conjoin
+    pub fn conjuncts(&self) -> Vec<&Self> {
+        self.flatten(|formula| match formula {
+            Self::Conjunction(a, b) => Some((a, b)),
+            _ => None,
+        })
+    }
+
+    /// Split nested disjunctions into their operands, from left to right, like [`conjuncts`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libprop_sat_solver::formula::PropositionalFormula;
+    /// let formula: PropositionalFormula = "((a|(b^c))|(-(d|e)))".parse().unwrap();
+    ///
+    /// let disjuncts: Vec<_> = formula.disjuncts().iter().map(ToString::to_string).collect();
+    /// assert_eq!(vec!["a", "(b^c)", "(-(d|e))"], disjuncts);
+    /// ```
+    ///
+    /// [`conjuncts`]: #method.conjuncts
+    pub fn disjuncts(&self) -> Vec<&Self> {
+        self.flatten(|formula| match formula {
+            Self::Disjunction(a, b) => Some((a, b)),
+            _ => None,
+        })
+    }
+
+    /// Split the formula into the antecedents of the implications down its right spine and the
+    /// final consequent, so that `(A1->(A2-> ... ->(An->B)))` gives `A1, ..., An` and `B`. A
+    /// formula which is not an implication is its own consequent, without antecedents.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libprop_sat_solver::formula::PropositionalFormula;
+    /// let formula: PropositionalFormula = "((a->b)->(c->d))".parse().unwrap();
+    ///
+    /// let (antecedents, consequent) = formula.implication_chain();
+    /// let antecedents: Vec<_> = antecedents.iter().map(ToString::to_string).collect();
+    /// assert_eq!(vec!["(a->b)", "c"], antecedents);
+    /// assert_eq!("d", consequent.to_string());
+    /// ```
+    pub fn implication_chain(&self) -> (Vec<&Self>, &Self) {
+        let mut antecedents = Vec::new();
+        let mut consequent = self;
+        while let Self::Implication(a, b) = consequent {
+            antecedents.push(&**a);
+            consequent = b;
+        }
+        (antecedents, consequent)
+    }
+
+    /// Split the nested applications of an associative connective into their operands, from left
+    /// to right, where `operands` gives the operands of an application.
+    fn flatten(&self, operands: impl Fn(&Self) -> Option<(&Self, &Self)>) -> Vec<&Self> {
+        let mut flattened = Vec::new();
+        let mut stack = vec![self];
+        while let Some(formula) = stack.pop() {
+            match operands(formula) {
+                Some((a, b)) => {
+                    stack.push(b);
+                    stack.push(a);
+                }
+                None => flattened.push(formula),
+            }
+        }
+        flattened
+    }
+
+    /// Take the operand out of a negation, or give the formula back if it is not a negation.
+    ///
+    /// Since formulas implement `Drop`, their operands cannot be moved out by pattern matching.
+    pub(crate) fn strip_negation(mut self) -> Result<Self, Self> {
+        if let Self::Negation(a) = &mut self {
+            return Ok(std::mem::replace(&mut **a, Self::Top));
+        }
+        Err(self)
     }
 
     /// Checks if the given `PropositionalFormula` is a literal (either a propositional variable
-    /// like `p` or its negation `-p`).
+    /// like `p` or its negation `-p`). The constants `1` and `0` and their negations count as
+    /// literals as well, since there is nothing left to expand.
     pub fn is_literal(&self) -> bool {
         match self {
             // A propositional variable `p` is trivially a literal.
             Self::Variable(_) => true,
+            // So are the constants.
+            Self::Top | Self::Bottom => true,
             // The negation of a propositional variable `(-p)` is also trivially a literal.
-            Self::Negation(Some(ref inner_formula)) if inner_formula.is_literal() => true,
+            Self::Negation(ref inner_formula) if inner_formula.is_literal() => true,
             // Any other complex propositional formula is not a literal.
             _ => false,
         }
     }
+
+    /// Get the distinct propositional variables occurring in the formula, in order of first
+    /// occurrence from left to right.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libprop_sat_solver::formula::{PropositionalFormula, Variable};
+    /// let a = PropositionalFormula::variable(Variable::new("a"));
+    /// let b = PropositionalFormula::variable(Variable::new("b"));
+    /// let formula = PropositionalFormula::conjunction(
+    ///     Box::new(b.clone()),
+    ///     Box::new(PropositionalFormula::disjunction(Box::new(a), Box::new(b))),
+    /// );
+    /// assert_eq!(vec![&Variable::new("b"), &Variable::new("a")], formula.variables());
+    /// ```
+    pub fn variables(&self) -> Vec<&Variable> {
+        let mut seen = HashSet::new();
+        self.iter_preorder()
+            .filter_map(|formula| match formula {
+                PropositionalFormula::Variable(v) if seen.insert(v) => Some(v),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Evaluate the formula under the `assignment`.
+    ///
+    /// Returns `None` if the truth value depends on a variable the assignment leaves unassigned;
+    /// operands which cannot change the result are not needed, e.g.
+    /// `(a|b)` is true if `a` is true whether or not `b` is assigned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libprop_sat_solver::formula::{Assignment, PropositionalFormula, Variable};
+    /// let formula: PropositionalFormula = "(a->(b^c))".parse().unwrap();
+    ///
+    /// let mut assignment = Assignment::new();
+    /// assignment.insert(Variable::new("a"), true);
+    /// assert_eq!(None, formula.evaluate(&assignment));
+    ///
+    /// assignment.insert(Variable::new("b"), false);
+    /// assert_eq!(Some(false), formula.evaluate(&assignment));
+    /// ```
+    pub fn evaluate(&self, assignment: &Assignment) -> Option<bool> {
+        let evaluate = |f: &PropositionalFormula| f.evaluate(assignment);
+
+        match self {
+            PropositionalFormula::Variable(v) => assignment.get(v).copied(),
+            PropositionalFormula::Top => Some(true),
+            PropositionalFormula::Bottom => Some(false),
+            PropositionalFormula::Negation(a) => evaluate(a).map(|a| !a),
+            PropositionalFormula::Conjunction(a, b) => match (evaluate(a), evaluate(b)) {
+                (Some(false), _) | (_, Some(false)) => Some(false),
+                (Some(true), Some(true)) => Some(true),
+                _ => None,
+            },
+            PropositionalFormula::Disjunction(a, b) => match (evaluate(a), evaluate(b)) {
+                (Some(true), _) | (_, Some(true)) => Some(true),
+                (Some(false), Some(false)) => Some(false),
+                _ => None,
+            },
+            PropositionalFormula::Implication(a, b) => match (evaluate(a), evaluate(b)) {
+                (Some(false), _) | (_, Some(true)) => Some(true),
+                (Some(true), Some(false)) => Some(false),
+                _ => None,
+            },
+            PropositionalFormula::Biimplication(a, b) => Some(evaluate(a)? == evaluate(b)?),
+        }
+    }
+}
+
+/// Drops the sub-formulas iteratively rather than recursively, so that dropping deeply nested
+/// formulas (e.g. long chains of negations from generated inputs) cannot overflow the stack.
+impl Drop for PropositionalFormula {
+    fn drop(&mut self) {
+        // Operands are swapped out for a constant, which has nothing left to drop.
+        fn take_operands(
+            formula: &mut PropositionalFormula,
+            stack: &mut Vec<PropositionalFormula>,
+        ) {
+            let mut take = |a: &mut Box<PropositionalFormula>| {
+                stack.push(std::mem::replace(&mut **a, PropositionalFormula::Top))
+            };
+
+            match formula {
+                PropositionalFormula::Variable(_)
+                | PropositionalFormula::Top
+                | PropositionalFormula::Bottom => {}
+                PropositionalFormula::Negation(a) => take(a),
+                PropositionalFormula::Conjunction(a, b)
+                | PropositionalFormula::Disjunction(a, b)
+                | PropositionalFormula::Implication(a, b)
+                | PropositionalFormula::Biimplication(a, b) => {
+                    take(a);
+                    take(b);
+                }
+            }
+        }
+
+        let mut stack = Vec::new();
+        take_operands(self, &mut stack);
+
+        // Each sub-formula is dropped only after its own operands were taken out of it.
+        while let Some(mut formula) = stack.pop() {
+            take_operands(&mut formula, &mut stack);
+        }
+    }
 }
 
 impl<V> From<V> for PropositionalFormula
@@ -198,3 +425,104 @@ where
         Self::Variable(v.into())
     }
 }
+
+/// Parses a formula in the input syntax, see [`parser::parse`].
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::formula::{PropositionalFormula, Variable};
+/// let formula: PropositionalFormula = "(a -> (not b))".parse().unwrap();
+/// assert_eq!("(a->(-b))", formula.to_string());
+/// ```
+///
+/// [`parser::parse`]: ../../parser/fn.parse.html
+impl FromStr for PropositionalFormula {
+    type Err = crate::parser::ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        crate::parser::parse(s)
+    }
+}
+
+/// Formats the formula in the fully-parenthesized input syntax, e.g. `((a^(-b))->c)`, so the output
+/// can be parsed back into the same formula.
+impl fmt::Display for PropositionalFormula {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Variable(v) => f.write_str(&v.quoted_name()),
+            Self::Top => write!(f, "1"),
+            Self::Bottom => write!(f, "0"),
+            Self::Negation(a) => write!(f, "(-{})", a),
+            Self::Conjunction(a, b) => write!(f, "({}^{})", a, b),
+            Self::Disjunction(a, b) => write!(f, "({}|{})", a, b),
+            Self::Implication(a, b) => write!(f, "({}->{})", a, b),
+            Self::Biimplication(a, b) => write!(f, "({}<->{})", a, b),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert2::check;
+
+    #[test]
+    fn test_display() {
+        // ((a^(-b))<->(c|(d->e)))
+        let formula = PropositionalFormula::biimplication(
+            Box::new(PropositionalFormula::conjunction(
+                Box::new(PropositionalFormula::variable(Variable::new("a"))),
+                Box::new(PropositionalFormula::negated(Box::new(
+                    PropositionalFormula::variable(Variable::new("b")),
+                ))),
+            )),
+            Box::new(PropositionalFormula::disjunction(
+                Box::new(PropositionalFormula::variable(Variable::new("c"))),
+                Box::new(PropositionalFormula::implication(
+                    Box::new(PropositionalFormula::variable(Variable::new("d"))),
+                    Box::new(PropositionalFormula::variable(Variable::new("e"))),
+                )),
+            )),
+        );
+
+        check!(formula.to_string() == "((a^(-b))<->(c|(d->e)))");
+    }
+
+    #[test]
+    fn test_drop_deep_formula() {
+        // Deep enough to overflow the stack of a recursive drop in a test thread.
+        let mut formula = PropositionalFormula::variable(Variable::new("a"));
+        for i in 0..500_000 {
+            let b = Box::new(PropositionalFormula::variable(Variable::new(format!(
+                "b{}",
+                i
+            ))));
+            formula = PropositionalFormula::conjunction(b, Box::new(formula));
+        }
+
+        check!(formula.conjuncts().len() == 500_001);
+        drop(formula);
+    }
+
+    #[test]
+    fn test_evaluate_agrees_with_substitute() {
+        use crate::formula::{substitute, Residual};
+        use crate::testing::formulas;
+
+        for (i, formula) in formulas(3, 4, 11, 100).enumerate() {
+            // Assign the variables `x1` to `x3` by the bits of `i`, leaving `x3` unassigned for
+            // every other formula.
+            let assignment: Assignment = (1..=3)
+                .filter(|&n| n < 3 || i % 2 == 0)
+                .map(|n| (Variable::new(format!("x{}", n)), i & (1 << n) != 0))
+                .collect();
+
+            let expected = match substitute(&formula, &assignment) {
+                Residual::Constant(value) => Some(value),
+                Residual::Formula(_) => None,
+            };
+            check!(formula.evaluate(&assignment) == expected);
+        }
+    }
+}
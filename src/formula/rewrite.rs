@@ -0,0 +1,361 @@
+//! Declarative rewriting of formulas by rules `pattern => replacement` with metavariables.
+//!
+//! A [`RewriteRule`] matches a formula against its pattern, binding each metavariable of the
+//! pattern to a sub-formula (the same sub-formula wherever the metavariable occurs), and rewrites
+//! it into its replacement with the metavariables replaced by what they are bound to. A
+//! [`RewriteSystem`] applies its rules everywhere in a formula until none of them applies any
+//! more.
+//!
+//! # Example
+//!
+//! ```
+//! use libprop_sat_solver::formula::rewrite::{RewriteRule, RewriteSystem};
+//! let system = RewriteSystem::new()
+//!     .with_rule(RewriteRule::parse("idempotence", "(A^A) => A", &["A"]).unwrap())
+//!     .with_rule(RewriteRule::parse("double-negation", "(-(-A)) => A", &["A"]).unwrap());
+//!
+//! let formula = "((-(-(a|b)))^(a|b))".parse().unwrap();
+//! assert_eq!("(a|b)", system.rewrite(&formula).unwrap().to_string());
+//! ```
+//!
+//! [`RewriteRule`]: struct.RewriteRule.html
+//! [`RewriteSystem`]: struct.RewriteSystem.html
+
+use std::collections::{HashMap, HashSet};
+
+use super::template::replace;
+use super::{PropositionalFormula, Variable};
+
+/// Default bound on the number of rewrites of [`RewriteSystem::rewrite`].
+///
+/// [`RewriteSystem::rewrite`]: struct.RewriteSystem.html#method.rewrite
+pub const DEFAULT_MAX_REWRITES: usize = 100_000;
+
+/// A rewrite rule `pattern => replacement`, in which some variables are metavariables standing for
+/// arbitrary formulas.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RewriteRule {
+    name: String,
+    pattern: PropositionalFormula,
+    replacement: PropositionalFormula,
+    metavariables: Vec<Variable>,
+}
+
+impl RewriteRule {
+    /// Construct a rule rewriting formulas matching the `pattern` into the `replacement`, in which
+    /// the variables named by `metavariables` are metavariables. All other variables, like the
+    /// constants, only match themselves.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the pattern is a bare metavariable, which would match every formula, or if a
+    /// metavariable does not occur in the pattern.
+    pub fn new<S: AsRef<str>>(
+        name: &str,
+        pattern: PropositionalFormula,
+        replacement: PropositionalFormula,
+        metavariables: &[S],
+    ) -> Result<Self, String> {
+        let metavariables: Vec<Variable> = metavariables
+            .iter()
+            .map(|name| Variable::new(name.as_ref()))
+            .collect();
+
+        if let PropositionalFormula::Variable(v) = &pattern {
+            if metavariables.contains(v) {
+                return Err(format!("pattern of rule {:?} matches every formula", name));
+            }
+        }
+
+        let variables: HashSet<&Variable> = pattern.variables().into_iter().collect();
+        if let Some(missing) = metavariables.iter().find(|v| !variables.contains(v)) {
+            return Err(format!(
+                "metavariable {:?} does not occur in the pattern of rule {:?}",
+                missing.name(),
+                name
+            ));
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            pattern,
+            replacement,
+            metavariables,
+        })
+    }
+
+    /// Construct a rule from its text `<pattern> => <replacement>`, see [`new`].
+    ///
+    /// # Errors
+    ///
+    /// Fails if the text is not of that form, if either side is not a well-formed formula, or if
+    /// [`new`] fails.
+    ///
+    /// [`new`]: #method.new
+    pub fn parse<S: AsRef<str>>(
+        name: &str,
+        rule: &str,
+        metavariables: &[S],
+    ) -> Result<Self, String> {
+        let (pattern, replacement) = match rule.find("=>") {
+            Some(i) => (&rule[..i], &rule[i + 2..]),
+            None => {
+                return Err(format!(
+                    "expected `<pattern> => <replacement>`, found {:?}",
+                    rule
+                ))
+            }
+        };
+
+        Self::new(
+            name,
+            pattern.trim().parse()?,
+            replacement.trim().parse()?,
+            metavariables,
+        )
+    }
+
+    /// Get the name of the rule.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Rewrite the formula by the rule if its pattern matches the whole formula.
+    pub fn apply(&self, formula: &PropositionalFormula) -> Option<PropositionalFormula> {
+        let mut bindings = HashMap::new();
+        if self.matches(&self.pattern, formula, &mut bindings) {
+            Some(replace(&self.replacement, &bindings))
+        } else {
+            None
+        }
+    }
+
+    /// Match the formula against the pattern, extending the bindings of the metavariables.
+    fn matches(
+        &self,
+        pattern: &PropositionalFormula,
+        formula: &PropositionalFormula,
+        bindings: &mut HashMap<Variable, PropositionalFormula>,
+    ) -> bool {
+        use PropositionalFormula::*;
+
+        let mut operands = |a: &PropositionalFormula, b| self.matches(a, b, bindings);
+
+        match (pattern, formula) {
+            (Variable(v), _) if self.metavariables.contains(v) => match bindings.get(v) {
+                Some(bound) => bound == formula,
+                None => {
+                    bindings.insert(v.clone(), formula.clone());
+                    true
+                }
+            },
+            (Variable(v), Variable(w)) => v == w,
+            (Top, Top) | (Bottom, Bottom) => true,
+            (Negation(a), Negation(b)) => operands(a, b),
+            (Conjunction(a1, b1), Conjunction(a2, b2))
+            | (Disjunction(a1, b1), Disjunction(a2, b2))
+            | (Implication(a1, b1), Implication(a2, b2))
+            | (Biimplication(a1, b1), Biimplication(a2, b2)) => {
+                operands(a1, a2) && operands(b1, b2)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// A list of [`RewriteRule`]s applied to fixpoint.
+///
+/// [`RewriteRule`]: struct.RewriteRule.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RewriteSystem {
+    rules: Vec<RewriteRule>,
+    max_rewrites: usize,
+}
+
+impl RewriteSystem {
+    /// A system without any rules, rewriting nothing.
+    pub fn new() -> Self {
+        Self {
+            rules: Vec::new(),
+            max_rewrites: DEFAULT_MAX_REWRITES,
+        }
+    }
+
+    /// The rules rewriting formulas into negation normal form, in which negations only apply to
+    /// variables and the only other connectives are `^` and `|`.
+    ///
+    /// Unlike [`push_negations`], biimplications are unfolded, which may blow up the formula.
+    ///
+    /// [`push_negations`]: ../transform/fn.push_negations.html
+    pub fn negation_normal_form() -> Self {
+        let rules: &[(&str, &str, &[&str])] = &[
+            ("double-negation", "(-(-A)) => A", &["A"]),
+            (
+                "de-morgan-conjunction",
+                "(-(A^B)) => ((-A)|(-B))",
+                &["A", "B"],
+            ),
+            (
+                "de-morgan-disjunction",
+                "(-(A|B)) => ((-A)^(-B))",
+                &["A", "B"],
+            ),
+            ("implication", "(A->B) => ((-A)|B)", &["A", "B"]),
+            (
+                "biimplication",
+                "(A<->B) => ((A^B)|((-A)^(-B)))",
+                &["A", "B"],
+            ),
+            ("negated-top", "(-1) => 0", &[]),
+            ("negated-bottom", "(-0) => 1", &[]),
+        ];
+
+        rules
+            .iter()
+            .fold(Self::new(), |system, (name, rule, metavariables)| {
+                // PANIC: the rules above are well-formed.
+                system.with_rule(RewriteRule::parse(name, rule, metavariables).unwrap())
+            })
+    }
+
+    /// Add the rule after all others, i.e. with the lowest priority.
+    pub fn with_rule(mut self, rule: RewriteRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Bound the number of rewrites of [`rewrite`], which is [`DEFAULT_MAX_REWRITES`] by default.
+    ///
+    /// [`rewrite`]: #method.rewrite
+    /// [`DEFAULT_MAX_REWRITES`]: constant.DEFAULT_MAX_REWRITES.html
+    pub fn with_max_rewrites(mut self, max_rewrites: usize) -> Self {
+        self.max_rewrites = max_rewrites;
+        self
+    }
+
+    /// Get the rules, in the order they are tried.
+    pub fn rules(&self) -> &[RewriteRule] {
+        &self.rules
+    }
+
+    /// Rewrite the formula until no rule applies to any of its sub-formulas. Sub-formulas are
+    /// rewritten before the formulas containing them, each by the first rule which applies.
+    ///
+    /// # Errors
+    ///
+    /// Fails if there is no such fixpoint within the bound on the number of rewrites, e.g. because
+    /// the rules undo each other.
+    pub fn rewrite(&self, formula: &PropositionalFormula) -> Result<PropositionalFormula, String> {
+        let mut rewrites = 0;
+        self.normalize(formula, &mut rewrites)
+    }
+
+    fn normalize(
+        &self,
+        formula: &PropositionalFormula,
+        rewrites: &mut usize,
+    ) -> Result<PropositionalFormula, String> {
+        let mut normalize = |f: &PropositionalFormula| self.normalize(f, rewrites).map(Box::new);
+
+        let formula = match formula {
+            PropositionalFormula::Variable(_)
+            | PropositionalFormula::Top
+            | PropositionalFormula::Bottom => formula.clone(),
+            PropositionalFormula::Negation(a) => PropositionalFormula::Negation(normalize(a)?),
+            PropositionalFormula::Conjunction(a, b) => {
+                PropositionalFormula::Conjunction(normalize(a)?, normalize(b)?)
+            }
+            PropositionalFormula::Disjunction(a, b) => {
+                PropositionalFormula::Disjunction(normalize(a)?, normalize(b)?)
+            }
+            PropositionalFormula::Implication(a, b) => {
+                PropositionalFormula::Implication(normalize(a)?, normalize(b)?)
+            }
+            PropositionalFormula::Biimplication(a, b) => {
+                PropositionalFormula::Biimplication(normalize(a)?, normalize(b)?)
+            }
+        };
+
+        match self.rules.iter().find_map(|rule| rule.apply(&formula)) {
+            Some(rewritten) => {
+                *rewrites += 1;
+                if *rewrites > self.max_rewrites {
+                    return Err(format!("no fixpoint within {} rewrites", self.max_rewrites));
+                }
+                self.normalize(&rewritten, rewrites)
+            }
+            None => Ok(formula),
+        }
+    }
+}
+
+impl Default for RewriteSystem {
+    /// A system without any rules, see [`new`].
+    ///
+    /// [`new`]: #method.new
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formula::push_negations;
+    use crate::solver::{equivalence_counterexample, SolverConfig};
+    use crate::testing::formulas;
+    use assert2::check;
+
+    #[test]
+    fn test_matching() {
+        let rule = RewriteRule::parse("absorption", "(A^(A|B)) => A", &["A", "B"]).unwrap();
+
+        let formula = "((p->q)^((p->q)|r))".parse().unwrap();
+        check!(rule.apply(&formula) == Some("(p->q)".parse().unwrap()));
+        // `A` must stand for the same formula at both of its occurrences.
+        check!(rule.apply(&"(p^(q|r))".parse().unwrap()) == None);
+
+        // Variables which are not metavariables only match themselves.
+        let rule = RewriteRule::parse("tautology", "(A|(-A)) => 1", &["A"]).unwrap();
+        let excluded_middle = "((p^q)|(-(p^q)))".parse().unwrap();
+        check!(rule.apply(&excluded_middle) == Some(PropositionalFormula::Top));
+
+        check!(RewriteRule::parse("everything", "A => 1", &["A"]).is_err());
+        check!(RewriteRule::parse("unused", "(A^B) => A", &["A", "C"]).is_err());
+        check!(RewriteRule::parse("arrow", "(A^B)", &["A", "B"]).is_err());
+    }
+
+    #[test]
+    fn test_negation_normal_form() {
+        let system = RewriteSystem::negation_normal_form();
+
+        for formula in formulas(4, 4, 12, 100) {
+            let normal = system.rewrite(&formula).unwrap();
+            check!(equivalence_counterexample(&formula, &normal, &SolverConfig::default()) == None);
+            check!(normal.iter_preorder().all(|f| match f {
+                PropositionalFormula::Negation(a) => {
+                    matches!(**a, PropositionalFormula::Variable(_))
+                }
+                PropositionalFormula::Implication(..) | PropositionalFormula::Biimplication(..) => {
+                    false
+                }
+                _ => true,
+            }));
+
+            // Without biimplications, it agrees with the hand-written transform.
+            if formula.connective_counts().biimplications == 0 {
+                check!(normal == push_negations(&formula));
+            }
+        }
+    }
+
+    #[test]
+    fn test_non_terminating_rules() {
+        let commutativity = RewriteRule::parse("commutativity", "(A^B) => (B^A)", &["A", "B"]);
+        let system = RewriteSystem::new()
+            .with_rule(commutativity.unwrap())
+            .with_max_rewrites(100);
+
+        check!(system.rewrite(&"(a^b)".parse().unwrap()).is_err());
+    }
+}
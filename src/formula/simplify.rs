@@ -0,0 +1,190 @@
+//! Structural simplification of propositional formulas.
+
+use super::PropositionalFormula;
+
+/// Simplify a propositional formula into an _equivalent_, no larger formula.
+///
+/// Sub-formulas are simplified bottom-up with the rewrite rules:
+///
+/// | Form                                               | Simplified |
+/// | -------------------------------------------------- | ---------- |
+/// | `(-(-A))`                                          | `A`        |
+/// | `(A^A)`, <code>(A\|A)</code>                       | `A`        |
+/// | <code>(A^(A\|B))</code>, <code>((A\|B)^A)</code>   | `A`        |
+/// | <code>(A\|(A^B))</code>, <code>((A^B)\|A)</code>   | `A`        |
+///
+/// where the operand order of `(A|B)` and `(A^B)` inside the absorption rules may also be swapped.
+/// The constants `1` and `0` are folded away, e.g. `(A^1)` becomes `A` and `(A->0)` becomes
+/// `(-A)`, so the result is either a constant or free of constants.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::formula::{simplify, PropositionalFormula, Variable};
+/// let a = PropositionalFormula::variable(Variable::new("a"));
+/// let formula = PropositionalFormula::conjunction(Box::new(a.clone()), Box::new(a.clone()));
+/// assert_eq!(a, simplify(&formula));
+/// ```
+pub fn simplify(formula: &PropositionalFormula) -> PropositionalFormula {
+    match formula {
+        PropositionalFormula::Variable(_)
+        | PropositionalFormula::Top
+        | PropositionalFormula::Bottom => formula.clone(),
+        PropositionalFormula::Negation(a) => negate(simplify(a)),
+        PropositionalFormula::Conjunction(a, b) => {
+            let (a, b) = (simplify(a), simplify(b));
+
+            if a == PropositionalFormula::Bottom || b == PropositionalFormula::Bottom {
+                PropositionalFormula::Bottom
+            } else if a == PropositionalFormula::Top {
+                b
+            } else if b == PropositionalFormula::Top || a == b || is_disjunction_with(&b, &a) {
+                a
+            } else if is_disjunction_with(&a, &b) {
+                b
+            } else {
+                PropositionalFormula::conjunction(Box::new(a), Box::new(b))
+            }
+        }
+        PropositionalFormula::Disjunction(a, b) => {
+            let (a, b) = (simplify(a), simplify(b));
+
+            if a == PropositionalFormula::Top || b == PropositionalFormula::Top {
+                PropositionalFormula::Top
+            } else if a == PropositionalFormula::Bottom {
+                b
+            } else if b == PropositionalFormula::Bottom || a == b || is_conjunction_with(&b, &a) {
+                a
+            } else if is_conjunction_with(&a, &b) {
+                b
+            } else {
+                PropositionalFormula::disjunction(Box::new(a), Box::new(b))
+            }
+        }
+        PropositionalFormula::Implication(a, b) => match (simplify(a), simplify(b)) {
+            (PropositionalFormula::Bottom, _) | (_, PropositionalFormula::Top) => {
+                PropositionalFormula::Top
+            }
+            (PropositionalFormula::Top, b) => b,
+            (a, PropositionalFormula::Bottom) => negate(a),
+            (a, b) => PropositionalFormula::implication(Box::new(a), Box::new(b)),
+        },
+        PropositionalFormula::Biimplication(a, b) => match (simplify(a), simplify(b)) {
+            (PropositionalFormula::Top, x) | (x, PropositionalFormula::Top) => x,
+            (PropositionalFormula::Bottom, x) | (x, PropositionalFormula::Bottom) => negate(x),
+            (a, b) => PropositionalFormula::biimplication(Box::new(a), Box::new(b)),
+        },
+    }
+}
+
+/// Negate an already simplified formula, folding constants and double negations.
+fn negate(formula: PropositionalFormula) -> PropositionalFormula {
+    match formula {
+        PropositionalFormula::Top => PropositionalFormula::Bottom,
+        PropositionalFormula::Bottom => PropositionalFormula::Top,
+        formula => formula
+            .strip_negation()
+            .unwrap_or_else(|formula| PropositionalFormula::negated(Box::new(formula))),
+    }
+}
+
+/// Checks if `formula` is a conjunction with `operand` as one of its operands.
+fn is_conjunction_with(formula: &PropositionalFormula, operand: &PropositionalFormula) -> bool {
+    match formula {
+        PropositionalFormula::Conjunction(a, b) => **a == *operand || **b == *operand,
+        _ => false,
+    }
+}
+
+/// Checks if `formula` is a disjunction with `operand` as one of its operands.
+fn is_disjunction_with(formula: &PropositionalFormula, operand: &PropositionalFormula) -> bool {
+    match formula {
+        PropositionalFormula::Disjunction(a, b) => **a == *operand || **b == *operand,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formula::Variable;
+    use assert2::check;
+
+    #[test]
+    fn test_double_negation() {
+        // (-(-a))
+        let a = PropositionalFormula::variable(Variable::new("a"));
+        let formula = PropositionalFormula::negated(Box::new(PropositionalFormula::negated(
+            Box::new(a.clone()),
+        )));
+
+        check!(simplify(&formula) == a);
+    }
+
+    #[test]
+    fn test_idempotence() {
+        // ((a|a)^(a|a))
+        let a = PropositionalFormula::variable(Variable::new("a"));
+        let a_or_a = PropositionalFormula::disjunction(Box::new(a.clone()), Box::new(a.clone()));
+        let formula = PropositionalFormula::conjunction(Box::new(a_or_a.clone()), Box::new(a_or_a));
+
+        check!(simplify(&formula) == a);
+    }
+
+    #[test]
+    fn test_absorption() {
+        // ((b|a)^a)
+        let a = PropositionalFormula::variable(Variable::new("a"));
+        let b = PropositionalFormula::variable(Variable::new("b"));
+        let formula = PropositionalFormula::conjunction(
+            Box::new(PropositionalFormula::disjunction(
+                Box::new(b.clone()),
+                Box::new(a.clone()),
+            )),
+            Box::new(a.clone()),
+        );
+
+        check!(simplify(&formula) == a.clone());
+
+        // (a|(a^b))
+        let formula = PropositionalFormula::disjunction(
+            Box::new(a.clone()),
+            Box::new(PropositionalFormula::conjunction(
+                Box::new(a.clone()),
+                Box::new(b),
+            )),
+        );
+
+        check!(simplify(&formula) == a);
+    }
+
+    #[test]
+    fn test_constant_folding() {
+        let a = PropositionalFormula::variable(Variable::new("a"));
+        let not_a = PropositionalFormula::negated(Box::new(a.clone()));
+
+        check!(simplify(&crate::formula!(a ^ 1)) == a.clone());
+        check!(simplify(&crate::formula!(a ^ (-1))) == PropositionalFormula::Bottom);
+        check!(simplify(&crate::formula!((a | 0) -> 0)) == not_a);
+        check!(simplify(&crate::formula!(0 <-> (-a))) == a);
+        check!(simplify(&crate::formula!((a -> 1) <-> (b | 1))) == PropositionalFormula::Top);
+    }
+
+    #[test]
+    fn test_nested() {
+        // (c->(-(-(a^a))))
+        let a = PropositionalFormula::variable(Variable::new("a"));
+        let c = PropositionalFormula::variable(Variable::new("c"));
+        let formula = PropositionalFormula::implication(
+            Box::new(c.clone()),
+            Box::new(PropositionalFormula::negated(Box::new(
+                PropositionalFormula::negated(Box::new(PropositionalFormula::conjunction(
+                    Box::new(a.clone()),
+                    Box::new(a.clone()),
+                ))),
+            ))),
+        );
+
+        check!(simplify(&formula) == PropositionalFormula::implication(Box::new(c), Box::new(a)));
+    }
+}
@@ -0,0 +1,166 @@
+//! Substitution of truth values for propositional variables.
+
+use super::{Assignment, PropositionalFormula};
+
+/// What remains of a formula after substituting truth values for some of its variables.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Residual {
+    /// The formula has become the constant `true` or `false`.
+    Constant(bool),
+    /// The formula still depends on unassigned variables.
+    Formula(PropositionalFormula),
+}
+
+/// Substitute the truth values of the `assignment` for the variables of the formula, and
+/// propagate the resulting constants upwards.
+///
+/// Constants are eliminated by the rewrite rules (with `T` for true and `F` for false):
+///
+/// | Form                  | Residual                              |
+/// | --------------------- | ------------------------------------- |
+/// | `(-T)`, `(-F)`        | `F`, `T`                              |
+/// | `(T^A)`, `(F^A)`      | `A`, `F`                              |
+/// | <code>(T\|A)</code>, <code>(F\|A)</code> | `T`, `A`           |
+/// | `(T->A)`, `(F->A)`    | `A`, `T`                              |
+/// | `(A->T)`, `(A->F)`    | `T`, `(-A)`                           |
+/// | `(T<->A)`, `(F<->A)`  | `A`, `(-A)`                           |
+///
+/// where the operands of `^`, `|` and `<->` may also be swapped. Negating a negation strips it
+/// instead of adding a double negation.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::formula::{
+///     substitute, Assignment, PropositionalFormula, Residual, Variable,
+/// };
+/// let formula = PropositionalFormula::implication(
+///     Box::new(PropositionalFormula::variable(Variable::new("p"))),
+///     Box::new(PropositionalFormula::variable(Variable::new("q"))),
+/// );
+///
+/// let mut assignment = Assignment::new();
+/// assignment.insert(Variable::new("p"), true);
+///
+/// assert_eq!(
+///     Residual::Formula(PropositionalFormula::variable(Variable::new("q"))),
+///     substitute(&formula, &assignment)
+/// );
+/// ```
+pub fn substitute(formula: &PropositionalFormula, assignment: &Assignment) -> Residual {
+    use Residual::{Constant, Formula};
+
+    match formula {
+        PropositionalFormula::Variable(v) => match assignment.get(v) {
+            Some(&value) => Constant(value),
+            None => Formula(formula.clone()),
+        },
+        PropositionalFormula::Top => Constant(true),
+        PropositionalFormula::Bottom => Constant(false),
+        PropositionalFormula::Negation(a) => negate(substitute(a, assignment)),
+        PropositionalFormula::Conjunction(a, b) => {
+            match (substitute(a, assignment), substitute(b, assignment)) {
+                (Constant(false), _) | (_, Constant(false)) => Constant(false),
+                (Constant(true), x) | (x, Constant(true)) => x,
+                (Formula(a), Formula(b)) => {
+                    Formula(PropositionalFormula::conjunction(Box::new(a), Box::new(b)))
+                }
+            }
+        }
+        PropositionalFormula::Disjunction(a, b) => {
+            match (substitute(a, assignment), substitute(b, assignment)) {
+                (Constant(true), _) | (_, Constant(true)) => Constant(true),
+                (Constant(false), x) | (x, Constant(false)) => x,
+                (Formula(a), Formula(b)) => {
+                    Formula(PropositionalFormula::disjunction(Box::new(a), Box::new(b)))
+                }
+            }
+        }
+        PropositionalFormula::Implication(a, b) => {
+            match (substitute(a, assignment), substitute(b, assignment)) {
+                (Constant(false), _) | (_, Constant(true)) => Constant(true),
+                (Constant(true), x) => x,
+                (x, Constant(false)) => negate(x),
+                (Formula(a), Formula(b)) => {
+                    Formula(PropositionalFormula::implication(Box::new(a), Box::new(b)))
+                }
+            }
+        }
+        PropositionalFormula::Biimplication(a, b) => {
+            match (substitute(a, assignment), substitute(b, assignment)) {
+                (Constant(true), x) | (x, Constant(true)) => x,
+                (Constant(false), x) | (x, Constant(false)) => negate(x),
+                (Formula(a), Formula(b)) => Formula(PropositionalFormula::biimplication(
+                    Box::new(a),
+                    Box::new(b),
+                )),
+            }
+        }
+    }
+}
+
+fn negate(residual: Residual) -> Residual {
+    match residual {
+        Residual::Constant(value) => Residual::Constant(!value),
+        Residual::Formula(formula) => Residual::Formula(
+            formula
+                .strip_negation()
+                .unwrap_or_else(|formula| PropositionalFormula::negated(Box::new(formula))),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formula::Variable;
+    use assert2::check;
+
+    fn var(name: &str) -> PropositionalFormula {
+        PropositionalFormula::variable(Variable::new(name))
+    }
+
+    #[test]
+    fn test_satisfied_disjunction() {
+        let formula = PropositionalFormula::disjunction(Box::new(var("p")), Box::new(var("q")));
+        let mut assignment = Assignment::new();
+        assignment.insert(Variable::new("p"), true);
+
+        check!(substitute(&formula, &assignment) == Residual::Constant(true));
+    }
+
+    #[test]
+    fn test_falsified_consequent() {
+        // ((-p)->q) with q := false leaves p.
+        let formula = PropositionalFormula::implication(
+            Box::new(PropositionalFormula::negated(Box::new(var("p")))),
+            Box::new(var("q")),
+        );
+        let mut assignment = Assignment::new();
+        assignment.insert(Variable::new("q"), false);
+
+        check!(substitute(&formula, &assignment) == Residual::Formula(var("p")));
+    }
+
+    #[test]
+    fn test_biimplication_with_false() {
+        // ((p^q)<->r) with r := false leaves (-(p^q)).
+        let conjunction = PropositionalFormula::conjunction(Box::new(var("p")), Box::new(var("q")));
+        let formula =
+            PropositionalFormula::biimplication(Box::new(conjunction.clone()), Box::new(var("r")));
+        let mut assignment = Assignment::new();
+        assignment.insert(Variable::new("r"), false);
+
+        check!(
+            substitute(&formula, &assignment)
+                == Residual::Formula(PropositionalFormula::negated(Box::new(conjunction)))
+        );
+    }
+
+    #[test]
+    fn test_unassigned_formula_is_unchanged() {
+        let formula = PropositionalFormula::conjunction(Box::new(var("p")), Box::new(var("q")));
+
+        check!(substitute(&formula, &Assignment::new()) == Residual::Formula(formula.clone()));
+    }
+}
@@ -0,0 +1,129 @@
+//! Interned variable names, see [`Symbol`].
+//!
+//! Every distinct name is stored once, in a process-wide table, and is never freed. Variables
+//! hold a [`Symbol`] pointing to that copy, so cloning, comparing and hashing them never touches
+//! the characters of the name.
+//!
+//! [`Symbol`]: struct.Symbol.html
+
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::{OnceLock, RwLock};
+
+/// Handle to a name in the global symbol table. Equal names get the same symbol, pointing to the
+/// one copy of the name in the table, so symbols are compared for equality and hashed by address
+/// and reading their name takes no lock. They are ordered by their names, though, so that
+/// anything sorted by variable stays in the same order regardless of which names were interned
+/// first.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::formula::symbol::Symbol;
+/// let a = Symbol::intern("a");
+/// assert_eq!(a, Symbol::intern(&String::from("a")));
+/// assert_eq!("a", a.as_str());
+/// assert!(Symbol::intern("b") > a);
+/// ```
+#[derive(Clone, Copy)]
+pub struct Symbol(&'static str);
+
+fn table() -> &'static RwLock<HashSet<&'static str>> {
+    static TABLE: OnceLock<RwLock<HashSet<&'static str>>> = OnceLock::new();
+    TABLE.get_or_init(Default::default)
+}
+
+impl Symbol {
+    /// Get the symbol of the `name`, adding the name to the table if it is new.
+    pub fn intern(name: &str) -> Self {
+        // PANIC: cannot panic because the lock is never held while panicking.
+        if let Some(&name) = table().read().unwrap().get(name) {
+            return Self(name);
+        }
+
+        let mut table = table().write().unwrap();
+        // Another thread may have interned the name in the meantime.
+        if let Some(&name) = table.get(name) {
+            return Self(name);
+        }
+        let name: &'static str = Box::leak(name.into());
+        table.insert(name);
+        Self(name)
+    }
+
+    /// Get the name of the symbol.
+    pub fn as_str(self) -> &'static str {
+        self.0
+    }
+}
+
+impl PartialEq for Symbol {
+    fn eq(&self, other: &Self) -> bool {
+        // Names are interned, so equal names are the same string.
+        std::ptr::eq(self.0, other.0)
+    }
+}
+
+impl Eq for Symbol {}
+
+impl Hash for Symbol {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.as_ptr().hash(state)
+    }
+}
+
+impl PartialOrd for Symbol {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Symbol {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if self == other {
+            return Ordering::Equal;
+        }
+        self.0.cmp(other.0)
+    }
+}
+
+impl fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Symbol").field(&self.as_str()).finish()
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert2::check;
+    use std::thread;
+
+    #[test]
+    fn test_intern_across_threads() {
+        let symbols: Vec<Vec<Symbol>> = (0..4)
+            .map(|_| {
+                thread::spawn(|| {
+                    (0..100)
+                        .map(|i| Symbol::intern(&format!("symbol{}", i)))
+                        .collect()
+                })
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|thread| thread.join().unwrap())
+            .collect();
+
+        check!(symbols.iter().all(|s| *s == symbols[0]));
+        check!(symbols[0][42].as_str() == "symbol42");
+        check!(symbols[0][10] < symbols[0][9]);
+    }
+}
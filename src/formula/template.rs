@@ -0,0 +1,219 @@
+//! Formula templates with placeholders, for stamping out families of structurally identical
+//! formulas.
+
+use std::collections::{HashMap, HashSet};
+
+use super::{PropositionalFormula, Variable};
+
+/// A propositional formula in which some variables are placeholders to be replaced by formulas.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+/// use libprop_sat_solver::formula::{PropositionalFormula, Template, Variable};
+/// // (X->Y)
+/// let template = Template::new(
+///     PropositionalFormula::implication(
+///         Box::new(PropositionalFormula::variable(Variable::new("X"))),
+///         Box::new(PropositionalFormula::variable(Variable::new("Y"))),
+///     ),
+///     &["X", "Y"],
+/// )
+/// .unwrap();
+///
+/// let a = PropositionalFormula::variable(Variable::new("a"));
+/// let not_a = PropositionalFormula::negated(Box::new(a.clone()));
+///
+/// assert_eq!(
+///     "(a->(-a))",
+///     template.apply(&[a.clone(), not_a.clone()]).unwrap().to_string()
+/// );
+///
+/// let mut bindings = HashMap::new();
+/// bindings.insert(Variable::new("X"), not_a);
+/// bindings.insert(Variable::new("Y"), a);
+/// assert_eq!("((-a)->a)", template.instantiate(&bindings).unwrap().to_string());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    pattern: PropositionalFormula,
+    placeholders: Vec<Variable>,
+}
+
+impl Template {
+    /// Construct a template from a pattern in which the variables named by `placeholders` are
+    /// placeholders. All other variables of the pattern are kept as they are when instantiating.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a placeholder is listed twice or does not occur in the pattern.
+    pub fn new<S: AsRef<str>>(
+        pattern: PropositionalFormula,
+        placeholders: &[S],
+    ) -> Result<Self, String> {
+        let variables: HashSet<&Variable> = pattern.variables().into_iter().collect();
+        let mut seen = HashSet::new();
+
+        for name in placeholders {
+            let name = name.as_ref();
+            if !seen.insert(name) {
+                return Err(format!("placeholder listed more than once: {:?}", name));
+            }
+            if !variables.contains(&Variable::new(name)) {
+                return Err(format!(
+                    "placeholder does not occur in the pattern: {:?}",
+                    name
+                ));
+            }
+        }
+
+        let placeholders = placeholders
+            .iter()
+            .map(|name| Variable::new(name.as_ref()))
+            .collect();
+
+        Ok(Self {
+            pattern,
+            placeholders,
+        })
+    }
+
+    /// Get the placeholders, in the order given on construction.
+    pub fn placeholders(&self) -> &[Variable] {
+        &self.placeholders
+    }
+
+    /// Number of placeholders.
+    pub fn arity(&self) -> usize {
+        self.placeholders.len()
+    }
+
+    /// Replace each placeholder by the formula bound to it.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a placeholder is unbound, or if a variable which is not a placeholder is bound.
+    pub fn instantiate(
+        &self,
+        bindings: &HashMap<Variable, PropositionalFormula>,
+    ) -> Result<PropositionalFormula, String> {
+        if let Some(unbound) = self.placeholders.iter().find(|p| !bindings.contains_key(p)) {
+            return Err(format!("unbound placeholder: {:?}", unbound.name()));
+        }
+
+        if let Some(unknown) = bindings.keys().find(|v| !self.placeholders.contains(v)) {
+            return Err(format!("not a placeholder: {:?}", unknown.name()));
+        }
+
+        Ok(replace(&self.pattern, bindings))
+    }
+
+    /// Replace the placeholders by the `arguments`, in the order the placeholders were given on
+    /// construction.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the number of arguments differs from the [arity] of the template.
+    ///
+    /// [arity]: #method.arity
+    pub fn apply(
+        &self,
+        arguments: &[PropositionalFormula],
+    ) -> Result<PropositionalFormula, String> {
+        if arguments.len() != self.arity() {
+            return Err(format!(
+                "expected {} arguments, got {}",
+                self.arity(),
+                arguments.len()
+            ));
+        }
+
+        let bindings = self
+            .placeholders
+            .iter()
+            .cloned()
+            .zip(arguments.iter().cloned())
+            .collect();
+
+        Ok(replace(&self.pattern, &bindings))
+    }
+}
+
+/// Replace each bound variable of the formula by the formula bound to it.
+pub(crate) fn replace(
+    formula: &PropositionalFormula,
+    bindings: &HashMap<Variable, PropositionalFormula>,
+) -> PropositionalFormula {
+    let replace_sub = |f: &PropositionalFormula| Box::new(replace(f, bindings));
+
+    match formula {
+        PropositionalFormula::Variable(v) => match bindings.get(v) {
+            Some(replacement) => replacement.clone(),
+            None => formula.clone(),
+        },
+        PropositionalFormula::Top | PropositionalFormula::Bottom => formula.clone(),
+        PropositionalFormula::Negation(a) => PropositionalFormula::Negation(replace_sub(a)),
+        PropositionalFormula::Conjunction(a, b) => {
+            PropositionalFormula::Conjunction(replace_sub(a), replace_sub(b))
+        }
+        PropositionalFormula::Disjunction(a, b) => {
+            PropositionalFormula::Disjunction(replace_sub(a), replace_sub(b))
+        }
+        PropositionalFormula::Implication(a, b) => {
+            PropositionalFormula::Implication(replace_sub(a), replace_sub(b))
+        }
+        PropositionalFormula::Biimplication(a, b) => {
+            PropositionalFormula::Biimplication(replace_sub(a), replace_sub(b))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert2::check;
+
+    fn var(name: &str) -> PropositionalFormula {
+        PropositionalFormula::variable(Variable::new(name))
+    }
+
+    /// `((X^c)|X)`
+    fn template() -> Template {
+        let pattern = PropositionalFormula::disjunction(
+            Box::new(PropositionalFormula::conjunction(
+                Box::new(var("X")),
+                Box::new(var("c")),
+            )),
+            Box::new(var("X")),
+        );
+        Template::new(pattern, &["X"]).unwrap()
+    }
+
+    #[test]
+    fn test_every_occurrence_is_replaced() {
+        let argument = PropositionalFormula::negated(Box::new(var("a")));
+        let formula = template().apply(&[argument]).unwrap();
+
+        check!(formula.to_string() == "(((-a)^c)|(-a))");
+    }
+
+    #[test]
+    fn test_invalid_placeholders() {
+        check!(Template::new(var("X"), &["X", "X"]).is_err());
+        check!(Template::new(var("X"), &["Y"]).is_err());
+    }
+
+    #[test]
+    fn test_invalid_arguments() {
+        let template = template();
+        check!(template.apply(&[]).is_err());
+
+        let mut bindings = HashMap::new();
+        bindings.insert(Variable::new("c"), var("a"));
+        check!(template.instantiate(&bindings).is_err());
+
+        bindings.insert(Variable::new("X"), var("a"));
+        check!(template.instantiate(&bindings).is_err());
+    }
+}
@@ -0,0 +1,93 @@
+//! Equivalence-preserving rewrites of propositional formulas.
+
+use super::PropositionalFormula;
+
+/// Push the negations of a propositional formula inwards until each one applies to a variable,
+/// by De Morgan's laws and their analogues:
+///
+/// | Form                   | Rewritten                     |
+/// | ---------------------- | ----------------------------- |
+/// | `(-(-A))`              | `A`                           |
+/// | `(-(A^B))`             | <code>((-A)\|(-B))</code>     |
+/// | <code>(-(A\|B))</code> | `((-A)^(-B))`                 |
+/// | `(A->B)`               | <code>((-A)\|B)</code>        |
+/// | `(-(A->B))`            | `(A^(-B))`                    |
+/// | `(-(A<->B))`           | `(A<->(-B))`                  |
+/// | `(-1)`, `(-0)`         | `0`, `1`                      |
+///
+/// where the negations on the right are pushed further in turn. The result is no more than a
+/// constant factor larger than the formula. Biimplications are kept rather than unfolded, which
+/// could blow up the formula; expanding them on a tableau still introduces negated operands, but
+/// no other expansion does.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::formula::transform::push_negations;
+/// use libprop_sat_solver::formula::PropositionalFormula;
+/// let formula: PropositionalFormula = "(-((a|(-b))->(-(c->1))))".parse().unwrap();
+///
+/// assert_eq!("((a|(-b))^((-c)|1))", push_negations(&formula).to_string());
+/// ```
+pub fn push_negations(formula: &PropositionalFormula) -> PropositionalFormula {
+    push(formula, false)
+}
+
+/// Push negations into the formula, or into its negation if `negated`.
+fn push(formula: &PropositionalFormula, negated: bool) -> PropositionalFormula {
+    let operand = |a: &PropositionalFormula, negated| Box::new(push(a, negated));
+
+    match formula {
+        PropositionalFormula::Variable(_) if negated => {
+            PropositionalFormula::negated(Box::new(formula.clone()))
+        }
+        PropositionalFormula::Variable(_) => formula.clone(),
+        PropositionalFormula::Top if negated => PropositionalFormula::Bottom,
+        PropositionalFormula::Bottom if negated => PropositionalFormula::Top,
+        PropositionalFormula::Top | PropositionalFormula::Bottom => formula.clone(),
+        PropositionalFormula::Negation(a) => push(a, !negated),
+        PropositionalFormula::Conjunction(a, b) if negated => {
+            PropositionalFormula::disjunction(operand(a, true), operand(b, true))
+        }
+        PropositionalFormula::Conjunction(a, b) => {
+            PropositionalFormula::conjunction(operand(a, false), operand(b, false))
+        }
+        PropositionalFormula::Disjunction(a, b) if negated => {
+            PropositionalFormula::conjunction(operand(a, true), operand(b, true))
+        }
+        PropositionalFormula::Disjunction(a, b) => {
+            PropositionalFormula::disjunction(operand(a, false), operand(b, false))
+        }
+        PropositionalFormula::Implication(a, b) if negated => {
+            PropositionalFormula::conjunction(operand(a, false), operand(b, true))
+        }
+        PropositionalFormula::Implication(a, b) => {
+            PropositionalFormula::disjunction(operand(a, true), operand(b, false))
+        }
+        PropositionalFormula::Biimplication(a, b) => {
+            PropositionalFormula::biimplication(operand(a, false), operand(b, negated))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{equivalence_counterexample, SolverConfig};
+    use crate::testing::formulas;
+    use assert2::check;
+
+    #[test]
+    fn test_push_negations() {
+        for formula in formulas(4, 5, 13, 200) {
+            let pushed = push_negations(&formula);
+
+            check!(equivalence_counterexample(&formula, &pushed, &SolverConfig::default()) == None);
+            check!(pushed.iter_preorder().all(|f| match f {
+                PropositionalFormula::Negation(a) =>
+                    matches!(**a, PropositionalFormula::Variable(_)),
+                _ => true,
+            }));
+        }
+    }
+}
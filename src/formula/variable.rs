@@ -1,22 +1,76 @@
 //! Propositional variable.
 
-/// A propositional formula variable.
-#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd)]
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+
+use super::symbol::Symbol;
+use crate::parser::variable::is_identifier;
+
+/// A (partial) truth assignment to propositional variables.
+pub type Assignment = HashMap<Variable, bool>;
+
+/// A propositional formula variable. Its name is interned, see [`Symbol`], so variables are
+/// cheap to clone and compare; they are ordered by name.
+///
+/// [`Symbol`]: ../symbol/struct.Symbol.html
+#[derive(Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Variable {
-    name: String,
+    name: Symbol,
 }
 
 impl Variable {
     /// Construct a new propositional variable from a given `name`.
     pub fn new<S>(name: S) -> Self
     where
-        S: Into<String>,
+        S: AsRef<str>,
     {
-        Self { name: name.into() }
+        Self {
+            name: Symbol::intern(name.as_ref()),
+        }
     }
 
     /// Get the name of the propositional variable.
     pub fn name(&self) -> &str {
-        &self.name
+        self.name.as_str()
+    }
+
+    /// Get the interned name of the propositional variable.
+    pub fn symbol(&self) -> Symbol {
+        self.name
+    }
+
+    /// Get the name as written in formulas: as it is if it is a plain identifier, and otherwise in
+    /// double quotes with `\` and `"` escaped, e.g. `"state 0"`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libprop_sat_solver::formula::Variable;
+    /// assert_eq!("x1", Variable::new("x1").quoted_name());
+    /// assert_eq!(r#""say \"hi\"""#, Variable::new("say \"hi\"").quoted_name());
+    /// assert_eq!(r#""not""#, Variable::new("not").quoted_name());
+    /// ```
+    pub fn quoted_name(&self) -> Cow<'_, str> {
+        let name = self.name();
+        if is_identifier(name) {
+            return Cow::Borrowed(name);
+        }
+        let escaped = name.replace('\\', "\\\\").replace('"', "\\\"");
+        Cow::Owned(format!("\"{}\"", escaped))
+    }
+}
+
+impl fmt::Debug for Variable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Variable")
+            .field("name", &self.name())
+            .finish()
+    }
+}
+
+impl fmt::Display for Variable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
     }
 }
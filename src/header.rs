@@ -0,0 +1,252 @@
+//! Per-file solver options, given by a header line at the top of an input file:
+//!
+//! ```text
+//! #! engine=dpll truth-table-max-vars=0 mode=v
+//! (a->(b->a))
+//! ```
+//!
+//! Each option is a `<flag>=<value>` pair named after the command line flag it stands for, so that
+//! benchmark suites can carry their intended configuration with them. Options given explicitly on
+//! the command line take precedence over the header.
+
+use std::fs;
+use std::io::{self, prelude::*};
+use std::path::Path;
+use std::time::Duration;
+
+use log::error;
+
+use libprop_sat_solver::solver::{Engine, SolverConfig};
+use libprop_sat_solver::tableaux_solver::{BranchHeuristic, SearchStrategy};
+
+/// Prefix marking the first line of an input file as a header instead of a formula.
+pub const PREFIX: &str = "#!";
+
+/// Solver options set by a header line. Options the header does not mention are `None`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FileOptions {
+    pub mode: Option<char>,
+    pub preprocess: Option<bool>,
+    pub eliminate_blocked_clauses: Option<bool>,
+    pub engine: Option<Engine>,
+    pub branch_heuristic: Option<BranchHeuristic>,
+    pub search_strategy: Option<SearchStrategy>,
+    pub eliminate_definitions: Option<bool>,
+    pub push_negations: Option<bool>,
+    pub tableau_threads: Option<usize>,
+    pub truth_table_max_vars: Option<usize>,
+    pub timeout: Option<Duration>,
+}
+
+/// Whether the line at `index` of an input file is its header.
+pub fn is_header(index: usize, line: &str) -> bool {
+    index == 0 && line.starts_with(PREFIX)
+}
+
+/// Parse the options of a header line, which must start with [`PREFIX`].
+///
+/// # Errors
+///
+/// Fails on options which are not `<flag>=<value>` pairs, unknown flags and malformed values.
+///
+/// [`PREFIX`]: constant.PREFIX.html
+pub fn parse(line: &str) -> Result<FileOptions, String> {
+    let options = line
+        .strip_prefix(PREFIX)
+        .ok_or_else(|| format!("header must start with `{}`", PREFIX))?;
+
+    let mut file_options = FileOptions::default();
+    for option in options.split_whitespace() {
+        let (flag, value) = match option.find('=') {
+            Some(i) => (&option[..i], &option[i + 1..]),
+            None => return Err(format!("expected `<flag>=<value>`, found {:?}", option)),
+        };
+
+        match flag {
+            "mode" => file_options.mode = Some(parse_mode(value)?),
+            "preprocess" => file_options.preprocess = Some(parse_bool(value)?),
+            "eliminate-blocked-clauses" => {
+                file_options.eliminate_blocked_clauses = Some(parse_bool(value)?)
+            }
+            "engine" => file_options.engine = Some(value.parse()?),
+            "branch-heuristic" => file_options.branch_heuristic = Some(value.parse()?),
+            "search-strategy" | "strategy" => file_options.search_strategy = Some(value.parse()?),
+            "eliminate-definitions" => {
+                file_options.eliminate_definitions = Some(parse_bool(value)?)
+            }
+            "push-negations" => file_options.push_negations = Some(parse_bool(value)?),
+            "tableau-threads" => file_options.tableau_threads = Some(parse_number(value)?),
+            "truth-table-max-vars" => {
+                file_options.truth_table_max_vars = Some(parse_number(value)?)
+            }
+            "timeout" => file_options.timeout = Some(parse_duration(value)?),
+            _ => return Err(format!("unknown option {:?}", flag)),
+        }
+    }
+
+    Ok(file_options)
+}
+
+/// Render a header line setting the options of the `config`, such that [`parse`] reads them back.
+///
+/// [`parse`]: fn.parse.html
+pub fn render(config: &SolverConfig) -> String {
+    let preprocess = config.preprocessing.is_some();
+    let eliminate_blocked_clauses = config
+        .preprocessing
+        .is_some_and(|options| options.blocked_clause_elimination);
+
+    format!(
+        "{} engine={} branch-heuristic={} search-strategy={} tableau-threads={} \
+         truth-table-max-vars={} preprocess={} eliminate-blocked-clauses={} \
+         eliminate-definitions={} push-negations={}",
+        PREFIX,
+        config.engine,
+        config.branch_heuristic,
+        config.search_strategy,
+        config.tableau_threads,
+        config.truth_table_max_variables,
+        u8::from(preprocess),
+        u8::from(eliminate_blocked_clauses),
+        u8::from(config.eliminate_definitions),
+        u8::from(config.push_negations),
+    )
+}
+
+fn parse_mode(value: &str) -> Result<char, String> {
+    match value {
+        "s" | "v" => Ok(value.chars().next().unwrap()),
+        _ => Err(format!("unknown mode {:?}", value)),
+    }
+}
+
+fn parse_bool(value: &str) -> Result<bool, String> {
+    match value {
+        "1" | "true" => Ok(true),
+        "0" | "false" => Ok(false),
+        _ => Err(format!("expected a boolean, found {:?}", value)),
+    }
+}
+
+fn parse_number(value: &str) -> Result<usize, String> {
+    value
+        .parse()
+        .map_err(|_| format!("expected a number, found {:?}", value))
+}
+
+/// Parse a duration in seconds (`2`, `2s`) or milliseconds (`500ms`), as taken by `--timeout`.
+///
+/// # Errors
+///
+/// Fails on anything but a whole number with one of these units.
+pub fn parse_duration(value: &str) -> Result<Duration, String> {
+    let (number, from_number): (_, fn(u64) -> Duration) = if let Some(ms) = value.strip_suffix("ms")
+    {
+        (ms, Duration::from_millis)
+    } else {
+        (
+            value.strip_suffix('s').unwrap_or(value),
+            Duration::from_secs,
+        )
+    };
+
+    number.parse().map(from_number).map_err(|_| {
+        format!(
+            "expected a duration such as `2s` or `500ms`, found {:?}",
+            value
+        )
+    })
+}
+
+/// Read the options of the header of the input file at `path`, if it has one.
+///
+/// # Errors
+///
+/// If the file cannot be read then the program aborts with exit code `5`; if the header is
+/// malformed then the program aborts with exit code `22`.
+pub fn read(path: &Path) -> Option<FileOptions> {
+    let first_line =
+        fs::File::open(path).and_then(|file| io::BufReader::new(file).lines().next().transpose());
+
+    match first_line {
+        Ok(Some(line)) if is_header(0, &line) => match parse(&line) {
+            Ok(options) => Some(options),
+            Err(e) => {
+                error!("ill-formed header in {:?}: {}", path, e);
+                std::process::exit(22);
+            }
+        },
+        Ok(_) => None,
+        Err(e) => {
+            error!("failed to read {:?}: {}", path, e);
+            std::process::exit(5);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert2::check;
+
+    #[test]
+    fn test_parse() {
+        let options = parse("#! engine=dpll mode=v  preprocess=1 truth-table-max-vars=0").unwrap();
+        check!(
+            options
+                == FileOptions {
+                    mode: Some('v'),
+                    preprocess: Some(true),
+                    engine: Some(Engine::Dpll),
+                    truth_table_max_vars: Some(0),
+                    ..FileOptions::default()
+                }
+        );
+
+        check!(
+            parse("#! timeout=2s engine=tableau strategy=dfs").unwrap()
+                == FileOptions {
+                    timeout: Some(Duration::from_secs(2)),
+                    engine: Some(Engine::Tableau),
+                    search_strategy: Some(SearchStrategy::DepthFirst),
+                    ..FileOptions::default()
+                }
+        );
+
+        check!(parse("#!").unwrap() == FileOptions::default());
+        check!(parse("#! timeout=500ms").unwrap().timeout == Some(Duration::from_millis(500)));
+        check!(parse("#! timeout=2m").is_err());
+        check!(parse("#! engine").is_err());
+        check!(parse("#! engine=cdcl").is_err());
+        check!(parse("#! tableau-threads=many").is_err());
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        check!(parse_duration("2") == Ok(Duration::from_secs(2)));
+        check!(parse_duration("2s") == Ok(Duration::from_secs(2)));
+        check!(parse_duration("500ms") == Ok(Duration::from_millis(500)));
+        check!(parse_duration("1.5s").is_err());
+        check!(parse_duration("s").is_err());
+    }
+
+    #[test]
+    fn test_render() {
+        let config = SolverConfig {
+            engine: Engine::Dpll,
+            search_strategy: SearchStrategy::BestFirst,
+            tableau_threads: 4,
+            ..SolverConfig::default()
+        };
+        let options = parse(&render(&config)).unwrap();
+
+        check!(options.engine == Some(Engine::Dpll));
+        check!(options.branch_heuristic == Some(config.branch_heuristic));
+        check!(options.search_strategy == Some(SearchStrategy::BestFirst));
+        check!(options.tableau_threads == Some(4));
+        check!(options.truth_table_max_vars == Some(config.truth_table_max_variables));
+        check!(options.preprocess == Some(false));
+        check!(options.eliminate_definitions == Some(false));
+        check!(options.push_negations == Some(false));
+    }
+}
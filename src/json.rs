@@ -0,0 +1,325 @@
+//! Minimal parser for JSON documents, enough to read back the JSON this crate writes (e.g. tableau
+//! proofs, see [`proof`]).
+//!
+//! [`proof`]: ../tableaux_solver/proof/index.html
+
+/// Deepest nesting of arrays and objects accepted by [`parse_json`]. The parser is recursive, so
+/// deeper documents are rejected rather than overflowing the stack.
+///
+/// [`parse_json`]: fn.parse_json.html
+pub const MAX_NESTING_DEPTH: usize = 256;
+
+/// A JSON value. Object members are kept in document order.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Get the member of an object with the given key, if this is an object and has one.
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(members) => members
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    /// Get the string, if this is a string.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Get the number as a non-negative integer, if this is one.
+    pub fn as_usize(&self) -> Option<usize> {
+        match self {
+            Json::Number(n) if *n >= 0.0 && n.fract() == 0.0 => Some(*n as usize),
+            _ => None,
+        }
+    }
+
+    /// Get the elements, if this is an array.
+    pub fn as_array(&self) -> Option<&[Json]> {
+        match self {
+            Json::Array(elements) => Some(elements),
+            _ => None,
+        }
+    }
+}
+
+/// Parse a whole JSON document, ignoring surrounding whitespace.
+///
+/// # Errors
+///
+/// Fails if the input is not a single well-formed JSON value, or if its arrays and objects are
+/// nested deeper than [`MAX_NESTING_DEPTH`].
+///
+/// [`MAX_NESTING_DEPTH`]: constant.MAX_NESTING_DEPTH.html
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::json::{parse_json, Json};
+/// let json = parse_json(r#"{ "a": [1, "x\"y", null] }"#).unwrap();
+/// assert_eq!(
+///     Some(&Json::Array(vec![Json::Number(1.0), Json::String("x\"y".to_string()), Json::Null])),
+///     json.get("a")
+/// );
+/// ```
+pub fn parse_json(input: &str) -> Result<Json, String> {
+    let mut parser = Parser {
+        input: input.as_bytes(),
+        position: 0,
+        depth: 0,
+    };
+
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    if parser.position < parser.input.len() {
+        return Err(parser.error("trailing input"));
+    }
+    Ok(value)
+}
+
+struct Parser<'a> {
+    input: &'a [u8],
+    position: usize,
+    /// Number of arrays and objects the parser is in.
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> String {
+        format!("{} at byte {}", message, self.position)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ') | Some(b'\t') | Some(b'\n') | Some(b'\r') = self.peek() {
+            self.position += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.position).copied()
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), String> {
+        self.skip_whitespace();
+        if self.peek() == Some(byte) {
+            self.position += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected `{}`", byte as char)))
+        }
+    }
+
+    fn keyword(&mut self, keyword: &str, value: Json) -> Result<Json, String> {
+        if self.input[self.position..].starts_with(keyword.as_bytes()) {
+            self.position += keyword.len();
+            Ok(value)
+        } else {
+            Err(self.error("unexpected token"))
+        }
+    }
+
+    fn value(&mut self) -> Result<Json, String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'{') => self.nested(Self::object),
+            Some(b'[') => self.nested(Self::array),
+            Some(b'"') => self.string().map(Json::String),
+            Some(b'n') => self.keyword("null", Json::Null),
+            Some(b't') => self.keyword("true", Json::Bool(true)),
+            Some(b'f') => self.keyword("false", Json::Bool(false)),
+            Some(b'-') | Some(b'0'..=b'9') => self.number(),
+            Some(_) => Err(self.error("unexpected character")),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    /// Parse an array or object with `parse`, one level deeper.
+    fn nested(&mut self, parse: fn(&mut Self) -> Result<Json, String>) -> Result<Json, String> {
+        if self.depth == MAX_NESTING_DEPTH {
+            return Err(self.error(&format!("nested more than {} deep", MAX_NESTING_DEPTH)));
+        }
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn object(&mut self) -> Result<Json, String> {
+        self.expect(b'{')?;
+        let mut members = Vec::new();
+
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.position += 1;
+            return Ok(Json::Object(members));
+        }
+
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.expect(b':')?;
+            members.push((key, self.value()?));
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.position += 1,
+                Some(b'}') => {
+                    self.position += 1;
+                    return Ok(Json::Object(members));
+                }
+                _ => return Err(self.error("expected `,` or `}`")),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Json, String> {
+        self.expect(b'[')?;
+        let mut elements = Vec::new();
+
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.position += 1;
+            return Ok(Json::Array(elements));
+        }
+
+        loop {
+            elements.push(self.value()?);
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.position += 1,
+                Some(b']') => {
+                    self.position += 1;
+                    return Ok(Json::Array(elements));
+                }
+                _ => return Err(self.error("expected `,` or `]`")),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect(b'"')?;
+        let mut bytes = Vec::new();
+
+        loop {
+            match self.peek() {
+                Some(b'"') => {
+                    self.position += 1;
+                    return String::from_utf8(bytes).map_err(|_| self.error("invalid UTF-8"));
+                }
+                Some(b'\\') => {
+                    self.position += 1;
+                    let escaped = match self.peek() {
+                        Some(b'"') => '"',
+                        Some(b'\\') => '\\',
+                        Some(b'/') => '/',
+                        Some(b'b') => '\u{8}',
+                        Some(b'f') => '\u{c}',
+                        Some(b'n') => '\n',
+                        Some(b'r') => '\r',
+                        Some(b't') => '\t',
+                        Some(b'u') => {
+                            let hex = self
+                                .input
+                                .get(self.position + 1..self.position + 5)
+                                .and_then(|hex| std::str::from_utf8(hex).ok())
+                                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                                .and_then(std::char::from_u32)
+                                .ok_or_else(|| self.error("invalid unicode escape"))?;
+                            self.position += 4;
+                            hex
+                        }
+                        _ => return Err(self.error("invalid escape")),
+                    };
+                    self.position += 1;
+                    let mut buffer = [0; 4];
+                    bytes.extend_from_slice(escaped.encode_utf8(&mut buffer).as_bytes());
+                }
+                Some(byte) => {
+                    self.position += 1;
+                    bytes.push(byte);
+                }
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<Json, String> {
+        let start = self.position;
+        while let Some(b'-') | Some(b'+') | Some(b'.') | Some(b'e') | Some(b'E')
+        | Some(b'0'..=b'9') = self.peek()
+        {
+            self.position += 1;
+        }
+
+        std::str::from_utf8(&self.input[start..self.position])
+            .ok()
+            .and_then(|number| number.parse().ok())
+            .map(Json::Number)
+            .ok_or_else(|| self.error("malformed number"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert2::check;
+
+    #[test]
+    fn test_parse_json() {
+        let json = parse_json(
+            " { \"a\" : [ true , false , -1.5e1 ] , \"b\" : { } , \"c\" : \"\\u0041\\n\" } ",
+        )
+        .unwrap();
+
+        check!(
+            json == Json::Object(vec![
+                (
+                    "a".to_string(),
+                    Json::Array(vec![
+                        Json::Bool(true),
+                        Json::Bool(false),
+                        Json::Number(-15.0)
+                    ])
+                ),
+                ("b".to_string(), Json::Object(vec![])),
+                ("c".to_string(), Json::String("A\n".to_string())),
+            ])
+        );
+
+        check!(parse_json("[1, 2").is_err());
+        check!(parse_json("[1] 2").is_err());
+        check!(parse_json("\"unterminated").is_err());
+        check!(parse_json("nul").is_err());
+    }
+
+    #[test]
+    fn test_nesting_depth_limit() {
+        let nested = |depth: usize| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        check!(parse_json(&nested(MAX_NESTING_DEPTH)).is_ok());
+        check!(
+            parse_json(&nested(MAX_NESTING_DEPTH + 1))
+                == Err(format!(
+                    "nested more than 256 deep at byte {}",
+                    MAX_NESTING_DEPTH
+                ))
+        );
+
+        check!(parse_json(&"[".repeat(200_000)).is_err());
+        check!(parse_json(&"{\"a\":".repeat(200_000)).is_err());
+    }
+}
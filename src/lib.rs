@@ -1,4 +1,14 @@
 //! Core propositional formula satisfiability solver using the tableaux construction method.
 
+pub mod cnf;
 pub mod formula;
+pub mod json;
+pub mod models;
+pub mod parser;
+pub mod qbf;
+pub mod session;
+pub mod solver;
 pub mod tableaux_solver;
+#[cfg(test)]
+mod testing;
+pub mod truth_table;
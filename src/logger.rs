@@ -18,7 +18,7 @@ pub fn setup(debug_mode: bool) {
         env::set_var("LOG", "trace");
     }
 
-    if let Err(_) = env::var("LOG") {
+    if env::var("LOG").is_err() {
         // Default to `info` level if the user did not specify.
         env::set_var("LOG", "INFO");
     }
@@ -1,17 +1,35 @@
-use std::path::PathBuf;
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 use colored::*;
 use log::{debug, error, info};
-use paw;
 use std::convert::TryFrom;
+use std::fmt;
 use std::fs;
 use std::io::{self, prelude::*};
 
-use libprop_sat_solver::formula::PropositionalFormula;
-use libprop_sat_solver::tableaux_solver::{is_satisfiable, is_valid};
+use libprop_sat_solver::formula::{substitute, Assignment, PropositionalFormula, Residual};
+use libprop_sat_solver::models;
+use libprop_sat_solver::parser::{self, ParserOptions};
+use libprop_sat_solver::solver::{self, Engine, SolverConfig};
+use libprop_sat_solver::tableaux_solver::context::{SharedContext, DEFAULT_MAX_BRANCHES};
+use libprop_sat_solver::tableaux_solver::{
+    proof, trace, BranchHeuristic, PreprocessingOptions, SearchStrategy, Tableau, TableauStats,
+    TimedOut,
+};
 
+use records::{OutputFormat, Record};
+
+pub mod commands;
+pub mod environment;
+pub mod header;
 pub mod logger;
-pub mod parser;
+pub mod pipeline;
+pub mod proofs;
+pub mod records;
+pub mod snapshots;
+pub mod watch;
 
 const VERSION: Option<&str> = option_env!("CARGO_PKG_VERSION");
 
@@ -33,11 +51,177 @@ pub struct Args {
     #[structopt(short = "m", long)]
     mode: Option<char>,
 
+    /// Preprocess formulas with bounded variable elimination before solving.
+    ///
+    /// Formulas are converted into an equisatisfiable CNF and variables with few occurrences are
+    /// eliminated by resolution before the remaining clauses are handed to the engine.
+    #[structopt(short = "p", long)]
+    preprocess: bool,
+
+    /// Also remove blocked clauses when preprocessing (implies `--preprocess`).
+    #[structopt(long)]
+    eliminate_blocked_clauses: bool,
+
+    /// Engine deciding formulas too large for a truth table: `tableau`, `dpll`, `interned` (the
+    /// tableau over hash-consed formulas), or `auto` to pick one for each formula. (OPTIONAL)
+    ///
+    /// Defaults to `tableau`.
+    #[structopt(long)]
+    engine: Option<Engine>,
+
+    /// Heuristic choosing which formula the tableau expands next: `arbitrary`, or `activity` to
+    /// prefer formulas whose expansion recently closed branches. (OPTIONAL)
+    ///
+    /// Defaults to `arbitrary`.
+    #[structopt(long)]
+    branch_heuristic: Option<BranchHeuristic>,
+
+    /// Order in which the tableau expands its branches: `breadth-first`, `depth-first` to finish
+    /// one branch before the next and keep few branches in memory, or `best-first` to prefer the
+    /// branches closest to being fully expanded, or their short names `bfs`, `dfs` and `best`.
+    /// (OPTIONAL)
+    ///
+    /// Defaults to `breadth-first`.
+    #[structopt(long)]
+    search_strategy: Option<SearchStrategy>,
+
+    /// Substitute away top-level definitions `(x<->A)`, where `x` does not occur in `A`, before
+    /// checking satisfiability.
+    #[structopt(long)]
+    eliminate_definitions: bool,
+
+    /// Push negations inwards until they only apply to variables before handing each formula to the
+    /// engine, sparing the tableau the rules for negated connectives.
+    #[structopt(long)]
+    push_negations: bool,
+
+    /// Number of threads expanding the tableau of each formula, stealing β-branches from each
+    /// other. (OPTIONAL)
+    ///
+    /// Defaults to `1`, i.e. the sequential tableau.
+    #[structopt(long)]
+    tableau_threads: Option<usize>,
+
+    /// Decide formulas with at most this many variables by truth table instead of the tableau.
+    /// (OPTIONAL)
+    ///
+    /// Defaults to `8`; pass `0` to always use the tableau.
+    #[structopt(long)]
+    truth_table_max_vars: Option<usize>,
+
+    /// Give up on each formula after this long, e.g. `2s` or `500ms`, and report it as `unknown`
+    /// along with the variable values on the most expanded open branch of its tableau. (OPTIONAL)
+    ///
+    /// Only formulas decided by the sequential tableau can time out; the others are always decided.
+    /// Does not apply to `--conjoin`, `--goal-directed`, `--watch` and tableau snapshots.
+    #[structopt(long, parse(try_from_str = header::parse_duration))]
+    timeout: Option<Duration>,
+
+    /// Treat all input formulas as a single conjunction instead of independent problems.
+    ///
+    /// One combined result is printed; in satisfiability mode an unsatisfiable conjunction is
+    /// followed by the (1-based) line numbers of a minimal subset of formulas that is already
+    /// unsatisfiable.
+    #[structopt(long)]
+    conjoin: bool,
+
+    /// Check the validity of each formula as premises entailing a conclusion, e.g.
+    /// `((A^B)->(C->D))` with premises `A`, `B`, `C` and conclusion `D`, and list the premises
+    /// needed to prove a valid formula (implies validity mode).
+    #[structopt(long)]
+    goal_directed: bool,
+
+    /// After each satisfiable formula, print the rule applications along an open branch of its
+    /// tableau and the literals they produce, explaining why the model works (satisfiability mode
+    /// and `text` format only).
+    #[structopt(long)]
+    explain: bool,
+
+    /// After each satisfiable formula, print up to this many of its models, one `<variable>=<0|1>`
+    /// list per line (satisfiability mode and `text` format only). (OPTIONAL)
+    ///
+    /// Models are listed in a deterministic order, and only as many as printed are enumerated.
+    #[structopt(long)]
+    max_models: Option<usize>,
+
+    /// After each invalid formula, print an assignment under which it is false, as a
+    /// `<variable>=<0|1>` list (validity mode and `text` format only).
+    #[structopt(long)]
+    counter_model: bool,
+
+    /// After each result, print the number of branches of the complete tableau of the formula (or,
+    /// in validity mode, of its negation), how many of them close and stay open, and the number of
+    /// steps of the tableau (`text` and `json` format only).
+    ///
+    /// The complete tableau is expanded separately from solving, in a reproducible order, so this
+    /// may take much longer than solving itself.
+    #[structopt(long)]
+    branch_stats: bool,
+
+    /// Path to a file of variables to fix before solving, one `<variable>=<0|1>` per line.
+    /// (OPTIONAL)
+    ///
+    /// The fixed values are substituted into each formula, and the simplified residual formula is
+    /// printed after each result.
+    #[structopt(long)]
+    fix_from: Option<PathBuf>,
+
+    /// Path to a file of formulas shared by all input formulas, e.g. a fixed rule base, one per
+    /// line. (OPTIONAL)
+    ///
+    /// The conjunction of the shared formulas is expanded into a partial tableau once, and each
+    /// input formula is decided as a query against it: in satisfiability mode, whether it is
+    /// satisfiable together with the shared formulas; in validity mode, whether they entail it.
+    /// Queries are always decided by the tableau.
+    #[structopt(long)]
+    context: Option<PathBuf>,
+
+    /// Directory to write a proof of each unsatisfiable formula (or, in validity mode, each valid
+    /// formula) to, as `formula-<line>.json`, or `formula-<line>_<position>.json` for one of
+    /// several formulas on a line. (OPTIONAL)
+    ///
+    /// The proofs can be verified independently with the `check-proof` subcommand.
+    #[structopt(long)]
+    proof_dir: Option<PathBuf>,
+
+    /// Format of proofs: `json`, or `latex` for `prooftree` environments of the LaTeX package
+    /// `prooftrees`, written as `formula-<line>.tex`.
+    #[structopt(long, default_value = "json")]
+    proof_format: proofs::ProofFormat,
+
+    /// Directory to write snapshots of the tableau to while solving. (OPTIONAL)
+    ///
+    /// Snapshots are named `formula-<line>-<expansions>.<format>`, with `<line>_<position>` for
+    /// one of several formulas on a line. Formulas are then always decided by the tableau, without
+    /// truth tables or preprocessing.
+    #[structopt(long)]
+    snapshot_dir: Option<PathBuf>,
+
+    /// Number of expansions between two snapshots of the tableau.
+    #[structopt(long, default_value = "1000")]
+    snapshot_every: usize,
+
+    /// Format of tableau snapshots: `json` or `dot`.
+    #[structopt(long, default_value = "json")]
+    snapshot_format: snapshots::SnapshotFormat,
+
+    /// Format of the output: `text`, or `json` and `csv` records which also show the wall time,
+    /// expansion count and peak size of the theory queue of each formula.
+    ///
+    /// Expansions and queue sizes are only known for formulas decided by the tableau. (OPTIONAL)
+    ///
+    /// Defaults to `text`.
+    #[structopt(long)]
+    format: Option<OutputFormat>,
+
     /// Path to input file. (OPTIONAL)
     ///
     /// If the `<input_file>` is specified then `stdin` is ignored.
     /// Otherwise `stdin` input stream is used instead. The program can handle multiple
     /// propositional formulas that are delimited by the newline character (`\n`).
+    ///
+    /// The first line of the file may be a header setting solver options for it, e.g.
+    /// `#! engine=dpll mode=v`; options given on the command line take precedence.
     #[structopt(short = "i", long = "input")]
     input_file: Option<PathBuf>,
 
@@ -46,6 +230,61 @@ pub struct Args {
     /// If the `<output_file>` is not specified then output of the program is written to `stdout`.
     #[structopt(short = "o", long = "output")]
     output_file: Option<PathBuf>,
+
+    /// Number of worker threads solving formulas. (OPTIONAL)
+    ///
+    /// Defaults to the available parallelism of the machine.
+    #[structopt(short = "j", long)]
+    jobs: Option<usize>,
+
+    /// Write each result as soon as its formula is solved instead of in input order.
+    ///
+    /// Results in the `text` format are then prefixed by their input line number and a tab; the
+    /// `json` and `csv` records already contain it.
+    #[structopt(long)]
+    stream: bool,
+
+    /// Report ill-formed formulas and solve the remaining ones instead of aborting at the first
+    /// ill-formed formula; the program still exits with code `22` at the end.
+    ///
+    /// Ill-formed formulas get no result, so results no longer line up with input lines in the
+    /// `text` format unless `--stream` prefixes them with their line numbers. Does not apply to
+    /// `--conjoin`, `--goal-directed` and `--watch`.
+    #[structopt(long)]
+    keep_going: bool,
+
+    /// Accept unary and binary operators without parentheses, e.g. `a ^ -b -> c`.
+    ///
+    /// Operators bind from loosest to tightest `<->` and `⊕`, `->`, `|`, `^`, `-`; `->`
+    /// associates to the right and the others to the left. Does not apply to `--watch`.
+    #[structopt(long)]
+    lenient: bool,
+
+    /// Reject the Unicode spellings of operators and constants, e.g. `∧` or `⊤`. Does not apply
+    /// to `--watch`.
+    #[structopt(long)]
+    ascii_only: bool,
+
+    /// Reject the constants, e.g. `1` or `false`. Does not apply to `--watch`.
+    #[structopt(long)]
+    no_constants: bool,
+
+    /// Deepest nesting accepted in formulas, counting parentheses, negations and binary operators.
+    /// Defaults to `256`. Does not apply to `--watch`.
+    #[structopt(long)]
+    max_depth: Option<usize>,
+
+    /// Keep watching the input file (`--input` is required), re-solving the lines which changed
+    /// whenever it is modified.
+    ///
+    /// Results of unchanged lines are reused; each run prints the results of the changed lines,
+    /// prefixed by their line number, and a summary of how many lines changed.
+    #[structopt(long)]
+    watch: bool,
+
+    /// Command to run on the given formula(s) instead of computing their satisfiability/validity.
+    #[structopt(subcommand)]
+    command: Option<commands::Command>,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Eq)]
@@ -88,6 +327,15 @@ impl TryFrom<String> for CliOutputMode {
 /// If the formula is not well-formed then the program also aborts with a non-zero exit code.
 #[paw::main]
 pub fn main(args: Args) -> io::Result<()> {
+    // Colors must be settled before the first log line is printed.
+    let env_options = environment::read();
+    if let Ok(environment::EnvOptions {
+        color: Some(color), ..
+    }) = env_options
+    {
+        colored::control::set_override(color);
+    }
+
     logger::setup(args.debug);
 
     info!(
@@ -98,22 +346,583 @@ pub fn main(args: Args) -> io::Result<()> {
 
     info!("arguments provided\n {:#?}", &args);
 
+    let env_options = match env_options {
+        Ok(env_options) => env_options,
+        Err(e) => {
+            error!(
+                "ill-formed {}* environment variable: {}",
+                environment::PREFIX,
+                e
+            );
+            std::process::exit(22);
+        }
+    };
+    if env_options != environment::EnvOptions::default() {
+        info!("options from the environment\n {:#?}", &env_options);
+    }
+
+    // A single formula takes precedence over the input file, and so does its header.
+    let file_options = match (&args.single_formula, &args.input_file) {
+        (None, Some(input_path)) => header::read(input_path),
+        _ => None,
+    }
+    .unwrap_or_default();
+    if file_options != header::FileOptions::default() {
+        info!("options from the input file header\n {:#?}", &file_options);
+    }
+
+    let mode = args
+        .mode
+        .or(file_options.mode)
+        .and_then(|c| CliOutputMode::try_from(c).ok());
+
+    let eliminate_blocked_clauses =
+        args.eliminate_blocked_clauses || file_options.eliminate_blocked_clauses == Some(true);
+    let preprocess =
+        args.preprocess || file_options.preprocess == Some(true) || eliminate_blocked_clauses;
+    let mut config = SolverConfig {
+        preprocessing: if preprocess {
+            Some(PreprocessingOptions {
+                blocked_clause_elimination: eliminate_blocked_clauses,
+                ..PreprocessingOptions::default()
+            })
+        } else {
+            None
+        },
+        eliminate_definitions: args.eliminate_definitions
+            || file_options.eliminate_definitions == Some(true),
+        push_negations: args.push_negations || file_options.push_negations == Some(true),
+        ..SolverConfig::default()
+    };
+    if let Some(engine) = args.engine.or(file_options.engine).or(env_options.engine) {
+        config.engine = engine;
+    }
+    if let Some(branch_heuristic) = args.branch_heuristic.or(file_options.branch_heuristic) {
+        config.branch_heuristic = branch_heuristic;
+    }
+    if let Some(search_strategy) = args.search_strategy.or(file_options.search_strategy) {
+        config.search_strategy = search_strategy;
+    }
+    if let Some(threads) = args.tableau_threads.or(file_options.tableau_threads) {
+        config.tableau_threads = threads;
+    }
+    if let Some(max_vars) = args
+        .truth_table_max_vars
+        .or(file_options.truth_table_max_vars)
+    {
+        config.truth_table_max_variables = max_vars;
+    }
+
+    if let Some(command) = &args.command {
+        let inputs = if command.reads_input() {
+            read_inputs(&args)?
+        } else {
+            Vec::new()
+        };
+
+        return commands::run(command, &inputs, &config, &parser_options(&args));
+    }
+
+    let source = if let Some(formula) = &args.single_formula {
+        pipeline::Source::Formula(formula.to_string())
+    } else if let Some(input_path) = &args.input_file {
+        pipeline::Source::File(input_path.clone())
+    } else {
+        pipeline::Source::Stdin
+    };
+    let jobs = args.jobs.unwrap_or_else(pipeline::default_jobs);
+
+    if preprocess {
+        info!("preprocessing formulas handed to the tableau");
+    }
+
+    if args.keep_going && (args.goal_directed || args.conjoin || args.watch) {
+        info!("--keep-going does not apply to --conjoin, --goal-directed and --watch");
+    }
+    if args.watch && parser_options(&args) != ParserOptions::default() {
+        info!("--lenient, --ascii-only, --no-constants and --max-depth do not apply to --watch");
+    }
+
+    if args.goal_directed {
+        let formulas: Vec<_> = read_formulas(&args)?
+            .into_iter()
+            .map(|(_, formula)| formula)
+            .collect();
+        return solve_goal_directed(&formulas, &config);
+    }
+
+    if args.conjoin {
+        let formulas = read_formulas(&args)?;
+        return solve_conjunction(&formulas, mode, &config);
+    }
+
+    let snapshots = args.snapshot_dir.as_ref().map(|dir| {
+        info!("writing tableau snapshots to {:?}", dir);
+        snapshots::Snapshots {
+            dir: dir.clone(),
+            every: args.snapshot_every,
+            format: args.snapshot_format,
+        }
+    });
+
+    let validity = mode == Some(CliOutputMode::Validity);
+    if validity {
+        info!("using validity mode");
+    } else {
+        // Default to satisfiability mode.
+        info!("using satisfiability mode");
+    }
+
+    let timeout = args
+        .timeout
+        .or(file_options.timeout)
+        .or(env_options.timeout);
+    if timeout.is_some() && snapshots.is_some() {
+        info!("--timeout does not apply to tableau snapshots");
+    }
+
+    let context = args.context.as_ref().map(|path| {
+        let formula = read_context(path, &parser_options(&args));
+        let shared = SharedContext::new(&formula, DEFAULT_MAX_BRANCHES);
+        info!(
+            "expanded the context into {} open branches",
+            shared.branches()
+        );
+        if snapshots.is_some() {
+            info!("tableau snapshots do not apply to --context");
+        }
+        (formula, shared)
+    });
+
+    let solve =
+        |location: Location, formula: &PropositionalFormula, timeout: Option<Duration>| match (
+            &context, &snapshots, validity, timeout,
+        ) {
+            (Some((_, shared)), _, _, _) => {
+                solve_in_context(shared, formula, validity, config.branch_heuristic, timeout)
+            }
+            (_, Some(snapshots), true, _) => {
+                let (result, stats) = snapshots.is_valid(location, formula);
+                (Ok(result), Some(stats))
+            }
+            (_, Some(snapshots), false, _) => {
+                let (result, stats) = snapshots.is_satisfiable(location, formula);
+                (Ok(result), Some(stats))
+            }
+            (_, None, true, Some(timeout)) => solver::is_valid_within(formula, &config, timeout),
+            (_, None, false, Some(timeout)) => {
+                solver::is_satisfiable_within(formula, &config, timeout)
+            }
+            (_, None, true, None) => {
+                let (result, stats) = solver::is_valid_with_stats(formula, &config);
+                (Ok(result), stats)
+            }
+            (_, None, false, None) => {
+                let (result, stats) = solver::is_satisfiable_with_stats(formula, &config);
+                (Ok(result), stats)
+            }
+        };
+
+    if args.watch {
+        let path = match &args.input_file {
+            Some(path) => path,
+            None => {
+                error!("--watch requires an input file");
+                std::process::exit(22);
+            }
+        };
+        if timeout.is_some() {
+            info!("--timeout does not apply to --watch");
+        }
+        info!("watching {:?}", path);
+        let location = |index: usize| Location {
+            line: index + 1,
+            position: None,
+        };
+        return watch::run(path, |index, formula| {
+            match solve(location(index), formula, None).0 {
+                Ok(result) => result,
+                Err(_) => unreachable!("solving without a timeout cannot time out"),
+            }
+        });
+    }
+
+    let assignment = match &args.fix_from {
+        Some(path) => {
+            let assignment = read_assignment(path)?;
+            info!("fixing {} variables before solving", assignment.len());
+            Some(assignment)
+        }
+        None => None,
+    };
+
+    let format = args
+        .format
+        .or(env_options.format)
+        .unwrap_or(OutputFormat::Text);
+
+    let explain = args.explain && !validity && format == OutputFormat::Text;
+    if args.explain && !explain {
+        info!("--explain only applies to text output in satisfiability mode");
+    }
+
+    let max_models = args
+        .max_models
+        .filter(|_| !validity && format == OutputFormat::Text);
+    if args.max_models.is_some() && max_models.is_none() {
+        info!("--max-models only applies to text output in satisfiability mode");
+    }
+
+    let counter_model = args.counter_model && validity && format == OutputFormat::Text;
+    if args.counter_model && !counter_model {
+        info!("--counter-model only applies to text output in validity mode");
+    }
+
+    let branch_stats = args.branch_stats && format != OutputFormat::Csv;
+    if args.branch_stats && !branch_stats {
+        info!("--branch-stats does not apply to csv output");
+    }
+
+    if format == OutputFormat::Csv {
+        io::stdout().write_fmt(format_args!("{}\n", records::CSV_HEADER))?;
+    }
+
+    pipeline::run(
+        source,
+        jobs,
+        args.stream,
+        args.keep_going,
+        parser_options(&args),
+        |location, label, formula| {
+            let start = Instant::now();
+
+            // The formula actually solved, or `None` if it was fixed to a constant.
+            let mut solved_formula = Some(Cow::Borrowed(formula));
+            let (residual, (result, stats)) = match &assignment {
+                Some(assignment) => match substitute(formula, assignment) {
+                    // A constant still has to be decided against the context.
+                    Residual::Constant(value) if context.is_some() => {
+                        let residual = if value {
+                            PropositionalFormula::Top
+                        } else {
+                            PropositionalFormula::Bottom
+                        };
+                        let solved = solve(location, &residual, timeout);
+                        solved_formula = Some(Cow::Owned(residual));
+                        (Some(value.to_string()), solved)
+                    }
+                    Residual::Constant(value) => {
+                        solved_formula = None;
+                        (Some(value.to_string()), (Ok(value), None))
+                    }
+                    Residual::Formula(residual) => {
+                        let solved = solve(location, &residual, timeout);
+                        let rendered = residual.to_string();
+                        solved_formula = Some(Cow::Owned(residual));
+                        (Some(rendered), solved)
+                    }
+                },
+                None => (None, solve(location, formula, timeout)),
+            };
+            let (result, partial) = match result {
+                Ok(result) => (Some(result), None),
+                Err(TimedOut { partial }) => (None, Some(partial)),
+            };
+
+            // Proofs, explanations, models and shapes are of the query together with the context.
+            let solved_formula = match (solved_formula, &context) {
+                (Some(query), Some((context, _))) => {
+                    let (context, query) =
+                        (Box::new(context.clone()), Box::new(query.into_owned()));
+                    Some(Cow::Owned(if validity {
+                        PropositionalFormula::implication(context, query)
+                    } else {
+                        PropositionalFormula::conjunction(context, query)
+                    }))
+                }
+                (solved_formula, _) => solved_formula,
+            };
+
+            let time = start.elapsed();
+
+            // A formula fixed to a constant by `--fix-from` has no tableau, and the complete
+            // tableau of a formula which timed out would take even longer.
+            let shape = match &solved_formula {
+                Some(solved_formula) if branch_stats && result.is_some() => Some(if validity {
+                    proof::tableau_shape(&PropositionalFormula::negated(Box::new(
+                        solved_formula.clone().into_owned(),
+                    )))
+                } else {
+                    proof::tableau_shape(solved_formula)
+                }),
+                _ => None,
+            };
+
+            let record = Record {
+                line: location.line,
+                label: label.map(str::to_string),
+                result,
+                residual,
+                time,
+                stats,
+                shape,
+                partial,
+            }
+            .render(format);
+
+            let record = if args.stream && format == OutputFormat::Text {
+                format!("{}\t{}", location, record)
+            } else {
+                record
+            };
+
+            if let (Some(dir), Some(solved_formula)) = (&args.proof_dir, &solved_formula) {
+                if result == Some(validity) {
+                    proofs::write(dir, location, solved_formula, validity, args.proof_format);
+                }
+            }
+
+            // A formula fixed to a constant by `--fix-from` has nothing left to explain.
+            let record = match &solved_formula {
+                Some(solved_formula) if explain && result == Some(true) => {
+                    match trace::explain_satisfiable(solved_formula) {
+                        Some(steps) => render_explanation(record, &steps),
+                        None => record,
+                    }
+                }
+                _ => record,
+            };
+
+            match (&solved_formula, max_models) {
+                (Some(solved_formula), Some(k)) if result == Some(true) => {
+                    render_models(record, solved_formula, k)
+                }
+                (Some(solved_formula), _) if counter_model && result == Some(false) => {
+                    render_counter_model(record, solved_formula)
+                }
+                _ => record,
+            }
+        },
+    )
+}
+
+/// Decide the `query` against the shared context like `--context`, recording the resources used by
+/// the tableau of the query.
+fn solve_in_context(
+    context: &SharedContext,
+    query: &PropositionalFormula,
+    validity: bool,
+    heuristic: BranchHeuristic,
+    timeout: Option<Duration>,
+) -> (Result<bool, TimedOut>, Option<TableauStats>) {
+    // The context entails the query iff it is unsatisfiable together with its negation.
+    let query = if validity {
+        Cow::Owned(PropositionalFormula::negated(Box::new(query.clone())))
+    } else {
+        Cow::Borrowed(query)
+    };
+
+    let mut stats = TableauStats::default();
+    let observer = |expansions: usize, tableau: &Tableau| stats.record(expansions, tableau);
+    let satisfiable = match timeout {
+        Some(timeout) => {
+            context.is_satisfiable_until(&query, heuristic, Instant::now() + timeout, observer)
+        }
+        None => Ok(context.is_satisfiable_observed(&query, heuristic, observer)),
+    };
+
+    (
+        satisfiable.map(|satisfiable| satisfiable != validity),
+        Some(stats),
+    )
+}
+
+/// Read the formulas of the context file at `path`, skipping blank lines, and conjoin them.
+///
+/// # Errors
+///
+/// If the file cannot be read then the program aborts with exit code `5`; if a formula is
+/// ill-formed then the program aborts with exit code `22`.
+fn read_context(path: &Path, options: &ParserOptions) -> PropositionalFormula {
+    let input = match fs::read_to_string(path) {
+        Ok(input) => input,
+        Err(e) => {
+            error!("failed to read context from {:?}: {}", path, e);
+            std::process::exit(5);
+        }
+    };
+
+    let lines: Vec<String> = input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(str::to_string)
+        .collect();
+
+    // The empty context is true.
+    PropositionalFormula::conjoin(parse_formulas(&lines, options))
+        .unwrap_or(PropositionalFormula::Top)
+}
+
+/// Append up to `k` models of the formula to the rendered result, one indented
+/// `model <n>: <variable>=<0|1>, ...` line each, with the variables in order of first occurrence.
+fn render_models(record: String, formula: &PropositionalFormula, k: usize) -> String {
+    let variables = formula.variables();
+
+    models::enumerate_models_bounded(formula, k)
+        .iter()
+        .enumerate()
+        .fold(record, |rendered, (i, model)| {
+            let values: Vec<String> = variables
+                .iter()
+                .map(|&variable| format!("{}={}", variable, u8::from(model[variable])))
+                .collect();
+            format!("{}\n  model {}: {}", rendered, i + 1, values.join(", "))
+        })
+}
+
+/// Append an assignment falsifying the formula to the rendered result, as an indented
+/// `counter-model: <variable>=<0|1>, ...` line with the variables in order of first occurrence.
+fn render_counter_model(record: String, formula: &PropositionalFormula) -> String {
+    match models::counter_model(formula) {
+        Some(model) => {
+            let values: Vec<String> = formula
+                .variables()
+                .iter()
+                .map(|&variable| format!("{}={}", variable, u8::from(model[variable])))
+                .collect();
+            format!("{}\n  counter-model: {}", record, values.join(", "))
+        }
+        None => record,
+    }
+}
+
+/// Append the steps of an open branch to the rendered result, one indented `step <n>: ...` line
+/// each.
+fn render_explanation(record: String, steps: &[trace::TraceStep]) -> String {
+    steps
+        .iter()
+        .enumerate()
+        .fold(record, |rendered, (i, step)| {
+            format!("{}\n  step {}: {}", rendered, i + 1, step)
+        })
+}
+
+/// Read a partial truth assignment from the file at `path`.
+///
+/// # Errors
+///
+/// If the file cannot be read then the program aborts with a non-zero exit code, and so it does if
+/// the assignment is malformed.
+fn read_assignment(path: &Path) -> io::Result<Assignment> {
+    let input = match fs::read_to_string(path) {
+        Ok(input) => input,
+        Err(e) => {
+            error!("failed to read assignment from {:?}: {}", path, e);
+            std::process::exit(5);
+        }
+    };
+
+    match parser::parse_assignment(&input) {
+        Ok(assignment) => Ok(assignment),
+        Err(e) => {
+            error!("ill-formed assignment in {:?}: {}", path, e);
+            std::process::exit(22);
+        }
+    }
+}
+
+/// Decide the conjunction of all formulas, printing an unsatisfiable core, by the locations of its
+/// formulas, if it is unsatisfiable in satisfiability mode.
+fn solve_conjunction(
+    located_formulas: &[(Location, PropositionalFormula)],
+    mode: Option<CliOutputMode>,
+    config: &SolverConfig,
+) -> io::Result<()> {
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+
+    let formulas: Vec<PropositionalFormula> = located_formulas
+        .iter()
+        .map(|(_, formula)| formula.clone())
+        .collect();
+
+    let conjunction = match PropositionalFormula::conjoin(formulas.iter().cloned()) {
+        Some(conjunction) => conjunction,
+        // The empty conjunction is true, hence both satisfiable and valid.
+        None => return stdout.write_fmt(format_args!("{:?}\n", true)),
+    };
+
+    if mode == Some(CliOutputMode::Validity) {
+        info!("using validity mode on the conjunction");
+        let result = solver::is_valid(&conjunction, config);
+        return stdout.write_fmt(format_args!("{:?}\n", result));
+    }
+
+    info!("using satisfiability mode on the conjunction");
+    match solver::unsatisfiable_core(&formulas, config) {
+        None => stdout.write_fmt(format_args!("{:?}\n", true)),
+        Some(core) => {
+            let lines: Vec<String> = core
+                .iter()
+                .map(|&i| located_formulas[i].0.to_string())
+                .collect();
+            stdout.write_fmt(format_args!("{:?}\n", false))?;
+            stdout.write_fmt(format_args!("core: {}\n", lines.join(", ")))
+        }
+    }
+}
+
+/// Decide the validity of each formula with [`solver::is_valid_goal_directed`], printing its
+/// premises, marking those needed to prove the conclusion.
+fn solve_goal_directed(formulas: &[PropositionalFormula], config: &SolverConfig) -> io::Result<()> {
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+
+    for (i, formula) in formulas.iter().enumerate() {
+        let validity = solver::is_valid_goal_directed(formula, config);
+        let verdict = if validity.is_valid() {
+            "valid"
+        } else {
+            "not valid"
+        };
+        stdout.write_fmt(format_args!("formula {}: {}\n", i + 1, verdict))?;
+
+        for (j, premise) in validity.premises.iter().enumerate() {
+            let needed = match &validity.needed_premises {
+                Some(needed) if needed.contains(&j) => " (needed)",
+                _ => "",
+            };
+            stdout.write_fmt(format_args!("  premise {}: {}{}\n", j + 1, premise, needed))?;
+        }
+        stdout.write_fmt(format_args!("  conclusion: {}\n", validity.conclusion))?;
+    }
+
+    Ok(())
+}
+
+/// Read the input lines, from (in order of precedence):
+///
+/// 1. Single formula input from `--formula`, `-c`.
+/// 2. File input from `--input`, `-i`.
+/// 3. Standard input from `stdin`.
+///
+/// # Errors
+///
+/// If reading a line fails then the program aborts with a non-zero exit code.
+pub fn read_inputs(args: &Args) -> io::Result<Vec<String>> {
     let mut inputs: Vec<String> = Vec::new();
 
-    // Input precedence:
-    //
-    // 1. Single formula input from `--formula`, `-c`.
-    // 2. File input from `--input`, `-i`.
-    // 3. Standard input from `stdin`.
     if let Some(input) = &args.single_formula {
         inputs.push(input.to_string());
     } else if let Some(input_path) = &args.input_file {
         let file = fs::File::open(input_path)?;
         let reader = io::BufReader::new(&file);
 
-        for line in reader.lines() {
+        for (index, line) in reader.lines().enumerate() {
             if let Ok(line) = line {
-                inputs.push(line);
+                if !header::is_header(index, &line) {
+                    inputs.push(line);
+                }
             } else {
                 error!(
                     "I/O error encountered when trying to read from {:#?}",
@@ -137,40 +946,132 @@ pub fn main(args: Args) -> io::Result<()> {
 
     debug!("raw inputs:\n{:#?}", &inputs);
 
-    let formulas: Vec<PropositionalFormula> = inputs
-        .iter()
-        .map(|f| match parser::parse(f) {
-            Ok(f) => f,
-            Err(_) => {
-                error!("ill-formed formula: {:#?}", &f);
-                std::process::exit(22);
-            }
-        })
-        .collect();
+    Ok(inputs)
+}
 
-    debug!("parsed formulas:\n{:#?}", &formulas);
+/// The grammar of the input formulas chosen by `--lenient`, `--ascii-only`, `--no-constants` and
+/// `--max-depth`.
+fn parser_options(args: &Args) -> ParserOptions {
+    let options = ParserOptions::new()
+        .with_require_parentheses(!args.lenient)
+        .with_allow_unicode(!args.ascii_only)
+        .with_allow_constants(!args.no_constants);
+
+    match args.max_depth {
+        Some(max_depth) => options.with_max_depth(max_depth),
+        None => options,
+    }
+}
 
-    let results: Vec<bool>;
+/// Where an input formula was read from: its line, counted from `1` (including the header of an
+/// input file and lines defining macros), and which of the formulas of the line it is if the line
+/// holds several separated by `;`, also counted from `1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    pub line: usize,
+    pub position: Option<usize>,
+}
 
-    let mode = args.mode.map(|c| CliOutputMode::try_from(c).ok()).flatten();
+impl Location {
+    /// Locate each of the `formulas` of the `line`.
+    fn of_line<T>(line: usize, formulas: Vec<T>) -> impl Iterator<Item = (Location, T)> {
+        let several = formulas.len() > 1;
+        formulas.into_iter().enumerate().map(move |(i, formula)| {
+            let position = if several { Some(i + 1) } else { None };
+            (Location { line, position }, formula)
+        })
+    }
 
-    match mode {
-        Some(CliOutputMode::Validity) => {
-            info!("using validity mode");
-            results = formulas.iter().map(is_valid).collect();
+    /// The location as part of the name of a file written for the formula, e.g. `3` or `3_2`.
+    pub fn file_stem(&self) -> String {
+        match self.position {
+            Some(position) => format!("{}_{}", self.line, position),
+            None => self.line.to_string(),
         }
-        _ => {
-            info!("using satisfiability mode");
-            // Default to satisfiability mode.
-            results = formulas.iter().map(is_satisfiable).collect();
+    }
+}
+
+impl fmt::Display for Location {
+    /// The line number, followed by the position of the formula in the line if it holds several,
+    /// e.g. `3` or `3:2`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.position {
+            Some(position) => write!(f, "{}:{}", self.line, position),
+            None => write!(f, "{}", self.line),
         }
     }
+}
 
-    let stdout = io::stdout();
-    let mut stdout = stdout.lock();
-    for result in results {
-        stdout.write_fmt(format_args!("{:?}\n", result))?;
+/// Read and parse the input formula(s) line by line, without holding the raw lines in memory,
+/// along with the location of each formula.
+///
+/// # Errors
+///
+/// If reading fails then the program aborts with exit code `5`; if any formula is not well-formed
+/// then the program aborts with exit code `22`.
+pub fn read_formulas(args: &Args) -> io::Result<Vec<(Location, PropositionalFormula)>> {
+    if let Some(formula) = &args.single_formula {
+        let formulas = parse_formulas(&[formula.to_string()], &parser_options(args));
+        return Ok(Location::of_line(1, formulas).collect());
     }
 
-    Ok(())
+    let (reader, has_header): (Box<dyn BufRead>, bool) = match &args.input_file {
+        Some(input_path) => (
+            Box::new(io::BufReader::new(fs::File::open(input_path)?)),
+            header::read(input_path).is_some(),
+        ),
+        None => (Box::new(io::BufReader::new(io::stdin())), false),
+    };
+
+    let mut formulas = Vec::new();
+    let lines = parser::parse_stream_with(reader, parser_options(args)).enumerate();
+    for (index, formula) in lines.skip(if has_header { 1 } else { 0 }) {
+        match formula {
+            Ok(line) => formulas.extend(Location::of_line(index + 1, line)),
+            Err(e) if e.is_io_error() => {
+                error!(
+                    "I/O error encountered when reading line {}: {}",
+                    index + 1,
+                    e.message
+                );
+                std::process::exit(5);
+            }
+            Err(e) => {
+                error!(
+                    "ill-formed formula on line {}: {}",
+                    index + 1,
+                    e.diagnostic()
+                );
+                std::process::exit(22);
+            }
+        }
+    }
+
+    debug!("parsed formulas:\n{:#?}", &formulas);
+
+    Ok(formulas)
+}
+
+/// Parse each input line into its propositional formulas, which are separated by `;`, expanding
+/// the macros defined by the lines before it, in the grammar chosen by the `options`.
+///
+/// # Errors
+///
+/// If any formula is not well-formed then the program aborts with a non-zero exit code.
+pub fn parse_formulas(inputs: &[String], options: &ParserOptions) -> Vec<PropositionalFormula> {
+    let mut macros = parser::Macros::with_options(*options);
+    let formulas: Vec<PropositionalFormula> = inputs
+        .iter()
+        .flat_map(|f| match macros.parse_line(f) {
+            Ok(f) => f.into_iter().map(|(_, f)| f),
+            Err(e) => {
+                error!("ill-formed formula: {}", e.diagnostic());
+                std::process::exit(22);
+            }
+        })
+        .collect();
+
+    debug!("parsed formulas:\n{:#?}", &formulas);
+
+    formulas
 }
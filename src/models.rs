@@ -0,0 +1,544 @@
+//! Enumeration of the models (satisfying assignments) of propositional formulas.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::formula::{substitute, Assignment, PropositionalFormula, Residual, Variable};
+
+/// Order in which the enumeration assigns variables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VariableOrdering {
+    /// In order of first occurrence from left to right, trying `false` before `true`.
+    FirstOccurrence,
+    /// Most occurrences first ("most constrained"), ties broken by first occurrence, trying first
+    /// the value which makes most occurrences of the variable true, i.e. `true` if the variable
+    /// occurs positively at least as often as negatively.
+    ///
+    /// Frequent variables decide the formula early, so falsified branches are pruned sooner.
+    MostConstrained,
+}
+
+/// Configuration of [`enumerate_models`].
+///
+/// [`enumerate_models`]: fn.enumerate_models.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnumerationConfig {
+    pub ordering: VariableOrdering,
+}
+
+impl Default for EnumerationConfig {
+    /// Enumerate by [`VariableOrdering::MostConstrained`].
+    ///
+    /// [`VariableOrdering::MostConstrained`]: enum.VariableOrdering.html#variant.MostConstrained
+    fn default() -> Self {
+        Self {
+            ordering: VariableOrdering::MostConstrained,
+        }
+    }
+}
+
+/// Lazily enumerate the models of the formula, i.e. the assignments to all of its variables under
+/// which it is true.
+///
+/// Variables are assigned one at a time in the order chosen by `config`, substituting each value
+/// into the formula (see [`substitute`]) and abandoning branches as soon as the formula becomes
+/// false. The order of the models only depends on the formula and `config`.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::formula::{PropositionalFormula, Variable};
+/// use libprop_sat_solver::models::{enumerate_models, EnumerationConfig};
+/// let formula = PropositionalFormula::disjunction(
+///     Box::new(PropositionalFormula::variable(Variable::new("a"))),
+///     Box::new(PropositionalFormula::variable(Variable::new("b"))),
+/// );
+/// assert_eq!(3, enumerate_models(&formula, &EnumerationConfig::default()).count());
+/// ```
+///
+/// [`substitute`]: ../formula/substitution/fn.substitute.html
+pub fn enumerate_models(formula: &PropositionalFormula, config: &EnumerationConfig) -> Models {
+    Models {
+        variables: variable_order(formula, config),
+        stack: vec![Frame {
+            values: Vec::new(),
+            residual: Residual::Formula(formula.clone()),
+        }],
+        position: Checkpoint::default(),
+    }
+}
+
+/// Resume the enumeration of the models of the formula at a [`Checkpoint`] taken by
+/// [`Models::checkpoint`], yielding the models which come after it.
+///
+/// The formula and `config` must be the ones the checkpoint was taken with, since they determine
+/// the order of the models. Resuming only takes a pass over the variables, however far into the
+/// enumeration the checkpoint is, so models can be fetched page by page across separate requests.
+///
+/// # Errors
+///
+/// Fails if the checkpoint assigns a different number of variables than the formula has.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::models::{enumerate_models, enumerate_models_from, EnumerationConfig};
+/// let formula = "(a|(b|c))".parse().unwrap();
+/// let config = EnumerationConfig::default();
+///
+/// let mut models = enumerate_models(&formula, &config);
+/// let first_page: Vec<_> = models.by_ref().take(5).collect();
+/// let checkpoint = models.checkpoint().to_string();
+///
+/// let rest = enumerate_models_from(&formula, &config, &checkpoint.parse().unwrap()).unwrap();
+/// assert_eq!(5, first_page.len());
+/// assert_eq!(2, rest.count());
+/// ```
+///
+/// [`Checkpoint`]: struct.Checkpoint.html
+/// [`Models::checkpoint`]: struct.Models.html#method.checkpoint
+pub fn enumerate_models_from(
+    formula: &PropositionalFormula,
+    config: &EnumerationConfig,
+    checkpoint: &Checkpoint,
+) -> Result<Models, String> {
+    let last = match &checkpoint.last {
+        Some(last) => last,
+        None => return Ok(enumerate_models(formula, config)),
+    };
+
+    let variables = variable_order(formula, config);
+    if last.len() != variables.len() {
+        return Err(format!(
+            "checkpoint assigns {} variables, but the formula has {}",
+            last.len(),
+            variables.len()
+        ));
+    }
+
+    // Rebuild the stack as it was right after yielding `last`: every variable still set to the
+    // value tried first has its second value left to explore, deeper variables first.
+    let mut stack = Vec::new();
+    let mut assignment = Assignment::new();
+    for (depth, ((variable, first), &value)) in variables.iter().zip(last).enumerate() {
+        if value == *first {
+            let mut alternative = assignment.clone();
+            alternative.insert(variable.clone(), !value);
+
+            let mut values = last[..depth].to_vec();
+            values.push(!value);
+            stack.push(Frame {
+                values,
+                residual: substitute(formula, &alternative),
+            });
+        }
+        assignment.insert(variable.clone(), value);
+    }
+
+    Ok(Models {
+        variables,
+        stack,
+        position: checkpoint.clone(),
+    })
+}
+
+/// Variables of the formula in the order they are assigned by `config`, with the value to try
+/// first.
+fn variable_order(
+    formula: &PropositionalFormula,
+    config: &EnumerationConfig,
+) -> Vec<(Variable, bool)> {
+    match config.ordering {
+        VariableOrdering::FirstOccurrence => formula
+            .variables()
+            .into_iter()
+            .map(|variable| (variable.clone(), false))
+            .collect(),
+        VariableOrdering::MostConstrained => {
+            let mut occurrences = HashMap::new();
+            count_occurrences(formula, true, &mut occurrences);
+
+            let mut variables: Vec<(Variable, bool)> = formula
+                .variables()
+                .into_iter()
+                .map(|variable| {
+                    let (positive, negative) = occurrences[variable];
+                    (variable.clone(), positive >= negative)
+                })
+                .collect();
+            // A stable sort keeps the order of first occurrence among ties.
+            variables.sort_by_key(|(variable, _)| {
+                let (positive, negative) = occurrences[variable];
+                std::cmp::Reverse(positive + negative)
+            });
+            variables
+        }
+    }
+}
+
+/// Collect at most `k` models of the formula, e.g. to show a few examples without enumerating all
+/// of them.
+///
+/// The models are enumerated by [`VariableOrdering::FirstOccurrence`], so they come in
+/// lexicographic order of the values of the variables in order of first occurrence, `false` before
+/// `true`. Only as many models as returned are ever constructed.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::formula::Variable;
+/// use libprop_sat_solver::models::enumerate_models_bounded;
+/// let models = enumerate_models_bounded(&"(a|b)".parse().unwrap(), 2);
+///
+/// assert_eq!(2, models.len());
+/// assert_eq!(Some(&false), models[0].get(&Variable::new("a")));
+/// assert_eq!(Some(&true), models[0].get(&Variable::new("b")));
+/// assert_eq!(Some(&true), models[1].get(&Variable::new("a")));
+/// assert_eq!(Some(&false), models[1].get(&Variable::new("b")));
+/// ```
+///
+/// [`VariableOrdering::FirstOccurrence`]: enum.VariableOrdering.html#variant.FirstOccurrence
+pub fn enumerate_models_bounded(formula: &PropositionalFormula, k: usize) -> Vec<Assignment> {
+    let config = EnumerationConfig {
+        ordering: VariableOrdering::FirstOccurrence,
+    };
+
+    enumerate_models(formula, &config).take(k).collect()
+}
+
+/// Find an assignment to all variables of the formula under which it is false, i.e. the first
+/// model of its negation in the order of [`enumerate_models_bounded`], or `None` if the formula is
+/// valid.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::formula::Variable;
+/// use libprop_sat_solver::models::counter_model;
+/// let model = counter_model(&"(a->(a^b))".parse().unwrap()).unwrap();
+/// assert_eq!(Some(&true), model.get(&Variable::new("a")));
+/// assert_eq!(Some(&false), model.get(&Variable::new("b")));
+///
+/// assert_eq!(None, counter_model(&"(a|(-a))".parse().unwrap()));
+/// ```
+///
+/// [`enumerate_models_bounded`]: fn.enumerate_models_bounded.html
+pub fn counter_model(formula: &PropositionalFormula) -> Option<Assignment> {
+    let negated_formula = PropositionalFormula::negated(Box::new(formula.clone()));
+    enumerate_models_bounded(&negated_formula, 1).pop()
+}
+
+/// Count the positive and negative occurrences of each variable, where an occurrence is negative
+/// if it is under an odd number of negations and antecedents of implications. Both sides of a
+/// biimplication count as both positive and negative occurrences.
+fn count_occurrences<'a>(
+    formula: &'a PropositionalFormula,
+    positive: bool,
+    occurrences: &mut HashMap<&'a Variable, (usize, usize)>,
+) {
+    match formula {
+        PropositionalFormula::Variable(v) => {
+            let (positives, negatives) = occurrences.entry(v).or_insert((0, 0));
+            if positive {
+                *positives += 1;
+            } else {
+                *negatives += 1;
+            }
+        }
+        PropositionalFormula::Top | PropositionalFormula::Bottom => {}
+        PropositionalFormula::Negation(a) => count_occurrences(a, !positive, occurrences),
+        PropositionalFormula::Conjunction(a, b) | PropositionalFormula::Disjunction(a, b) => {
+            count_occurrences(a, positive, occurrences);
+            count_occurrences(b, positive, occurrences);
+        }
+        PropositionalFormula::Implication(a, b) => {
+            count_occurrences(a, !positive, occurrences);
+            count_occurrences(b, positive, occurrences);
+        }
+        PropositionalFormula::Biimplication(a, b) => {
+            for operand in &[a, b] {
+                count_occurrences(operand, positive, occurrences);
+                count_occurrences(operand, !positive, occurrences);
+            }
+        }
+    }
+}
+
+/// Iterator over the models of a formula, see [`enumerate_models`].
+///
+/// [`enumerate_models`]: fn.enumerate_models.html
+#[derive(Debug, Clone)]
+pub struct Models {
+    /// Variables in the order they are assigned, with the value to try first.
+    variables: Vec<(Variable, bool)>,
+    stack: Vec<Frame>,
+    /// Position of the enumeration, right after the last model yielded.
+    position: Checkpoint,
+}
+
+impl Models {
+    /// The position of the enumeration, to resume it later with [`enumerate_models_from`] after
+    /// the models yielded so far.
+    ///
+    /// [`enumerate_models_from`]: fn.enumerate_models_from.html
+    pub fn checkpoint(&self) -> Checkpoint {
+        self.position.clone()
+    }
+}
+
+/// A position in the enumeration of the models of a formula, see [`Models::checkpoint`].
+///
+/// Checkpoints are serialized as `start`, before the first model, or as `after:` followed by the
+/// values of the variables (`0` or `1`, in the order they are assigned) in the last model yielded,
+/// e.g. `after:0110`.
+///
+/// [`Models::checkpoint`]: struct.Models.html#method.checkpoint
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Checkpoint {
+    /// Values of the last model yielded, if any.
+    last: Option<Vec<bool>>,
+}
+
+impl fmt::Display for Checkpoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.last {
+            Some(last) => {
+                write!(f, "after:")?;
+                for &value in last {
+                    write!(f, "{}", u8::from(value))?;
+                }
+                Ok(())
+            }
+            None => write!(f, "start"),
+        }
+    }
+}
+
+impl FromStr for Checkpoint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "start" {
+            return Ok(Self::default());
+        }
+
+        let values = s
+            .strip_prefix("after:")
+            .ok_or_else(|| format!("expected `start` or `after:<values>`, found {:?}", s))?;
+        let last = values
+            .chars()
+            .map(|c| match c {
+                '0' => Ok(false),
+                '1' => Ok(true),
+                _ => Err(format!("expected `0` or `1`, found {:?}", c)),
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { last: Some(last) })
+    }
+}
+
+/// A partial assignment to the first `values.len()` variables, with what remains of the formula.
+#[derive(Debug, Clone)]
+struct Frame {
+    values: Vec<bool>,
+    residual: Residual,
+}
+
+impl Iterator for Models {
+    type Item = Assignment;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(frame) = self.stack.pop() {
+            if frame.residual == Residual::Constant(false) {
+                continue;
+            }
+
+            let depth = frame.values.len();
+            if depth == self.variables.len() {
+                // Every variable of the formula is assigned, so the residual is a constant.
+                self.position = Checkpoint {
+                    last: Some(frame.values.clone()),
+                };
+                let model = self
+                    .variables
+                    .iter()
+                    .map(|(variable, _)| variable.clone())
+                    .zip(frame.values)
+                    .collect();
+                return Some(model);
+            }
+
+            let (variable, first) = &self.variables[depth];
+
+            // Push the value to try second first, so that the first value is popped next.
+            for &value in &[!*first, *first] {
+                let residual = match &frame.residual {
+                    Residual::Formula(formula) => {
+                        let mut assignment = HashMap::new();
+                        assignment.insert(variable.clone(), value);
+                        substitute(formula, &assignment)
+                    }
+                    constant => constant.clone(),
+                };
+
+                let mut values = frame.values.clone();
+                values.push(value);
+                self.stack.push(Frame { values, residual });
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::formulas;
+    use assert2::check;
+
+    fn var(name: &str) -> PropositionalFormula {
+        PropositionalFormula::variable(Variable::new(name))
+    }
+
+    fn model(values: &[(&str, bool)]) -> Assignment {
+        values
+            .iter()
+            .map(|&(name, value)| (Variable::new(name), value))
+            .collect()
+    }
+
+    #[test]
+    fn test_first_occurrence_order() {
+        // (a->b)
+        let formula = PropositionalFormula::implication(Box::new(var("a")), Box::new(var("b")));
+        let config = EnumerationConfig {
+            ordering: VariableOrdering::FirstOccurrence,
+        };
+
+        let models: Vec<_> = enumerate_models(&formula, &config).collect();
+
+        check!(
+            models
+                == vec![
+                    model(&[("a", false), ("b", false)]),
+                    model(&[("a", false), ("b", true)]),
+                    model(&[("a", true), ("b", true)]),
+                ]
+        );
+    }
+
+    #[test]
+    fn test_bounded_enumeration() {
+        // (a|(b|c)) has 7 models.
+        let formula = PropositionalFormula::disjunction(
+            Box::new(var("a")),
+            Box::new(PropositionalFormula::disjunction(
+                Box::new(var("b")),
+                Box::new(var("c")),
+            )),
+        );
+
+        check!(enumerate_models_bounded(&formula, 0).is_empty());
+        check!(enumerate_models_bounded(&formula, 100).len() == 7);
+        check!(
+            enumerate_models_bounded(&formula, 3)
+                == vec![
+                    model(&[("a", false), ("b", false), ("c", true)]),
+                    model(&[("a", false), ("b", true), ("c", false)]),
+                    model(&[("a", false), ("b", true), ("c", true)]),
+                ]
+        );
+    }
+
+    #[test]
+    fn test_resume_from_checkpoint() {
+        for formula in formulas(4, 4, 14, 50) {
+            for config in &[
+                EnumerationConfig::default(),
+                EnumerationConfig {
+                    ordering: VariableOrdering::FirstOccurrence,
+                },
+            ] {
+                let all: Vec<_> = enumerate_models(&formula, config).collect();
+
+                for page in 0..=all.len() {
+                    let mut models = enumerate_models(&formula, config);
+                    let mut resumed: Vec<_> = models.by_ref().take(page).collect();
+
+                    let checkpoint: Checkpoint = models.checkpoint().to_string().parse().unwrap();
+                    resumed.extend(enumerate_models_from(&formula, config, &checkpoint).unwrap());
+
+                    check!(resumed == all.clone());
+                }
+            }
+        }
+
+        check!("after:01x".parse::<Checkpoint>().is_err());
+        check!("01".parse::<Checkpoint>().is_err());
+        let checkpoint = "after:1".parse().unwrap();
+        check!(
+            enumerate_models_from(&var("a"), &EnumerationConfig::default(), &checkpoint)
+                .unwrap()
+                .next()
+                .is_none()
+        );
+        check!(enumerate_models_from(
+            &"(a^b)".parse().unwrap(),
+            &EnumerationConfig::default(),
+            &checkpoint
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_most_constrained_order() {
+        // ((a|b)^(-b)) decides `b` first, and only false satisfies it.
+        let formula = PropositionalFormula::conjunction(
+            Box::new(PropositionalFormula::disjunction(
+                Box::new(var("a")),
+                Box::new(var("b")),
+            )),
+            Box::new(PropositionalFormula::negated(Box::new(var("b")))),
+        );
+
+        let models = enumerate_models(&formula, &EnumerationConfig::default());
+        check!(models.variables[0].clone() == (Variable::new("b"), true));
+
+        let models: Vec<_> = models.collect();
+        check!(models == vec![model(&[("a", true), ("b", false)])]);
+    }
+
+    #[test]
+    fn test_orderings_find_the_same_models() {
+        fn render(models: Models) -> Vec<String> {
+            let mut rendered: Vec<_> = models
+                .map(|model| {
+                    let mut values: Vec<_> = model
+                        .iter()
+                        .map(|(variable, value)| format!("{}={}", variable, value))
+                        .collect();
+                    values.sort();
+                    values.join(",")
+                })
+                .collect();
+            rendered.sort();
+            rendered
+        }
+
+        let first_occurrence = EnumerationConfig {
+            ordering: VariableOrdering::FirstOccurrence,
+        };
+        let most_constrained = EnumerationConfig::default();
+
+        for formula in formulas(4, 4, 15, 50) {
+            let expected = render(enumerate_models(&formula, &first_occurrence));
+            let actual = render(enumerate_models(&formula, &most_constrained));
+
+            check!(!actual.is_empty() == crate::truth_table::is_satisfiable(&formula));
+            check!(expected == actual);
+        }
+    }
+}
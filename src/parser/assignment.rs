@@ -0,0 +1,74 @@
+//! Parser for (partial) truth assignments, one `<variable>=<value>` per line.
+//!
+//! ```text
+//! # an optional comment
+//! a=1
+//! b = 0
+//! ```
+//!
+//! Values are `1` or `true` and `0` or `false`. Blank lines and lines starting with `#` are
+//! ignored.
+
+use crate::formula::Assignment;
+
+use super::variable::variable;
+
+/// Parse a truth assignment.
+///
+/// # Errors
+///
+/// Fails if a line is malformed, or if a variable is assigned twice.
+pub fn parse_assignment(input: &str) -> Result<Assignment, String> {
+    let mut assignment = Assignment::new();
+
+    for line in input
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+    {
+        let malformed = || format!("malformed assignment: {:?}", line);
+
+        let mut parts = line.splitn(2, '=').map(str::trim);
+        let name = parts.next().ok_or_else(malformed)?;
+        let value = match parts.next() {
+            Some("1") | Some("true") => true,
+            Some("0") | Some("false") => false,
+            _ => return Err(malformed()),
+        };
+
+        let var = match variable(name) {
+            Ok(("", var)) => var,
+            _ => return Err(malformed()),
+        };
+
+        if assignment.insert(var, value).is_some() {
+            return Err(format!("variable assigned more than once: {:?}", name));
+        }
+    }
+
+    Ok(assignment)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formula::Variable;
+    use assert2::check;
+
+    #[test]
+    fn test_assignment() {
+        let assignment = parse_assignment("# fixed choices\na=1\n\n b = false\n").unwrap();
+
+        check!(assignment.len() == 2);
+        check!(assignment.get(&Variable::new("a")) == Some(&true));
+        check!(assignment.get(&Variable::new("b")) == Some(&false));
+    }
+
+    #[test]
+    fn test_malformed_assignment() {
+        check!(parse_assignment("a").is_err());
+        check!(parse_assignment("a=2").is_err());
+        check!(parse_assignment("(a)=1").is_err());
+        check!(parse_assignment("a=1\na=0").is_err());
+    }
+}
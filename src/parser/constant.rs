@@ -0,0 +1,65 @@
+//! Parser for the Boolean constants.
+
+use super::operators::{latex_macro, operator_word};
+use super::ParseResult;
+
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::combinator::value;
+
+/// Word spellings of the constants, which are reserved and cannot be used as variable names.
+pub const CONSTANT_WORDS: [&str; 2] = ["true", "false"];
+
+/// Checks if `name` is (case-insensitively) one of the [`CONSTANT_WORDS`].
+///
+/// [`CONSTANT_WORDS`]: constant.CONSTANT_WORDS.html
+pub fn is_constant_word(name: &str) -> bool {
+    CONSTANT_WORDS
+        .iter()
+        .any(|word| word.eq_ignore_ascii_case(name))
+}
+
+/// Parses a Boolean constant: `1`, `⊤`, `true` or `\top` for true, `0`, `⊥`, `false` or `\bot`
+/// for false.
+pub fn constant(input: &str) -> ParseResult<&str, bool> {
+    alt((
+        value(
+            true,
+            alt((
+                operator_word("1"),
+                tag("⊤"),
+                operator_word("true"),
+                latex_macro(r"\top"),
+            )),
+        ),
+        value(
+            false,
+            alt((
+                operator_word("0"),
+                tag("⊥"),
+                operator_word("false"),
+                latex_macro(r"\bot"),
+            )),
+        ),
+    ))(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert2::check;
+
+    #[test]
+    fn test_constant() {
+        check!(("", true) == constant("1").unwrap());
+        check!(("", false) == constant("0").unwrap());
+        check!((")", true) == constant("TRUE)").unwrap());
+        check!(("", false) == constant("false").unwrap());
+        check!((")", true) == constant("⊤)").unwrap());
+        check!(("", false) == constant("⊥").unwrap());
+        check!((" ", true) == constant(r"\top ").unwrap());
+        check!(constant(r"\bottom").is_err());
+        check!(constant("truth").is_err());
+        check!(constant("10").is_err());
+    }
+}
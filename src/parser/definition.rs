@@ -0,0 +1,167 @@
+//! Definitions of formula macros, e.g. `def big := ((a^b)^c)`, which are expanded in the formulas
+//! after them, see [`Macros`].
+//!
+//! [`Macros`]: struct.Macros.html
+
+use std::collections::HashMap;
+
+use super::propositional_formula::{formula_list_with, formula_with, space};
+use super::variable::variable;
+use super::{parse_whole, ParseError, ParseResult, ParserOptions};
+
+use crate::formula::template::replace;
+use crate::formula::{PropositionalFormula, Variable};
+
+use nom::branch::alt;
+use nom::bytes::complete::{tag, take_while1};
+use nom::combinator::{cut, map};
+use nom::sequence::{preceded, separated_pair, terminated, tuple};
+
+/// A line of input: either a definition or (optionally labeled) formulas separated by `;`.
+enum Line {
+    Definition(Variable, PropositionalFormula),
+    Formulas(Vec<(Option<String>, PropositionalFormula)>),
+}
+
+/// Parser for a definition `def <variable> := <formula>`, e.g. `def big := ((a^b)^c)`.
+pub fn definition(input: &str) -> ParseResult<&str, (Variable, PropositionalFormula)> {
+    definition_with(input, &ParserOptions::default())
+}
+
+fn definition_with<'a>(
+    input: &'a str,
+    options: &ParserOptions,
+) -> ParseResult<&'a str, (Variable, PropositionalFormula)> {
+    preceded(
+        terminated(tag("def"), take_while1(|c| c == ' ' || c == '\t')),
+        separated_pair(
+            variable,
+            tuple((space, tag(":="), space)),
+            cut(|input| formula_with(input, options)),
+        ),
+    )(input)
+}
+
+fn line<'a>(input: &'a str, options: &ParserOptions) -> ParseResult<&'a str, Line> {
+    alt((
+        map(
+            |input| definition_with(input, options),
+            |(name, formula)| Line::Definition(name, formula),
+        ),
+        map(|input| formula_list_with(input, options), Line::Formulas),
+    ))(input)
+}
+
+/// Formula macros defined so far, which are expanded in the formulas parsed after them, along
+/// with the options of the grammar the lines are parsed in.
+///
+/// A macro is used like a variable, and stands for its definition wherever it occurs. The
+/// definition is expanded when it is made, so it can use the macros defined before it; a variable
+/// which is redefined keeps standing for its old definition in the definitions made in between.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::parser::Macros;
+/// let mut macros = Macros::new();
+///
+/// assert!(macros.parse_line("def big := ((a^b)^c)").unwrap().is_empty());
+/// let formulas = macros.parse_line("(big -> a)").unwrap();
+/// assert_eq!("(((a^b)^c)->a)", formulas[0].1.to_string());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Macros {
+    definitions: HashMap<Variable, PropositionalFormula>,
+    options: ParserOptions,
+}
+
+impl Macros {
+    /// Construct an empty set of macros, parsing lines in the default grammar.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Construct an empty set of macros, parsing lines in the grammar chosen by the `options`.
+    pub fn with_options(options: ParserOptions) -> Self {
+        Self {
+            definitions: HashMap::new(),
+            options,
+        }
+    }
+
+    /// Number of macros defined.
+    pub fn len(&self) -> usize {
+        self.definitions.len()
+    }
+
+    /// Check if no macros are defined.
+    pub fn is_empty(&self) -> bool {
+        self.definitions.is_empty()
+    }
+
+    /// Define the macro `name` as the `formula`, after expanding the macros in it.
+    pub fn define(&mut self, name: Variable, formula: &PropositionalFormula) {
+        let formula = self.expand(formula);
+        self.definitions.insert(name, formula);
+    }
+
+    /// Replace the macros in the formula by their definitions.
+    pub fn expand(&self, formula: &PropositionalFormula) -> PropositionalFormula {
+        if self.definitions.is_empty() {
+            return formula.clone();
+        }
+        replace(formula, &self.definitions)
+    }
+
+    /// Parse a whole line, which is either a definition or (optionally labeled) formulas separated
+    /// by `;` like for [`parse_labeled_list`]. A definition is remembered and gives no formulas;
+    /// the macros in formulas are expanded.
+    ///
+    /// # Errors
+    ///
+    /// The errors of [`parse`], for the formula of the definition or any of the formulas.
+    ///
+    /// [`parse`]: ../fn.parse.html
+    /// [`parse_labeled_list`]: ../fn.parse_labeled_list.html
+    pub fn parse_line(
+        &mut self,
+        input: &str,
+    ) -> Result<Vec<(Option<String>, PropositionalFormula)>, ParseError> {
+        let options = self.options;
+        match parse_whole(input, &options, |input| line(input, &options))?.node {
+            Line::Definition(name, formula) => {
+                self.define(name, &formula);
+                Ok(Vec::new())
+            }
+            Line::Formulas(formulas) => Ok(formulas
+                .into_iter()
+                .map(|(label, formula)| (label, self.expand(&formula)))
+                .collect()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert2::check;
+
+    #[test]
+    fn test_definitions_build_on_each_other() {
+        let mut macros = Macros::new();
+        let mut parse = |input: &str| {
+            macros
+                .parse_line(input)
+                .map(|formulas| formulas.into_iter().map(|(_, f)| f.to_string()).collect())
+        };
+
+        check!(parse("def ab := (a^b)") == Ok(Vec::<String>::new()));
+        check!(parse("def abc := (ab ^ c)") == Ok(Vec::<String>::new()));
+        check!(parse("def ab := d") == Ok(Vec::<String>::new()));
+        check!(
+            parse("l: (abc -> ab); def")
+                == Ok(vec!["(((a^b)^c)->d)".to_string(), "def".to_string()])
+        );
+        check!(parse("def x := (a^?)").unwrap_err().position() == 12);
+    }
+}
@@ -0,0 +1,309 @@
+//! Errors of the formula parsers, see [`ParseError`].
+//!
+//! [`ParseError`]: struct.ParseError.html
+
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+use super::Span;
+
+/// Why an input is not a well-formed formula: what was found where, and what would have been
+/// accepted there instead.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::parser::parse;
+/// let error = parse("((a^b) | c d)").unwrap_err();
+///
+/// assert_eq!(11, error.position());
+/// assert_eq!(Some('d'), error.found);
+/// assert_eq!(vec!["`)`"], error.expected);
+/// assert_eq!("unexpected `d` at 11..12, expected `)`", error.to_string());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// What went wrong, e.g. ``unexpected `d` `` or that the formula is nested too deep.
+    pub message: String,
+    /// The offending part of the input, which is empty at the end of the input.
+    pub span: Span,
+    /// The offending character, or `None` at the end of the input.
+    pub found: Option<char>,
+    /// Descriptions of what would have been accepted at the offending position, e.g. `` `)` ``
+    /// or `a formula`, without repetitions.
+    pub expected: Vec<&'static str>,
+    /// What was probably meant at the offending position, e.g. `` `->` `` where `=>` was typed.
+    pub suggestion: Option<&'static str>,
+    /// The line of the input containing the error, without its line terminator.
+    pub source_line: String,
+    /// Column of the error in the `source_line` in characters, counting from `0`.
+    pub column: usize,
+    kind: Kind,
+}
+
+/// What kind of error a [`ParseError`] is, beyond its message.
+///
+/// [`ParseError`]: struct.ParseError.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    /// The input is not a well-formed formula.
+    IllFormed,
+    /// The input is nested deeper than the parser allows.
+    TooDeep,
+    /// The input could not even be read, see [`parse_stream`].
+    ///
+    /// [`parse_stream`]: ../fn.parse_stream.html
+    Io,
+}
+
+impl ParseError {
+    /// Construct the error about the `span` of the `input`, locating its source line.
+    pub(crate) fn new(
+        input: &str,
+        message: String,
+        span: Span,
+        found: Option<char>,
+        expected: Vec<&'static str>,
+    ) -> Self {
+        let line_start = input[..span.start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = input[span.start..]
+            .find('\n')
+            .map_or(input.len(), |i| span.start + i);
+        let source_line = input[line_start..line_end].trim_end_matches('\r');
+
+        Self {
+            message,
+            span,
+            found,
+            expected,
+            suggestion: None,
+            source_line: source_line.to_string(),
+            column: span.line_column(input).1,
+            kind: Kind::IllFormed,
+        }
+    }
+
+    /// Construct the error of the parenthesis or operator at byte offset `i` of the `input`
+    /// nesting the formula more than `max_depth` deep.
+    pub(crate) fn too_deep(input: &str, i: usize, max_depth: usize) -> Self {
+        let found = input[i..].chars().next();
+        let end = i + found.map_or(0, char::len_utf8);
+        Self {
+            kind: Kind::TooDeep,
+            ..Self::new(
+                input,
+                format!("formula is nested more than {} deep", max_depth),
+                Span::new(i, end),
+                found,
+                Vec::new(),
+            )
+        }
+    }
+
+    /// Construct the error of failing to read the input.
+    pub(crate) fn io(error: &io::Error) -> Self {
+        Self {
+            kind: Kind::Io,
+            ..Self::new(
+                "",
+                format!("failed to read the input: {}", error),
+                Span::default(),
+                None,
+                Vec::new(),
+            )
+        }
+    }
+
+    /// Checks if the input could not be read, rather than being ill-formed.
+    pub fn is_io_error(&self) -> bool {
+        self.kind == Kind::Io
+    }
+
+    /// Checks if the input is nested deeper than the parser allows, see
+    /// [`ParserOptions::max_depth`], rather than being otherwise ill-formed.
+    ///
+    /// [`ParserOptions::max_depth`]: ../options/struct.ParserOptions.html#structfield.max_depth
+    pub fn is_too_deep(&self) -> bool {
+        self.kind == Kind::TooDeep
+    }
+
+    /// Byte offset of the error in the input.
+    pub fn position(&self) -> usize {
+        self.span.start
+    }
+
+    /// Render the error as a diagnostic, with the source line and a caret under the offending
+    /// character, e.g.
+    ///
+    /// ```text
+    /// unexpected `d`, expected `)`
+    ///   |
+    ///   | ((a^b) | c d)
+    ///   |            ^
+    /// ```
+    ///
+    /// followed by a line like `` = help: did you mean `->`? `` if there is a suggestion.
+    pub fn diagnostic(&self) -> String {
+        let help = match self.suggestion {
+            Some(suggestion) => format!("\n  = help: did you mean {}?", suggestion),
+            None => String::new(),
+        };
+        format!(
+            "{}{}\n  |\n  | {}\n  | {}^{}",
+            self.message,
+            self.expectation(),
+            self.source_line,
+            " ".repeat(self.column),
+            help
+        )
+    }
+
+    /// `, expected a, b or c`, or nothing if there are no expectations.
+    fn expectation(&self) -> String {
+        match self.expected.split_last() {
+            None => String::new(),
+            Some((last, [])) => format!(", expected {}", last),
+            Some((last, rest)) => format!(", expected {} or {}", rest.join(", "), last),
+        }
+    }
+}
+
+/// Formats the message, span, expectations and suggestion on one line, e.g.
+/// ``unexpected `d` at 11..12, expected `)` ``.
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {}{}", self.message, self.span, self.expectation())?;
+        match self.suggestion {
+            Some(suggestion) => write!(f, "; did you mean {}?", suggestion),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// Lets `?` turn the error into the one-line message where errors are strings.
+impl From<ParseError> for String {
+    fn from(error: ParseError) -> Self {
+        error.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::{
+        parse, parse_quantified, parse_stream, parse_with, ParserOptions, MAX_NESTING_DEPTH,
+    };
+    use assert2::check;
+
+    #[test]
+    fn test_expected() {
+        let expected = |input: &str| parse(input).unwrap_err().expected;
+
+        check!(expected("") == vec!["a formula"]);
+        check!(expected("(a^?)") == vec!["a formula"]);
+        check!(expected("(?)") == vec!["`-`", "a formula"]);
+        check!(expected("(a b)") == vec!["`^`", "`|`", "`->`", "`<->`", "`⊕`"]);
+        check!(expected("(a^b") == vec!["`)`"]);
+        check!(expected("a b") == vec!["end of input"]);
+        check!(crate::parser::parse_list("a; ?").unwrap_err().expected == vec!["a formula"]);
+    }
+
+    #[test]
+    fn test_stream_stops_at_io_error() {
+        // Invalid UTF-8 cannot be read as a line.
+        let results: Vec<_> = parse_stream(&b"(a^b)\n\xff\n(a|b)\n"[..]).collect();
+
+        check!(results.len() == 2);
+        check!(results[0].is_ok());
+        check!(results[1].as_ref().unwrap_err().is_io_error());
+        check!(!parse("(a^?)").unwrap_err().is_io_error());
+    }
+
+    #[test]
+    fn test_too_deep() {
+        let nested = format!("{}a{}", "(-".repeat(100_000), ")".repeat(100_000));
+        let error = parse(&nested).unwrap_err();
+
+        check!(error.is_too_deep());
+        check!(error.position() == 2 * MAX_NESTING_DEPTH);
+        check!(error.to_string() == "formula is nested more than 256 deep at 512..513");
+        check!(!parse("(a^?)").unwrap_err().is_too_deep());
+
+        let options = ParserOptions::new().with_max_depth(1);
+        check!(parse_with("(-a)", &options).is_ok());
+        check!(parse_with("(-(-a))", &options).unwrap_err().is_too_deep());
+    }
+
+    #[test]
+    fn test_too_deep_without_parentheses() {
+        let options = ParserOptions::new().with_require_parentheses(false);
+        let negations = format!("{}a", "-".repeat(100_000));
+        let error = parse_with(&negations, &options).unwrap_err();
+        check!(error.is_too_deep());
+        check!(error.position() == MAX_NESTING_DEPTH);
+
+        let conjunctions = vec!["a"; 100_000].join(" ^ ");
+        check!(parse_with(&conjunctions, &options)
+            .unwrap_err()
+            .is_too_deep());
+        let implications = vec!["a"; 100_000].join(" -> ");
+        check!(parse_with(&implications, &options)
+            .unwrap_err()
+            .is_too_deep());
+
+        let options = options.with_max_depth(2);
+        check!(parse_with("-a ^ b", &options).is_ok());
+        check!(parse_with("--a ^ b", &options).unwrap_err().is_too_deep());
+        check!(parse_with("a ^ b ^ c", &options).is_ok());
+        check!(parse_with("a -> b -> c", &options).is_ok());
+        check!(parse_with("a ^ b ^ --c", &options)
+            .unwrap_err()
+            .is_too_deep());
+        check!(
+            parse_with("a ^ b ^ c ^ d", &options)
+                .unwrap_err()
+                .position()
+                == 10
+        );
+        check!(parse_with("---a", &options).unwrap_err().position() == 2);
+        check!(parse_with("((a))", &options).is_ok());
+        check!(parse_with("(((a)))", &options).unwrap_err().is_too_deep());
+    }
+
+    #[test]
+    fn test_too_deep_quantifiers() {
+        let binders = format!("{}a", "forall a. ".repeat(100_000));
+        check!(parse_quantified(&binders).unwrap_err().is_too_deep());
+        check!(parse_quantified(&format!("{}a", "forall a. ".repeat(MAX_NESTING_DEPTH))).is_ok());
+    }
+
+    #[test]
+    fn test_diagnostic() {
+        let error = parse("(¬a ^ b)").unwrap_err();
+        check!(error.column == 4);
+        check!(error.diagnostic() == "unexpected `^`, expected `)`\n  |\n  | (¬a ^ b)\n  |     ^");
+    }
+
+    #[test]
+    fn test_arrow_suggestions() {
+        let suggestion = |input: &str| parse(input).unwrap_err().suggestion;
+
+        check!(suggestion("(a => b)") == Some("`->`"));
+        check!(suggestion("(a > b)") == Some("`->`"));
+        check!(suggestion("(a = b)") == Some("`->`"));
+        check!(suggestion("(a <=> b)") == Some("`<->`"));
+        check!(suggestion("(a ? b)") == None);
+        check!(
+            parse("(a => b)").unwrap_err().diagnostic()
+                == concat!(
+                    "unexpected `=`, expected `^`, `|`, `->`, `<->` or `⊕`\n",
+                    "  |\n",
+                    "  | (a => b)\n",
+                    "  |    ^\n",
+                    "  = help: did you mean `->`?",
+                )
+        );
+    }
+}
@@ -1,10 +1,39 @@
 //! Parser combinators for parsing propositional formulas from strings.
 
+pub mod assignment;
+pub mod constant;
+pub mod definition;
+pub mod error;
 pub mod operators;
+pub mod options;
+pub mod prefix;
 pub mod propositional_formula;
+pub mod qdimacs;
+pub mod quantified_formula;
+pub mod smtlib;
+pub mod span;
+pub mod token;
+pub mod tptp;
 pub mod variable;
 
-use libprop_sat_solver::formula::PropositionalFormula;
+pub use assignment::parse_assignment;
+pub use definition::Macros;
+pub use error::ParseError;
+pub use options::ParserOptions;
+pub use prefix::parse_prefix;
+pub use qdimacs::parse_qdimacs;
+pub use smtlib::parse_smtlib;
+pub use span::{Span, Spanned};
+pub use token::{tokenize, tokens, Token};
+pub use tptp::parse_tptp;
+
+use nom::error::ErrorKind;
+use nom::InputLength;
+use std::cmp::Ordering;
+use std::io::BufRead;
+
+use crate::formula::PropositionalFormula;
+use crate::qbf::QuantifiedFormula;
 
 /// Newtype for [`nom::IResult`] so we don't expose third-party API.
 ///
@@ -15,19 +44,410 @@ use libprop_sat_solver::formula::PropositionalFormula;
 ///
 /// # Error Type
 ///
-/// We default to `nom`'s error type which implements `std::error::Error` so we can use the `?`
-/// operator.
+/// Errors are [`Failure`]s, which keep the input remaining where parsing got furthest, so that the
+/// error can point at the offending characters.
 ///
 /// [`nom::IResult`]: https://docs.rs/nom/5.1.1/nom/type.IResult.html
-pub type ParseResult<I, O> = nom::IResult<I, O>;
+/// [`Failure`]: struct.Failure.html
+pub type ParseResult<I, O> = nom::IResult<I, O, Failure<I>>;
+
+/// Error of the parser combinators: the input remaining where a parser failed, which kind of
+/// parser it was, and what would have been accepted there.
+///
+/// Out of the alternatives of a choice which all fail, the one which got furthest into the input
+/// is kept, since it most likely failed on the actual mistake; e.g. in `(a^?)` that is the `?`
+/// rather than the `^`, which only fails to be `|`. Alternatives which got equally far pool what
+/// they expected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure<I> {
+    pub input: I,
+    pub kind: ErrorKind,
+    /// Descriptions of what would have been accepted at `input`, attached by
+    /// [`nom::error::context`].
+    ///
+    /// [`nom::error::context`]: https://docs.rs/nom/5.1.1/nom/error/fn.context.html
+    pub expected: Vec<&'static str>,
+}
+
+impl<I: InputLength> nom::error::ParseError<I> for Failure<I> {
+    fn from_error_kind(input: I, kind: ErrorKind) -> Self {
+        Self {
+            input,
+            kind,
+            expected: Vec::new(),
+        }
+    }
+
+    /// Keep the failure of the inner parser, which is where parsing actually stopped.
+    fn append(_: I, _: ErrorKind, other: Self) -> Self {
+        other
+    }
+
+    fn or(mut self, other: Self) -> Self {
+        match other.input.input_len().cmp(&self.input.input_len()) {
+            Ordering::Less => other,
+            Ordering::Greater => self,
+            Ordering::Equal => {
+                for expected in other.expected {
+                    if !self.expected.contains(&expected) {
+                        self.expected.push(expected);
+                    }
+                }
+                self
+            }
+        }
+    }
+
+    /// Describe the failure by the `context` if the parser failed right where it started, since
+    /// the context (e.g. `a formula`) sums up what its parts expected (e.g. `(` or a variable).
+    fn add_context(input: I, context: &'static str, mut other: Self) -> Self {
+        if other.input.input_len() == input.input_len() {
+            other.expected = vec![context];
+        }
+        other
+    }
+}
+
+/// Deepest nesting accepted by [`parse`] and [`parse_quantified`], counting the parentheses,
+/// negations, binary operators and quantifiers around each sub-formula. The parsers are recursive,
+/// and so is most of what is done with the formulas, so deeper inputs are rejected as soon as they
+/// are reached rather than overflowing the stack.
+///
+/// [`parse`]: fn.parse.html
+/// [`parse_quantified`]: fn.parse_quantified.html
+pub const MAX_NESTING_DEPTH: usize = 256;
+
+/// Parse a whole string into a propositional formula, ignoring trailing whitespace.
+///
+/// # Errors
+///
+/// Fails if the input does not start with a well-formed formula, or has more input after it, or
+/// if it is nested deeper than [`MAX_NESTING_DEPTH`] (for which [`ParseError::is_too_deep`]
+/// holds). The [`ParseError`] points at the offending character and lists what would have been
+/// accepted there.
+///
+/// [`MAX_NESTING_DEPTH`]: constant.MAX_NESTING_DEPTH.html
+/// [`ParseError`]: error/struct.ParseError.html
+/// [`ParseError::is_too_deep`]: error/struct.ParseError.html#method.is_too_deep
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::parser::parse;
+/// assert_eq!(
+///     "unexpected `?` at 3..4, expected a formula",
+///     parse("(a^?)").unwrap_err().to_string(),
+/// );
+/// ```
+pub fn parse(input: &str) -> Result<PropositionalFormula, ParseError> {
+    parse_spanned(input).map(|formula| formula.node)
+}
+
+/// Parse a whole string into a propositional formula in the grammar chosen by the `options`, e.g.
+/// without requiring parentheses.
+///
+/// # Errors
+///
+/// The errors of [`parse`], where the nesting depth is limited by the `options` and characters
+/// which are not ASCII are rejected unless the `options` allow Unicode spellings.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::parser::{parse_with, ParserOptions};
+/// let options = ParserOptions::new().with_allow_constants(false);
+/// assert!(parse_with("(a ^ b)", &options).is_ok());
+/// assert_eq!(
+///     "unexpected `1` at 5..6, expected a formula",
+///     parse_with("(a ^ 1)", &options).unwrap_err().to_string(),
+/// );
+/// ```
+///
+/// [`parse`]: fn.parse.html
+pub fn parse_with(
+    input: &str,
+    options: &ParserOptions,
+) -> Result<PropositionalFormula, ParseError> {
+    parse_whole(input, options, |input| {
+        propositional_formula::formula_with(input, options)
+    })
+    .map(|formula| formula.node)
+}
+
+/// Parse a whole string into a propositional formula like [`parse`], along with its label if it
+/// has one, e.g. `lemma1` in `lemma1: (a -> (b -> a))`.
+///
+/// # Errors
+///
+/// The errors of [`parse`].
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::parser::parse_labeled;
+/// let (label, formula) = parse_labeled("lemma1: (a -> (b -> a))").unwrap();
+/// assert_eq!(Some("lemma1".to_string()), label);
+/// assert_eq!("(a->(b->a))", formula.to_string());
+///
+/// assert_eq!(None, parse_labeled("(a^b)").unwrap().0);
+/// ```
+///
+/// [`parse`]: fn.parse.html
+pub fn parse_labeled(input: &str) -> Result<(Option<String>, PropositionalFormula), ParseError> {
+    parse_whole(
+        input,
+        &ParserOptions::default(),
+        propositional_formula::labeled_formula,
+    )
+    .map(|formula| formula.node)
+}
 
-pub fn parse(input: &str) -> Result<PropositionalFormula, String> {
-    let (remaining_input, formula) =
-        propositional_formula::propositional_formula(input).map_err(|_| "failed to parse input")?;
+/// Parse a whole string into the (optionally labeled) formulas separated by `;` in it, e.g.
+/// `a; (a->c); neg: (-d)`, like [`parse_labeled`].
+///
+/// # Errors
+///
+/// The errors of [`parse`], for any of the formulas. A `;` must be followed by a formula.
+///
+/// [`parse`]: fn.parse.html
+/// [`parse_labeled`]: fn.parse_labeled.html
+pub fn parse_labeled_list(
+    input: &str,
+) -> Result<Vec<(Option<String>, PropositionalFormula)>, ParseError> {
+    parse_whole(
+        input,
+        &ParserOptions::default(),
+        propositional_formula::formula_list,
+    )
+    .map(|formulas| formulas.node)
+}
 
-    if !remaining_input.trim().is_empty() {
-        Err(format!("still input remaining: {:?}", &input))
+/// Parse a whole string into the formulas separated by `;` in it, e.g. `a; (a->c); (-d)`, so that
+/// several formulas fit on one line. Labels are accepted but dropped, see [`parse_labeled_list`].
+///
+/// # Errors
+///
+/// The errors of [`parse`], for any of the formulas. A `;` must be followed by a formula.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::parser::parse_list;
+/// let formulas = parse_list("(a^b); (a->c) ;(-d)").unwrap();
+/// assert_eq!(3, formulas.len());
+/// assert_eq!("(a->c)", formulas[1].to_string());
+///
+/// assert_eq!(1, parse_list("(a^b)").unwrap().len());
+/// assert!(parse_list("(a^b);").is_err());
+/// ```
+///
+/// [`parse`]: fn.parse.html
+/// [`parse_labeled_list`]: fn.parse_labeled_list.html
+pub fn parse_list(input: &str) -> Result<Vec<PropositionalFormula>, ParseError> {
+    parse_labeled_list(input)
+        .map(|formulas| formulas.into_iter().map(|(_, formula)| formula).collect())
+}
+
+/// Lazily parse each line read from the `reader` into its formulas, like [`parse_list`], so that
+/// huge inputs never have to be held in memory at once. Lines may also define macros, which are
+/// expanded in the lines after them and give no formulas themselves, see [`Macros`].
+///
+/// Exactly one item is yielded per line, so `enumerate` gives the line numbers. If reading a line
+/// fails, the last item is an error describing it, for which [`ParseError::is_io_error`] holds.
+///
+/// [`ParseError::is_io_error`]: error/struct.ParseError.html#method.is_io_error
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::parser::parse_stream;
+/// let input = "(a^b)\n(a|?)\n-a\nb; c\n";
+/// let results: Vec<_> = parse_stream(input.as_bytes()).map(|result| result.is_ok()).collect();
+/// assert_eq!(vec![true, false, false, true], results);
+///
+/// let input = "def ab := (a^b)\n(ab|c)\n";
+/// let results: Vec<_> = parse_stream(input.as_bytes()).map(Result::unwrap).collect();
+/// assert_eq!(0, results[0].len());
+/// assert_eq!("((a^b)|c)", results[1][0].to_string());
+/// ```
+///
+/// [`parse_list`]: fn.parse_list.html
+/// [`Macros`]: definition/struct.Macros.html
+pub fn parse_stream<R: BufRead>(
+    reader: R,
+) -> impl Iterator<Item = Result<Vec<PropositionalFormula>, ParseError>> {
+    parse_stream_with(reader, ParserOptions::default())
+}
+
+/// Lazily parse each line read from the `reader` like [`parse_stream`], in the grammar chosen by
+/// the `options`.
+///
+/// [`parse_stream`]: fn.parse_stream.html
+pub fn parse_stream_with<R: BufRead>(
+    reader: R,
+    options: ParserOptions,
+) -> impl Iterator<Item = Result<Vec<PropositionalFormula>, ParseError>> {
+    reader.lines().scan(
+        (false, Macros::with_options(options)),
+        |(failed, macros), line| {
+            if *failed {
+                return None;
+            }
+            Some(match line {
+                Ok(line) => macros
+                    .parse_line(&line)
+                    .map(|formulas| formulas.into_iter().map(|(_, formula)| formula).collect()),
+                Err(e) => {
+                    *failed = true;
+                    Err(ParseError::io(&e))
+                }
+            })
+        },
+    )
+}
+
+/// Parse a whole string into a propositional formula like [`parse`], keeping the span of the
+/// formula in the input (see [`Spanned::sub_formulas`] for the spans of its sub-formulas).
+///
+/// # Errors
+///
+/// The errors of [`parse`].
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::parser::{parse_spanned, Span};
+/// let formula = parse_spanned("(a ^ b)  ").unwrap();
+/// assert_eq!(Span::new(0, 7), formula.span);
+/// ```
+///
+/// [`parse`]: fn.parse.html
+/// [`Spanned::sub_formulas`]: span/struct.Spanned.html#method.sub_formulas
+pub fn parse_spanned(input: &str) -> Result<Spanned<PropositionalFormula>, ParseError> {
+    parse_whole(
+        input,
+        &ParserOptions::default(),
+        propositional_formula::propositional_formula,
+    )
+}
+
+/// Parse the formula at the start of a string like [`parse`], returning it along with the rest of
+/// the input after it, e.g. for a formula followed by metadata on the same line.
+///
+/// # Errors
+///
+/// The errors of [`parse`], except that more input may follow the formula.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::parser::parse_partial;
+/// let (formula, rest) = parse_partial("(a ^ b) # from lecture 3").unwrap();
+/// assert_eq!("(a^b)", formula.to_string());
+/// assert_eq!(" # from lecture 3", rest);
+///
+/// assert!(parse_partial("(a ^ ?) # b").is_err());
+/// ```
+///
+/// [`parse`]: fn.parse.html
+pub fn parse_partial(input: &str) -> Result<(PropositionalFormula, &str), ParseError> {
+    match propositional_formula::propositional_formula(input) {
+        Ok((remaining_input, formula)) => Ok((formula, remaining_input)),
+        Err(nom::Err::Error(e)) | Err(nom::Err::Failure(e)) => {
+            Err(failed(input, e, MAX_NESTING_DEPTH))
+        }
+        Err(nom::Err::Incomplete(_)) => Err(unexpected(input, "", Vec::new())),
+    }
+}
+
+/// Parse a whole string into a quantified Boolean formula in prenex form, like [`parse`].
+///
+/// [`parse`]: fn.parse.html
+pub fn parse_quantified(input: &str) -> Result<QuantifiedFormula, ParseError> {
+    parse_whole(
+        input,
+        &ParserOptions::default(),
+        quantified_formula::quantified_formula,
+    )
+    .map(|formula| formula.node)
+}
+
+/// Run the `parser` on the whole `input`, allowing trailing whitespace, after checking the
+/// spellings allowed by the `options`.
+fn parse_whole<'a, O>(
+    input: &'a str,
+    options: &ParserOptions,
+    parser: impl Fn(&'a str) -> ParseResult<&'a str, O>,
+) -> Result<Spanned<O>, ParseError> {
+    check_spellings(input, options)?;
+
+    let (remaining_input, expected) = match parser(input) {
+        Ok((remaining_input, node)) if remaining_input.trim().is_empty() => {
+            let end = input.len() - remaining_input.len();
+            return Ok(Spanned::new(node, Span::new(0, end)));
+        }
+        Ok((remaining_input, _)) => (remaining_input.trim_start(), vec!["end of input"]),
+        Err(nom::Err::Error(e)) | Err(nom::Err::Failure(e)) => {
+            return Err(failed(input, e, options.max_depth))
+        }
+        Err(nom::Err::Incomplete(_)) => ("", Vec::new()),
+    };
+
+    Err(unexpected(input, remaining_input, expected))
+}
+
+/// The error of the parser `failure` on the `input`, which is nested too deep if the failure is
+/// of kind `TooLarge` (see [`propositional_formula::formula_with`]).
+///
+/// [`propositional_formula::formula_with`]: propositional_formula/fn.formula_with.html
+fn failed(input: &str, failure: Failure<&str>, max_depth: usize) -> ParseError {
+    if failure.kind == ErrorKind::TooLarge {
+        ParseError::too_deep(input, input.len() - failure.input.len(), max_depth)
     } else {
-        Ok(formula)
+        unexpected(input, failure.input, failure.expected)
+    }
+}
+
+/// The error of finding the first character of the `remaining_input` (or the end of the `input`)
+/// where something `expected` should be.
+fn unexpected(input: &str, remaining_input: &str, expected: Vec<&'static str>) -> ParseError {
+    let start = input.len() - remaining_input.len();
+    let found = remaining_input.chars().next();
+    let (message, span) = match found {
+        Some(c) => (
+            format!("unexpected `{}`", c),
+            Span::new(start, start + c.len_utf8()),
+        ),
+        None => (
+            "unexpected end of input".to_string(),
+            Span::new(start, start),
+        ),
+    };
+    let mut error = ParseError::new(input, message, span, found, expected);
+    error.suggestion = arrow_suggestion(remaining_input);
+    error
+}
+
+/// The arrow probably meant by a half-typed or misspelled one at the start of the
+/// `remaining_input`, e.g. `=>` for `->`.
+fn arrow_suggestion(remaining_input: &str) -> Option<&'static str> {
+    if remaining_input.starts_with("<=>") || remaining_input.starts_with("<>") {
+        Some("`<->`")
+    } else if remaining_input.starts_with('=') || remaining_input.starts_with('>') {
+        Some("`->`")
+    } else {
+        None
+    }
+}
+
+/// Unquoted variables and labels are ASCII, so any other character outside quotes is a Unicode
+/// spelling of an operator or constant.
+fn check_spellings(input: &str, options: &ParserOptions) -> Result<(), ParseError> {
+    if options.allow_unicode {
+        return Ok(());
+    }
+    match variable::unquoted_char_indices(input).find(|(_, c)| !c.is_ascii()) {
+        Some((i, _)) => Err(unexpected(input, &input[i..], vec!["an ASCII spelling"])),
+        None => Ok(()),
     }
 }
@@ -2,35 +2,142 @@
 
 use super::ParseResult;
 
-use libprop_sat_solver::formula::{BinaryOperator, UnaryOperator};
+use crate::formula::{BinaryOperator, UnaryOperator};
 
-use nom::bytes::complete::tag;
-use nom::character::complete::char;
-use nom::combinator::value;
+use nom::branch::alt;
+use nom::bytes::complete::{tag, tag_no_case};
+use nom::character::complete::anychar;
+use nom::combinator::{not, value, verify};
+use nom::error::context;
+use nom::sequence::terminated;
 
-/// Parses the negation operator.
+/// Word spellings of the operators, which are reserved and cannot be used as variable names.
+pub const OPERATOR_WORDS: [&str; 5] = ["not", "and", "or", "implies", "iff"];
+
+/// Checks if `name` is (case-insensitively) one of the [`OPERATOR_WORDS`].
+///
+/// [`OPERATOR_WORDS`]: constant.OPERATOR_WORDS.html
+pub fn is_operator_word(name: &str) -> bool {
+    OPERATOR_WORDS
+        .iter()
+        .any(|word| word.eq_ignore_ascii_case(name))
+}
+
+/// Parses the case-insensitive word `word`, which must not be immediately followed by another
+/// alphanumeric character (so that `andy` is not mistaken for `and`).
+pub(crate) fn operator_word<'a>(
+    word: &'static str,
+) -> impl Fn(&'a str) -> ParseResult<&'a str, &'a str> {
+    terminated(
+        tag_no_case(word),
+        not(verify(anychar, |c: &char| c.is_ascii_alphanumeric())),
+    )
+}
+
+/// Parses the LaTeX macro `\name`, which must not be immediately followed by another letter (so
+/// that `\top` is not mistaken for `\to`).
+pub(crate) fn latex_macro<'a>(
+    name: &'static str,
+) -> impl Fn(&'a str) -> ParseResult<&'a str, &'a str> {
+    terminated(
+        tag(name),
+        not(verify(anychar, |c: &char| c.is_ascii_alphabetic())),
+    )
+}
+
+/// Parses the negation operator: `-`, `!`, `~`, `¬`, `not`, `\neg` or `\lnot`.
 pub fn negation_operator(input: &str) -> ParseResult<&str, UnaryOperator> {
-    value(UnaryOperator::Negation, char('-'))(input)
+    context(
+        "`-`",
+        value(
+            UnaryOperator::Negation,
+            alt((
+                tag("-"),
+                tag("!"),
+                tag("~"),
+                tag("¬"),
+                operator_word("not"),
+                latex_macro(r"\neg"),
+                latex_macro(r"\lnot"),
+            )),
+        ),
+    )(input)
 }
 
-/// Parses the logical AND operator.
+/// Parses the logical AND operator: `^`, `&&`, `∧`, `and`, `\land` or `\wedge`.
 pub fn and_operator(input: &str) -> ParseResult<&str, BinaryOperator> {
-    value(BinaryOperator::And, char('^'))(input)
+    context(
+        "`^`",
+        value(
+            BinaryOperator::And,
+            alt((
+                tag("^"),
+                tag("&&"),
+                tag("∧"),
+                operator_word("and"),
+                latex_macro(r"\land"),
+                latex_macro(r"\wedge"),
+            )),
+        ),
+    )(input)
 }
 
-/// Parses the logical OR operator.
+/// Parses the logical OR operator: `|`, `||`, `∨`, `or`, `\lor` or `\vee`.
 pub fn or_operator(input: &str) -> ParseResult<&str, BinaryOperator> {
-    value(BinaryOperator::Or, char('|'))(input)
+    context(
+        "`|`",
+        value(
+            BinaryOperator::Or,
+            // `||` before `|`, which would leave the second `|` behind.
+            alt((
+                tag("||"),
+                tag("|"),
+                tag("∨"),
+                operator_word("or"),
+                latex_macro(r"\lor"),
+                latex_macro(r"\vee"),
+            )),
+        ),
+    )(input)
 }
 
-/// Parses the implication operator.
+/// Parses the implication operator: `->`, `→`, `implies`, `\rightarrow` or `\to`.
 pub fn implication_operator(input: &str) -> ParseResult<&str, BinaryOperator> {
-    value(BinaryOperator::Implication, tag("->"))(input)
+    context(
+        "`->`",
+        value(
+            BinaryOperator::Implication,
+            alt((
+                tag("->"),
+                tag("→"),
+                operator_word("implies"),
+                latex_macro(r"\rightarrow"),
+                latex_macro(r"\to"),
+            )),
+        ),
+    )(input)
 }
 
-/// Parses the biimplication operator.
+/// Parses the biimplication operator: `<->`, `↔`, `iff` or `\leftrightarrow`.
 pub fn biimplication_operator(input: &str) -> ParseResult<&str, BinaryOperator> {
-    value(BinaryOperator::Biimplication, tag("<->"))(input)
+    context(
+        "`<->`",
+        value(
+            BinaryOperator::Biimplication,
+            alt((
+                tag("<->"),
+                tag("↔"),
+                operator_word("iff"),
+                latex_macro(r"\leftrightarrow"),
+            )),
+        ),
+    )(input)
+}
+
+/// Parses the exclusive-or operator `⊕` or `\oplus`. There is no exclusive-or connective, so
+/// `(A ⊕ B)` is parsed as `(-(A<->B))`.
+pub fn exclusive_or_operator(input: &str) -> ParseResult<&str, ()> {
+    context("`⊕`", value((), alt((tag("⊕"), latex_macro(r"\oplus")))))(input)
 }
 
 #[cfg(test)]
@@ -62,4 +169,51 @@ mod tests {
     fn test_biimplication_operator() {
         check!(("", BinaryOperator::Biimplication) == biimplication_operator("<->").unwrap());
     }
+
+    #[test]
+    fn test_word_operators() {
+        check!(("", UnaryOperator::Negation) == negation_operator("NOT").unwrap());
+        check!((" b", BinaryOperator::And) == and_operator("and b").unwrap());
+        check!(("", BinaryOperator::Or) == or_operator("Or").unwrap());
+        check!(("", BinaryOperator::Implication) == implication_operator("implies").unwrap());
+        check!(("", BinaryOperator::Biimplication) == biimplication_operator("IFF").unwrap());
+    }
+
+    #[test]
+    fn test_c_style_operators() {
+        check!(("a", UnaryOperator::Negation) == negation_operator("!a").unwrap());
+        check!(("", BinaryOperator::And) == and_operator("&&").unwrap());
+        check!(("", BinaryOperator::Or) == or_operator("||").unwrap());
+        check!(and_operator("&").is_err());
+    }
+
+    #[test]
+    fn test_latex_operators() {
+        check!((" a", UnaryOperator::Negation) == negation_operator(r"\neg a").unwrap());
+        check!(("", BinaryOperator::And) == and_operator(r"\land").unwrap());
+        check!(("", BinaryOperator::Or) == or_operator(r"\vee").unwrap());
+        check!(("", BinaryOperator::Implication) == implication_operator(r"\to").unwrap());
+        check!(
+            ("", BinaryOperator::Biimplication)
+                == biimplication_operator(r"\leftrightarrow").unwrap()
+        );
+        check!(implication_operator(r"\top").is_err());
+        check!(and_operator(r"\landx").is_err());
+    }
+
+    #[test]
+    fn test_unicode_operators() {
+        check!(("a", UnaryOperator::Negation) == negation_operator("¬a").unwrap());
+        check!(("", BinaryOperator::And) == and_operator("∧").unwrap());
+        check!(("", BinaryOperator::Or) == or_operator("∨").unwrap());
+        check!(("", BinaryOperator::Implication) == implication_operator("→").unwrap());
+        check!(("", BinaryOperator::Biimplication) == biimplication_operator("↔").unwrap());
+        check!(("", ()) == exclusive_or_operator("⊕").unwrap());
+    }
+
+    #[test]
+    fn test_word_operator_prefix_of_variable() {
+        check!(and_operator("andy").is_err());
+        check!(negation_operator("nota").is_err());
+    }
 }
@@ -0,0 +1,87 @@
+//! Options choosing the grammar accepted by the parsers, see [`ParserOptions`].
+//!
+//! [`ParserOptions`]: struct.ParserOptions.html
+
+use super::MAX_NESTING_DEPTH;
+
+/// Which formulas the parsers accept, on top of the fully-parenthesized grammar of [`parse`].
+///
+/// The default options accept exactly what [`parse`] does. They can be made stricter (e.g. only
+/// ASCII spellings) or more permissive (e.g. binary operators without parentheses) with the
+/// builder methods.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::parser::{parse_with, ParserOptions};
+/// let options = ParserOptions::new().with_require_parentheses(false);
+/// let formula = parse_with("a ^ -b -> c | d", &options).unwrap();
+/// assert_eq!("((a^(-b))->(c|d))", formula.to_string());
+///
+/// let options = ParserOptions::new().with_allow_unicode(false);
+/// assert!(parse_with("(a ∧ b)", &options).is_err());
+/// ```
+///
+/// [`parse`]: ../fn.parse.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParserOptions {
+    /// Whether every unary and binary operation must be parenthesized. Without, operators bind
+    /// from loosest to tightest `<->` and `⊕`, `->`, `|`, `^`, `-`; `->` associates to the right
+    /// and the others to the left.
+    pub require_parentheses: bool,
+    /// Whether the Unicode spellings of operators and constants, e.g. `∧` or `⊤`, are accepted.
+    pub allow_unicode: bool,
+    /// Whether the constants, e.g. `1` or `false`, are accepted.
+    pub allow_constants: bool,
+    /// Deepest nesting accepted, counting the parentheses, negations and binary operators around
+    /// each sub-formula. Deeper inputs are rejected as soon as they are reached, before the
+    /// recursive parser could overflow the stack.
+    pub max_depth: usize,
+}
+
+impl Default for ParserOptions {
+    /// The grammar of [`parse`]: parentheses required, Unicode and constants allowed, nested at
+    /// most [`MAX_NESTING_DEPTH`] deep.
+    ///
+    /// [`parse`]: ../fn.parse.html
+    /// [`MAX_NESTING_DEPTH`]: ../constant.MAX_NESTING_DEPTH.html
+    fn default() -> Self {
+        Self {
+            require_parentheses: true,
+            allow_unicode: true,
+            allow_constants: true,
+            max_depth: MAX_NESTING_DEPTH,
+        }
+    }
+}
+
+impl ParserOptions {
+    /// Construct the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether every unary and binary operation must be parenthesized.
+    pub fn with_require_parentheses(mut self, require_parentheses: bool) -> Self {
+        self.require_parentheses = require_parentheses;
+        self
+    }
+
+    /// Set whether the Unicode spellings of operators and constants are accepted.
+    pub fn with_allow_unicode(mut self, allow_unicode: bool) -> Self {
+        self.allow_unicode = allow_unicode;
+        self
+    }
+
+    /// Set whether the constants are accepted.
+    pub fn with_allow_constants(mut self, allow_constants: bool) -> Self {
+        self.allow_constants = allow_constants;
+        self
+    }
+
+    /// Set the deepest nesting accepted.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+}
@@ -0,0 +1,160 @@
+//! Parser for formulas in Polish (prefix) notation, as printed by
+//! `PropositionalFormula::to_prefix_string`, see [`formula::prefix`].
+//!
+//! [`formula::prefix`]: ../../formula/prefix/index.html
+
+use super::token::{token, Token};
+use super::variable::unquoted_char_indices;
+
+use crate::formula::{BinaryOperator, PropositionalFormula, UnaryOperator};
+
+use nom::combinator::all_consuming;
+
+/// Parse a whole string in Polish (prefix) notation into a propositional formula, e.g.
+/// `-> ^ a b c` into `((a^b)->c)`.
+///
+/// Tokens are separated by whitespace (other than in quoted variable names). Connectives,
+/// constants and variables are spelled as in the regular syntax (see [`parse`]), so
+/// `implies and a b c` is the same formula.
+///
+/// # Errors
+///
+/// Fails on unknown tokens, connectives missing operands and operands left over at the end.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::parser::parse_prefix;
+/// let formula = parse_prefix("-> ^ a - b c").unwrap();
+/// assert_eq!("((a^(-b))->c)", formula.to_string());
+///
+/// assert!(parse_prefix("^ a").is_err());
+/// assert!(parse_prefix("a b").is_err());
+/// ```
+///
+/// [`parse`]: ../fn.parse.html
+pub fn parse_prefix(input: &str) -> Result<PropositionalFormula, String> {
+    let tokens = split_tokens(input)
+        .into_iter()
+        .map(|token| parse_token(token).ok_or_else(|| format!("unknown token {:?}", token)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Reading the tokens backwards, every operand a connective needs has been read already.
+    let mut operands = Vec::new();
+    for token in tokens.into_iter().rev() {
+        let formula = match token {
+            Token::Constant(true) => PropositionalFormula::Top,
+            Token::Constant(false) => PropositionalFormula::Bottom,
+            Token::Variable(v) => PropositionalFormula::variable(v),
+            Token::Unary(UnaryOperator::Negation) => {
+                let a = operands.pop().ok_or("missing operand of `-`")?;
+                PropositionalFormula::negated(Box::new(a))
+            }
+            Token::Binary(operator) => {
+                let (a, b) = match (operands.pop(), operands.pop()) {
+                    (Some(a), Some(b)) => (Box::new(a), Box::new(b)),
+                    _ => return Err(format!("missing operands of {:?}", operator)),
+                };
+
+                match operator {
+                    BinaryOperator::And => PropositionalFormula::conjunction(a, b),
+                    BinaryOperator::Or => PropositionalFormula::disjunction(a, b),
+                    BinaryOperator::Implication => PropositionalFormula::implication(a, b),
+                    BinaryOperator::Biimplication => PropositionalFormula::biimplication(a, b),
+                }
+            }
+            // `⊕` is parsed as a negated biimplication.
+            Token::ExclusiveOr => {
+                let (a, b) = match (operands.pop(), operands.pop()) {
+                    (Some(a), Some(b)) => (Box::new(a), Box::new(b)),
+                    _ => return Err("missing operands of `⊕`".to_string()),
+                };
+                PropositionalFormula::negated(Box::new(PropositionalFormula::biimplication(a, b)))
+            }
+            // PANIC: cannot panic because `parse_token` rejects parentheses and punctuation.
+            Token::Open | Token::Close | Token::Semicolon | Token::Colon | Token::Define => {
+                unreachable!("parentheses and punctuation are not prefix tokens")
+            }
+        };
+        operands.push(formula);
+    }
+
+    match (operands.pop(), operands.is_empty()) {
+        (Some(formula), true) => Ok(formula),
+        (None, _) => Err("empty input".to_string()),
+        (Some(_), false) => Err(format!("{} operands left over", operands.len())),
+    }
+}
+
+/// Split the input at the whitespace outside of quoted variable names.
+fn split_tokens(input: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    for (i, c) in unquoted_char_indices(input) {
+        if c.is_whitespace() {
+            tokens.push(&input[start..i]);
+            start = i + c.len_utf8();
+        }
+    }
+    tokens.push(&input[start..]);
+    tokens.retain(|token| !token.is_empty());
+    tokens
+}
+
+fn parse_token(input: &str) -> Option<Token> {
+    match all_consuming(token)(input) {
+        // There is no grouping in prefix notation, and a token is a single formula.
+        Ok((_, Token::Open)) | Ok((_, Token::Close)) | Err(_) => None,
+        Ok((_, Token::Semicolon)) | Ok((_, Token::Colon)) | Ok((_, Token::Define)) => None,
+        Ok((_, token)) => Some(token),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::formulas;
+    use assert2::check;
+
+    #[test]
+    fn test_round_trip() {
+        for formula in formulas(4, 6, 16, 100) {
+            check!(parse_prefix(&formula.to_prefix_string()) == Ok(formula));
+        }
+    }
+
+    #[test]
+    fn test_quoted_variables() {
+        let formula: PropositionalFormula = r#"("state 0" ^ (-'x-prime'))"#.parse().unwrap();
+        check!(formula.to_prefix_string() == r#"^ "state 0" - "x-prime""#);
+        check!(parse_prefix(&formula.to_prefix_string()) == Ok(formula));
+    }
+
+    #[test]
+    fn test_deep_formula() {
+        // Deep enough to overflow the stack of a recursive printer or parser in a test thread.
+        let depth = 200_000;
+        let prefix = format!("{}a", "- ".repeat(depth));
+        let formula = parse_prefix(&prefix).unwrap();
+
+        check!(formula.depth() == depth);
+        check!(formula.to_prefix_string() == prefix);
+    }
+
+    #[test]
+    fn test_words_and_errors() {
+        check!(
+            parse_prefix("IMPLIES and a b NOT true")
+                .unwrap()
+                .to_string()
+                == "((a^b)->(-1))"
+        );
+        let unicode = parse_prefix("→ ∧ a ¬ b ⊕ ⊤ c").unwrap();
+        check!(unicode.to_string() == "((a^(-b))->(-(1<->c)))");
+        check!(parse_prefix("").is_err());
+        check!(parse_prefix("- ").is_err());
+        check!(parse_prefix("| a").is_err());
+        check!(parse_prefix("a b").is_err());
+        check!(parse_prefix("^ a (b)").is_err());
+    }
+}
@@ -1,27 +1,32 @@
 //! Parser for a propositional formula.
 
+use super::constant::constant;
 use super::operators::{
-    and_operator, biimplication_operator, implication_operator, negation_operator, or_operator,
+    and_operator, biimplication_operator, exclusive_or_operator, implication_operator,
+    negation_operator, or_operator,
 };
 use super::variable::variable;
-use super::ParseResult;
+use super::{Failure, ParseResult, ParserOptions};
 
-use libprop_sat_solver::formula::{BinaryOperator, PropositionalFormula};
+use crate::formula::{BinaryOperator, PropositionalFormula};
 use nom::branch::alt;
-use nom::bytes::complete::take_while;
+use nom::bytes::complete::{take_while, take_while1};
 use nom::character::complete::char;
-use nom::sequence::{preceded, separated_pair, terminated};
+use nom::combinator::{cut, map, opt, value};
+use nom::error::{context, ErrorKind};
+use nom::multi::separated_nonempty_list;
+use nom::sequence::{pair, preceded, separated_pair, terminated, tuple};
 
 /// Parses a string into a propositional variable.
 ///
 /// # Example
 ///
 /// ```
-/// use prop_sat_solver::parser::propositional_variable;
+/// use libprop_sat_solver::parser::propositional_formula::propositional_variable;
 /// use libprop_sat_solver::formula::{PropositionalFormula, Variable};
 /// let input = "foo";
-/// let (_, variable) = propositional_variable(input)?;
-/// assert_eq!(Variable::new("foo"), variable);
+/// let (_, variable) = propositional_variable(input).unwrap();
+/// assert_eq!(PropositionalFormula::variable(Variable::new("foo")), variable);
 /// ```
 #[inline]
 pub fn propositional_variable(input: &str) -> ParseResult<&str, PropositionalFormula> {
@@ -30,6 +35,27 @@ pub fn propositional_variable(input: &str) -> ParseResult<&str, PropositionalFor
     Ok((remaining_input, formula))
 }
 
+/// Parses a Boolean constant into the formula `1` or `0`.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::parser::propositional_formula::constant_formula;
+/// use libprop_sat_solver::formula::PropositionalFormula;
+/// let (_, formula) = constant_formula("true").unwrap();
+/// assert_eq!(PropositionalFormula::Top, formula);
+/// ```
+#[inline]
+pub fn constant_formula(input: &str) -> ParseResult<&str, PropositionalFormula> {
+    let (remaining_input, value) = constant(input)?;
+    let formula = if value {
+        PropositionalFormula::Top
+    } else {
+        PropositionalFormula::Bottom
+    };
+    Ok((remaining_input, formula))
+}
+
 /// Parser for spaces (excluding newline delimiters, which are used to separate multiple
 /// propositional formulas.
 ///
@@ -53,15 +79,32 @@ where
 {
     preceded(
         char('('),
-        terminated(preceded(space, inner_parser), preceded(space, char(')'))),
+        terminated(
+            preceded(space, inner_parser),
+            preceded(space, context("`)`", char(')'))),
+        ),
     )
 }
 
-/// Parser for a propositional negated formula: `( - <propositional-formula> )`
+/// Parser for a propositional negated formula: `( - <propositional-formula> )` or
+/// `( not <propositional-formula> )`.
 #[inline]
 pub fn negated_formula(input: &str) -> ParseResult<&str, PropositionalFormula> {
-    let (remaining_input, sub_formula) =
-        paired_parentheses(preceded(negation_operator, propositional_formula))(input)?;
+    negated(input, &ParserOptions::default(), 0)
+}
+
+fn negated<'a>(
+    input: &'a str,
+    options: &ParserOptions,
+    depth: usize,
+) -> ParseResult<&'a str, PropositionalFormula> {
+    if input.starts_with('(') && depth >= options.max_depth {
+        return too_deep(input);
+    }
+    let (remaining_input, sub_formula) = paired_parentheses(preceded(
+        negation_operator,
+        preceded(space, |input| formula_at(input, options, depth + 1)),
+    ))(input)?;
 
     Ok((
         remaining_input,
@@ -98,18 +141,40 @@ pub fn parse_binary_formula(
     ) -> PropositionalFormula,
 ) -> impl Fn(&str) -> ParseResult<&str, PropositionalFormula> {
     move |input| {
-        let (remaining_input, (left_sub_formula, right_sub_formula)) =
-            paired_parentheses(separated_pair(
-                preceded(space, propositional_formula),
-                preceded(space, main_connective_parser),
-                preceded(space, propositional_formula),
-            ))(input)?;
+        binary(
+            input,
+            &ParserOptions::default(),
+            0,
+            main_connective_parser,
+            value_constructor_fn,
+        )
+    }
+}
 
-        Ok((
-            remaining_input,
-            value_constructor_fn(Box::new(left_sub_formula), Box::new(right_sub_formula)),
-        ))
+fn binary<'a, O>(
+    input: &'a str,
+    options: &ParserOptions,
+    depth: usize,
+    main_connective_parser: fn(&'a str) -> ParseResult<&'a str, O>,
+    value_constructor_fn: fn(
+        Box<PropositionalFormula>,
+        Box<PropositionalFormula>,
+    ) -> PropositionalFormula,
+) -> ParseResult<&'a str, PropositionalFormula> {
+    if input.starts_with('(') && depth >= options.max_depth {
+        return too_deep(input);
     }
+    let (remaining_input, (left_sub_formula, right_sub_formula)) =
+        paired_parentheses(separated_pair(
+            preceded(space, |input| formula_at(input, options, depth + 1)),
+            preceded(space, main_connective_parser),
+            preceded(space, |input| formula_at(input, options, depth + 1)),
+        ))(input)?;
+
+    Ok((
+        remaining_input,
+        value_constructor_fn(Box::new(left_sub_formula), Box::new(right_sub_formula)),
+    ))
 }
 
 /// Parser for a propositional formula with logical AND as the main connective.
@@ -136,26 +201,380 @@ pub fn biimplication_formula(input: &str) -> ParseResult<&str, PropositionalForm
     parse_binary_formula(biimplication_operator, PropositionalFormula::biimplication)(input)
 }
 
+/// Parser for an exclusive disjunction `( <propositional-formula> ⊕ <propositional-formula> )`,
+/// which is parsed as the negated biimplication `(-(A<->B))`.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::parser::propositional_formula::exclusive_disjunction_formula;
+/// let (_, formula) = exclusive_disjunction_formula("(a ⊕ b)").unwrap();
+/// assert_eq!("(-(a<->b))", formula.to_string());
+/// ```
+#[inline]
+pub fn exclusive_disjunction_formula(input: &str) -> ParseResult<&str, PropositionalFormula> {
+    binary(
+        input,
+        &ParserOptions::default(),
+        0,
+        exclusive_or_operator,
+        exclusive_disjunction,
+    )
+}
+
+fn exclusive_disjunction(
+    a: Box<PropositionalFormula>,
+    b: Box<PropositionalFormula>,
+) -> PropositionalFormula {
+    PropositionalFormula::negated(Box::new(PropositionalFormula::biimplication(a, b)))
+}
+
 /// Parser for a propositional formula.
 ///
 /// This is the root parser for a single propositional formula.
 #[inline]
 pub fn propositional_formula(input: &str) -> ParseResult<&str, PropositionalFormula> {
+    formula_with(input, &ParserOptions::default())
+}
+
+/// Parser for a propositional formula in the grammar chosen by the `options`.
+///
+/// Fails with a [`Failure`] of kind [`ErrorKind::TooLarge`] at the parenthesis or operator which
+/// would nest the formula deeper than the `options` allow, before recursing any deeper.
+///
+/// [`Failure`]: ../struct.Failure.html
+/// [`ErrorKind::TooLarge`]: https://docs.rs/nom/5.1.1/nom/error/enum.ErrorKind.html
+pub fn formula_with<'a>(
+    input: &'a str,
+    options: &ParserOptions,
+) -> ParseResult<&'a str, PropositionalFormula> {
+    formula_at(input, options, 0)
+}
+
+/// Parser for a propositional formula nested `depth` deep in the formula being parsed, counting
+/// the parentheses, negations and binary operators around it.
+pub(super) fn formula_at<'a>(
+    input: &'a str,
+    options: &ParserOptions,
+    depth: usize,
+) -> ParseResult<&'a str, PropositionalFormula> {
+    if !options.require_parentheses {
+        return without_parentheses(input, options, depth);
+    }
+
+    context(
+        "a formula",
+        alt((
+            |input| allowed_constant(input, options),
+            propositional_variable,
+            |input| parenthesized_strict(input, options, depth),
+        )),
+    )(input)
+}
+
+/// Parser for a parenthesized negation or binary formula nested `depth` deep.
+///
+/// The operands are parsed once whichever the connective is, rather than once per connective
+/// tried, which keeps the parsing time linear and the stack frames small however deep the
+/// formula is nested.
+fn parenthesized_strict<'a>(
+    input: &'a str,
+    options: &ParserOptions,
+    depth: usize,
+) -> ParseResult<&'a str, PropositionalFormula> {
+    if input.starts_with('(') && depth >= options.max_depth {
+        return too_deep(input);
+    }
+    let sub_formula = |input| formula_at(input, options, depth + 1);
+
+    paired_parentheses(alt((
+        map(
+            preceded(negation_operator, preceded(space, sub_formula)),
+            |sub_formula| PropositionalFormula::negated(Box::new(sub_formula)),
+        ),
+        map(
+            tuple((
+                sub_formula,
+                preceded(space, binary_connective),
+                preceded(space, sub_formula),
+            )),
+            |(left, constructor, right)| constructor(Box::new(left), Box::new(right)),
+        ),
+    )))(input)
+}
+
+/// Parser for any binary connective, giving the constructor of the formula it is the main
+/// connective of.
+fn binary_connective(input: &str) -> ParseResult<&str, Constructor> {
+    alt((
+        value(
+            PropositionalFormula::conjunction as Constructor,
+            and_operator,
+        ),
+        value(
+            PropositionalFormula::disjunction as Constructor,
+            or_operator,
+        ),
+        value(
+            PropositionalFormula::implication as Constructor,
+            implication_operator,
+        ),
+        value(
+            PropositionalFormula::biimplication as Constructor,
+            biimplication_operator,
+        ),
+        value(exclusive_disjunction as Constructor, exclusive_or_operator),
+    ))(input)
+}
+
+/// Parser for a propositional formula without required parentheses nested `depth` deep, kept out
+/// of [`formula_at`] so as not to grow its stack frame, which is on the stack once per nesting.
+///
+/// [`formula_at`]: fn.formula_at.html
+fn without_parentheses<'a>(
+    input: &'a str,
+    options: &ParserOptions,
+    depth: usize,
+) -> ParseResult<&'a str, PropositionalFormula> {
+    let (remaining_input, (formula, _)) = context("a formula", |input| {
+        operator_level(input, options, 0, depth)
+    })(input)?;
+    Ok((remaining_input, formula))
+}
+
+/// A constant, if the `options` allow them.
+fn allowed_constant<'a>(
+    input: &'a str,
+    options: &ParserOptions,
+) -> ParseResult<&'a str, PropositionalFormula> {
+    if !options.allow_constants {
+        return Err(nom::Err::Error(nom::error::ParseError::from_error_kind(
+            input,
+            ErrorKind::Verify,
+        )));
+    }
+    constant_formula(input)
+}
+
+/// The failure of a parenthesis or operator at the start of the `input` nesting the formula too
+/// deep.
+pub(super) fn too_deep<O>(input: &str) -> ParseResult<&str, O> {
+    Err(nom::Err::Failure(Failure {
+        input,
+        kind: ErrorKind::TooLarge,
+        expected: Vec::new(),
+    }))
+}
+
+/// Constructor of a binary formula from its operands.
+type Constructor = fn(Box<PropositionalFormula>, Box<PropositionalFormula>) -> PropositionalFormula;
+
+/// Parser for the operators of precedence `level`, from loosest `0` to tightest.
+fn level_operator(level: usize, input: &str) -> ParseResult<&str, Constructor> {
+    match level {
+        0 => alt((
+            value(
+                PropositionalFormula::biimplication as Constructor,
+                biimplication_operator,
+            ),
+            value(exclusive_disjunction as Constructor, exclusive_or_operator),
+        ))(input),
+        1 => value(
+            PropositionalFormula::implication as Constructor,
+            implication_operator,
+        )(input),
+        2 => value(
+            PropositionalFormula::disjunction as Constructor,
+            or_operator,
+        )(input),
+        _ => value(
+            PropositionalFormula::conjunction as Constructor,
+            and_operator,
+        )(input),
+    }
+}
+
+/// Parser for any operator without parentheses, along with its precedence level.
+fn any_level_operator(input: &str) -> ParseResult<&str, (usize, Constructor)> {
     alt((
-        propositional_variable,
-        negated_formula,
-        conjunction_formula,
-        disjunction_formula,
-        implication_formula,
-        biimplication_formula,
+        |input| level_operator(0, input).map(|(rest, operator)| (rest, (0, operator))),
+        |input| level_operator(1, input).map(|(rest, operator)| (rest, (1, operator))),
+        |input| level_operator(2, input).map(|(rest, operator)| (rest, (2, operator))),
+        |input| level_operator(3, input).map(|(rest, operator)| (rest, (3, operator))),
     ))(input)
 }
 
+/// Parser for a formula without required parentheses, nested `depth` deep, whose operators all
+/// bind at least as tightly as those of precedence `level`, e.g. `a ^ b | c` at level `2`.
+///
+/// Operands are combined by precedence climbing: a left-associative chain of operators is parsed
+/// iteratively, and the parser only goes deeper for a tighter operator, an implication (which
+/// associates to the right) or parentheses. Along with the formula, returns the depth of its
+/// deepest sub-formula, so that a formula nested too deep is rejected at the operator which nests
+/// it deeper.
+fn operator_level<'a>(
+    input: &'a str,
+    options: &ParserOptions,
+    level: usize,
+    depth: usize,
+) -> ParseResult<&'a str, (PropositionalFormula, usize)> {
+    let (mut remaining_input, (mut formula, mut reached)) = unary_operand(input, options, depth)?;
+
+    loop {
+        let operator_input = space(remaining_input)?.0;
+        let (after_operator, (precedence, operator)) = match any_level_operator(operator_input) {
+            Ok((_, (precedence, _))) if precedence < level => break,
+            Ok(parsed) => parsed,
+            Err(nom::Err::Error(_)) => break,
+            Err(e) => return Err(e),
+        };
+        if depth >= options.max_depth {
+            return too_deep(operator_input);
+        }
+
+        // Implications associate to the right, the other operators to the left.
+        let operand_level = if precedence == 1 {
+            precedence
+        } else {
+            precedence + 1
+        };
+        let (after_operand, (operand, operand_reached)) = preceded(space, |input| {
+            context("a formula", |input| {
+                operator_level(input, options, operand_level, depth + 1)
+            })(input)
+        })(after_operator)?;
+
+        // The formula so far becomes the left operand, one deeper.
+        reached = (reached + 1).max(operand_reached);
+        if reached > options.max_depth {
+            return too_deep(operator_input);
+        }
+        formula = operator(Box::new(formula), Box::new(operand));
+        remaining_input = after_operand;
+    }
+
+    Ok((remaining_input, (formula, reached)))
+}
+
+/// Parser for a constant, variable or parenthesized formula preceded by any number of negations,
+/// e.g. `- not (a ^ b)`, along with the depth of its deepest sub-formula like [`operator_level`].
+///
+/// [`operator_level`]: fn.operator_level.html
+fn unary_operand<'a>(
+    input: &'a str,
+    options: &ParserOptions,
+    depth: usize,
+) -> ParseResult<&'a str, (PropositionalFormula, usize)> {
+    let mut negations = 0;
+    let mut remaining_input = input;
+    while let Ok((after_negation, _)) = terminated(negation_operator, space)(remaining_input) {
+        if depth + negations >= options.max_depth {
+            return too_deep(remaining_input);
+        }
+        negations += 1;
+        remaining_input = after_negation;
+    }
+
+    let depth = depth + negations;
+    let (remaining_input, (operand, operand_depth)) = context(
+        "a formula",
+        alt((
+            map(
+                |input| allowed_constant(input, options),
+                |formula| (formula, depth),
+            ),
+            map(propositional_variable, |formula| (formula, depth)),
+            |input| parenthesized(input, options, depth),
+        )),
+    )(remaining_input)?;
+
+    let formula = (0..negations).fold(operand, |formula, _| {
+        PropositionalFormula::negated(Box::new(formula))
+    });
+    Ok((remaining_input, (formula, operand_depth)))
+}
+
+/// Parser for a parenthesized formula without required parentheses, which counts as nested one
+/// deeper so that the recursion stays bounded.
+fn parenthesized<'a>(
+    input: &'a str,
+    options: &ParserOptions,
+    depth: usize,
+) -> ParseResult<&'a str, (PropositionalFormula, usize)> {
+    if input.starts_with('(') && depth >= options.max_depth {
+        return too_deep(input);
+    }
+    paired_parentheses(|input| {
+        context("a formula", |input| {
+            operator_level(input, options, 0, depth + 1)
+        })(input)
+    })(input)
+}
+
+/// Parser for the label of a formula, e.g. `lemma1` in `lemma1: (a -> (b -> a))`.
+///
+/// A label is one or more alphanumeric characters or underscores `[[:alnum:]_]+`, followed by a
+/// `:` which is not part of the label.
+pub fn label(input: &str) -> ParseResult<&str, String> {
+    map(
+        terminated(
+            take_while1(|c: char| c.is_ascii_alphanumeric() || c == '_'),
+            tuple((space, char(':'), space)),
+        ),
+        str::to_string,
+    )(input)
+}
+
+/// Parser for a propositional formula with an optional [`label`], e.g. `lemma1: (a -> (b -> a))`.
+///
+/// [`label`]: fn.label.html
+pub fn labeled_formula(input: &str) -> ParseResult<&str, (Option<String>, PropositionalFormula)> {
+    labeled(input, &ParserOptions::default())
+}
+
+fn labeled<'a>(
+    input: &'a str,
+    options: &ParserOptions,
+) -> ParseResult<&'a str, (Option<String>, PropositionalFormula)> {
+    pair(opt(label), |input| formula_with(input, options))(input)
+}
+
+/// Parser for one or more (optionally labeled) propositional formulas separated by `;`, e.g.
+/// `a; (a->c); neg: (-d)`.
+///
+/// No formula contains a `;`, so every one of them separates two formulas, and a formula must
+/// follow it.
+pub fn formula_list(input: &str) -> ParseResult<&str, Vec<(Option<String>, PropositionalFormula)>> {
+    formula_list_with(input, &ParserOptions::default())
+}
+
+/// Parser for one or more (optionally labeled) propositional formulas separated by `;` in the
+/// grammar chosen by the `options`, see [`formula_list`].
+///
+/// [`formula_list`]: fn.formula_list.html
+pub fn formula_list_with<'a>(
+    input: &'a str,
+    options: &ParserOptions,
+) -> ParseResult<&'a str, Vec<(Option<String>, PropositionalFormula)>> {
+    separated_nonempty_list(
+        tuple((space, char(';'), space)),
+        cut(|input| labeled(input, options)),
+    )(input)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::formula::Variable;
     use assert2::check;
-    use libprop_sat_solver::formula::Variable;
+
+    #[test]
+    fn test_nesting_depth_limit() {
+        let nested = |depth: usize| format!("{}a{}", "(-".repeat(depth), ")".repeat(depth));
+
+        check!(crate::parser::parse(&nested(crate::parser::MAX_NESTING_DEPTH)).is_ok());
+        check!(crate::parser::parse(&nested(crate::parser::MAX_NESTING_DEPTH + 1)).is_err());
+    }
 
     #[test]
     fn test_space() {
@@ -232,4 +651,105 @@ mod tests {
 
         check!(("", expected_formula) == propositional_formula("((a<->b)|c)").unwrap());
     }
+
+    #[test]
+    fn word_operator_formula() {
+        let expected_formula = PropositionalFormula::implication(
+            Box::new(PropositionalFormula::conjunction(
+                Box::new(PropositionalFormula::variable(Variable::new("a"))),
+                Box::new(PropositionalFormula::negated(Box::new(
+                    PropositionalFormula::variable(Variable::new("b")),
+                ))),
+            )),
+            Box::new(PropositionalFormula::variable(Variable::new("c"))),
+        );
+        check!(
+            ("", expected_formula.clone())
+                == propositional_formula("((a AND (not b)) implies c)").unwrap()
+        );
+        check!(
+            ("", expected_formula) == propositional_formula("((a AND (NOT b)) IMPLIES c)").unwrap()
+        );
+    }
+
+    #[test]
+    fn constant_formula() {
+        let expected_formula = PropositionalFormula::disjunction(
+            Box::new(PropositionalFormula::Top),
+            Box::new(PropositionalFormula::negated(Box::new(
+                PropositionalFormula::Bottom,
+            ))),
+        );
+        check!(("", expected_formula.clone()) == propositional_formula("(1|(-0))").unwrap());
+        check!(("", expected_formula) == propositional_formula("(True | (not false))").unwrap());
+        check!(propositional_formula("(a^truely)").is_ok());
+    }
+
+    #[test]
+    fn c_style_operator_formula() {
+        let expected_formula: PropositionalFormula = "((a^(-b))|c)".parse().unwrap();
+        check!(("", expected_formula) == propositional_formula("((a && (!b)) || c)").unwrap());
+    }
+
+    #[test]
+    fn tilde_and_bang_negation() {
+        let expected_formula: PropositionalFormula = "((-a)^(-(-b)))".parse().unwrap();
+        check!(crate::parser::parse("((~a) ^ (!(~b)))") == Ok(expected_formula));
+        check!(crate::parser::parse("(~a)") == crate::parser::parse("(-a)"));
+        check!(crate::parser::parse("(a ~ b)").is_err());
+    }
+
+    #[test]
+    fn latex_operator_formula() {
+        let expected_formula: PropositionalFormula =
+            "(((p^(-q))->(r|1))<->(-(s<->0)))".parse().unwrap();
+        let input = r"(((p \land (\neg q)) \rightarrow (r | \top)) <-> (s \oplus \bot))";
+        check!(("", expected_formula) == propositional_formula(input).unwrap());
+    }
+
+    #[test]
+    fn unicode_operator_formula() {
+        let expected_formula: PropositionalFormula =
+            "(((a^(-b))->(c|1))<->(-(d<->0)))".parse().unwrap();
+        let input = "(((a ∧ (¬b)) → (c ∨ ⊤)) ↔ (d ⊕ ⊥))";
+        check!(("", expected_formula) == propositional_formula(input).unwrap());
+    }
+
+    #[test]
+    fn labeled() {
+        let formula: PropositionalFormula = "(a->(b->a))".parse().unwrap();
+        check!(
+            ("", (Some("lemma_1".to_string()), formula.clone()))
+                == labeled_formula("lemma_1 : (a -> (b -> a))").unwrap()
+        );
+        check!(("", (None, formula)) == labeled_formula("(a -> (b -> a))").unwrap());
+        check!(
+            (
+                "",
+                (None, PropositionalFormula::variable(Variable::new("a")))
+            ) == labeled_formula("a").unwrap()
+        );
+        // At most one label.
+        check!(labeled_formula("l1: l2: a").unwrap().0 == ": a");
+    }
+
+    #[test]
+    fn without_parentheses() {
+        let options = ParserOptions::new().with_require_parentheses(false);
+        let parse = |input| formula_with(input, &options).map(|(rest, f)| (rest, f.to_string()));
+
+        check!(parse("a | b ^ c -> d <-> e") == Ok(("", "(((a|(b^c))->d)<->e)".to_string())));
+        check!(parse("a -> b -> c") == Ok(("", "(a->(b->c))".to_string())));
+        check!(parse("a ^ b ^ c") == Ok(("", "((a^b)^c)".to_string())));
+        check!(parse("not -(a or b) ⊕ c") == Ok(("", "(-((-(-(a|b)))<->c))".to_string())));
+        check!(parse("((a^b))") == Ok(("", "(a^b)".to_string())));
+        check!(parse("a ^ ?").is_err());
+        check!(parse("- - a") == Ok(("", "(-(-a))".to_string())));
+    }
+
+    #[test]
+    fn word_operator_requires_separation() {
+        check!(propositional_formula("(a andb)").is_err());
+        check!(propositional_formula("(notb)").is_err());
+    }
 }
@@ -0,0 +1,135 @@
+//! Parser for the QDIMACS format of quantified Boolean formulas in prenex CNF.
+//!
+//! ```text
+//! c an optional comment
+//! p cnf 2 2
+//! a 1 0
+//! e 2 0
+//! 1 -2 0
+//! -1 2 0
+//! ```
+//!
+//! After the `p cnf <variables> <clauses>` header, each `a` (forall) or `e` (exists) line binds
+//! a block of variables from the outermost block inwards, and the remaining lines list the clauses
+//! of the matrix. Every block and clause is terminated by `0`. Variable `n` is named `xn`.
+
+use crate::cnf::{Clause, CnfFormula, Literal};
+use crate::formula::Variable;
+use crate::qbf::{QuantifiedFormula, Quantifier};
+
+/// Parse a QDIMACS instance into a quantified formula.
+///
+/// # Errors
+///
+/// Fails if the header is missing, if a line is malformed, or if the matrix is trivial (it has
+/// no clauses, or contains the empty clause), since trivial matrices have no propositional formula
+/// representation.
+pub fn parse_qdimacs(input: &str) -> Result<QuantifiedFormula, String> {
+    let mut lines = input
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('c'));
+
+    match lines.next() {
+        Some(header) if header.starts_with("p cnf") => {}
+        _ => return Err("missing `p cnf <variables> <clauses>` header".to_string()),
+    }
+
+    let mut prefix = Vec::new();
+    let mut cnf = CnfFormula::new();
+    let mut literals = Vec::new();
+
+    for line in lines {
+        let (quantifier, rest) = if let Some(rest) = line.strip_prefix('a') {
+            (Some(Quantifier::Forall), rest)
+        } else if let Some(rest) = line.strip_prefix('e') {
+            (Some(Quantifier::Exists), rest)
+        } else {
+            (None, line)
+        };
+
+        let numbers = rest
+            .split_whitespace()
+            .map(|n| n.parse::<i64>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| format!("malformed line: {:?}", line))?;
+
+        match quantifier {
+            Some(quantifier) => {
+                if numbers.last() != Some(&0) {
+                    return Err(format!("unterminated quantifier block: {:?}", line));
+                }
+
+                for &n in numbers.iter().take_while(|&&n| n != 0) {
+                    if n < 0 {
+                        return Err(format!("negative variable in quantifier block: {:?}", line));
+                    }
+                    prefix.push((quantifier, variable(n)));
+                }
+            }
+            None => {
+                // Clauses may span several lines, so we only cut them at `0`.
+                for n in numbers {
+                    if n == 0 {
+                        cnf.add(Clause::new(literals.drain(..)));
+                    } else if n > 0 {
+                        literals.push(Literal::positive(variable(n)));
+                    } else {
+                        literals.push(Literal::negative(variable(-n)));
+                    }
+                }
+            }
+        }
+    }
+
+    if !literals.is_empty() {
+        return Err("unterminated clause at end of input".to_string());
+    }
+
+    let matrix = cnf
+        .to_formula()
+        .ok_or_else(|| "matrix has no clauses or contains the empty clause".to_string())?;
+
+    Ok(prefix.into_iter().rev().fold(
+        QuantifiedFormula::propositional(matrix),
+        |formula, (quantifier, variable)| {
+            QuantifiedFormula::Quantified(quantifier, variable, Box::new(formula))
+        },
+    ))
+}
+
+fn variable(n: i64) -> Variable {
+    Variable::new(format!("x{}", n))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert2::check;
+
+    #[test]
+    fn simple_instance() {
+        let input = "c forall x1. exists x2. (x1<->x2)\np cnf 2 2\na 1 0\ne 2 0\n1 -2 0\n-1 2 0\n";
+        let formula = parse_qdimacs(input).unwrap();
+
+        check!(formula.to_string() == "forall x1. exists x2. ((x1|(-x2))^((-x1)|x2))");
+    }
+
+    #[test]
+    fn clause_spanning_lines() {
+        let input = "p cnf 3 1\n1 2\n3 0\n";
+        let formula = parse_qdimacs(input).unwrap();
+
+        check!(formula.to_string() == "(x1|(x2|x3))");
+    }
+
+    #[test]
+    fn missing_header() {
+        check!(parse_qdimacs("1 2 0\n").is_err());
+    }
+
+    #[test]
+    fn no_clauses() {
+        check!(parse_qdimacs("p cnf 1 0\ne 1 0\n").is_err());
+    }
+}
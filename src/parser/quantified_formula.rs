@@ -0,0 +1,119 @@
+//! Parser for a quantified Boolean formula in prenex form.
+
+use super::propositional_formula::{formula_at, space, too_deep};
+use super::variable::variable;
+use super::{ParseResult, ParserOptions};
+
+use crate::qbf::{QuantifiedFormula, Quantifier};
+use nom::branch::alt;
+use nom::bytes::complete::{tag, take_while1};
+use nom::character::complete::char;
+use nom::combinator::{map, value};
+use nom::sequence::preceded;
+
+/// Parses a quantifier keyword: `exists` or `forall`.
+pub fn quantifier(input: &str) -> ParseResult<&str, Quantifier> {
+    alt((
+        value(Quantifier::Exists, tag("exists")),
+        value(Quantifier::Forall, tag("forall")),
+    ))(input)
+}
+
+/// Parser for a quantified formula, conforming to the BNF:
+///
+/// ```ebnf
+/// <quantified-formula>
+///     ::= exists <propositional-variable> . <quantified-formula>
+///     | forall <propositional-variable> . <quantified-formula>
+///     | <formula>
+/// ```
+///
+/// At least one space is required between the quantifier keyword and the variable, so `existsx`
+/// remains an ordinary propositional variable.
+pub fn quantified_formula(input: &str) -> ParseResult<&str, QuantifiedFormula> {
+    quantified_at(input, &ParserOptions::default(), 0)
+}
+
+/// Parser for a quantified formula nested `depth` deep, in which each binder counts as one level
+/// of nesting like the operators of the [`formula_at`] the binders end with.
+///
+/// [`formula_at`]: ../propositional_formula/fn.formula_at.html
+fn quantified_at<'a>(
+    input: &'a str,
+    options: &ParserOptions,
+    depth: usize,
+) -> ParseResult<&'a str, QuantifiedFormula> {
+    alt((
+        |input| binder(input, options, depth),
+        map(
+            |input| formula_at(input, options, depth),
+            QuantifiedFormula::propositional,
+        ),
+    ))(input)
+}
+
+fn binder<'a>(
+    input: &'a str,
+    options: &ParserOptions,
+    depth: usize,
+) -> ParseResult<&'a str, QuantifiedFormula> {
+    let (remaining_input, quantifier) = quantifier(input)?;
+    let (remaining_input, _) = take_while1(|c| c == ' ' || c == '\t')(remaining_input)?;
+    let (remaining_input, variable) = variable(remaining_input)?;
+    let (remaining_input, _) = preceded(space, char('.'))(remaining_input)?;
+    if depth >= options.max_depth {
+        return too_deep(input);
+    }
+    let (remaining_input, sub_formula) =
+        preceded(space, |input| quantified_at(input, options, depth + 1))(remaining_input)?;
+
+    Ok((
+        remaining_input,
+        QuantifiedFormula::Quantified(quantifier, variable, Box::new(sub_formula)),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formula::{PropositionalFormula, Variable};
+    use assert2::check;
+
+    #[test]
+    fn test_quantifier() {
+        check!(("", Quantifier::Exists) == quantifier("exists").unwrap());
+        check!(("", Quantifier::Forall) == quantifier("forall").unwrap());
+    }
+
+    #[test]
+    fn unquantified_formula() {
+        let expected_formula =
+            QuantifiedFormula::propositional(PropositionalFormula::variable(Variable::new("a")));
+        check!(("", expected_formula) == quantified_formula("a").unwrap());
+    }
+
+    #[test]
+    fn nested_binders() {
+        let expected_formula = QuantifiedFormula::forall(
+            Variable::new("x"),
+            QuantifiedFormula::exists(
+                Variable::new("y"),
+                QuantifiedFormula::propositional(PropositionalFormula::biimplication(
+                    Box::new(PropositionalFormula::variable(Variable::new("x"))),
+                    Box::new(PropositionalFormula::variable(Variable::new("y"))),
+                )),
+            ),
+        );
+        check!(
+            ("", expected_formula) == quantified_formula("forall x. exists y .(x<->y)").unwrap()
+        );
+    }
+
+    #[test]
+    fn keyword_prefixed_variable() {
+        let expected_formula = QuantifiedFormula::propositional(PropositionalFormula::variable(
+            Variable::new("existsx"),
+        ));
+        check!(("", expected_formula) == quantified_formula("existsx").unwrap());
+    }
+}
@@ -0,0 +1,459 @@
+//! Parser for SMT-LIB 2 scripts restricted to the `Bool` sort, as emitted by many tools for
+//! simple Boolean queries.
+//!
+//! ```text
+//! ; an optional comment
+//! (set-logic QF_UF)
+//! (declare-const a Bool)
+//! (declare-fun b () Bool)
+//! (assert (and a (not b)))
+//! (check-sat)
+//! ```
+//!
+//! The supported commands are `declare-const` and `declare-fun` (of nullary `Bool` functions),
+//! `define-fun` (of nullary `Bool` functions), `assert`, `check-sat` and `exit`; `set-logic`,
+//! `set-info`, `set-option`, `get-info`, `get-model` and `get-value` are ignored.
+//!
+//! The supported terms are `true`, `false`, declared and defined constants, `not`, `and`, `or`,
+//! `=>`, `xor`, `=`, `distinct` and `ite` over Boolean terms, `let` bindings and `!` annotations
+//! (whose attributes are ignored).
+
+use std::collections::HashMap;
+
+use super::MAX_NESTING_DEPTH;
+use crate::formula::{PropositionalFormula, Variable};
+
+/// An S-expression: an atom (symbol, keyword, numeral or string literal) or a parenthesized list.
+#[derive(Debug, Clone, PartialEq)]
+enum SExpr {
+    Atom(String),
+    List(Vec<SExpr>),
+}
+
+/// Parse an SMT-LIB 2 script into the query of each `(check-sat)`: the conjunction of the
+/// assertions made before it, or `1` if there are none.
+///
+/// # Errors
+///
+/// Fails on malformed S-expressions, unsupported commands or terms, sorts other than `Bool`, and
+/// undeclared or redeclared constants.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::parser::parse_smtlib;
+/// let script = "
+///     (declare-const a Bool)
+///     (declare-fun b () Bool)
+///     (assert (or a b))
+///     (check-sat)
+///     (assert (=> a b))
+///     (check-sat)
+/// ";
+///
+/// let queries: Vec<_> = parse_smtlib(script).unwrap().iter().map(ToString::to_string).collect();
+/// assert_eq!(vec!["(a|b)", "((a|b)^(a->b))"], queries);
+/// ```
+pub fn parse_smtlib(input: &str) -> Result<Vec<PropositionalFormula>, String> {
+    let mut script = Script::default();
+    let mut queries = Vec::new();
+
+    for command in read_sexprs(input)? {
+        let (name, arguments) = match &command {
+            SExpr::List(list) => match list.split_first() {
+                Some((SExpr::Atom(name), arguments)) => (name.as_str(), arguments),
+                _ => return Err(format!("malformed command: {}", command)),
+            },
+            SExpr::Atom(atom) => return Err(format!("expected a command, found `{}`", atom)),
+        };
+
+        match (name, arguments) {
+            ("set-logic", _) | ("set-info", _) | ("set-option", _) | ("get-info", _) => {}
+            ("get-model", _) | ("get-value", _) => {}
+            ("declare-const", [SExpr::Atom(symbol), sort]) => script.declare(symbol, sort)?,
+            ("declare-fun", [SExpr::Atom(symbol), SExpr::List(parameters), sort])
+                if parameters.is_empty() =>
+            {
+                script.declare(symbol, sort)?
+            }
+            ("define-fun", [SExpr::Atom(symbol), SExpr::List(parameters), sort, body])
+                if parameters.is_empty() =>
+            {
+                expect_bool(sort)?;
+                let formula = script.term(body, &mut Vec::new())?;
+                script.define(symbol, formula)?;
+            }
+            ("assert", [term]) => {
+                let formula = script.term(term, &mut Vec::new())?;
+                script.assertions.push(formula);
+            }
+            ("check-sat", []) => queries.push(
+                PropositionalFormula::conjoin(script.assertions.iter().cloned())
+                    .unwrap_or(PropositionalFormula::Top),
+            ),
+            ("exit", []) => break,
+            _ => return Err(format!("unsupported command: {}", command)),
+        }
+    }
+
+    Ok(queries)
+}
+
+/// Constants and assertions of the script so far.
+#[derive(Debug, Default)]
+struct Script {
+    /// Declared constants, which become variables, and defined ones, which stand for formulas.
+    constants: HashMap<String, PropositionalFormula>,
+    assertions: Vec<PropositionalFormula>,
+}
+
+impl Script {
+    fn declare(&mut self, symbol: &str, sort: &SExpr) -> Result<(), String> {
+        expect_bool(sort)?;
+        self.define(
+            symbol,
+            PropositionalFormula::variable(Variable::new(symbol)),
+        )
+    }
+
+    fn define(&mut self, symbol: &str, formula: PropositionalFormula) -> Result<(), String> {
+        if self.constants.contains_key(symbol) {
+            return Err(format!("`{}` is already declared", symbol));
+        }
+        self.constants.insert(symbol.to_string(), formula);
+        Ok(())
+    }
+
+    /// Translate a Boolean term, where `scopes` are the bindings of the enclosing `let`s from the
+    /// outermost inwards.
+    fn term(
+        &self,
+        term: &SExpr,
+        scopes: &mut Vec<HashMap<String, PropositionalFormula>>,
+    ) -> Result<PropositionalFormula, String> {
+        let (operator, operands) = match term {
+            SExpr::Atom(symbol) => return self.symbol(symbol, scopes),
+            SExpr::List(list) => match list.split_first() {
+                Some((SExpr::Atom(operator), operands)) => (operator.as_str(), operands),
+                _ => return Err(format!("unsupported term: {}", term)),
+            },
+        };
+
+        match (operator, operands) {
+            ("let", [SExpr::List(bindings), body]) => {
+                // The bound terms are translated in the enclosing scope, i.e. in parallel.
+                let mut scope = HashMap::new();
+                for binding in bindings {
+                    match binding {
+                        SExpr::List(binding) => match binding.as_slice() {
+                            [SExpr::Atom(symbol), bound] => {
+                                scope.insert(symbol.clone(), self.term(bound, scopes)?);
+                            }
+                            _ => return Err(format!("malformed let binding in: {}", term)),
+                        },
+                        _ => return Err(format!("malformed let binding in: {}", term)),
+                    }
+                }
+                scopes.push(scope);
+                let body = self.term(body, scopes);
+                scopes.pop();
+                body
+            }
+            ("!", [annotated, ..]) => self.term(annotated, scopes),
+            ("ite", [condition, then, otherwise]) => {
+                let condition = self.term(condition, scopes)?;
+                let (then, otherwise) = (self.term(then, scopes)?, self.term(otherwise, scopes)?);
+                Ok(PropositionalFormula::conjunction(
+                    Box::new(PropositionalFormula::implication(
+                        Box::new(condition.clone()),
+                        Box::new(then),
+                    )),
+                    Box::new(PropositionalFormula::implication(
+                        Box::new(PropositionalFormula::negated(Box::new(condition))),
+                        Box::new(otherwise),
+                    )),
+                ))
+            }
+            ("not", [operand]) => Ok(PropositionalFormula::negated(Box::new(
+                self.term(operand, scopes)?,
+            ))),
+            ("and", _) | ("or", _) | ("=>", _) | ("xor", _) | ("=", _) | ("distinct", _) => {
+                let operands = operands
+                    .iter()
+                    .map(|operand| self.term(operand, scopes))
+                    .collect::<Result<Vec<_>, _>>()?;
+                connective(operator, operands).ok_or_else(|| {
+                    format!("`{}` needs at least two operands in: {}", operator, term)
+                })
+            }
+            _ => Err(format!("unsupported term: {}", term)),
+        }
+    }
+
+    fn symbol(
+        &self,
+        symbol: &str,
+        scopes: &[HashMap<String, PropositionalFormula>],
+    ) -> Result<PropositionalFormula, String> {
+        match symbol {
+            "true" => return Ok(PropositionalFormula::Top),
+            "false" => return Ok(PropositionalFormula::Bottom),
+            _ => {}
+        }
+
+        scopes
+            .iter()
+            .rev()
+            .chain(std::iter::once(&self.constants))
+            .find_map(|scope| scope.get(symbol))
+            .cloned()
+            .ok_or_else(|| format!("undeclared symbol `{}`", symbol))
+    }
+}
+
+/// Apply the (variadic) SMT-LIB connective to the operands, or `None` if there are too few.
+///
+/// `and` and `or` accept any number of operands; the others need at least two, with `=>` being
+/// right-associative, `xor` left-associative, and `=` and `distinct` relating all (pairs of)
+/// operands.
+fn connective(
+    operator: &str,
+    mut operands: Vec<PropositionalFormula>,
+) -> Option<PropositionalFormula> {
+    let xor = |a, b| {
+        PropositionalFormula::negated(Box::new(PropositionalFormula::biimplication(
+            Box::new(a),
+            Box::new(b),
+        )))
+    };
+    let biimplication = |a: &PropositionalFormula, b: &PropositionalFormula| {
+        PropositionalFormula::biimplication(Box::new(a.clone()), Box::new(b.clone()))
+    };
+
+    match operator {
+        "and" => Some(PropositionalFormula::conjoin(operands).unwrap_or(PropositionalFormula::Top)),
+        "or" => Some(
+            operands
+                .into_iter()
+                .fold(None, |disjunction, formula| match disjunction {
+                    Some(disjunction) => Some(PropositionalFormula::disjunction(
+                        Box::new(disjunction),
+                        Box::new(formula),
+                    )),
+                    None => Some(formula),
+                })
+                .unwrap_or(PropositionalFormula::Bottom),
+        ),
+        _ if operands.len() < 2 => None,
+        "=>" => {
+            let conclusion = operands.pop()?;
+            Some(operands.into_iter().rev().fold(conclusion, |b, a| {
+                PropositionalFormula::implication(Box::new(a), Box::new(b))
+            }))
+        }
+        "xor" => {
+            let mut operands = operands.into_iter();
+            let first = operands.next()?;
+            Some(operands.fold(first, xor))
+        }
+        "=" => PropositionalFormula::conjoin(
+            operands
+                .windows(2)
+                .map(|pair| biimplication(&pair[0], &pair[1])),
+        ),
+        "distinct" => {
+            PropositionalFormula::conjoin(operands.iter().enumerate().flat_map(|(i, a)| {
+                operands[i + 1..]
+                    .iter()
+                    .map(move |b| PropositionalFormula::negated(Box::new(biimplication(a, b))))
+            }))
+        }
+        _ => None,
+    }
+}
+
+fn expect_bool(sort: &SExpr) -> Result<(), String> {
+    match sort {
+        SExpr::Atom(sort) if sort == "Bool" => Ok(()),
+        _ => Err(format!("unsupported sort {}, only Bool is supported", sort)),
+    }
+}
+
+/// Read the top-level S-expressions of the input.
+fn read_sexprs(input: &str) -> Result<Vec<SExpr>, String> {
+    // The lists being read, from the outermost inwards, below the top level.
+    let mut lists: Vec<Vec<SExpr>> = vec![Vec::new()];
+    let mut chars = input.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        let atom = match c {
+            ';' => {
+                while chars.next_if(|&(_, c)| c != '\n').is_some() {}
+                continue;
+            }
+            '(' => {
+                if lists.len() > MAX_NESTING_DEPTH {
+                    return Err(format!(
+                        "script is nested more than {} deep",
+                        MAX_NESTING_DEPTH
+                    ));
+                }
+                lists.push(Vec::new());
+                continue;
+            }
+            ')' => {
+                if lists.len() == 1 {
+                    return Err(format!("unbalanced `)` at byte {}", start));
+                }
+                // PANIC: cannot panic because there is an open list below the top level.
+                let list = lists.pop().unwrap();
+                lists.last_mut().unwrap().push(SExpr::List(list));
+                continue;
+            }
+            c if c.is_whitespace() => continue,
+            '|' => {
+                // A quoted symbol stands for the symbol between the bars.
+                let mut symbol = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '|')) => break,
+                        Some((_, c)) => symbol.push(c),
+                        None => return Err("unterminated quoted symbol".to_string()),
+                    }
+                }
+                symbol
+            }
+            '"' => {
+                // String literals only occur in ignored attributes, so they are kept verbatim.
+                let mut literal = String::from('"');
+                loop {
+                    match chars.next() {
+                        Some((_, '"')) if chars.next_if(|&(_, c)| c == '"').is_some() => {
+                            literal.push_str("\"\"")
+                        }
+                        Some((_, '"')) => break,
+                        Some((_, c)) => literal.push(c),
+                        None => return Err("unterminated string literal".to_string()),
+                    }
+                }
+                literal.push('"');
+                literal
+            }
+            c => {
+                let mut atom = c.to_string();
+                while let Some((_, c)) =
+                    chars.next_if(|&(_, c)| !c.is_whitespace() && !"();|\"".contains(c))
+                {
+                    atom.push(c);
+                }
+                atom
+            }
+        };
+        // PANIC: cannot panic because the top level is never popped.
+        lists.last_mut().unwrap().push(SExpr::Atom(atom));
+    }
+
+    match lists.len() {
+        // PANIC: cannot panic because there is exactly one list.
+        1 => Ok(lists.pop().unwrap()),
+        _ => Err("unbalanced `(`: missing `)` at the end of the script".to_string()),
+    }
+}
+
+/// Formats the S-expression as in the script, e.g. `(assert (and a b))`.
+impl std::fmt::Display for SExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SExpr::Atom(atom) => f.write_str(atom),
+            SExpr::List(list) => {
+                f.write_str("(")?;
+                for (i, element) in list.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(" ")?;
+                    }
+                    write!(f, "{}", element)?;
+                }
+                f.write_str(")")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::truth_table;
+    use assert2::check;
+
+    fn query(script: &str) -> PropositionalFormula {
+        let mut queries = parse_smtlib(script).unwrap();
+        check!(queries.len() == 1);
+        queries.pop().unwrap()
+    }
+
+    fn equivalent(a: &PropositionalFormula, b: &str) -> bool {
+        truth_table::is_valid(&PropositionalFormula::biimplication(
+            Box::new(a.clone()),
+            Box::new(b.parse().unwrap()),
+        ))
+    }
+
+    #[test]
+    fn test_terms() {
+        let declarations = "(declare-const a Bool) (declare-const b Bool) (declare-const c Bool)";
+        let cases = [
+            ("(=> a b c)", "(a->(b->c))"),
+            ("(xor a b c)", "(-((-(a<->b))<->c))"),
+            ("(= a b c)", "((a<->b)^(b<->c))"),
+            ("(distinct a b)", "(-(a<->b))"),
+            ("(ite a b c)", "((a->b)^((-a)->c))"),
+            ("(let ((x (and a b)) (a c)) (or x a))", "((a^b)|c)"),
+            ("(! (or a false) :named goal)", "a"),
+            ("(and)", "1"),
+        ];
+
+        for &(term, expected) in &cases {
+            let formula = query(&format!("{} (assert {}) (check-sat)", declarations, term));
+            check!(
+                equivalent(&formula, expected),
+                "{} is not {}",
+                term,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_script() {
+        let script = r#"
+            ; comment with (unbalanced parentheses
+            (set-info :source |Example "one"|)
+            (set-option :produce-models true)
+            (set-logic QF_UF)
+            (declare-fun |p q| () Bool)
+            (define-fun r () Bool (not |p q|))
+            (assert (or |p q| r))
+            (check-sat)
+            (get-model)
+            (exit)
+            (assert false)
+            (check-sat)
+        "#;
+        let formula = query(script);
+        check!(formula.to_string() == r#"("p q"|(-"p q"))"#);
+        check!(parse_smtlib("(check-sat)").unwrap() == vec![PropositionalFormula::Top]);
+    }
+
+    #[test]
+    fn test_errors() {
+        check!(parse_smtlib("(declare-const x Int)").is_err());
+        check!(parse_smtlib("(declare-fun f (Bool) Bool)").is_err());
+        check!(parse_smtlib("(declare-const x Bool) (declare-const x Bool)").is_err());
+        check!(parse_smtlib("(assert y)").is_err());
+        check!(parse_smtlib("(push 1)").is_err());
+        check!(parse_smtlib("(declare-const x Bool) (assert (=> x))").is_err());
+        check!(parse_smtlib("(assert true").is_err());
+        check!(parse_smtlib("(assert true))").is_err());
+        check!(parse_smtlib(&"(".repeat(1000)).is_err());
+    }
+}
@@ -0,0 +1,256 @@
+//! Byte ranges of the input, for pointing parse errors (and later messages) at the characters
+//! they are about.
+
+use std::fmt;
+
+use super::token::{tokens, Token};
+use crate::formula::PropositionalFormula;
+
+/// A range `start..end` of byte offsets into the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Span {
+    /// Offset of the first byte.
+    pub start: usize,
+    /// Offset one past the last byte.
+    pub end: usize,
+}
+
+impl Span {
+    /// Construct the span `start..end`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `end` is before `start`.
+    pub fn new(start: usize, end: usize) -> Self {
+        assert!(start <= end, "span ends before it starts");
+        Self { start, end }
+    }
+
+    /// Number of bytes in the span.
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// Checks if the span is empty, e.g. when pointing at the end of the input.
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// The part of the `input` within the span.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the span is out of bounds of `input` or not on character boundaries.
+    pub fn slice<'a>(&self, input: &'a str) -> &'a str {
+        &input[self.start..self.end]
+    }
+
+    /// Line and column of the start of the span in the `input`, both counting from `0`, where
+    /// lines end at `\n` and columns count characters rather than bytes, as editors do.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the span starts out of bounds of `input` or not on a character boundary.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libprop_sat_solver::parser::Span;
+    /// assert_eq!((1, 2), Span::new(11, 12).line_column("(a ^ b)\n(¬c)"));
+    /// ```
+    pub fn line_column(&self, input: &str) -> (usize, usize) {
+        let before = &input[..self.start];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        (
+            before.matches('\n').count(),
+            before[line_start..].chars().count(),
+        )
+    }
+
+    /// Underline the span of the `input` with carets on the line below it, e.g.
+    ///
+    /// ```text
+    /// (a^?)
+    ///    ^
+    /// ```
+    ///
+    /// An empty span gets a single caret.
+    pub fn underline(&self, input: &str) -> String {
+        let indent = input[..self.start].chars().count();
+        let carets = self.slice(input).chars().count().max(1);
+        format!("{}\n{}{}", input, " ".repeat(indent), "^".repeat(carets))
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
+
+/// A value together with the span of the input it came from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Spanned<T> {
+    /// The value.
+    pub node: T,
+    /// Where the value is in the input.
+    pub span: Span,
+}
+
+impl<T> Spanned<T> {
+    /// Attach the `span` to the `node`.
+    pub fn new(node: T, span: Span) -> Self {
+        Self { node, span }
+    }
+}
+
+/// Formats the value followed by its span, e.g. `unexpected `?` at 3..4` for an error message.
+impl<T: fmt::Display> fmt::Display for Spanned<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {}", self.node, self.span)
+    }
+}
+
+impl Spanned<PropositionalFormula> {
+    /// Pair each sub-formula of the formula with its span in the `input` it was parsed from (by
+    /// [`parse_spanned`]), in pre-order like the [`Preorder`] iterator.
+    ///
+    /// Since every compound formula is parenthesized, the sub-formulas start in the input in
+    /// preorder, so the spans are recovered from the [`tokens`] of the input rather than stored in
+    /// the formula. Both the negation and the biimplication of an exclusive disjunction `(A ⊕ B)`
+    /// span all of it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libprop_sat_solver::parser::parse_spanned;
+    /// let input = "((-a) ^ b)";
+    /// let formula = parse_spanned(input).unwrap();
+    ///
+    /// let sub_formulas: Vec<_> = formula
+    ///     .sub_formulas(input)
+    ///     .iter()
+    ///     .map(|s| (s.node.to_string(), s.span.slice(input)))
+    ///     .collect();
+    /// assert_eq!(
+    ///     vec![("((-a)^b)".to_string(), "((-a) ^ b)"), ("(-a)".to_string(), "(-a)"),
+    ///          ("a".to_string(), "a"), ("b".to_string(), "b")],
+    ///     sub_formulas,
+    /// );
+    /// ```
+    ///
+    /// [`parse_spanned`]: ../fn.parse_spanned.html
+    /// [`tokens`]: ../token/fn.tokens.html
+    /// [`Preorder`]: ../../formula/iter/struct.Preorder.html
+    pub fn sub_formulas<'a>(&'a self, input: &str) -> Vec<Spanned<&'a PropositionalFormula>> {
+        let text = &input[self.span.start..self.span.end];
+        let mut starts = Vec::new();
+        let mut spans = Vec::new();
+
+        for token in tokens(text) {
+            // PANIC: cannot panic because the input was parsed, so it consists of tokens.
+            let token = token.expect("a parsed formula is made of tokens");
+            match token.node {
+                Token::Open => {
+                    spans.push(Span::new(token.span.start, token.span.start));
+                    starts.push(spans.len() - 1);
+                }
+                Token::Close => {
+                    // PANIC: cannot panic because the input was parsed, so its parentheses match.
+                    let open = starts.pop().unwrap();
+                    spans[open].end = token.span.end;
+                    // The biimplication of an exclusive disjunction, see below.
+                    if spans.get(open + 1).map(|span| span.start) == Some(spans[open].start) {
+                        spans[open + 1].end = token.span.end;
+                    }
+                }
+                Token::ExclusiveOr => {
+                    // `(A ⊕ B)` is parsed as `(-(A<->B))`, whose biimplication comes right after
+                    // the negation in preorder. The left operand is complete, so the innermost
+                    // open parenthesis is that of the exclusive disjunction.
+                    // PANIC: cannot panic because the input was parsed, so `⊕` is parenthesized.
+                    let open = *starts.last().unwrap();
+                    spans.insert(open + 1, spans[open]);
+                }
+                Token::Constant(_) | Token::Variable(_) => spans.push(token.span),
+                Token::Unary(_)
+                | Token::Binary(_)
+                | Token::Semicolon
+                | Token::Colon
+                | Token::Define => {}
+            }
+        }
+
+        self.node
+            .iter_preorder()
+            .zip(spans)
+            .map(|(formula, span)| {
+                let span = Span::new(span.start + self.span.start, span.end + self.span.start);
+                Spanned::new(formula, span)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_spanned;
+    use assert2::check;
+
+    #[test]
+    fn test_sub_formula_spans() {
+        let input = "((x1 and (not true)) <-> (-y))  ";
+        let formula = parse_spanned(input).unwrap();
+        check!(formula.span.slice(input) == input.trim());
+
+        let sub_formulas = formula.sub_formulas(input);
+        check!(sub_formulas.len() == formula.node.iter_preorder().count());
+        for sub_formula in sub_formulas {
+            let parsed: PropositionalFormula = sub_formula.span.slice(input).parse().unwrap();
+            check!(&parsed == sub_formula.node);
+        }
+    }
+
+    #[test]
+    fn test_unicode_sub_formula_spans() {
+        let input = "((¬⊤) ⊕ (a → ⊥))";
+        let formula = parse_spanned(input).unwrap();
+
+        let spans: Vec<_> = formula
+            .sub_formulas(input)
+            .iter()
+            .map(|s| s.span.slice(input))
+            .collect();
+        check!(spans == vec![input, input, "(¬⊤)", "⊤", "(a → ⊥)", "a", "⊥"]);
+    }
+
+    #[test]
+    fn test_latex_sub_formula_spans() {
+        let input = r"((\neg \top) \lor (a \land \bot))";
+        let formula = parse_spanned(input).unwrap();
+
+        let spans: Vec<_> = formula
+            .sub_formulas(input)
+            .iter()
+            .map(|s| s.span.slice(input))
+            .collect();
+        check!(
+            spans
+                == vec![
+                    input,
+                    r"(\neg \top)",
+                    r"\top",
+                    r"(a \land \bot)",
+                    "a",
+                    r"\bot"
+                ]
+        );
+    }
+
+    #[test]
+    fn test_underline() {
+        check!(Span::new(3, 4).underline("(a^?)") == "(a^?)\n   ^");
+        check!(Span::new(4, 4).underline("(a^b") == "(a^b\n    ^");
+    }
+}
@@ -0,0 +1,252 @@
+//! Tokens of the formula syntax, see [`token`] and [`tokens`].
+//!
+//! Tokens are recognized by the same parser combinators as the formula parsers, so every spelling
+//! of an operator or constant is defined once, in [`operators`] and [`constant`], and is known to
+//! the sub-formula spans and the prefix notation as soon as the formula parsers accept it.
+//!
+//! [`token`]: fn.token.html
+//! [`tokens`]: fn.tokens.html
+//! [`operators`]: ../operators/index.html
+//! [`constant`]: ../constant/index.html
+
+use super::constant::constant;
+use super::operators::{
+    and_operator, biimplication_operator, exclusive_or_operator, implication_operator,
+    negation_operator, or_operator,
+};
+use super::propositional_formula::space;
+use super::variable::variable;
+use super::{unexpected, ParseError, ParseResult, Span, Spanned};
+
+use crate::formula::{BinaryOperator, UnaryOperator, Variable};
+
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::character::complete::char;
+use nom::combinator::{map, value};
+use nom::error::context;
+
+/// A token of the formula syntax.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Token {
+    /// `(`.
+    Open,
+    /// `)`.
+    Close,
+    /// A Boolean constant, e.g. `1` or `false`.
+    Constant(bool),
+    /// A propositional variable.
+    Variable(Variable),
+    /// A unary operator, e.g. `-` or `not`.
+    Unary(UnaryOperator),
+    /// A binary operator, e.g. `^` or `implies`.
+    Binary(BinaryOperator),
+    /// The exclusive-or operator `⊕`, which has no connective of its own.
+    ExclusiveOr,
+    /// `;`, separating the formulas on a line.
+    Semicolon,
+    /// `:`, after the label of a formula.
+    Colon,
+    /// `:=`, in the definition of a macro.
+    Define,
+}
+
+/// Parses a single token, in any of its spellings.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::formula::BinaryOperator;
+/// use libprop_sat_solver::parser::token::{token, Token};
+/// assert_eq!(Ok((" b", Token::Binary(BinaryOperator::Implication))), token("-> b"));
+/// ```
+pub fn token(input: &str) -> ParseResult<&str, Token> {
+    context(
+        "a token",
+        alt((
+            value(Token::Open, char('(')),
+            value(Token::Close, char(')')),
+            value(Token::Semicolon, char(';')),
+            // Before the colon, which would take the `:` of `:=`.
+            value(Token::Define, tag(":=")),
+            value(Token::Colon, char(':')),
+            map(constant, Token::Constant),
+            map(variable, Token::Variable),
+            // Before negation, which would take the `-` of `->`.
+            map(
+                alt((
+                    and_operator,
+                    or_operator,
+                    implication_operator,
+                    biimplication_operator,
+                )),
+                Token::Binary,
+            ),
+            map(negation_operator, Token::Unary),
+            value(Token::ExclusiveOr, exclusive_or_operator),
+        )),
+    )(input)
+}
+
+/// Lazily split the input into tokens with their spans, skipping the spaces between them, so that
+/// huge inputs never have to be held as tokens in memory at once.
+///
+/// Each character which does not start a token is yielded as an error, after which the iterator
+/// carries on after it.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::parser::token::{tokens, Token};
+/// let mut tokens = tokens("(a ? b)");
+/// assert_eq!(Some(Token::Open), tokens.next().map(|t| t.unwrap().node));
+/// assert_eq!(1, tokens.next().unwrap().unwrap().span.start);
+/// assert_eq!(3, tokens.next().unwrap().unwrap_err().position());
+/// assert_eq!(2, tokens.count());
+/// ```
+pub fn tokens(input: &str) -> Tokens<'_> {
+    Tokens { input, rest: input }
+}
+
+/// Iterator over the tokens of an input, see [`tokens`].
+///
+/// [`tokens`]: fn.tokens.html
+#[derive(Debug, Clone)]
+pub struct Tokens<'a> {
+    input: &'a str,
+    rest: &'a str,
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = Result<Spanned<Token>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // PANIC: cannot panic because `space` accepts the empty string.
+        let (after_space, _) = space(self.rest).unwrap();
+        if after_space.is_empty() {
+            self.rest = after_space;
+            return None;
+        }
+
+        let start = self.input.len() - after_space.len();
+        match token(after_space) {
+            Ok((remaining, token)) => {
+                let end = self.input.len() - remaining.len();
+                self.rest = remaining;
+                Some(Ok(Spanned::new(token, Span::new(start, end))))
+            }
+            Err(_) => {
+                // PANIC: cannot panic because `after_space` is not empty.
+                let skipped = after_space.chars().next().unwrap();
+                self.rest = &after_space[skipped.len_utf8()..];
+                Some(Err(unexpected(self.input, after_space, vec!["a token"])))
+            }
+        }
+    }
+}
+
+/// Split the input into tokens with their spans, skipping the spaces between them.
+///
+/// # Errors
+///
+/// Fails at the first character which does not start a token.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::parser::token::{tokenize, Token};
+/// let tokens: Vec<_> = tokenize("(not a)").unwrap().into_iter().map(|t| t.span.start).collect();
+/// assert_eq!(vec![0, 1, 5, 6], tokens);
+/// ```
+pub fn tokenize(input: &str) -> Result<Vec<Spanned<Token>>, ParseError> {
+    tokens(input).collect()
+}
+
+/// Split the input into tokens with their spans like [`tokenize`], but skip each character which
+/// does not start a token instead of failing, so that all such characters are reported at once.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::parser::token::tokenize_all;
+/// let (tokens, errors) = tokenize_all("(a ? b) = c");
+/// assert_eq!(5, tokens.len());
+/// let positions: Vec<_> = errors.iter().map(|e| e.position()).collect();
+/// assert_eq!(vec![3, 8], positions);
+/// ```
+///
+/// [`tokenize`]: fn.tokenize.html
+pub fn tokenize_all(input: &str) -> (Vec<Spanned<Token>>, Vec<ParseError>) {
+    let mut tokens_found = Vec::new();
+    let mut errors = Vec::new();
+    for token in tokens(input) {
+        match token {
+            Ok(token) => tokens_found.push(token),
+            Err(error) => errors.push(error),
+        }
+    }
+    (tokens_found, errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert2::check;
+
+    #[test]
+    fn test_tokenize() {
+        let tokens: Vec<_> = tokenize("((x1 and (¬true)) <-> (y ⊕ \\bot))")
+            .unwrap()
+            .into_iter()
+            .map(|token| token.node)
+            .collect();
+
+        check!(
+            tokens
+                == vec![
+                    Token::Open,
+                    Token::Open,
+                    Token::Variable(Variable::new("x1")),
+                    Token::Binary(BinaryOperator::And),
+                    Token::Open,
+                    Token::Unary(UnaryOperator::Negation),
+                    Token::Constant(true),
+                    Token::Close,
+                    Token::Close,
+                    Token::Binary(BinaryOperator::Biimplication),
+                    Token::Open,
+                    Token::Variable(Variable::new("y")),
+                    Token::ExclusiveOr,
+                    Token::Constant(false),
+                    Token::Close,
+                    Token::Close,
+                ]
+        );
+        check!(tokenize("(a ? b)").unwrap_err().position() == 3);
+    }
+
+    #[test]
+    fn test_punctuation_and_recovery() {
+        let tokens: Vec<_> = tokenize("l: a; def b := c")
+            .unwrap()
+            .into_iter()
+            .map(|token| token.node)
+            .collect();
+        check!(
+            &tokens[1..4]
+                == &[
+                    Token::Colon,
+                    Token::Variable(Variable::new("a")),
+                    Token::Semicolon
+                ]
+        );
+        check!(&tokens[6] == &Token::Define);
+
+        let (tokens, errors) = tokenize_all("$(a ^ \"b) ¿");
+        check!(tokens.len() == 5);
+        check!(
+            errors.iter().map(|e| e.found).collect::<Vec<_>>()
+                == vec![Some('$'), Some('"'), Some('¿')]
+        );
+    }
+}
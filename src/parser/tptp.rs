@@ -0,0 +1,446 @@
+//! Parser for propositional problems in the TPTP syntax of the automated theorem proving
+//! community, i.e. `fof` and `cnf` formulas without quantifiers and predicates with arguments.
+//!
+//! ```text
+//! % an optional comment
+//! fof(rain_makes_wet, axiom, rain => wet).
+//! fof(it_rains, axiom, rain).
+//! fof(goal, conjecture, wet | $false).
+//! ```
+//!
+//! The connectives are `~`, `&`, `|`, `=>`, `<=`, `<=>`, `<~>` (exclusive or), `~|` (nor) and
+//! `~&` (nand), with the constants `$true` and `$false`. As in TPTP, `&` and `|` may be chained,
+//! while the other binary connectives need parentheses around operands which are binary formulas
+//! themselves. Atoms are lower-case words or single-quoted.
+
+use super::MAX_NESTING_DEPTH;
+use crate::formula::{PropositionalFormula, Variable};
+
+/// A propositional TPTP problem: its axioms, with the formulas of every role other than
+/// `conjecture` counting as axioms (e.g. `hypothesis` or `negated_conjecture`), and its conjecture
+/// if it has one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TptpProblem {
+    /// The axioms in order of the input.
+    pub axioms: Vec<PropositionalFormula>,
+    /// The conjecture to prove from the axioms, if any.
+    pub conjecture: Option<PropositionalFormula>,
+}
+
+/// The status of a problem in the SZS ontology used by TPTP provers, as printed in the
+/// `% SZS status <status>` line of their output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SzsStatus {
+    /// The conjecture follows from the axioms.
+    Theorem,
+    /// The conjecture does not follow from the axioms.
+    CounterSatisfiable,
+    /// There is no conjecture and the axioms are satisfiable.
+    Satisfiable,
+    /// There is no conjecture and the axioms are unsatisfiable.
+    Unsatisfiable,
+}
+
+impl TptpProblem {
+    /// The conjunction of the axioms and the negated conjecture, which is unsatisfiable if and only
+    /// if the problem is a theorem (or, without a conjecture, unsatisfiable). An empty conjunction
+    /// is `1`.
+    pub fn refutation_formula(&self) -> PropositionalFormula {
+        let negated_conjecture = self
+            .conjecture
+            .iter()
+            .map(|conjecture| PropositionalFormula::negated(Box::new(conjecture.clone())));
+
+        PropositionalFormula::conjoin(self.axioms.iter().cloned().chain(negated_conjecture))
+            .unwrap_or(PropositionalFormula::Top)
+    }
+
+    /// The status of the problem given whether its [`refutation_formula`] is satisfiable.
+    ///
+    /// [`refutation_formula`]: #method.refutation_formula
+    pub fn status(&self, refutation_satisfiable: bool) -> SzsStatus {
+        match (self.conjecture.is_some(), refutation_satisfiable) {
+            (true, true) => SzsStatus::CounterSatisfiable,
+            (true, false) => SzsStatus::Theorem,
+            (false, true) => SzsStatus::Satisfiable,
+            (false, false) => SzsStatus::Unsatisfiable,
+        }
+    }
+}
+
+/// Parse a propositional TPTP problem.
+///
+/// # Errors
+///
+/// Fails on malformed input, on first-order formulas (with variables, quantifiers or predicates
+/// with arguments), on `include` directives, and on problems with more than one conjecture.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::parser::parse_tptp;
+/// let problem = parse_tptp("
+///     fof(a1, axiom, p => q).
+///     cnf(a2, axiom, p | ~ q).
+///     fof(c, conjecture, (p <=> q) & ~ ~ q).
+/// ").unwrap();
+///
+/// assert_eq!("(p->q)", problem.axioms[0].to_string());
+/// assert_eq!("(p|(-q))", problem.axioms[1].to_string());
+/// assert_eq!("((p<->q)^(-(-q)))", problem.conjecture.unwrap().to_string());
+/// ```
+pub fn parse_tptp(input: &str) -> Result<TptpProblem, String> {
+    let mut parser = Parser {
+        tokens: tokenize(input)?,
+        position: 0,
+        depth: 0,
+    };
+    let mut problem = TptpProblem {
+        axioms: Vec::new(),
+        conjecture: None,
+    };
+
+    while let Some(token) = parser.next() {
+        match token {
+            Token::Word(language) if language == "fof" || language == "cnf" => {}
+            Token::Word(directive) if directive == "include" => {
+                return Err("`include` directives are not supported".to_string())
+            }
+            token => return Err(format!("expected `fof` or `cnf`, found {}", token)),
+        }
+
+        parser.expect(Token::Open)?;
+        parser.name()?;
+        parser.expect(Token::Comma)?;
+        let role = match parser.next() {
+            Some(Token::Word(role)) => role,
+            token => return Err(format!("expected a role, found {}", describe(token))),
+        };
+        parser.expect(Token::Comma)?;
+        let formula = parser.formula()?;
+        if parser.peek() == Some(&Token::Comma) {
+            parser.skip_annotations()?;
+        }
+        parser.expect(Token::Close)?;
+        parser.expect(Token::Period)?;
+
+        match role.as_str() {
+            "conjecture" if problem.conjecture.is_some() => {
+                return Err("problems may have at most one conjecture".to_string())
+            }
+            "conjecture" => problem.conjecture = Some(formula),
+            _ => problem.axioms.push(formula),
+        }
+    }
+
+    Ok(problem)
+}
+
+/// A token of the TPTP syntax.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Open,
+    Close,
+    Comma,
+    Period,
+    Not,
+    And,
+    Or,
+    /// A binary connective which cannot be chained: `=>`, `<=`, `<=>`, `<~>`, `~|` or `~&`.
+    NonAssociative(&'static str),
+    /// `$true`, `$false` or another defined word, with its `$`.
+    Defined(String),
+    /// A lower-case word, or the text of a single-quoted one.
+    Word(String),
+    /// An upper-case word, which is a first-order variable.
+    Variable(String),
+    /// Any other symbol, e.g. the `!` of a quantifier or the `[` of a list.
+    Other(char),
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Open => f.write_str("`(`"),
+            Token::Close => f.write_str("`)`"),
+            Token::Comma => f.write_str("`,`"),
+            Token::Period => f.write_str("`.`"),
+            Token::Not => f.write_str("`~`"),
+            Token::And => f.write_str("`&`"),
+            Token::Or => f.write_str("`|`"),
+            Token::NonAssociative(connective) => write!(f, "`{}`", connective),
+            Token::Defined(word) | Token::Word(word) | Token::Variable(word) => {
+                write!(f, "`{}`", word)
+            }
+            Token::Other(c) => write!(f, "`{}`", c),
+        }
+    }
+}
+
+fn describe(token: Option<Token>) -> String {
+    token.map_or("end of input".to_string(), |token| token.to_string())
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    const SYMBOLS: [(&str, Token); 13] = [
+        ("<=>", Token::NonAssociative("<=>")),
+        ("<~>", Token::NonAssociative("<~>")),
+        ("=>", Token::NonAssociative("=>")),
+        ("<=", Token::NonAssociative("<=")),
+        ("~|", Token::NonAssociative("~|")),
+        ("~&", Token::NonAssociative("~&")),
+        ("~", Token::Not),
+        ("&", Token::And),
+        ("|", Token::Or),
+        ("(", Token::Open),
+        (")", Token::Close),
+        (",", Token::Comma),
+        (".", Token::Period),
+    ];
+    let is_word_char = |c: char| c.is_ascii_alphanumeric() || c == '_';
+
+    let mut tokens = Vec::new();
+    let mut rest = input;
+    while let Some(c) = rest.chars().next() {
+        if c.is_whitespace() {
+            rest = &rest[c.len_utf8()..];
+        } else if c == '%' {
+            rest = rest.find('\n').map_or("", |end| &rest[end..]);
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            let end = comment
+                .find("*/")
+                .ok_or_else(|| "unterminated comment".to_string())?;
+            rest = &comment[end + 2..];
+        } else if let Some((symbol, token)) = SYMBOLS.iter().find(|(s, _)| rest.starts_with(*s)) {
+            tokens.push(token.clone());
+            rest = &rest[symbol.len()..];
+        } else if let Some(quoted) = rest.strip_prefix('\'') {
+            let end = quoted
+                .find('\'')
+                .ok_or_else(|| "unterminated single-quoted word".to_string())?;
+            tokens.push(Token::Word(quoted[..end].to_string()));
+            rest = &quoted[end + 1..];
+        } else if c == '$' || is_word_char(c) {
+            let end = rest[1..]
+                .find(|c: char| !is_word_char(c))
+                .map_or(rest.len(), |end| end + 1);
+            let word = rest[..end].to_string();
+            tokens.push(match c {
+                '$' => Token::Defined(word),
+                c if c.is_ascii_uppercase() => Token::Variable(word),
+                _ => Token::Word(word),
+            });
+            rest = &rest[end..];
+        } else {
+            tokens.push(Token::Other(c));
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    /// Number of enclosing parentheses and negations of the formula being parsed.
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), String> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            token => Err(format!("expected {}, found {}", expected, describe(token))),
+        }
+    }
+
+    /// Parse the name of an annotated formula: a word or an integer.
+    fn name(&mut self) -> Result<(), String> {
+        match self.next() {
+            Some(Token::Word(_)) => Ok(()),
+            token => Err(format!("expected a name, found {}", describe(token))),
+        }
+    }
+
+    /// Skip the source and useful-info annotations up to the `)` closing the annotated formula.
+    fn skip_annotations(&mut self) -> Result<(), String> {
+        let mut depth = 0usize;
+        loop {
+            match self.peek() {
+                Some(Token::Close) if depth == 0 => return Ok(()),
+                Some(Token::Close) => depth -= 1,
+                Some(Token::Open) => depth += 1,
+                Some(_) => {}
+                None => return Err("unterminated annotations".to_string()),
+            }
+            self.position += 1;
+        }
+    }
+
+    /// Parse a formula: a unitary formula, a chain of `&` or of `|` between unitary formulas, or
+    /// two unitary formulas joined by a non-associative connective.
+    fn formula(&mut self) -> Result<PropositionalFormula, String> {
+        let first = self.unitary()?;
+
+        let chained = match self.peek() {
+            Some(Token::And) => Token::And,
+            Some(Token::Or) => Token::Or,
+            Some(Token::NonAssociative(connective)) => {
+                let connective = *connective;
+                self.position += 1;
+                let second = self.unitary()?;
+                return Ok(non_associative(connective, first, second));
+            }
+            _ => return Ok(first),
+        };
+
+        let mut formula = first;
+        while self.peek() == Some(&chained) {
+            self.position += 1;
+            let (a, b) = (Box::new(formula), Box::new(self.unitary()?));
+            formula = match chained {
+                Token::And => PropositionalFormula::conjunction(a, b),
+                _ => PropositionalFormula::disjunction(a, b),
+            };
+        }
+        Ok(formula)
+    }
+
+    /// Parse a unitary formula: an atom, a constant, a negated unitary formula or a parenthesized
+    /// formula.
+    fn unitary(&mut self) -> Result<PropositionalFormula, String> {
+        if self.depth >= MAX_NESTING_DEPTH {
+            return Err(format!(
+                "formula is nested more than {} deep",
+                MAX_NESTING_DEPTH
+            ));
+        }
+
+        match self.next() {
+            Some(Token::Word(atom)) => {
+                if self.peek() == Some(&Token::Open) {
+                    return Err(format!(
+                        "predicate `{}` has arguments, only propositional atoms are supported",
+                        atom
+                    ));
+                }
+                Ok(PropositionalFormula::variable(Variable::new(atom)))
+            }
+            Some(Token::Defined(word)) if word == "$true" => Ok(PropositionalFormula::Top),
+            Some(Token::Defined(word)) if word == "$false" => Ok(PropositionalFormula::Bottom),
+            Some(Token::Not) => {
+                self.depth += 1;
+                let formula = self.unitary();
+                self.depth -= 1;
+                Ok(PropositionalFormula::negated(Box::new(formula?)))
+            }
+            Some(Token::Open) => {
+                self.depth += 1;
+                let formula = self.formula();
+                self.depth -= 1;
+                let formula = formula?;
+                self.expect(Token::Close)?;
+                Ok(formula)
+            }
+            Some(Token::Variable(variable)) => Err(format!(
+                "variable `{}` is first-order, only propositional formulas are supported",
+                variable
+            )),
+            Some(Token::Other(c)) if c == '!' || c == '?' => Err(format!(
+                "quantifier `{}` is first-order, only propositional formulas are supported",
+                c
+            )),
+            token => Err(format!("expected a formula, found {}", describe(token))),
+        }
+    }
+}
+
+fn non_associative(
+    connective: &str,
+    a: PropositionalFormula,
+    b: PropositionalFormula,
+) -> PropositionalFormula {
+    let (a, b) = (Box::new(a), Box::new(b));
+    let negated = |formula| PropositionalFormula::negated(Box::new(formula));
+
+    match connective {
+        "=>" => PropositionalFormula::implication(a, b),
+        "<=" => PropositionalFormula::implication(b, a),
+        "<=>" => PropositionalFormula::biimplication(a, b),
+        "<~>" => negated(PropositionalFormula::biimplication(a, b)),
+        "~|" => negated(PropositionalFormula::disjunction(a, b)),
+        "~&" => negated(PropositionalFormula::conjunction(a, b)),
+        _ => unreachable!("unknown connective `{}`", connective),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::truth_table;
+    use assert2::check;
+
+    fn conjecture(formula: &str) -> PropositionalFormula {
+        let problem = parse_tptp(&format!("fof(c, conjecture, {}).", formula)).unwrap();
+        problem.conjecture.unwrap()
+    }
+
+    #[test]
+    fn test_connectives() {
+        let cases = [
+            ("a & b & c", "((a^b)^c)"),
+            ("a | ~ b | $false", "((a|(-b))|0)"),
+            ("a <= b", "(b->a)"),
+            ("a <~> (b ~| c)", "(-(a<->(-(b|c))))"),
+            ("~ (a ~& 'B c')", r#"(-(-(a^"B c")))"#),
+            ("(a => b) <=> ($true)", "((a->b)<->1)"),
+        ];
+
+        for &(tptp, expected) in &cases {
+            check!(conjecture(tptp).to_string() == expected);
+        }
+    }
+
+    #[test]
+    fn test_problem() {
+        let input = "
+            % Modus ponens, with annotations.
+            fof(ax1, axiom, p => q, file('source.p', ax1), [useful]).
+            /* a block
+               comment */
+            fof(ax2, hypothesis, p).
+            cnf(neg, negated_conjecture, ~ q | r).
+            fof(goal, conjecture, q).
+        ";
+        let problem = parse_tptp(input).unwrap();
+        check!(problem.axioms.len() == 3);
+
+        let refutation = problem.refutation_formula();
+        check!(!truth_table::is_satisfiable(&refutation));
+        check!(problem.status(false) == SzsStatus::Theorem);
+        check!(parse_tptp("").unwrap().refutation_formula() == PropositionalFormula::Top);
+    }
+
+    #[test]
+    fn test_errors() {
+        check!(parse_tptp("fof(a, axiom, p(X)).").is_err());
+        check!(parse_tptp("fof(a, axiom, ! [X] : X).").is_err());
+        check!(parse_tptp("fof(a, axiom, a => b => c).").is_err());
+        check!(parse_tptp("fof(a, axiom, a & b | c).").is_err());
+        check!(parse_tptp("fof(a, axiom, a)").is_err());
+        check!(parse_tptp("include('Axioms/SET001-0.ax').").is_err());
+        check!(parse_tptp("fof(a, conjecture, a). fof(b, conjecture, b).").is_err());
+        check!(parse_tptp(&format!("fof(a, axiom, {}a).", "~".repeat(1000))).is_err());
+    }
+}
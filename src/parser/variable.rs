@@ -1,12 +1,16 @@
 //! Propositional variable parser.
 
-use super::ParseResult;
+use super::constant::is_constant_word;
+use super::operators::is_operator_word;
+use super::{Failure, ParseResult};
 
-use libprop_sat_solver::formula::Variable;
+use crate::formula::Variable;
 
+use nom::branch::alt;
 use nom::character::complete::{alphanumeric0, anychar};
 use nom::character::is_alphabetic;
 use nom::combinator::verify;
+use nom::error::{ErrorKind, ParseError};
 use nom::sequence::tuple;
 
 /// Parser for a propositional variable.
@@ -18,21 +22,130 @@ use nom::sequence::tuple;
 /// ```
 ///
 /// That is, a single alpha character `[a-zA-Z]` followed by zero or more alpha or numeric
-/// characters `[a-zA-Z0-9]`.
+/// characters `[a-zA-Z0-9]`. Operator words such as `and` or `NOT` and the constants `true` and
+/// `false` are reserved and are not variables.
+///
+/// Any other name, e.g. one containing spaces or operator characters, can be written in double or
+/// single quotes, see [`quoted_variable`].
+///
+/// [`quoted_variable`]: fn.quoted_variable.html
 pub fn variable(input: &str) -> ParseResult<&str, Variable> {
+    alt((identifier, quoted_variable))(input)
+}
+
+fn identifier(input: &str) -> ParseResult<&str, Variable> {
     let leading = verify(anychar, is_alphabetic_char);
     let rest = alphanumeric0;
-    let (remaining_input, (leading, rest)) = tuple((leading, rest))(input)?;
+    let (remaining_input, _) = tuple((leading, rest))(input)?;
+    // The name is interned straight from the input, without an intermediate `String`.
+    let name = &input[..input.len() - remaining_input.len()];
 
-    let mut name = String::new();
-    name.push(leading);
-    name.push_str(rest);
+    if is_operator_word(name) || is_constant_word(name) {
+        return Err(nom::Err::Error(ParseError::from_error_kind(
+            input,
+            ErrorKind::Verify,
+        )));
+    }
 
     Ok((remaining_input, Variable::new(name)))
 }
 
+/// Checks if the variable `name` can be written without quotes, see [`variable`].
+///
+/// [`variable`]: fn.variable.html
+pub fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| is_alphabetic_char(&c))
+        && chars.all(|c| c.is_ascii_alphanumeric())
+        && !is_operator_word(name)
+        && !is_constant_word(name)
+}
+
+/// Parser for a variable name in double or single quotes, e.g. `"state 0"` or `'x-prime'`.
+///
+/// A backslash makes the character after it part of the name, so `\"` and `\\` stand for `"` and
+/// `\` in a name in double quotes (and `\'` for `'` in a name in single quotes). The name must not
+/// be empty.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::parser::variable::quoted_variable;
+/// use libprop_sat_solver::formula::Variable;
+/// assert_eq!(Ok(("", Variable::new("state 0"))), quoted_variable("\"state 0\""));
+/// assert_eq!(Ok(("", Variable::new("say \"hi\""))), quoted_variable(r#""say \"hi\"""#));
+/// assert_eq!(Ok((" b", Variable::new("x-prime"))), quoted_variable("'x-prime' b"));
+/// ```
+pub fn quoted_variable(input: &str) -> ParseResult<&str, Variable> {
+    let quote = match input.chars().next() {
+        Some(quote @ '"') | Some(quote @ '\'') => quote,
+        _ => {
+            return Err(nom::Err::Error(ParseError::from_error_kind(
+                input,
+                ErrorKind::Char,
+            )))
+        }
+    };
+
+    let mut name = String::new();
+    let mut chars = input.char_indices().skip(1);
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some((_, escaped)) => name.push(escaped),
+                None => break,
+            },
+            c if c == quote && name.is_empty() => {
+                return Err(nom::Err::Failure(Failure {
+                    input: &input[i..],
+                    kind: ErrorKind::Verify,
+                    expected: vec!["a variable name"],
+                }));
+            }
+            c if c == quote => return Ok((&input[i + 1..], Variable::new(name))),
+            c => name.push(c),
+        }
+    }
+
+    // Once a name is opened, only its closing quote can follow.
+    Err(nom::Err::Failure(Failure {
+        input: &input[input.len()..],
+        kind: ErrorKind::Char,
+        expected: vec![if quote == '"' { "`\"`" } else { "`'`" }],
+    }))
+}
+
+/// The characters of the `input` with their byte offsets, skipping quoted variable names (along
+/// with their quotes), so that their contents are not mistaken for parentheses or operators.
+pub(crate) fn unquoted_char_indices(input: &str) -> impl Iterator<Item = (usize, char)> + '_ {
+    let mut quote = None;
+    let mut escaped = false;
+    input.char_indices().filter(move |&(_, c)| match quote {
+        Some(_) if escaped => {
+            escaped = false;
+            false
+        }
+        Some(_) if c == '\\' => {
+            escaped = true;
+            false
+        }
+        Some(q) => {
+            if c == q {
+                quote = None;
+            }
+            false
+        }
+        None if c == '"' || c == '\'' => {
+            quote = Some(c);
+            false
+        }
+        None => true,
+    })
+}
+
 fn is_alphabetic_char(c: &char) -> bool {
-    is_alphabetic(*c as u8)
+    // Checked before narrowing, since e.g. `≡` (U+2261) would narrow to `a`.
+    c.is_ascii() && is_alphabetic(*c as u8)
 }
 
 #[cfg(test)]
@@ -54,4 +167,39 @@ mod tests {
     fn invalid_name_fails() {
         check!(variable("_").is_err());
     }
+
+    #[test]
+    fn quoted() {
+        check!(("^ b", Variable::new("a (b)")) == variable("\"a (b)\"^ b").unwrap());
+        check!(("", Variable::new("and")) == variable("'and'").unwrap());
+        check!(("", Variable::new("it's")) == variable(r"'it\'s'").unwrap());
+        check!(variable("\"\"").is_err());
+        check!(variable("\"open").is_err());
+    }
+
+    #[test]
+    fn identifiers() {
+        check!(is_identifier("a0"));
+        check!(!is_identifier("0a"));
+        check!(!is_identifier("not"));
+        check!(!is_identifier("state 0"));
+        check!(!is_identifier(""));
+    }
+
+    #[test]
+    fn unquoted_chars() {
+        let unquoted: String = unquoted_char_indices(r#"(a ^ "(\")") | 'x'"#)
+            .map(|(_, c)| c)
+            .collect();
+        check!(unquoted == "(a ^ ) | ");
+    }
+
+    #[test]
+    fn operator_words_are_reserved() {
+        check!(variable("and").is_err());
+        check!(variable("Iff").is_err());
+        check!(("", Variable::new("andy")) == variable("andy").unwrap());
+        // Quoting makes the reserved words usable as names after all.
+        check!(("", Variable::new("AND")) == variable("'AND'").unwrap());
+    }
 }
@@ -0,0 +1,339 @@
+//! Pipelined reading, parsing and solving of input formulas.
+//!
+//! Reading, parsing and solving each run on their own thread(s) and are connected by bounded
+//! channels, so that solving can start as soon as the first formula has been parsed and huge
+//! input files never have to be held in memory all at once. Results are written in input order,
+//! unless they are streamed as soon as they are available.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io::{self, prelude::*};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use log::{debug, error};
+
+use libprop_sat_solver::formula::PropositionalFormula;
+
+use libprop_sat_solver::parser::{self, ParserOptions};
+
+use crate::{header, Location};
+
+/// Maximum number of lines or formulas buffered between two stages of the pipeline.
+const CHANNEL_CAPACITY: usize = 1024;
+
+/// A formula with its label, if it has one.
+type Labeled = (Option<String>, PropositionalFormula);
+
+/// Where a formula is in the input, which results are written in the order of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Key {
+    /// Index of the input line.
+    line_index: usize,
+    /// Index of the formula among those of the line.
+    position_in_line: usize,
+    /// Whether the formula is the last one of the line, so that the writer knows which comes next.
+    last_in_line: bool,
+}
+
+impl Key {
+    /// The line index and position in the line, which results are ordered by.
+    fn pair(self) -> (usize, usize) {
+        (self.line_index, self.position_in_line)
+    }
+
+    /// The pair of the formula after this one.
+    fn next(self) -> (usize, usize) {
+        if self.last_in_line {
+            (self.line_index + 1, 0)
+        } else {
+            (self.line_index, self.position_in_line + 1)
+        }
+    }
+
+    fn location(self) -> Location {
+        let several = !(self.position_in_line == 0 && self.last_in_line);
+        Location {
+            line: self.line_index + 1,
+            position: if several {
+                Some(self.position_in_line + 1)
+            } else {
+                None
+            },
+        }
+    }
+}
+
+/// Where the input lines are read from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+    /// A single formula given on the command line.
+    Formula(String),
+    /// A file with one formula per line.
+    File(PathBuf),
+    /// Standard input, with one formula per line.
+    Stdin,
+}
+
+/// Solve each formula from the `source` with `solve` on `jobs` worker threads, writing the results
+/// to `stdout` in input order, or in order of completion if `stream` is set. `solve` is also given
+/// the location of the formula, i.e. its input line and, if the line holds several formulas
+/// separated by `;`, which of them it is, and the label of the formula if it has one (e.g.
+/// `lemma1` in `lemma1: (a -> (b -> a))`). Formulas are parsed in the grammar chosen by the
+/// `options`.
+///
+/// # Errors
+///
+/// If reading a line fails then the program aborts with exit code `5`; if a formula is not
+/// well-formed then the program aborts with exit code `22`. Results of the formulas preceding the
+/// offending line may already have been written at that point.
+///
+/// If `keep_going` is set, ill-formed formulas are reported and skipped (without a result) instead,
+/// and the program only aborts with exit code `22` once all other formulas have been solved.
+pub fn run<F, R>(
+    source: Source,
+    jobs: usize,
+    stream: bool,
+    keep_going: bool,
+    options: ParserOptions,
+    solve: F,
+) -> io::Result<()>
+where
+    F: Fn(Location, Option<&str>, &PropositionalFormula) -> R + Sync,
+    R: fmt::Display + Send,
+{
+    // Skip the header of an input file, keeping the line numbers of the formulas after it.
+    let first_index = match &source {
+        Source::File(path) if header::read(path).is_some() => 1,
+        _ => 0,
+    };
+
+    let (line_sender, line_receiver) = mpsc::sync_channel(CHANNEL_CAPACITY);
+    let (formula_sender, formula_receiver) = mpsc::sync_channel(CHANNEL_CAPACITY);
+    let (result_sender, result_receiver) = mpsc::sync_channel(CHANNEL_CAPACITY);
+
+    // Shared by the workers, so that the parser stops once all of them are gone.
+    let formula_receiver = Arc::new(Mutex::new(formula_receiver));
+    let solve = &solve;
+
+    let (written, ill_formed) = thread::scope(|scope| {
+        scope.spawn(move || read_lines(source, first_index, line_sender));
+        let parser =
+            scope.spawn(move || parse_lines(line_receiver, formula_sender, keep_going, options));
+
+        for _ in 0..jobs.max(1) {
+            let formula_receiver = Arc::clone(&formula_receiver);
+            let result_sender = result_sender.clone();
+            scope.spawn(move || solve_formulas(&formula_receiver, result_sender, solve));
+        }
+        drop(formula_receiver);
+        drop(result_sender);
+
+        let written = write_results(result_receiver, first_index, stream);
+        // PANIC: cannot panic because the parser only exits the process, it does not panic.
+        (written, parser.join().unwrap())
+    });
+
+    if !ill_formed.is_empty() {
+        let lines: Vec<String> = ill_formed
+            .iter()
+            .map(|index| (index + 1).to_string())
+            .collect();
+        error!(
+            "{} ill-formed formula(s), on line(s) {}",
+            lines.len(),
+            lines.join(", ")
+        );
+        std::process::exit(22);
+    }
+
+    written
+}
+
+/// Number of worker threads to use when none is requested explicitly.
+pub fn default_jobs() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+fn read_lines(source: Source, first_index: usize, sender: SyncSender<(usize, String)>) {
+    let lines: Box<dyn Iterator<Item = io::Result<String>>> = match source {
+        Source::Formula(formula) => Box::new(std::iter::once(Ok(formula))),
+        Source::File(path) => match fs::File::open(&path) {
+            Ok(file) => Box::new(io::BufReader::new(file).lines()),
+            Err(e) => {
+                error!("failed to open {:#?}: {}", &path, e);
+                std::process::exit(5);
+            }
+        },
+        Source::Stdin => Box::new(io::BufReader::new(io::stdin()).lines()),
+    };
+
+    for (index, line) in lines.enumerate().skip(first_index) {
+        match line {
+            Ok(line) => {
+                if sender.send((index, line)).is_err() {
+                    return;
+                }
+            }
+            Err(e) => {
+                error!(
+                    "I/O error encountered when reading line {}: {}",
+                    index + 1,
+                    e
+                );
+                std::process::exit(5);
+            }
+        }
+    }
+}
+
+/// Parse each line into its formulas, keyed by the index of the line and their position in it,
+/// and return the indices of the ill-formed lines if `keep_going` is set. An ill-formed line, as
+/// well as a line defining a macro, is sent as a single `None` formula, so that results can still
+/// be written in input order.
+fn parse_lines(
+    receiver: Receiver<(usize, String)>,
+    sender: SyncSender<(Key, Option<Labeled>)>,
+    keep_going: bool,
+    options: ParserOptions,
+) -> Vec<usize> {
+    let mut ill_formed = Vec::new();
+    let mut macros = parser::Macros::with_options(options);
+
+    for (line_index, line) in receiver {
+        let formulas = match macros.parse_line(&line) {
+            Ok(formulas) if formulas.is_empty() => vec![None],
+            Ok(formulas) => formulas.into_iter().map(Some).collect(),
+            Err(e) => {
+                error!("ill-formed formula {}: {}", line_index + 1, e.diagnostic());
+                // Report the characters further on the line which start no token at all as well,
+                // rather than one mistake per run.
+                for lexical_error in parser::tokens(&line)
+                    .filter_map(Result::err)
+                    .filter(|lexical_error| lexical_error.position() > e.position())
+                {
+                    error!(
+                        "ill-formed formula {}: {}",
+                        line_index + 1,
+                        lexical_error.diagnostic()
+                    );
+                }
+                if !keep_going {
+                    std::process::exit(22);
+                }
+                ill_formed.push(line_index);
+                vec![None]
+            }
+        };
+
+        let count = formulas.len();
+        for (position_in_line, formula) in formulas.into_iter().enumerate() {
+            let key = Key {
+                line_index,
+                position_in_line,
+                last_in_line: position_in_line + 1 == count,
+            };
+            if let Some((_, formula)) = &formula {
+                debug!("parsed formula {}: {:?}", key.location(), formula);
+            }
+            if sender.send((key, formula)).is_err() {
+                return ill_formed;
+            }
+        }
+    }
+
+    ill_formed
+}
+
+fn solve_formulas<F, R>(
+    receiver: &Mutex<Receiver<(Key, Option<Labeled>)>>,
+    sender: SyncSender<(Key, Option<R>)>,
+    solve: &F,
+) where
+    F: Fn(Location, Option<&str>, &PropositionalFormula) -> R,
+{
+    loop {
+        // Only hold the lock while waiting for the next formula, not while solving it.
+        let next = receiver.lock().unwrap().recv();
+        let (key, formula) = match next {
+            Ok(next) => next,
+            Err(_) => return,
+        };
+
+        let result =
+            formula.map(|(label, formula)| solve(key.location(), label.as_deref(), &formula));
+        if sender.send((key, result)).is_err() {
+            return;
+        }
+    }
+}
+
+/// Write the results, from the first formula of the line `first_index` on, skipping the `None`s of
+/// ill-formed formulas.
+fn write_results<R: fmt::Display>(
+    receiver: Receiver<(Key, Option<R>)>,
+    first_index: usize,
+    stream: bool,
+) -> io::Result<()> {
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+
+    if stream {
+        for (_, result) in receiver {
+            let result = match result {
+                Some(result) => result,
+                None => continue,
+            };
+            stdout.write_fmt(format_args!("{}\n", result))?;
+            stdout.flush()?;
+        }
+
+        return Ok(());
+    }
+
+    // Results finishing out of order wait here until all preceding results have been written.
+    let mut pending = BTreeMap::new();
+    let mut next = (first_index, 0);
+
+    for (key, result) in receiver {
+        pending.insert(key.pair(), (key, result));
+
+        while let Some((key, result)) = pending.remove(&next) {
+            if let Some(result) = result {
+                stdout.write_fmt(format_args!("{}\n", result))?;
+            }
+            next = key.next();
+        }
+
+        stdout.flush()?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert2::check;
+
+    #[test]
+    fn test_keys() {
+        let key = |position_in_line, last_in_line| Key {
+            line_index: 2,
+            position_in_line,
+            last_in_line,
+        };
+
+        check!(key(0, true).next() == (3, 0));
+        check!(key(0, true).location().to_string() == "3");
+
+        // The formulas of a line holding several, e.g. `a; b`.
+        check!(key(0, false).next() == (2, 1));
+        check!(key(0, false).location().to_string() == "3:1");
+        check!(key(1, true).next() == (3, 0));
+        check!(key(1, true).location().to_string() == "3:2");
+    }
+}
@@ -0,0 +1,88 @@
+//! Recorded proofs of unsatisfiable and valid formulas, see `--proof-dir`.
+
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use log::{error, info};
+
+use libprop_sat_solver::formula::PropositionalFormula;
+use libprop_sat_solver::tableaux_solver::proof::{prove_unsatisfiable, prove_valid, Proof};
+
+use crate::Location;
+
+/// Format of recorded proofs.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ProofFormat {
+    /// The format read back by `check-proof`.
+    Json,
+    /// A `prooftree` environment of the LaTeX package `prooftrees`.
+    Latex,
+}
+
+impl ProofFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Latex => "tex",
+        }
+    }
+
+    fn render(self, proof: &Proof) -> String {
+        match self {
+            Self::Json => proof.to_json(),
+            Self::Latex => proof.to_latex(),
+        }
+    }
+}
+
+impl FromStr for ProofFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_ref() {
+            "json" => Ok(Self::Json),
+            "latex" => Ok(Self::Latex),
+            _ => Err(format!("unknown proof format: {:?}", s)),
+        }
+    }
+}
+
+/// Write a proof that the formula at `location` in the input is unsatisfiable (or, with `validity`,
+/// valid) to `formula-<line>.<extension>` in `dir`, or `formula-<line>_<position>.<extension>` if
+/// the line holds several formulas, in the given `format`.
+///
+/// # Errors
+///
+/// If the proof cannot be written then the program aborts with a non-zero exit code.
+pub fn write(
+    dir: &Path,
+    location: Location,
+    formula: &PropositionalFormula,
+    validity: bool,
+    format: ProofFormat,
+) {
+    let proof = if validity {
+        prove_valid(formula)
+    } else {
+        prove_unsatisfiable(formula)
+    };
+
+    // The solver and the proof search agree, so a proof always exists here.
+    let proof = match proof {
+        Some(proof) => proof,
+        None => return,
+    };
+
+    let path = dir.join(format!(
+        "formula-{}.{}",
+        location.file_stem(),
+        format.extension()
+    ));
+    info!("writing proof {:?}", &path);
+
+    if let Err(e) = fs::write(&path, format.render(&proof)) {
+        error!("failed to write proof {:?}: {}", &path, e);
+        std::process::exit(5);
+    }
+}
@@ -0,0 +1,272 @@
+//! Quantified Boolean formulas (QBF).
+//!
+//! A quantified Boolean formula extends a propositional formula with the binders `exists x. φ` and
+//! `forall x. φ`, where `exists x. φ` is true iff `φ` is true for _some_ truth value of `x`, and
+//! `forall x. φ` is true iff `φ` is true for _all_ truth values of `x`.
+//!
+//! We only support formulas in _prenex_ form, i.e. a prefix of binders followed by a
+//! quantifier-free propositional matrix, e.g. `forall x. exists y. (x<->y)`. This is the form used
+//! by QDIMACS and by the usual 2QBF encodings of two-player games.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::formula::{Literal, PropositionalFormula, Variable};
+use crate::tableaux_solver::is_satisfiable;
+
+use log::debug;
+
+/// The two quantifiers.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Quantifier {
+    /// `exists x. φ`
+    Exists,
+    /// `forall x. φ`
+    Forall,
+}
+
+/// A quantified Boolean formula in prenex form.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum QuantifiedFormula {
+    /// A quantifier binding a propositional variable in the quantified sub-formula.
+    Quantified(Quantifier, Variable, Box<QuantifiedFormula>),
+    /// The quantifier-free matrix.
+    Propositional(PropositionalFormula),
+}
+
+impl QuantifiedFormula {
+    /// Construct `exists variable. formula`.
+    #[inline]
+    pub fn exists(variable: Variable, formula: QuantifiedFormula) -> Self {
+        Self::Quantified(Quantifier::Exists, variable, Box::new(formula))
+    }
+
+    /// Construct `forall variable. formula`.
+    #[inline]
+    pub fn forall(variable: Variable, formula: QuantifiedFormula) -> Self {
+        Self::Quantified(Quantifier::Forall, variable, Box::new(formula))
+    }
+
+    /// Construct a quantified formula with no binders from a propositional formula.
+    #[inline]
+    pub fn propositional(formula: PropositionalFormula) -> Self {
+        Self::Propositional(formula)
+    }
+
+    /// Split the formula into its quantifier prefix (outermost binder first) and its matrix.
+    pub fn prenex(&self) -> (Vec<(Quantifier, &Variable)>, &PropositionalFormula) {
+        let mut prefix = Vec::new();
+        let mut formula = self;
+
+        while let Self::Quantified(quantifier, variable, sub_formula) = formula {
+            prefix.push((*quantifier, variable));
+            formula = sub_formula;
+        }
+
+        match formula {
+            Self::Propositional(matrix) => (prefix, matrix),
+            // PANIC: cannot panic because the loop above only stops at a matrix.
+            Self::Quantified(..) => unreachable!(),
+        }
+    }
+}
+
+impl fmt::Display for QuantifiedFormula {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Quantified(Quantifier::Exists, v, sub_formula) => {
+                write!(f, "exists {}. {}", v, sub_formula)
+            }
+            Self::Quantified(Quantifier::Forall, v, sub_formula) => {
+                write!(f, "forall {}. {}", v, sub_formula)
+            }
+            Self::Propositional(matrix) => write!(f, "{}", matrix),
+        }
+    }
+}
+
+/// Checks if the given quantified Boolean formula is _true_.
+///
+/// Variables of the matrix which are not bound by any quantifier are treated as existentially
+/// quantified at the outermost level (as in QDIMACS).
+///
+/// # Algorithm
+///
+/// We expand the quantifier prefix from the outside in, trying both truth values of each bound
+/// variable: `exists x. φ` is true iff `φ[x := true]` or `φ[x := false]` is, and
+/// `forall x. φ` is true iff both are. As soon as the remaining prefix only contains existential
+/// quantifiers, the rest of the problem is plain satisfiability of the matrix under the chosen
+/// truth values, which is handed to the tableau solver. A 2QBF `forall X. exists Y. φ` thus
+/// needs `2^|X|` tableau calls, regardless of the size of `Y`.
+pub fn is_true(formula: &QuantifiedFormula) -> bool {
+    let (mut prefix, matrix) = formula.prenex();
+
+    let free_variables = matrix
+        .variables()
+        .into_iter()
+        .filter(|v| !prefix.iter().any(|(_, bound)| bound == v))
+        .map(|v| (Quantifier::Exists, v))
+        .collect::<Vec<_>>();
+    prefix.splice(0..0, free_variables);
+
+    decide(&prefix, matrix, &mut HashMap::new())
+}
+
+fn decide<'a>(
+    prefix: &[(Quantifier, &'a Variable)],
+    matrix: &PropositionalFormula,
+    assignment: &mut HashMap<&'a Variable, bool>,
+) -> bool {
+    if prefix
+        .iter()
+        .all(|(quantifier, _)| *quantifier == Quantifier::Exists)
+    {
+        // Variables which are re-bound further in are not fixed by the outer binders.
+        let mut assignment = assignment.clone();
+        for (_, variable) in prefix {
+            assignment.remove(variable);
+        }
+
+        debug!("checking matrix under assignment {:?}", &assignment);
+        return is_satisfiable(&assume(matrix, &assignment));
+    }
+
+    // PANIC: cannot panic because an empty prefix only contains existential quantifiers.
+    let ((quantifier, variable), rest) = prefix.split_first().unwrap();
+    let previous = assignment.get(variable).copied();
+
+    let mut results = [true, false].iter().map(|&value| {
+        assignment.insert(variable, value);
+        decide(rest, matrix, assignment)
+    });
+
+    let result = match quantifier {
+        Quantifier::Exists => results.any(|result| result),
+        Quantifier::Forall => results.all(|result| result),
+    };
+
+    match previous {
+        Some(value) => assignment.insert(variable, value),
+        None => assignment.remove(variable),
+    };
+
+    result
+}
+
+/// Conjoin the matrix with the literals fixed by the assignment.
+fn assume(
+    matrix: &PropositionalFormula,
+    assignment: &HashMap<&Variable, bool>,
+) -> PropositionalFormula {
+    assignment
+        .iter()
+        .fold(matrix.clone(), |formula, (&variable, &value)| {
+            let literal = Literal::with_value(variable.clone(), value).to_formula();
+            PropositionalFormula::conjunction(Box::new(literal), Box::new(formula))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert2::check;
+
+    fn variable(name: &str) -> PropositionalFormula {
+        PropositionalFormula::variable(Variable::new(name))
+    }
+
+    #[test]
+    fn test_prenex() {
+        let formula = QuantifiedFormula::forall(
+            Variable::new("x"),
+            QuantifiedFormula::exists(
+                Variable::new("y"),
+                QuantifiedFormula::propositional(variable("y")),
+            ),
+        );
+        let (prefix, matrix) = formula.prenex();
+
+        check!(prefix.len() == 2);
+        check!(prefix[0].0 == Quantifier::Forall);
+        check!(prefix[1].0 == Quantifier::Exists);
+        check!(matrix == &variable("y"));
+    }
+
+    #[test]
+    fn test_forall_exists_true() {
+        // forall x. exists y. (x<->y)
+        let formula = QuantifiedFormula::forall(
+            Variable::new("x"),
+            QuantifiedFormula::exists(
+                Variable::new("y"),
+                QuantifiedFormula::propositional(PropositionalFormula::biimplication(
+                    Box::new(variable("x")),
+                    Box::new(variable("y")),
+                )),
+            ),
+        );
+
+        check!(is_true(&formula));
+    }
+
+    #[test]
+    fn test_exists_forall_false() {
+        // exists y. forall x. (x<->y)
+        let formula = QuantifiedFormula::exists(
+            Variable::new("y"),
+            QuantifiedFormula::forall(
+                Variable::new("x"),
+                QuantifiedFormula::propositional(PropositionalFormula::biimplication(
+                    Box::new(variable("x")),
+                    Box::new(variable("y")),
+                )),
+            ),
+        );
+
+        check!(!is_true(&formula));
+    }
+
+    #[test]
+    fn test_free_variables_are_outermost_existential() {
+        // forall x. (x<->y), with `y` free, means exists y. forall x. (x<->y).
+        let formula = QuantifiedFormula::forall(
+            Variable::new("x"),
+            QuantifiedFormula::propositional(PropositionalFormula::biimplication(
+                Box::new(variable("x")),
+                Box::new(variable("y")),
+            )),
+        );
+
+        check!(!is_true(&formula));
+    }
+
+    #[test]
+    fn test_forall_tautology() {
+        // forall x. (x|(-x))
+        let formula = QuantifiedFormula::forall(
+            Variable::new("x"),
+            QuantifiedFormula::propositional(PropositionalFormula::disjunction(
+                Box::new(variable("x")),
+                Box::new(PropositionalFormula::negated(Box::new(variable("x")))),
+            )),
+        );
+
+        check!(is_true(&formula));
+    }
+
+    #[test]
+    fn test_display() {
+        let formula = QuantifiedFormula::forall(
+            Variable::new("x"),
+            QuantifiedFormula::exists(
+                Variable::new("y"),
+                QuantifiedFormula::propositional(PropositionalFormula::biimplication(
+                    Box::new(variable("x")),
+                    Box::new(variable("y")),
+                )),
+            ),
+        );
+
+        check!(formula.to_string() == "forall x. exists y. (x<->y)");
+    }
+}
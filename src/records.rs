@@ -0,0 +1,259 @@
+//! Per-formula output records in text, JSON or CSV format, see `--format`.
+
+use std::str::FromStr;
+use std::time::Duration;
+
+use libprop_sat_solver::formula::Assignment;
+use libprop_sat_solver::tableaux_solver::{proof::TableauShape, TableauStats};
+
+/// Format of the output records.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Just the result, followed by the residual formula if there is one.
+    Text,
+    /// One JSON object per line.
+    Json,
+    /// Comma-separated values, after a header line.
+    Csv,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_ref() {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            _ => Err(format!("unknown output format: {:?}", s)),
+        }
+    }
+}
+
+/// Header line of the CSV format.
+pub const CSV_HEADER: &str = "line,label,result,time_us,expansions,peak_queue,residual";
+
+/// Outcome of solving the formula on one input line, along with the resources it took.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    /// 1-based input line number.
+    pub line: usize,
+    /// The label of the formula, e.g. `lemma1` in `lemma1: (a -> (b -> a))`.
+    pub label: Option<String>,
+    /// The satisfiability/validity of the formula, or `None` if solving it timed out.
+    pub result: Option<bool>,
+    /// The formula left after fixing variables with `--fix-from`, if given.
+    pub residual: Option<String>,
+    /// Wall time spent on the formula.
+    pub time: Duration,
+    /// Resources used by the tableau, if the formula was handed to it.
+    pub stats: Option<TableauStats>,
+    /// Shape of the complete tableau, if requested with `--branch-stats`. Not part of the `csv`
+    /// format.
+    pub shape: Option<TableauShape>,
+    /// Variable values on the most expanded open branch when solving timed out, see `--timeout`.
+    /// Not part of the `csv` format.
+    pub partial: Option<Assignment>,
+}
+
+impl Record {
+    /// Render the record in the given format, without a trailing newline.
+    pub fn render(&self, format: OutputFormat) -> String {
+        match format {
+            OutputFormat::Text => {
+                let result = optional(self.result, "unknown");
+                let mut text = match &self.residual {
+                    Some(residual) => format!("{}\t{}", result, residual),
+                    None => result,
+                };
+                if let Some(label) = &self.label {
+                    text = format!("{}: {}", label, text);
+                }
+                if let Some(shape) = &self.shape {
+                    text.push_str(&format!(
+                        "\n  branches: {} ({} closed, {} open), size: {}",
+                        shape.branches, shape.closed, shape.open, shape.size
+                    ));
+                }
+                if let Some(partial) = &self.partial {
+                    let values: Vec<String> = sorted(partial)
+                        .into_iter()
+                        .map(|(variable, value)| format!("{}={}", variable, u8::from(value)))
+                        .collect();
+                    text.push_str(&format!("\n  partial: {}", values.join(", ")));
+                }
+                text
+            }
+            OutputFormat::Json => {
+                let mut json = format!(
+                    "{{\"line\": {}, {}\"result\": {}, \"time_us\": {}, \"expansions\": {}, \
+                     \"peak_queue\": {}",
+                    self.line,
+                    self.label
+                        .as_ref()
+                        .map(|label| format!("\"label\": \"{}\", ", escape(label)))
+                        .unwrap_or_default(),
+                    optional(self.result, "null"),
+                    self.time.as_micros(),
+                    optional(self.stats.map(|s| s.expansions), "null"),
+                    optional(self.stats.map(|s| s.peak_queue), "null"),
+                );
+                if let Some(shape) = &self.shape {
+                    json.push_str(&format!(
+                        ", \"branches\": {}, \"closed\": {}, \"open\": {}, \"size\": {}",
+                        shape.branches, shape.closed, shape.open, shape.size
+                    ));
+                }
+                if let Some(partial) = &self.partial {
+                    let values: Vec<String> = sorted(partial)
+                        .into_iter()
+                        .map(|(variable, value)| format!("\"{}\": {}", escape(variable), value))
+                        .collect();
+                    json.push_str(&format!(", \"partial\": {{{}}}", values.join(", ")));
+                }
+                if let Some(residual) = &self.residual {
+                    json.push_str(&format!(", \"residual\": \"{}\"", escape(residual)));
+                }
+                json.push('}');
+                json
+            }
+            OutputFormat::Csv => format!(
+                "{},{},{},{},{},{},{}",
+                self.line,
+                self.label.as_deref().unwrap_or_default(),
+                optional(self.result, "unknown"),
+                self.time.as_micros(),
+                optional(self.stats.map(|s| s.expansions), ""),
+                optional(self.stats.map(|s| s.peak_queue), ""),
+                self.residual
+                    .as_ref()
+                    .map(|residual| format!("\"{}\"", residual.replace('"', "\"\"")))
+                    .unwrap_or_default(),
+            ),
+        }
+    }
+}
+
+fn optional<T: ToString>(value: Option<T>, missing: &str) -> String {
+    value.map_or_else(|| missing.to_string(), |value| value.to_string())
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// The values of the assignment, ordered by variable name.
+fn sorted(assignment: &Assignment) -> Vec<(&str, bool)> {
+    let mut values: Vec<_> = assignment
+        .iter()
+        .map(|(variable, &value)| (variable.name(), value))
+        .collect();
+    values.sort_unstable();
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert2::check;
+    use libprop_sat_solver::formula::Variable;
+
+    fn record() -> Record {
+        Record {
+            line: 3,
+            label: None,
+            result: Some(true),
+            residual: None,
+            time: Duration::from_micros(42),
+            stats: Some(TableauStats {
+                expansions: 7,
+                peak_queue: 2,
+            }),
+            shape: None,
+            partial: None,
+        }
+    }
+
+    #[test]
+    fn test_text() {
+        check!(record().render(OutputFormat::Text) == "true");
+    }
+
+    #[test]
+    fn test_json() {
+        check!(
+            record().render(OutputFormat::Json)
+                == "{\"line\": 3, \"result\": true, \"time_us\": 42, \"expansions\": 7, \
+                    \"peak_queue\": 2}"
+        );
+    }
+
+    #[test]
+    fn test_label() {
+        let record = Record {
+            label: Some("lemma1".to_string()),
+            ..record()
+        };
+
+        check!(record.render(OutputFormat::Text) == "lemma1: true");
+        check!(
+            record.render(OutputFormat::Json)
+                == "{\"line\": 3, \"label\": \"lemma1\", \"result\": true, \"time_us\": 42, \
+                    \"expansions\": 7, \"peak_queue\": 2}"
+        );
+        check!(record.render(OutputFormat::Csv) == "3,lemma1,true,42,7,2,");
+    }
+
+    #[test]
+    fn test_shape() {
+        let record = Record {
+            shape: Some(TableauShape {
+                branches: 3,
+                closed: 2,
+                open: 1,
+                size: 6,
+            }),
+            ..record()
+        };
+
+        check!(
+            record.render(OutputFormat::Text) == "true\n  branches: 3 (2 closed, 1 open), size: 6"
+        );
+        check!(
+            record.render(OutputFormat::Json)
+                == "{\"line\": 3, \"result\": true, \"time_us\": 42, \"expansions\": 7, \
+                    \"peak_queue\": 2, \"branches\": 3, \"closed\": 2, \"open\": 1, \"size\": 6}"
+        );
+    }
+
+    #[test]
+    fn test_csv_without_stats() {
+        let record = Record {
+            stats: None,
+            residual: Some("(a|b)".to_string()),
+            ..record()
+        };
+
+        check!(record.render(OutputFormat::Csv) == "3,,true,42,,,\"(a|b)\"");
+    }
+
+    #[test]
+    fn test_timed_out() {
+        let mut partial = Assignment::new();
+        partial.insert(Variable::new("b"), false);
+        partial.insert(Variable::new("a"), true);
+        let record = Record {
+            result: None,
+            partial: Some(partial),
+            ..record()
+        };
+
+        check!(record.render(OutputFormat::Text) == "unknown\n  partial: a=1, b=0");
+        check!(
+            record.render(OutputFormat::Json)
+                == "{\"line\": 3, \"result\": null, \"time_us\": 42, \"expansions\": 7, \
+                    \"peak_queue\": 2, \"partial\": {\"a\": true, \"b\": false}}"
+        );
+        check!(record.render(OutputFormat::Csv) == "3,,unknown,42,7,2,");
+    }
+}
@@ -0,0 +1,288 @@
+//! Solving sessions which reuse results across many related queries.
+
+use std::collections::HashMap;
+
+use log::debug;
+
+use crate::formula::arena::{FormulaArena, FormulaId, Node};
+use crate::formula::PropositionalFormula;
+use crate::solver::{self, SolverConfig};
+
+/// Default number of distinct formulas a [`Session`] keeps, see [`Session::with_capacity`].
+///
+/// [`Session`]: struct.Session.html
+/// [`Session::with_capacity`]: struct.Session.html#method.with_capacity
+pub const DEFAULT_CAPACITY: usize = 1 << 20;
+
+/// Counters of a [`Session`].
+///
+/// [`Session`]: struct.Session.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SessionStats {
+    /// Number of satisfiability queries, including those made on behalf of validity queries.
+    pub queries: usize,
+    /// Number of queries answered from known results without running the solver.
+    pub cache_hits: usize,
+}
+
+/// A solving session which remembers the satisfiability of every formula it has seen, so that
+/// repeated and closely related queries do not redo the same work.
+///
+/// Besides the queried formulas themselves, results are recorded for sub-formulas whenever they
+/// follow from the result of the whole: both conjuncts of a satisfiable conjunction are
+/// satisfiable, and both disjuncts of an unsatisfiable disjunction are unsatisfiable. Conversely,
+/// a conjunction with a known unsatisfiable conjunct and a disjunction with a known satisfiable
+/// disjunct are decided without solving.
+///
+/// Queries are interned in a [`FormulaArena`], so each distinct sub-formula is stored once however
+/// many queries share it, and results are looked up by [`FormulaId`].
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::formula::{PropositionalFormula, Variable};
+/// use libprop_sat_solver::session::Session;
+/// let a = PropositionalFormula::variable(Variable::new("a"));
+/// let contradiction = PropositionalFormula::conjunction(
+///     Box::new(a.clone()),
+///     Box::new(PropositionalFormula::negated(Box::new(a.clone()))),
+/// );
+///
+/// let mut session = Session::default();
+/// assert!(!session.is_satisfiable(&contradiction));
+///
+/// // Decided from the known result for `contradiction`.
+/// let formula = PropositionalFormula::conjunction(Box::new(contradiction), Box::new(a));
+/// assert!(!session.is_satisfiable(&formula));
+/// assert_eq!(1, session.stats().cache_hits);
+/// ```
+///
+/// [`FormulaArena`]: ../formula/arena/struct.FormulaArena.html
+/// [`FormulaId`]: ../formula/arena/struct.FormulaId.html
+#[derive(Debug, Clone)]
+pub struct Session {
+    config: SolverConfig,
+    arena: FormulaArena,
+    results: HashMap<FormulaId, bool>,
+    capacity: usize,
+    stats: SessionStats,
+}
+
+impl Default for Session {
+    /// A session solving with the default configuration, keeping up to [`DEFAULT_CAPACITY`]
+    /// formulas.
+    ///
+    /// [`DEFAULT_CAPACITY`]: constant.DEFAULT_CAPACITY.html
+    fn default() -> Self {
+        Self::new(SolverConfig::default())
+    }
+}
+
+impl Session {
+    /// Construct a session solving with the given configuration.
+    pub fn new(config: SolverConfig) -> Self {
+        Self {
+            config,
+            arena: FormulaArena::new(),
+            results: HashMap::new(),
+            capacity: DEFAULT_CAPACITY,
+            stats: SessionStats::default(),
+        }
+    }
+
+    /// Bound the number of distinct formulas and sub-formulas the session keeps, which is
+    /// [`DEFAULT_CAPACITY`] by default. A query which would take the session beyond its capacity
+    /// makes it forget everything first.
+    ///
+    /// [`DEFAULT_CAPACITY`]: constant.DEFAULT_CAPACITY.html
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Checks if the given propositional formula is _satisfiable_, reusing known results.
+    pub fn is_satisfiable(&mut self, formula: &PropositionalFormula) -> bool {
+        let id = self.intern(|arena| arena.intern(formula));
+        self.decide(id, |config| solver::is_satisfiable(formula, config))
+    }
+
+    /// Checks if the given propositional formula is _valid_, i.e. if its negation is
+    /// unsatisfiable, reusing known results.
+    pub fn is_valid(&mut self, formula: &PropositionalFormula) -> bool {
+        let id = self.intern(|arena| {
+            let id = arena.intern(formula);
+            arena.negation(id)
+        });
+        !self.decide(id, |config| {
+            let negated_formula = PropositionalFormula::negated(Box::new(formula.clone()));
+            solver::is_satisfiable(&negated_formula, config)
+        })
+    }
+
+    /// Number of formulas whose satisfiability is known.
+    pub fn known_results(&self) -> usize {
+        self.results.len()
+    }
+
+    /// Counters of the queries made so far.
+    pub fn stats(&self) -> SessionStats {
+        self.stats
+    }
+
+    /// Forget all known results and interned formulas.
+    pub fn clear(&mut self) {
+        self.arena = FormulaArena::new();
+        self.results.clear();
+    }
+
+    /// Intern a query with `intern`, forgetting everything first if the arena would outgrow the
+    /// capacity.
+    fn intern<F>(&mut self, intern: F) -> FormulaId
+    where
+        F: Fn(&mut FormulaArena) -> FormulaId,
+    {
+        let id = intern(&mut self.arena);
+        if self.arena.len() <= self.capacity {
+            return id;
+        }
+
+        debug!("forgetting {} known results", self.results.len());
+        self.clear();
+        intern(&mut self.arena)
+    }
+
+    /// Decide the satisfiability of the interned formula `id`, calling `solve` only if it does not
+    /// follow from known results.
+    fn decide<F>(&mut self, id: FormulaId, solve: F) -> bool
+    where
+        F: FnOnce(&SolverConfig) -> bool,
+    {
+        self.stats.queries += 1;
+
+        if let Some(result) = self.known_result(id) {
+            debug!(
+                "reusing known result {} for {}",
+                result,
+                self.arena.to_formula(id)
+            );
+            self.stats.cache_hits += 1;
+            self.record(id, result);
+            return result;
+        }
+
+        let result = solve(&self.config);
+        self.record(id, result);
+        result
+    }
+
+    fn known_result(&self, id: FormulaId) -> Option<bool> {
+        if let Some(&result) = self.results.get(&id) {
+            return Some(result);
+        }
+
+        match *self.arena.node(id) {
+            Node::Conjunction(a, b) => {
+                if self.results.get(&a) == Some(&false) || self.results.get(&b) == Some(&false) {
+                    Some(false)
+                } else {
+                    None
+                }
+            }
+            Node::Disjunction(a, b) => match (self.results.get(&a), self.results.get(&b)) {
+                (Some(true), _) | (_, Some(true)) => Some(true),
+                (Some(false), Some(false)) => Some(false),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn record(&mut self, id: FormulaId, result: bool) {
+        let mut pending = vec![id];
+        while let Some(id) = pending.pop() {
+            if self.results.insert(id, result).is_some() {
+                // Everything implied by this result has already been recorded.
+                continue;
+            }
+
+            match (self.arena.node(id), result) {
+                (&Node::Conjunction(a, b), true) | (&Node::Disjunction(a, b), false) => {
+                    pending.push(a);
+                    pending.push(b);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formula::Variable;
+    use crate::testing::formulas;
+    use assert2::check;
+
+    #[test]
+    fn test_repeated_query_is_cached() {
+        let formula = PropositionalFormula::implication(
+            Box::new(PropositionalFormula::variable(Variable::new("a"))),
+            Box::new(PropositionalFormula::variable(Variable::new("b"))),
+        );
+
+        let mut session = Session::default();
+        check!(session.is_satisfiable(&formula));
+        check!(session.is_satisfiable(&formula));
+        check!(
+            session.stats()
+                == SessionStats {
+                    queries: 2,
+                    cache_hits: 1,
+                }
+        );
+    }
+
+    #[test]
+    fn test_satisfiable_conjunction_records_conjuncts() {
+        let a = PropositionalFormula::variable(Variable::new("a"));
+        let b = PropositionalFormula::negated(Box::new(PropositionalFormula::variable(
+            Variable::new("b"),
+        )));
+        let formula = PropositionalFormula::conjunction(Box::new(a.clone()), Box::new(b.clone()));
+
+        let mut session = Session::default();
+        check!(session.is_satisfiable(&formula));
+        check!(session.known_results() == 3);
+
+        check!(session.is_satisfiable(&b));
+        check!(session.stats().cache_hits == 1);
+    }
+
+    #[test]
+    fn test_capacity_bounds_known_results() {
+        let mut session = Session::default().with_capacity(4);
+
+        // `a`, `b` and the conjunction.
+        check!(session.is_satisfiable(&"(a^b)".parse().unwrap()));
+        check!(session.known_results() == 3);
+
+        // `c`, `(-c)` and the disjunction would make six formulas.
+        check!(session.is_satisfiable(&"(c|(-c))".parse().unwrap()));
+        check!(session.known_results() == 1);
+        check!(session.stats().cache_hits == 0);
+    }
+
+    #[test]
+    fn test_agrees_with_solver() {
+        let config = SolverConfig::default();
+        let mut session = Session::new(config);
+
+        // Few variables and a shallow depth make the generated formulas share many sub-formulas.
+        for formula in formulas(2, 3, 17, 200) {
+            check!(session.is_satisfiable(&formula) == solver::is_satisfiable(&formula, &config));
+            check!(session.is_valid(&formula) == solver::is_valid(&formula, &config));
+        }
+
+        check!(session.stats().cache_hits > 0);
+    }
+}
@@ -0,0 +1,114 @@
+//! Periodic snapshots of the tableau while solving, see `--snapshot-dir`.
+
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use log::{error, info};
+
+use libprop_sat_solver::formula::PropositionalFormula;
+use libprop_sat_solver::tableaux_solver::{snapshot, Solver, Tableau, TableauStats};
+
+use crate::Location;
+
+/// Format of tableau snapshots.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SnapshotFormat {
+    Json,
+    Dot,
+}
+
+impl SnapshotFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Dot => "dot",
+        }
+    }
+
+    fn render(self, tableau: &Tableau) -> String {
+        match self {
+            Self::Json => snapshot::to_json(tableau),
+            Self::Dot => snapshot::to_dot(tableau),
+        }
+    }
+}
+
+impl FromStr for SnapshotFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_ref() {
+            "json" => Ok(Self::Json),
+            "dot" => Ok(Self::Dot),
+            _ => Err(format!("unknown snapshot format: {:?}", s)),
+        }
+    }
+}
+
+/// Solves formulas with the tableau, writing a snapshot of it to `dir` every `every` expansions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshots {
+    pub dir: PathBuf,
+    pub every: usize,
+    pub format: SnapshotFormat,
+}
+
+impl Snapshots {
+    /// Checks if the formula at `location` in the input is _satisfiable_, also returning the
+    /// resources used by the tableau.
+    ///
+    /// # Errors
+    ///
+    /// If a snapshot cannot be written then the program aborts with a non-zero exit code.
+    pub fn is_satisfiable(
+        &self,
+        location: Location,
+        formula: &PropositionalFormula,
+    ) -> (bool, TableauStats) {
+        let mut stats = TableauStats::default();
+
+        let result = Solver::new().solve(formula, |expansions, tableau| {
+            stats.record(expansions, tableau);
+
+            if self.every > 0 && expansions % self.every == 0 {
+                self.write(location, expansions, tableau);
+            }
+        });
+        let result =
+            result.unwrap_or_else(|_| unreachable!("the tableau cannot give up without limits"));
+
+        (result, stats)
+    }
+
+    /// Checks if the formula at `location` in the input is _valid_, also returning the resources
+    /// used by the tableau.
+    ///
+    /// # Errors
+    ///
+    /// If a snapshot cannot be written then the program aborts with a non-zero exit code.
+    pub fn is_valid(
+        &self,
+        location: Location,
+        formula: &PropositionalFormula,
+    ) -> (bool, TableauStats) {
+        let negated_formula = PropositionalFormula::negated(Box::new(formula.clone()));
+        let (satisfiable, stats) = self.is_satisfiable(location, &negated_formula);
+        (!satisfiable, stats)
+    }
+
+    fn write(&self, location: Location, expansions: usize, tableau: &Tableau) {
+        let path = self.dir.join(format!(
+            "formula-{}-{}.{}",
+            location.file_stem(),
+            expansions,
+            self.format.extension()
+        ));
+        info!("writing tableau snapshot {:?}", &path);
+
+        if let Err(e) = fs::write(&path, self.format.render(tableau)) {
+            error!("failed to write tableau snapshot {:?}: {}", &path, e);
+            std::process::exit(5);
+        }
+    }
+}
@@ -0,0 +1,1114 @@
+//! Engine selection between the truth table, the propositional tableau, DPLL and the 2-SAT and
+//! Horn solvers.
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use log::{debug, info};
+
+use crate::cnf::{dpll, horn, two_sat, CnfFormula};
+use crate::formula::{
+    analysis::FormulaMetrics, eliminate_definitions, push_negations, Assignment,
+    PropositionalFormula,
+};
+use crate::models::counter_model;
+use crate::tableaux_solver::{
+    self, BranchHeuristic, Limit, LimitReached, Limits, PreprocessingOptions, SearchStrategy,
+    Tableau, TableauStats, TimedOut,
+};
+use crate::truth_table;
+
+/// Engine deciding formulas which are too large for a truth table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Engine {
+    /// The propositional tableau, see [`tableaux_solver`].
+    ///
+    /// [`tableaux_solver`]: ../tableaux_solver/index.html
+    Tableau,
+    /// DPLL on the CNF of the formula, see [`dpll`].
+    ///
+    /// [`dpll`]: ../cnf/dpll/index.html
+    Dpll,
+    /// The propositional tableau over hash-consed formulas, see [`interned`].
+    ///
+    /// [`interned`]: ../tableaux_solver/interned/index.html
+    Interned,
+    /// Pick a backend for each formula from its metrics, see [`select_backend`].
+    ///
+    /// [`select_backend`]: fn.select_backend.html
+    Auto,
+}
+
+impl FromStr for Engine {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_ref() {
+            "tableau" => Ok(Self::Tableau),
+            "dpll" => Ok(Self::Dpll),
+            "interned" => Ok(Self::Interned),
+            "auto" => Ok(Self::Auto),
+            _ => Err(format!("unknown engine: {:?}", s)),
+        }
+    }
+}
+
+impl fmt::Display for Engine {
+    /// The name of the engine, as accepted by [`FromStr`](#impl-FromStr).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Tableau => "tableau",
+            Self::Dpll => "dpll",
+            Self::Interned => "interned",
+            Self::Auto => "auto",
+        })
+    }
+}
+
+/// Configuration of [`is_satisfiable`] and [`is_valid`].
+///
+/// [`is_satisfiable`]: fn.is_satisfiable.html
+/// [`is_valid`]: fn.is_valid.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SolverConfig {
+    /// Formulas with at most this many distinct variables are decided by enumerating their truth
+    /// table instead of by the `engine`.
+    pub truth_table_max_variables: usize,
+    /// Engine deciding the remaining formulas.
+    pub engine: Engine,
+    /// Preprocessing of formulas handed to the engine, or `None` to hand them over as they are.
+    pub preprocessing: Option<PreprocessingOptions>,
+    /// Heuristic choosing the formula to expand next when the engine is the tableau.
+    pub branch_heuristic: BranchHeuristic,
+    /// Order in which the tableau expands its branches, trading memory for time.
+    pub search_strategy: SearchStrategy,
+    /// Whether to substitute away top-level definitions `(x<->A)` before checking satisfiability,
+    /// see [`eliminate_definitions`].
+    ///
+    /// [`eliminate_definitions`]: ../formula/definitions/fn.eliminate_definitions.html
+    pub eliminate_definitions: bool,
+    /// Whether to push negations inwards until they only apply to variables before handing the
+    /// formula to the engine, see [`push_negations`].
+    ///
+    /// [`push_negations`]: ../formula/transform/fn.push_negations.html
+    pub push_negations: bool,
+    /// Number of worker threads expanding the tableau, see [`parallel`], or [`pool`] with the
+    /// `parallel` feature; with a single thread the sequential tableau is used. The
+    /// `branch_heuristic` and `search_strategy` only apply to the sequential tableau.
+    ///
+    /// [`parallel`]: ../tableaux_solver/parallel/index.html
+    /// [`pool`]: ../tableaux_solver/pool/index.html
+    pub tableau_threads: usize,
+    /// Time after which [`solve`] gives up on a formula, or `None` to wait however long it takes.
+    ///
+    /// [`solve`]: fn.solve.html
+    pub timeout: Option<Duration>,
+    /// Number of tableau expansions after which [`solve`] gives up on a formula, or `None` for no
+    /// limit.
+    ///
+    /// [`solve`]: fn.solve.html
+    pub max_expansions: Option<usize>,
+    /// Number of theories queued in the tableau beyond which [`solve`] gives up on a formula, or
+    /// `None` for no limit.
+    ///
+    /// [`solve`]: fn.solve.html
+    pub max_queue: Option<usize>,
+    /// Number of closed theories the tableau remembers so that branches reaching them again close
+    /// straight away, or `None` not to remember any, see [`Solver::with_theory_cache`][cache].
+    ///
+    /// [cache]: ../tableaux_solver/struct.Solver.html#method.with_theory_cache
+    pub theory_cache_capacity: Option<usize>,
+}
+
+impl Default for SolverConfig {
+    /// Truth tables for formulas with at most `8` variables, and the tableau without preprocessing
+    /// for the rest, expanding formulas in arbitrary order.
+    fn default() -> Self {
+        Self {
+            truth_table_max_variables: 8,
+            engine: Engine::Tableau,
+            preprocessing: None,
+            branch_heuristic: BranchHeuristic::Arbitrary,
+            search_strategy: SearchStrategy::BreadthFirst,
+            eliminate_definitions: false,
+            push_negations: false,
+            tableau_threads: 1,
+            timeout: None,
+            max_expansions: None,
+            max_queue: None,
+            theory_cache_capacity: None,
+        }
+    }
+}
+
+/// Checks if the given propositional formula is _satisfiable_, using the engine selected by
+/// `config` for the number of variables of the formula. The `timeout` and the other limits of the
+/// `config` are ignored, see [`solve`] to respect them.
+///
+/// [`solve`]: fn.solve.html
+pub fn is_satisfiable(formula: &PropositionalFormula, config: &SolverConfig) -> bool {
+    is_satisfiable_with_stats(formula, config).0
+}
+
+/// Checks if the given propositional formula is _valid_, using the engine selected by `config` for
+/// the number of variables of the formula.
+pub fn is_valid(formula: &PropositionalFormula, config: &SolverConfig) -> bool {
+    is_valid_with_stats(formula, config).0
+}
+
+/// Checks if the given propositional formula is _satisfiable_ like [`is_satisfiable`], also
+/// returning the resource usage of the tableau if the formula was handed to it.
+///
+/// [`is_satisfiable`]: fn.is_satisfiable.html
+pub fn is_satisfiable_with_stats(
+    formula: &PropositionalFormula,
+    config: &SolverConfig,
+) -> (bool, Option<TableauStats>) {
+    let (result, stats) = decide(formula, config, Limits::default());
+    (
+        result.unwrap_or_else(|_| unreachable!("the solver cannot give up without limits")),
+        stats,
+    )
+}
+
+/// Checks if the given propositional formula is _satisfiable_ like [`is_satisfiable_with_stats`],
+/// giving up after `timeout`.
+///
+/// Only the sequential tableau (with or without preprocessing) can be interrupted; formulas handed
+/// to any other engine are decided however long it takes.
+///
+/// # Errors
+///
+/// Returns [`TimedOut`] if the tableau has not decided the formula after `timeout`, with the
+/// values of the formula's variables on the most expanded open branch.
+///
+/// [`is_satisfiable_with_stats`]: fn.is_satisfiable_with_stats.html
+/// [`TimedOut`]: ../tableaux_solver/struct.TimedOut.html
+pub fn is_satisfiable_within(
+    formula: &PropositionalFormula,
+    config: &SolverConfig,
+    timeout: Duration,
+) -> (Result<bool, TimedOut>, Option<TableauStats>) {
+    let limits = Limits {
+        deadline: Some(Instant::now() + timeout),
+        ..Limits::default()
+    };
+    let (result, stats) = decide(formula, config, limits);
+    let result = result.map_err(|LimitReached { mut partial, .. }| {
+        // Drop the fresh variables introduced by preprocessing.
+        let variables = formula.variables();
+        partial.retain(|variable, _| variables.contains(&variable));
+        TimedOut { partial }
+    });
+    (result, stats)
+}
+
+/// Outcome of [`solve`].
+///
+/// [`solve`]: fn.solve.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolverOutcome {
+    /// The formula is satisfiable.
+    Sat,
+    /// The formula is unsatisfiable.
+    Unsat,
+    /// The solver gave up before deciding the formula.
+    Unknown(Reason),
+}
+
+impl SolverOutcome {
+    /// Whether the formula is satisfiable, or `None` if the solver gave up.
+    pub fn is_satisfiable(self) -> Option<bool> {
+        match self {
+            Self::Sat => Some(true),
+            Self::Unsat => Some(false),
+            Self::Unknown(_) => None,
+        }
+    }
+}
+
+impl From<bool> for SolverOutcome {
+    fn from(satisfiable: bool) -> Self {
+        if satisfiable {
+            Self::Sat
+        } else {
+            Self::Unsat
+        }
+    }
+}
+
+/// Why the solver gave up on a formula, see [`SolverOutcome::Unknown`].
+///
+/// [`SolverOutcome::Unknown`]: enum.SolverOutcome.html#variant.Unknown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reason {
+    /// The `timeout` of the [`SolverConfig`] passed.
+    ///
+    /// [`SolverConfig`]: struct.SolverConfig.html
+    Timeout,
+    /// The tableau made `max_expansions` expansions.
+    ExpansionLimit,
+    /// The tableau queued more than `max_queue` theories.
+    QueueLimit,
+}
+
+/// Checks if the given propositional formula is _satisfiable_ like [`is_satisfiable`], giving up
+/// once the `timeout` of the `config` has passed or the tableau has reached its `max_expansions`
+/// or `max_queue`, if there are any.
+///
+/// Only the sequential tableau (with or without preprocessing) can be interrupted, like in
+/// [`is_satisfiable_within`].
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use libprop_sat_solver::solver::{solve, Reason, SolverConfig, SolverOutcome};
+/// let formula = "(((a|b)^(c|d))^(-a))".parse().unwrap();
+///
+/// let config = SolverConfig {
+///     truth_table_max_variables: 0,
+///     ..SolverConfig::default()
+/// };
+/// assert_eq!(SolverOutcome::Sat, solve(&formula, &config));
+///
+/// let config = SolverConfig {
+///     timeout: Some(Duration::from_secs(0)),
+///     ..config
+/// };
+/// assert_eq!(SolverOutcome::Unknown(Reason::Timeout), solve(&formula, &config));
+/// ```
+///
+/// [`is_satisfiable`]: fn.is_satisfiable.html
+/// [`is_satisfiable_within`]: fn.is_satisfiable_within.html
+pub fn solve(formula: &PropositionalFormula, config: &SolverConfig) -> SolverOutcome {
+    let limits = Limits {
+        deadline: config.timeout.map(|timeout| Instant::now() + timeout),
+        max_expansions: config.max_expansions,
+        max_queue: config.max_queue,
+    };
+    match decide(formula, config, limits).0 {
+        Ok(satisfiable) => SolverOutcome::from(satisfiable),
+        Err(LimitReached { limit, .. }) => SolverOutcome::Unknown(match limit {
+            Limit::Deadline => Reason::Timeout,
+            Limit::Expansions => Reason::ExpansionLimit,
+            Limit::Queue => Reason::QueueLimit,
+        }),
+    }
+}
+
+fn decide(
+    formula: &PropositionalFormula,
+    config: &SolverConfig,
+    limits: Limits,
+) -> (Result<bool, LimitReached>, Option<TableauStats>) {
+    if config.eliminate_definitions {
+        let (remaining, definitions) = eliminate_definitions(formula);
+        if !definitions.is_empty() {
+            debug!("eliminated {} definitions", definitions.len());
+            let config = SolverConfig {
+                eliminate_definitions: false,
+                ..*config
+            };
+            return match remaining {
+                Some(remaining) => decide(&remaining, &config, limits),
+                // Definitions alone are satisfied by assigning each variable its definition.
+                None => (Ok(true), None),
+            };
+        }
+    }
+
+    if config.push_negations {
+        let config = SolverConfig {
+            push_negations: false,
+            ..*config
+        };
+        return decide(&push_negations(formula), &config, limits);
+    }
+
+    if let Some(classification) = quick_classify(formula) {
+        debug!("syntactic {:?}", classification);
+        return (Ok(classification == Classification::Tautology), None);
+    }
+
+    if config.engine == Engine::Auto {
+        let engine = match select_backend(formula, config) {
+            Backend::TruthTable => return (Ok(truth_table::is_satisfiable(formula)), None),
+            backend @ Backend::TwoSat | backend @ Backend::Horn => {
+                let cnf = CnfFormula::from_clausal_formula(formula)
+                    .expect("2-SAT and Horn are only selected for formulas in CNF");
+                let result = if backend == Backend::TwoSat {
+                    two_sat::is_satisfiable(&cnf)
+                } else {
+                    horn::is_satisfiable(&cnf)
+                };
+                return (Ok(result), None);
+            }
+            Backend::Tableau => Engine::Tableau,
+            Backend::Dpll => Engine::Dpll,
+        };
+        return decide(formula, &SolverConfig { engine, ..*config }, limits);
+    }
+
+    if uses_truth_table(formula, config) {
+        return (Ok(truth_table::is_satisfiable(formula)), None);
+    }
+
+    if config.engine == Engine::Tableau && config.tableau_threads > 1 {
+        let result = match &config.preprocessing {
+            Some(options) => {
+                let cnf = tableaux_solver::preprocess(formula, options);
+                !cnf.has_empty_clause()
+                    && cnf.to_formula().is_none_or(|formula| {
+                        is_satisfiable_in_parallel(&formula, config.tableau_threads)
+                    })
+            }
+            None => is_satisfiable_in_parallel(formula, config.tableau_threads),
+        };
+        return (Ok(result), None);
+    }
+
+    let mut stats = TableauStats::default();
+    let observer = |expansions: usize, tableau: &Tableau| stats.record(expansions, tableau);
+    let mut solver = tableaux_solver::Solver::new()
+        .with_heuristic(config.branch_heuristic)
+        .with_strategy(config.search_strategy)
+        .with_limits(limits);
+    if let Some(capacity) = config.theory_cache_capacity {
+        solver = solver.with_theory_cache(capacity);
+    }
+
+    let result = match (config.engine, &config.preprocessing) {
+        (Engine::Tableau, Some(options)) => {
+            let cnf = tableaux_solver::preprocess(formula, options);
+            if cnf.has_empty_clause() {
+                Ok(false)
+            } else {
+                // No clauses left means no constraints left.
+                cnf.to_formula()
+                    .map_or(Ok(true), |formula| solver.solve(&formula, observer))
+            }
+        }
+        (Engine::Tableau, None) => solver.solve(formula, observer),
+        (Engine::Dpll, Some(options)) => {
+            return (
+                Ok(dpll::is_satisfiable(&tableaux_solver::preprocess(
+                    formula, options,
+                ))),
+                None,
+            );
+        }
+        (Engine::Dpll, None) => {
+            // Formulas already in CNF need no fresh Tseitin variables.
+            let cnf = CnfFormula::from_clausal_formula(formula)
+                .unwrap_or_else(|| CnfFormula::from_formula(formula));
+            return (Ok(dpll::is_satisfiable(&cnf)), None);
+        }
+        (Engine::Interned, Some(options)) => {
+            let cnf = tableaux_solver::preprocess(formula, options);
+            return (
+                Ok(!cnf.has_empty_clause()
+                    && cnf
+                        .to_formula()
+                        .is_none_or(|formula| tableaux_solver::interned::is_satisfiable(&formula))),
+                None,
+            );
+        }
+        (Engine::Interned, None) => {
+            return (Ok(tableaux_solver::interned::is_satisfiable(formula)), None);
+        }
+        (Engine::Auto, _) => unreachable!("`Engine::Auto` is resolved by `select_backend`"),
+    };
+
+    (result, Some(stats))
+}
+
+/// The parallel tableau, on a rayon thread pool with the `parallel` feature.
+fn is_satisfiable_in_parallel(formula: &PropositionalFormula, threads: usize) -> bool {
+    #[cfg(feature = "parallel")]
+    let satisfiable = tableaux_solver::pool::is_satisfiable(formula, threads);
+    #[cfg(not(feature = "parallel"))]
+    let satisfiable = tableaux_solver::parallel::is_satisfiable(formula, threads);
+    satisfiable
+}
+
+/// Checks if the given propositional formula is _valid_ like [`is_valid`], i.e. if its negation is
+/// unsatisfiable, also returning the resource usage of the tableau if the negation was handed to
+/// it.
+///
+/// [`is_valid`]: fn.is_valid.html
+pub fn is_valid_with_stats(
+    formula: &PropositionalFormula,
+    config: &SolverConfig,
+) -> (bool, Option<TableauStats>) {
+    let negated_formula = PropositionalFormula::negated(Box::new(formula.clone()));
+    let (satisfiable, stats) = is_satisfiable_with_stats(&negated_formula, config);
+    (!satisfiable, stats)
+}
+
+/// Checks if the given propositional formula is _valid_ like [`is_valid_with_stats`], giving up
+/// after `timeout`, see [`is_satisfiable_within`].
+///
+/// # Errors
+///
+/// Returns [`TimedOut`] if the tableau has not decided the negation of the formula after
+/// `timeout`. Its partial assignment then falsifies the formula if it extends to a model of the
+/// negation.
+///
+/// [`is_valid_with_stats`]: fn.is_valid_with_stats.html
+/// [`is_satisfiable_within`]: fn.is_satisfiable_within.html
+/// [`TimedOut`]: ../tableaux_solver/struct.TimedOut.html
+pub fn is_valid_within(
+    formula: &PropositionalFormula,
+    config: &SolverConfig,
+    timeout: Duration,
+) -> (Result<bool, TimedOut>, Option<TableauStats>) {
+    let negated_formula = PropositionalFormula::negated(Box::new(formula.clone()));
+    let (satisfiable, stats) = is_satisfiable_within(&negated_formula, config, timeout);
+    (satisfiable.map(|satisfiable| !satisfiable), stats)
+}
+
+/// Outcome of [`check_validity`].
+///
+/// [`check_validity`]: fn.check_validity.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidityResult {
+    /// True under every assignment.
+    Valid,
+    /// False under the assignment, which assigns every variable of the formula.
+    Invalid(Assignment),
+}
+
+impl ValidityResult {
+    /// Checks if the formula is valid.
+    pub fn is_valid(&self) -> bool {
+        *self == Self::Valid
+    }
+}
+
+/// Checks if the given propositional formula is _valid_ like [`is_valid`], and if it is not, finds
+/// an assignment falsifying it (a model of its negation, see [`counter_model`]) to show why.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::formula::Variable;
+/// use libprop_sat_solver::solver::{check_validity, SolverConfig, ValidityResult};
+/// let config = SolverConfig::default();
+/// assert_eq!(ValidityResult::Valid, check_validity(&"(a->(b->a))".parse().unwrap(), &config));
+///
+/// match check_validity(&"((a->b)->a)".parse().unwrap(), &config) {
+///     ValidityResult::Invalid(model) => assert_eq!(Some(&false), model.get(&Variable::new("a"))),
+///     ValidityResult::Valid => unreachable!(),
+/// }
+/// ```
+///
+/// [`is_valid`]: fn.is_valid.html
+/// [`counter_model`]: ../models/fn.counter_model.html
+pub fn check_validity(formula: &PropositionalFormula, config: &SolverConfig) -> ValidityResult {
+    if is_valid(formula, config) {
+        return ValidityResult::Valid;
+    }
+    // PANIC: cannot panic because the formula is not valid, so its negation has a model.
+    ValidityResult::Invalid(counter_model(formula).expect("an invalid formula has a counter-model"))
+}
+
+/// Checks if the formulas `a` and `b` are _equivalent_, i.e. true under the same assignments, by
+/// checking the validity of `(a<->b)` like [`is_valid`].
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::solver::{equivalent, SolverConfig};
+/// let config = SolverConfig::default();
+/// let a = "(-(p^q))".parse().unwrap();
+/// assert!(equivalent(&a, &"((-p)|(-q))".parse().unwrap(), &config));
+/// assert!(!equivalent(&a, &"((-p)^(-q))".parse().unwrap(), &config));
+/// ```
+///
+/// [`is_valid`]: fn.is_valid.html
+pub fn equivalent(
+    a: &PropositionalFormula,
+    b: &PropositionalFormula,
+    config: &SolverConfig,
+) -> bool {
+    is_valid(&biimplication(a, b), config)
+}
+
+/// Find an assignment under which exactly one of the formulas `a` and `b` is true, like
+/// [`check_validity`] of `(a<->b)`, or `None` if they are [`equivalent`].
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::formula::Variable;
+/// use libprop_sat_solver::solver::{equivalence_counterexample, SolverConfig};
+/// let config = SolverConfig::default();
+/// let a = "(p->q)".parse().unwrap();
+/// let model = equivalence_counterexample(&a, &"(q->p)".parse().unwrap(), &config).unwrap();
+/// assert_ne!(model[&Variable::new("p")], model[&Variable::new("q")]);
+///
+/// assert_eq!(None, equivalence_counterexample(&a, &"((-p)|q)".parse().unwrap(), &config));
+/// ```
+///
+/// [`check_validity`]: fn.check_validity.html
+/// [`equivalent`]: fn.equivalent.html
+pub fn equivalence_counterexample(
+    a: &PropositionalFormula,
+    b: &PropositionalFormula,
+    config: &SolverConfig,
+) -> Option<Assignment> {
+    match check_validity(&biimplication(a, b), config) {
+        ValidityResult::Valid => None,
+        ValidityResult::Invalid(model) => Some(model),
+    }
+}
+
+fn biimplication(a: &PropositionalFormula, b: &PropositionalFormula) -> PropositionalFormula {
+    PropositionalFormula::biimplication(Box::new(a.clone()), Box::new(b.clone()))
+}
+
+/// Syntactic classification of a formula, see [`quick_classify`].
+///
+/// [`quick_classify`]: fn.quick_classify.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Classification {
+    /// True under every assignment.
+    Tautology,
+    /// False under every assignment.
+    Contradiction,
+}
+
+/// Recognize trivially valid or unsatisfiable formulas without running an engine.
+///
+/// The patterns `(A|(-A))`, `(A->A)` and `(A<->A)` are tautologies and `(A^(-A))` is a
+/// contradiction, with the operands of `|` and `^` in either order, and so are the constants `1`
+/// and `0` respectively. These are propagated through the connectives where the result doesn't
+/// depend on the other operand, e.g. `(B|(A->A))` is a tautology and the negation of a tautology
+/// is a contradiction. Returns `None` if the formula matches none of them, which says nothing
+/// about whether it is satisfiable or valid.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::formula::{PropositionalFormula, Variable};
+/// use libprop_sat_solver::solver::{quick_classify, Classification};
+/// let a = PropositionalFormula::variable(Variable::new("a"));
+/// let not_a = PropositionalFormula::negated(Box::new(a.clone()));
+///
+/// let formula = PropositionalFormula::conjunction(Box::new(a.clone()), Box::new(not_a));
+/// assert_eq!(Some(Classification::Contradiction), quick_classify(&formula));
+/// assert_eq!(None, quick_classify(&a));
+/// ```
+pub fn quick_classify(formula: &PropositionalFormula) -> Option<Classification> {
+    use Classification::*;
+    use PropositionalFormula::*;
+
+    fn is_negation_of(negation: &PropositionalFormula, formula: &PropositionalFormula) -> bool {
+        match negation {
+            Negation(inner) => **inner == *formula,
+            _ => false,
+        }
+    }
+
+    fn are_complementary(a: &PropositionalFormula, b: &PropositionalFormula) -> bool {
+        is_negation_of(a, b) || is_negation_of(b, a)
+    }
+
+    match formula {
+        Variable(_) => None,
+        Top => Some(Tautology),
+        Bottom => Some(Contradiction),
+        Negation(a) => match quick_classify(a)? {
+            Tautology => Some(Contradiction),
+            Contradiction => Some(Tautology),
+        },
+        Disjunction(a, b) => {
+            if are_complementary(a, b) {
+                return Some(Tautology);
+            }
+            match (quick_classify(a), quick_classify(b)) {
+                (Some(Tautology), _) | (_, Some(Tautology)) => Some(Tautology),
+                (Some(Contradiction), Some(Contradiction)) => Some(Contradiction),
+                _ => None,
+            }
+        }
+        Conjunction(a, b) => {
+            if are_complementary(a, b) {
+                return Some(Contradiction);
+            }
+            match (quick_classify(a), quick_classify(b)) {
+                (Some(Contradiction), _) | (_, Some(Contradiction)) => Some(Contradiction),
+                (Some(Tautology), Some(Tautology)) => Some(Tautology),
+                _ => None,
+            }
+        }
+        Implication(a, b) => {
+            if a == b {
+                return Some(Tautology);
+            }
+            match (quick_classify(a), quick_classify(b)) {
+                (Some(Contradiction), _) | (_, Some(Tautology)) => Some(Tautology),
+                (Some(Tautology), Some(Contradiction)) => Some(Contradiction),
+                _ => None,
+            }
+        }
+        Biimplication(a, b) => {
+            if a == b {
+                return Some(Tautology);
+            }
+            if are_complementary(a, b) {
+                return Some(Contradiction);
+            }
+            match (quick_classify(a)?, quick_classify(b)?) {
+                (x, y) if x == y => Some(Tautology),
+                _ => Some(Contradiction),
+            }
+        }
+    }
+}
+
+/// Find a minimal unsatisfiable subset of the given formulas, i.e. one whose conjunction is
+/// unsatisfiable but becomes satisfiable if any of its formulas is left out. Returns the indices of
+/// the formulas in the subset in ascending order, or `None` if the conjunction of all the formulas
+/// is satisfiable.
+///
+/// Each formula is tentatively dropped in turn and is kept only if the rest became satisfiable
+/// without it, which takes one satisfiability check per formula.
+pub fn unsatisfiable_core(
+    formulas: &[PropositionalFormula],
+    config: &SolverConfig,
+) -> Option<Vec<usize>> {
+    let conjunction_is_satisfiable = |indices: &[usize]| {
+        match PropositionalFormula::conjoin(indices.iter().map(|&i| formulas[i].clone())) {
+            Some(conjunction) => is_satisfiable(&conjunction, config),
+            // The empty conjunction is true.
+            None => true,
+        }
+    };
+
+    let mut core: Vec<usize> = (0..formulas.len()).collect();
+    if conjunction_is_satisfiable(&core) {
+        return None;
+    }
+
+    let mut i = 0;
+    while i < core.len() {
+        let mut candidate = core.clone();
+        candidate.remove(i);
+
+        if conjunction_is_satisfiable(&candidate) {
+            i += 1;
+        } else {
+            core = candidate;
+        }
+    }
+
+    Some(core)
+}
+
+/// Outcome of [`is_valid_goal_directed`].
+///
+/// [`is_valid_goal_directed`]: fn.is_valid_goal_directed.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GoalDirectedValidity {
+    /// The premises `A1, ..., An` of the formula, in order.
+    pub premises: Vec<PropositionalFormula>,
+    /// The conclusion `C` of the formula.
+    pub conclusion: PropositionalFormula,
+    /// Indices of a minimal set of premises which already entail the conclusion, in ascending
+    /// order, or `None` if the formula is not valid.
+    pub needed_premises: Option<Vec<usize>>,
+}
+
+impl GoalDirectedValidity {
+    /// Checks if the formula is valid, i.e. if its premises entail its conclusion.
+    pub fn is_valid(&self) -> bool {
+        self.needed_premises.is_some()
+    }
+}
+
+/// Checks if the given propositional formula is _valid_ while keeping its implication structure:
+/// the formula is read as premises entailing a conclusion, and if it is valid, the premises which
+/// are actually needed for the conclusion are reported.
+///
+/// Premises are collected from the antecedents of the implications down the right spine of the
+/// formula, splitting conjunctions, so `((A^B)->(C->D))` has the premises `A`, `B`, `C` and the
+/// conclusion `D`. A formula without implications has no premises. The needed premises are found
+/// as an [`unsatisfiable_core`] of the premises along with the negated conclusion; if the core
+/// does not contain the negated conclusion, the premises are contradictory by themselves.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::formula::PropositionalFormula;
+/// use libprop_sat_solver::solver::{is_valid_goal_directed, SolverConfig};
+/// let formula: PropositionalFormula = "(((p->q)^(r^p))->q)".parse().unwrap();
+/// let validity = is_valid_goal_directed(&formula, &SolverConfig::default());
+///
+/// assert_eq!(3, validity.premises.len());
+/// assert_eq!(Some(vec![0, 2]), validity.needed_premises);
+/// ```
+///
+/// [`unsatisfiable_core`]: fn.unsatisfiable_core.html
+pub fn is_valid_goal_directed(
+    formula: &PropositionalFormula,
+    config: &SolverConfig,
+) -> GoalDirectedValidity {
+    let (antecedents, conclusion) = formula.implication_chain();
+    let premises: Vec<PropositionalFormula> = antecedents
+        .into_iter()
+        .flat_map(PropositionalFormula::conjuncts)
+        .cloned()
+        .collect();
+
+    let mut formulas = premises.clone();
+    formulas.push(PropositionalFormula::negated(Box::new(conclusion.clone())));
+
+    let needed_premises = unsatisfiable_core(&formulas, config).map(|core| {
+        core.into_iter()
+            .filter(|&index| index < premises.len())
+            .collect()
+    });
+
+    GoalDirectedValidity {
+        premises,
+        conclusion: conclusion.clone(),
+        needed_premises,
+    }
+}
+
+/// Decision procedure picked by [`select_backend`] for [`Engine::Auto`].
+///
+/// [`select_backend`]: fn.select_backend.html
+/// [`Engine::Auto`]: enum.Engine.html#variant.Auto
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// Enumerate the truth table, see [`truth_table`].
+    ///
+    /// [`truth_table`]: ../truth_table/index.html
+    TruthTable,
+    /// Strongly connected components of the implication graph, see [`two_sat`].
+    ///
+    /// [`two_sat`]: ../cnf/two_sat/index.html
+    TwoSat,
+    /// Forward chaining, see [`horn`].
+    ///
+    /// [`horn`]: ../cnf/horn/index.html
+    Horn,
+    /// The propositional tableau.
+    Tableau,
+    /// DPLL on the CNF of the formula.
+    Dpll,
+}
+
+/// Formulas whose tableau has at most `2^AUTO_TABLEAU_MAX_BRANCHING` branches are left to the
+/// tableau by [`select_backend`].
+///
+/// [`select_backend`]: fn.select_backend.html
+pub const AUTO_TABLEAU_MAX_BRANCHING: usize = 16;
+
+/// Pick the backend [`Engine::Auto`] decides the formula with, logging the decision:
+///
+/// 1. the truth table for formulas with at most `config.truth_table_max_variables` variables;
+/// 2. the polynomial 2-SAT or Horn procedures for formulas in CNF which fall in these classes,
+///    and DPLL for the other formulas in CNF, which need no Tseitin transformation;
+/// 3. the tableau if its number of branches is small, i.e. if at most
+///    [`AUTO_TABLEAU_MAX_BRANCHING`] branching (β) formulas occur in the formula, since it then
+///    avoids the overhead of the CNF transformation;
+/// 4. DPLL otherwise, where unit propagation prunes the search space far better than the tableau.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::solver::{select_backend, Backend, SolverConfig};
+/// let formula = "((a|(-b))^(b|c))".parse().unwrap();
+/// let config = SolverConfig {
+///     truth_table_max_variables: 0,
+///     ..SolverConfig::default()
+/// };
+/// assert_eq!(Backend::TwoSat, select_backend(&formula, &config));
+/// ```
+///
+/// [`Engine::Auto`]: enum.Engine.html#variant.Auto
+/// [`AUTO_TABLEAU_MAX_BRANCHING`]: constant.AUTO_TABLEAU_MAX_BRANCHING.html
+pub fn select_backend(formula: &PropositionalFormula, config: &SolverConfig) -> Backend {
+    let metrics = FormulaMetrics::of(formula);
+    let branching = count_branching(formula, true);
+
+    let (backend, reason) = if metrics.variables <= config.truth_table_max_variables {
+        (Backend::TruthTable, "few variables")
+    } else if metrics.is_two_sat {
+        (Backend::TwoSat, "2-CNF")
+    } else if metrics.is_horn {
+        (Backend::Horn, "Horn CNF")
+    } else if metrics.is_cnf {
+        (Backend::Dpll, "CNF")
+    } else if branching <= AUTO_TABLEAU_MAX_BRANCHING {
+        (Backend::Tableau, "few branching formulas")
+    } else {
+        (Backend::Dpll, "many branching formulas")
+    };
+
+    info!(
+        "{} variables, {} branching formulas: using {:?} ({})",
+        metrics.variables, branching, backend, reason
+    );
+    backend
+}
+
+/// Count the sub-formulas which split a tableau branch in two when expanded, i.e. disjunctions,
+/// implications and negated conjunctions (and the negations thereof), and all biimplications.
+fn count_branching(formula: &PropositionalFormula, positive: bool) -> usize {
+    use PropositionalFormula::*;
+
+    let (operands, branches): (Vec<_>, bool) = match formula {
+        Variable(_) | Top | Bottom => return 0,
+        Negation(a) => return count_branching(a, !positive),
+        Conjunction(a, b) => (vec![(a, positive), (b, positive)], !positive),
+        Disjunction(a, b) => (vec![(a, positive), (b, positive)], positive),
+        Implication(a, b) => (vec![(a, !positive), (b, positive)], positive),
+        Biimplication(a, b) => (vec![(a, positive), (b, positive)], true),
+    };
+
+    branches as usize
+        + operands
+            .into_iter()
+            .map(|(operand, positive)| count_branching(operand, positive))
+            .sum::<usize>()
+}
+
+fn uses_truth_table(formula: &PropositionalFormula, config: &SolverConfig) -> bool {
+    let variables = formula.variables().len();
+    let truth_table = variables <= config.truth_table_max_variables;
+    if truth_table {
+        debug!("{} variables, using the truth table", variables);
+    } else {
+        debug!("{} variables, using {:?}", variables, config.engine);
+    }
+    truth_table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formula::Variable;
+    use crate::testing::formulas;
+    use assert2::check;
+
+    #[test]
+    fn test_unsatisfiable_core() {
+        let a = PropositionalFormula::variable(Variable::new("a"));
+        let b = PropositionalFormula::variable(Variable::new("b"));
+        let formulas = vec![
+            PropositionalFormula::implication(Box::new(a.clone()), Box::new(b.clone())),
+            PropositionalFormula::variable(Variable::new("c")),
+            a.clone(),
+            PropositionalFormula::negated(Box::new(b.clone())),
+            PropositionalFormula::disjunction(Box::new(a), Box::new(b)),
+        ];
+        let config = SolverConfig::default();
+
+        check!(unsatisfiable_core(&formulas, &config) == Some(vec![0, 3, 4]));
+        check!(unsatisfiable_core(&formulas[..3], &config) == None);
+    }
+
+    #[test]
+    fn test_counter_models_falsify() {
+        let config = SolverConfig::default();
+        for formula in formulas(4, 4, 18, 100) {
+            match check_validity(&formula, &config) {
+                ValidityResult::Valid => {
+                    check!(is_valid(&formula, &config));
+                }
+                ValidityResult::Invalid(model) => {
+                    check!(model.len() == formula.variables().len());
+                    check!(formula.evaluate(&model) == Some(false));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_solve_limits() {
+        let formula = "((((a|b)^(c|d))^((e|f)^(g|h)))^(-a))".parse().unwrap();
+        let timed_out = SolverConfig {
+            truth_table_max_variables: 0,
+            timeout: Some(Duration::from_secs(0)),
+            ..SolverConfig::default()
+        };
+        check!(solve(&formula, &timed_out) == SolverOutcome::Unknown(Reason::Timeout));
+
+        // DPLL cannot be interrupted, so it decides the formula regardless.
+        let dpll = SolverConfig {
+            engine: Engine::Dpll,
+            ..timed_out
+        };
+        check!(solve(&formula, &dpll).is_satisfiable() == Some(true));
+
+        let untimed = SolverConfig {
+            timeout: None,
+            ..timed_out
+        };
+        check!(solve(&formula, &untimed) == SolverOutcome::Sat);
+
+        let expansions = SolverConfig {
+            max_expansions: Some(3),
+            ..untimed
+        };
+        check!(solve(&formula, &expansions) == SolverOutcome::Unknown(Reason::ExpansionLimit));
+        let queue = SolverConfig {
+            max_queue: Some(3),
+            ..untimed
+        };
+        check!(solve(&formula, &queue) == SolverOutcome::Unknown(Reason::QueueLimit));
+
+        // Limits generous enough for the formula do not change the outcome.
+        let generous = SolverConfig {
+            max_expansions: Some(100),
+            max_queue: Some(100),
+            ..untimed
+        };
+        check!(solve(&formula, &generous) == SolverOutcome::Sat);
+    }
+
+    #[test]
+    fn test_quick_classify() {
+        for formula in formulas(3, 4, 19, 200) {
+            match quick_classify(&formula) {
+                Some(Classification::Tautology) => {
+                    check!(truth_table::is_valid(&formula));
+                }
+                Some(Classification::Contradiction) => {
+                    check!(!truth_table::is_satisfiable(&formula));
+                }
+                None => {}
+            }
+        }
+
+        let a = PropositionalFormula::variable(Variable::new("a"));
+        let b = PropositionalFormula::variable(Variable::new("b"));
+        let a_implies_a = PropositionalFormula::implication(Box::new(a.clone()), Box::new(a));
+        let formula = PropositionalFormula::negated(Box::new(PropositionalFormula::disjunction(
+            Box::new(b),
+            Box::new(a_implies_a),
+        )));
+        check!(quick_classify(&formula) == Some(Classification::Contradiction));
+    }
+
+    #[test]
+    fn test_goal_directed_validity() {
+        let config = SolverConfig::default();
+
+        for formula in formulas(3, 4, 20, 100) {
+            let validity = is_valid_goal_directed(&formula, &config);
+            check!(validity.is_valid() == is_valid(&formula, &config));
+        }
+
+        // Contradictory premises entail anything.
+        let formula: PropositionalFormula = "((a^(-a))->b)".parse().unwrap();
+        let validity = is_valid_goal_directed(&formula, &config);
+        check!(validity.needed_premises == Some(vec![0, 1]));
+    }
+
+    #[test]
+    fn test_engines_agree() {
+        let truth_table = SolverConfig::default();
+        let tableau = SolverConfig {
+            truth_table_max_variables: 0,
+            ..SolverConfig::default()
+        };
+        let preprocessed = SolverConfig {
+            truth_table_max_variables: 0,
+            preprocessing: Some(PreprocessingOptions::default()),
+            ..SolverConfig::default()
+        };
+        let dpll = SolverConfig {
+            truth_table_max_variables: 0,
+            engine: Engine::Dpll,
+            ..SolverConfig::default()
+        };
+        let activity = SolverConfig {
+            truth_table_max_variables: 0,
+            branch_heuristic: BranchHeuristic::Activity,
+            ..SolverConfig::default()
+        };
+        let depth_first = SolverConfig {
+            search_strategy: SearchStrategy::DepthFirst,
+            ..tableau
+        };
+        let best_first = SolverConfig {
+            search_strategy: SearchStrategy::BestFirst,
+            ..preprocessed
+        };
+        let definitions = SolverConfig {
+            eliminate_definitions: true,
+            ..tableau
+        };
+        let pushed = SolverConfig {
+            push_negations: true,
+            ..tableau
+        };
+        let parallel = SolverConfig {
+            tableau_threads: 3,
+            ..preprocessed
+        };
+        let auto = SolverConfig {
+            engine: Engine::Auto,
+            ..tableau
+        };
+
+        for formula in formulas(3, 4, 21, 50) {
+            let satisfiable = is_satisfiable(&formula, &truth_table);
+            check!(satisfiable == is_satisfiable(&formula, &tableau));
+            check!(satisfiable == is_satisfiable(&formula, &preprocessed));
+            check!(satisfiable == is_satisfiable(&formula, &dpll));
+            check!(satisfiable == is_satisfiable(&formula, &activity));
+            check!(satisfiable == is_satisfiable(&formula, &depth_first));
+            check!(satisfiable == is_satisfiable(&formula, &best_first));
+            check!(satisfiable == is_satisfiable(&formula, &definitions));
+            check!(satisfiable == is_satisfiable(&formula, &pushed));
+            check!(satisfiable == is_satisfiable(&formula, &parallel));
+            check!(satisfiable == is_satisfiable(&formula, &auto));
+
+            let valid = is_valid(&formula, &truth_table);
+            check!(valid == is_valid(&formula, &tableau));
+            check!(valid == is_valid(&formula, &preprocessed));
+            check!(valid == is_valid(&formula, &dpll));
+            check!(valid == is_valid(&formula, &activity));
+            check!(valid == is_valid(&formula, &depth_first));
+            check!(valid == is_valid(&formula, &best_first));
+            check!(valid == is_valid(&formula, &definitions));
+            check!(valid == is_valid(&formula, &pushed));
+            check!(valid == is_valid(&formula, &parallel));
+            check!(valid == is_valid(&formula, &auto));
+        }
+    }
+
+    #[test]
+    fn test_select_backend() {
+        let config = SolverConfig {
+            truth_table_max_variables: 0,
+            engine: Engine::Auto,
+            ..SolverConfig::default()
+        };
+        let backend = |formula: &str| select_backend(&formula.parse().unwrap(), &config);
+
+        check!(backend("((a|(-b))^(b|c))") == Backend::TwoSat);
+        check!(backend("(((-a)|((-b)|c))^a)") == Backend::Horn);
+        check!(backend("((a|(b|c))^(-a))") == Backend::Dpll);
+        check!(backend("((a->b)^(-(b^c)))") == Backend::Tableau);
+        check!(
+            select_backend(&"(a->b)".parse().unwrap(), &SolverConfig::default())
+                == Backend::TruthTable
+        );
+
+        let cnf: PropositionalFormula =
+            "((a|b)^(((-a)|b)^((a|(-b))^((-a)|(-b)))))".parse().unwrap();
+        check!(!is_satisfiable(&cnf, &config));
+        let horn: PropositionalFormula = "((a^(b^((-a)|((-b)|c))))^(-c))".parse().unwrap();
+        check!(select_backend(&horn, &config) == Backend::Horn);
+        check!(!is_satisfiable(&horn, &config));
+    }
+}
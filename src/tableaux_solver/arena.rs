@@ -0,0 +1,109 @@
+//! Storage of the formulas produced by expansions, scoped to a single run of the tableau.
+//!
+//! Without it, every expansion allocates fresh copies of the operands of the expanded formula, and
+//! since sibling branches share the formulas of their common ancestor, the same formula ends up
+//! being expanded (and its operands copied) once per branch. The [`ExpansionArena`] remembers the
+//! expansion of each stored formula instead, so all branches expanding it share one allocation of
+//! each of its operands. Everything it stores is freed at once when the run finishes.
+//!
+//! [`ExpansionArena`]: struct.ExpansionArena.html
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::formula::PropositionalFormula;
+
+use super::{expand_non_literal_formula, ExpansionKind, RuleSet};
+
+/// The expansion of a formula, with its formulas shared between the theories of a tableau.
+#[derive(Debug, Clone)]
+pub(crate) enum SharedExpansion {
+    Alpha(Arc<PropositionalFormula>, Option<Arc<PropositionalFormula>>),
+    Beta(Arc<PropositionalFormula>, Arc<PropositionalFormula>),
+}
+
+/// Expansions of the formulas stored in the theories of one tableau.
+///
+/// Formulas are looked up by address, which is stable because the arena keeps every formula it
+/// has expanded alive; structurally equal formulas stored separately are simply expanded again.
+#[derive(Debug, Default)]
+pub(crate) struct ExpansionArena {
+    expansions: HashMap<*const PropositionalFormula, (Arc<PropositionalFormula>, SharedExpansion)>,
+}
+
+impl ExpansionArena {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Expand the non-literal formula by the `rules` (the standard rules if `None`), reusing its
+    /// earlier expansion if there is one.
+    ///
+    /// Returns `None` if no rule applies to the formula.
+    pub(crate) fn expand(
+        &mut self,
+        formula: &Arc<PropositionalFormula>,
+        rules: Option<&RuleSet>,
+    ) -> Option<SharedExpansion> {
+        if let Some((_, expansion)) = self.expansions.get(&Arc::as_ptr(formula)) {
+            return Some(expansion.clone());
+        }
+
+        let expansion = match rules {
+            Some(rules) => rules.expand(formula),
+            None => expand_non_literal_formula(formula),
+        }?;
+        let expansion = match expansion {
+            ExpansionKind::Alpha(alpha_1, alpha_2) => {
+                SharedExpansion::Alpha(Arc::new(*alpha_1), alpha_2.map(|a| Arc::new(*a)))
+            }
+            ExpansionKind::Beta(beta_1, beta_2) => {
+                SharedExpansion::Beta(Arc::new(*beta_1), Arc::new(*beta_2))
+            }
+        };
+
+        self.expansions.insert(
+            Arc::as_ptr(formula),
+            (Arc::clone(formula), expansion.clone()),
+        );
+        Some(expansion)
+    }
+
+    /// Number of formulas whose expansion is stored.
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.expansions.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert2::check;
+
+    #[test]
+    fn test_expansions_are_shared() {
+        let formula = Arc::new("((a^b)|c)".parse::<PropositionalFormula>().unwrap());
+        let mut arena = ExpansionArena::new();
+
+        let (left, right) = match arena.expand(&formula, None) {
+            Some(SharedExpansion::Beta(left, right)) => (left, right),
+            expansion => panic!("unexpected expansion {:?}", expansion),
+        };
+        check!(left.clone() == Arc::new("(a^b)".parse().unwrap()));
+        check!(right.clone() == Arc::new("c".parse().unwrap()));
+
+        match arena.expand(&formula, None) {
+            Some(SharedExpansion::Beta(left_again, right_again)) => {
+                check!(Arc::ptr_eq(&left, &left_again));
+                check!(Arc::ptr_eq(&right, &right_again));
+            }
+            expansion => panic!("unexpected expansion {:?}", expansion),
+        }
+        check!(arena.len() == 1);
+
+        let variable = Arc::new("a".parse::<PropositionalFormula>().unwrap());
+        check!(arena.expand(&variable, None).is_none());
+        check!(arena.len() == 1);
+    }
+}
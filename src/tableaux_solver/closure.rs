@@ -0,0 +1,81 @@
+//! Conditions under which a branch of the tableau closes.
+
+use super::Theory;
+
+/// A condition under which a `Theory` (a branch of the tableau) is closed, i.e. cannot be
+/// satisfied.
+///
+/// Extensions such as theory reasoning or user-defined axioms contribute their own conditions by
+/// implementing this trait, and combine them with [`ComplementaryLiterals`] as a pair, which closes
+/// a branch if either of its checks does (see [`Solver::with_closure_check`]).
+///
+/// Checks must be monotone: a theory with more formulas than a closed one (in particular, one
+/// expanded from it) must be closed as well.
+///
+/// [`ComplementaryLiterals`]: struct.ComplementaryLiterals.html
+/// [`Solver::with_closure_check`]: ../struct.Solver.html#method.with_closure_check
+pub trait ClosureCheck {
+    /// Checks if the branch represented by the `Theory` is closed.
+    fn is_closed(&self, theory: &Theory) -> bool;
+}
+
+/// The propositional closure condition: the `Theory` contains a literal and its negation, see
+/// [`Theory::has_contradictions`].
+///
+/// [`Theory::has_contradictions`]: ../theory/struct.Theory.html#method.has_contradictions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ComplementaryLiterals;
+
+impl ClosureCheck for ComplementaryLiterals {
+    fn is_closed(&self, theory: &Theory) -> bool {
+        theory.has_contradictions()
+    }
+}
+
+impl<A: ClosureCheck, B: ClosureCheck> ClosureCheck for (A, B) {
+    fn is_closed(&self, theory: &Theory) -> bool {
+        self.0.is_closed(theory) || self.1.is_closed(theory)
+    }
+}
+
+impl<F: Fn(&Theory) -> bool> ClosureCheck for F {
+    fn is_closed(&self, theory: &Theory) -> bool {
+        self(theory)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formula::{PropositionalFormula, Variable};
+    use crate::tableaux_solver::Solver;
+    use crate::testing::formulas;
+    use crate::truth_table;
+    use assert2::check;
+
+    #[test]
+    fn test_axiom_agrees_with_conjoined_axiom() {
+        // The axiom `(x1->x2)`, as a check closing branches with `x1` and `(-x2)`.
+        let x1 = PropositionalFormula::variable(Variable::new("x1"));
+        let x2 = PropositionalFormula::variable(Variable::new("x2"));
+        let not_x2 = PropositionalFormula::negated(Box::new(x2.clone()));
+        let axiom = |theory: &Theory| {
+            let formulas: Vec<_> = theory.formulas().collect();
+            formulas.contains(&&x1) && formulas.contains(&&not_x2)
+        };
+        let implication = PropositionalFormula::implication(Box::new(x1.clone()), Box::new(x2));
+
+        let check = (ComplementaryLiterals, axiom);
+        let solver = Solver::new().with_closure_check(&check);
+
+        for formula in formulas(3, 4, 22, 100) {
+            let with_axiom = PropositionalFormula::conjunction(
+                Box::new(formula.clone()),
+                Box::new(implication.clone()),
+            );
+            check!(
+                solver.solve(&formula, |_, _| {}) == Ok(truth_table::is_satisfiable(&with_axiom))
+            );
+        }
+    }
+}
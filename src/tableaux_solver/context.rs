@@ -0,0 +1,227 @@
+//! Solving many queries against a shared context, e.g. a fixed rule base, whose tableau is
+//! expanded once instead of once per query.
+//!
+//! Deciding `(C^Q)` for a large context `C` and many small queries `Q` expands `C` over and over
+//! again. A [`SharedContext`] expands `C` up front into a partial tableau, and each query is only
+//! added to its open branches, so the tableau of a query starts where the expansion of `C` left
+//! off.
+//!
+//! [`SharedContext`]: struct.SharedContext.html
+
+use std::time::Instant;
+
+use crate::formula::PropositionalFormula;
+
+use super::{
+    enqueue, expand_non_literal_formula, run_tableau, timed_out, BranchHeuristic,
+    ComplementaryLiterals, ExpansionKind, Limits, Solver, Tableau, Theory, TimedOut,
+};
+
+/// Default bound on the number of branches of the partial tableau of a context, see
+/// [`SharedContext::new`].
+///
+/// [`SharedContext::new`]: struct.SharedContext.html#method.new
+pub const DEFAULT_MAX_BRANCHES: usize = 64;
+
+/// A context formula pre-expanded into a partial tableau, against which queries are decided.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::tableaux_solver::context::{SharedContext, DEFAULT_MAX_BRANCHES};
+/// let rules = "((rain->wet)^((wet->slippery)^(sprinkler|rain)))".parse().unwrap();
+/// let context = SharedContext::new(&rules, DEFAULT_MAX_BRANCHES);
+///
+/// assert!(context.is_satisfiable(&"(-sprinkler)".parse().unwrap()));
+/// assert!(context.entails(&"((-sprinkler)->slippery)".parse().unwrap()));
+/// assert!(!context.entails(&"slippery".parse().unwrap()));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SharedContext {
+    /// The open branches of the partial tableau.
+    branches: Vec<Theory>,
+}
+
+impl SharedContext {
+    /// Pre-expand the `context` into a partial tableau of at most `max_branches` open branches
+    /// (but at least one, unless the context is unsatisfiable).
+    ///
+    /// α formulas are expanded wherever they occur, since they do not add branches; β formulas
+    /// are expanded while the bound allows it and are left to the queries afterwards. A larger
+    /// bound moves more work out of the queries, at the cost of adding each query to more
+    /// branches.
+    pub fn new(context: &PropositionalFormula, max_branches: usize) -> Self {
+        let mut pending = Tableau::new();
+        enqueue(
+            &mut pending,
+            Theory::from_propositional_formula(context.clone()),
+            &ComplementaryLiterals,
+        );
+
+        let mut branches = Vec::new();
+        while let Some(mut theory) = pending.pop_theory() {
+            let alpha =
+                theory
+                    .non_literal_formulas()
+                    .find_map(|formula| match expand_non_literal_formula(formula) {
+                        Some(ExpansionKind::Alpha(alpha_1, alpha_2)) => {
+                            Some((formula.clone(), alpha_1, alpha_2))
+                        }
+                        _ => None,
+                    });
+
+            if let Some((formula, alpha_1, alpha_2)) = alpha {
+                match alpha_2 {
+                    Some(alpha_2) => theory.swap_formula2(&formula, (*alpha_1, *alpha_2)),
+                    None => theory.swap_formula(&formula, *alpha_1),
+                }
+                enqueue(&mut pending, theory, &ComplementaryLiterals);
+                continue;
+            }
+
+            // Splitting the current branch adds one more open branch.
+            let open = branches.len() + pending.len() + 1;
+            match theory.get_non_literal_formula() {
+                Some(formula) if open < max_branches => {
+                    match expand_non_literal_formula(&formula) {
+                        Some(ExpansionKind::Beta(beta_1, beta_2)) => {
+                            let mut theory_1 = theory.clone();
+                            theory_1.swap_formula(&formula, *beta_1);
+                            theory.swap_formula(&formula, *beta_2);
+                            enqueue(&mut pending, theory_1, &ComplementaryLiterals);
+                            enqueue(&mut pending, theory, &ComplementaryLiterals);
+                        }
+                        _ => unreachable!("only β formulas are left after the α expansions"),
+                    }
+                }
+                _ => branches.push(theory),
+            }
+        }
+
+        Self { branches }
+    }
+
+    /// Number of open branches of the partial tableau.
+    pub fn branches(&self) -> usize {
+        self.branches.len()
+    }
+
+    /// Checks if all branches of the context closed while pre-expanding it, i.e. the context is
+    /// unsatisfiable by itself.
+    pub fn is_unsatisfiable(&self) -> bool {
+        self.branches.is_empty()
+    }
+
+    /// Checks if the conjunction of the context and the `query` is _satisfiable_.
+    pub fn is_satisfiable(&self, query: &PropositionalFormula) -> bool {
+        self.is_satisfiable_observed(query, BranchHeuristic::default(), |_, _| {})
+    }
+
+    /// Checks if the `query` is true in every model of the context, i.e. the conjunction of the
+    /// context and the negation of the `query` is _unsatisfiable_.
+    pub fn entails(&self, query: &PropositionalFormula) -> bool {
+        !self.is_satisfiable(&PropositionalFormula::negated(Box::new(query.clone())))
+    }
+
+    /// Checks if the conjunction of the context and the `query` is _satisfiable_ like
+    /// [`is_satisfiable`], expanding formulas chosen by the given `heuristic` and calling the
+    /// `observer` after every expansion of the tableau of the query, see [`Solver::solve`].
+    ///
+    /// [`is_satisfiable`]: #method.is_satisfiable
+    /// [`Solver::solve`]: ../struct.Solver.html#method.solve
+    pub fn is_satisfiable_observed<F>(
+        &self,
+        query: &PropositionalFormula,
+        heuristic: BranchHeuristic,
+        observer: F,
+    ) -> bool
+    where
+        F: FnMut(usize, &Tableau),
+    {
+        self.run(query, heuristic, None, observer)
+            .unwrap_or_else(|_| unreachable!("the tableau cannot time out without a deadline"))
+    }
+
+    /// Checks if the conjunction of the context and the `query` is _satisfiable_ like
+    /// [`is_satisfiable_observed`], giving up once the `deadline` has passed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TimedOut`] with the partial assignment of the most promising open branch if the
+    /// query is still undecided at the `deadline`.
+    ///
+    /// [`is_satisfiable_observed`]: #method.is_satisfiable_observed
+    /// [`TimedOut`]: ../struct.TimedOut.html
+    pub fn is_satisfiable_until<F>(
+        &self,
+        query: &PropositionalFormula,
+        heuristic: BranchHeuristic,
+        deadline: Instant,
+        observer: F,
+    ) -> Result<bool, TimedOut>
+    where
+        F: FnMut(usize, &Tableau),
+    {
+        self.run(query, heuristic, Some(deadline), observer)
+    }
+
+    fn run<F>(
+        &self,
+        query: &PropositionalFormula,
+        heuristic: BranchHeuristic,
+        deadline: Option<Instant>,
+        observer: F,
+    ) -> Result<bool, TimedOut>
+    where
+        F: FnMut(usize, &Tableau),
+    {
+        // Branches share the formulas of the context with each other, so copying them is cheap.
+        let mut tableau = Tableau::new();
+        for branch in &self.branches {
+            let mut theory = branch.clone();
+            theory.add(query.clone());
+            enqueue(&mut tableau, theory, &ComplementaryLiterals);
+        }
+
+        let solver = Solver::new()
+            .with_heuristic(heuristic)
+            .with_limits(Limits::until(deadline));
+        timed_out(run_tableau(tableau, &solver, observer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tableaux_solver::is_satisfiable;
+    use crate::testing::formulas;
+    use assert2::check;
+
+    #[test]
+    fn test_agrees_with_conjunction() {
+        let queries: Vec<_> = formulas(4, 3, 23, 20).collect();
+
+        for context in formulas(4, 5, 24, 20) {
+            for &max_branches in &[1, 4, DEFAULT_MAX_BRANCHES] {
+                let shared = SharedContext::new(&context, max_branches);
+                check!(shared.branches() <= max_branches);
+
+                for query in &queries {
+                    let conjunction = PropositionalFormula::conjunction(
+                        Box::new(context.clone()),
+                        Box::new(query.clone()),
+                    );
+                    check!(shared.is_satisfiable(query) == is_satisfiable(&conjunction));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_unsatisfiable_context() {
+        let context = SharedContext::new(&"((a|b)^((-a)^(-b)))".parse().unwrap(), 8);
+
+        check!(context.is_unsatisfiable());
+        check!(!context.is_satisfiable(&"c".parse().unwrap()));
+    }
+}
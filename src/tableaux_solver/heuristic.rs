@@ -0,0 +1,128 @@
+//! Heuristics choosing which non-literal formula of a `Theory` to expand next.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::formula::PropositionalFormula;
+
+use super::Theory;
+
+/// Heuristic choosing which non-literal formula of a theory the tableau expands next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BranchHeuristic {
+    /// Whichever non-literal formula the theory yields first.
+    #[default]
+    Arbitrary,
+    /// The non-literal formula with the highest activity, which is bumped whenever expanding the
+    /// formula closes a branch and decays as more branches close, so formulas which recently
+    /// closed branches are expanded first. Ties are broken like `Arbitrary`.
+    Activity,
+}
+
+impl FromStr for BranchHeuristic {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_ref() {
+            "arbitrary" => Ok(Self::Arbitrary),
+            "activity" => Ok(Self::Activity),
+            _ => Err(format!("unknown branch heuristic: {:?}", s)),
+        }
+    }
+}
+
+impl fmt::Display for BranchHeuristic {
+    /// The name of the heuristic, as accepted by [`FromStr`](#impl-FromStr).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Arbitrary => "arbitrary",
+            Self::Activity => "activity",
+        })
+    }
+}
+
+/// Factor by which the activity of every formula decays after each branch closure.
+const DECAY: f64 = 0.95;
+
+/// Activities are rescaled once the bump increment exceeds this, to stay within `f64` range.
+const RESCALE_LIMIT: f64 = 1e100;
+
+/// Activity scores of formulas for [`BranchHeuristic::Activity`].
+///
+/// Rather than decaying every score after each closure, the increment added by later bumps grows
+/// by `1 / DECAY`, which orders the formulas the same way.
+///
+/// [`BranchHeuristic::Activity`]: enum.BranchHeuristic.html#variant.Activity
+#[derive(Debug, Clone)]
+pub(crate) struct Activity {
+    scores: HashMap<PropositionalFormula, f64>,
+    increment: f64,
+}
+
+impl Activity {
+    pub(crate) fn new() -> Self {
+        Self {
+            scores: HashMap::new(),
+            increment: 1.0,
+        }
+    }
+
+    /// Pick the most active non-literal formula of the theory, if it has any.
+    pub(crate) fn pick(&self, theory: &Theory) -> Option<Arc<PropositionalFormula>> {
+        let mut best: Option<(&Arc<PropositionalFormula>, f64)> = None;
+
+        for formula in theory.shared_non_literal_formulas() {
+            let score = self.scores.get(&**formula).copied().unwrap_or(0.0);
+            match best {
+                Some((_, best_score)) if best_score >= score => {}
+                _ => best = Some((formula, score)),
+            }
+        }
+
+        best.map(|(formula, _)| formula.clone())
+    }
+
+    /// Record that expanding the formula closed a branch.
+    pub(crate) fn bump(&mut self, formula: &PropositionalFormula) {
+        *self.scores.entry(formula.clone()).or_insert(0.0) += self.increment;
+        self.increment /= DECAY;
+
+        if self.increment > RESCALE_LIMIT {
+            for score in self.scores.values_mut() {
+                *score /= RESCALE_LIMIT;
+            }
+            self.increment /= RESCALE_LIMIT;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formula::Variable;
+    use assert2::check;
+
+    #[test]
+    fn test_pick_most_active() {
+        let a = PropositionalFormula::variable(Variable::new("a"));
+        let b = PropositionalFormula::variable(Variable::new("b"));
+        let conjunction =
+            PropositionalFormula::conjunction(Box::new(a.clone()), Box::new(b.clone()));
+        let disjunction = PropositionalFormula::disjunction(Box::new(a.clone()), Box::new(b));
+
+        let mut theory = Theory::from_propositional_formula(conjunction.clone());
+        theory.add(disjunction.clone());
+        theory.add(a);
+
+        let mut activity = Activity::new();
+        activity.bump(&conjunction);
+        activity.bump(&disjunction);
+        check!(activity.pick(&theory) == Some(Arc::new(disjunction.clone())));
+
+        // Older bumps count for less than newer ones.
+        activity.bump(&conjunction);
+        check!(activity.pick(&theory) == Some(Arc::new(conjunction)));
+    }
+}
@@ -0,0 +1,370 @@
+//! Propositional tableau over hash-consed formulas, where branches are sets of [`FormulaId`]s
+//! into a single [`FormulaArena`] instead of sets of formula trees.
+//!
+//! Expanding a formula only looks up (or adds) its operands in the arena, so no sub-formula is
+//! ever copied, and comparing branches for closure and duplicates compares ids. This pays off for
+//! large formulas, where cloning whole trees on every expansion dominates [`is_satisfiable`].
+//!
+//! [`FormulaId`]: ../../formula/arena/struct.FormulaId.html
+//! [`FormulaArena`]: ../../formula/arena/struct.FormulaArena.html
+//! [`is_satisfiable`]: ../fn.is_satisfiable.html
+
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+use log::debug;
+
+use crate::formula::arena::{FormulaArena, FormulaId, Node};
+use crate::formula::PropositionalFormula;
+
+/// A branch of the tableau: the ids of its formulas.
+type Branch = BTreeSet<FormulaId>;
+
+/// Expansion of a non-literal formula, see [`ExpansionKind`].
+///
+/// [`ExpansionKind`]: ../enum.ExpansionKind.html
+enum Expansion {
+    Alpha(FormulaId, Option<FormulaId>),
+    Beta(FormulaId, FormulaId),
+}
+
+/// Checks if the given propositional formula is _satisfiable_ with the tableau, storing its
+/// formulas in a [`FormulaArena`].
+///
+/// Branches are expanded by the same rules (including the dedicated rule for biimplication chains)
+/// as in [`is_satisfiable`], and simplified under their literals before they are queued in the
+/// same way (see [`Theory::propagate_literals`]).
+///
+/// [`FormulaArena`]: ../../formula/arena/struct.FormulaArena.html
+/// [`is_satisfiable`]: ../fn.is_satisfiable.html
+/// [`Theory::propagate_literals`]: ../theory/struct.Theory.html#method.propagate_literals
+pub fn is_satisfiable(formula: &PropositionalFormula) -> bool {
+    let mut arena = FormulaArena::new();
+    let root: Branch = std::iter::once(arena.intern(formula)).collect();
+
+    // Expanded branches are checked before they are queued, but the root has to be checked here,
+    // e.g. for the formula `0`.
+    if is_closed(&arena, &root) {
+        return false;
+    }
+
+    let mut tableau = VecDeque::new();
+    tableau.push_back(root);
+
+    while let Some(branch) = tableau.pop_front() {
+        let non_literal = match branch.iter().find(|&&id| !is_literal(&arena, id)) {
+            Some(&id) => id,
+            // Closed branches are never queued, so a fully expanded branch stays open.
+            None => return true,
+        };
+
+        let expanded = |replacements: &[FormulaId]| {
+            let mut expanded = branch.clone();
+            expanded.remove(&non_literal);
+            expanded.extend(replacements);
+            expanded
+        };
+
+        match expand(&mut arena, non_literal) {
+            Expansion::Alpha(a, None) => enqueue(&mut arena, &mut tableau, expanded(&[a])),
+            Expansion::Alpha(a, Some(b)) => enqueue(&mut arena, &mut tableau, expanded(&[a, b])),
+            Expansion::Beta(a, b) => {
+                enqueue(&mut arena, &mut tableau, expanded(&[a]));
+                enqueue(&mut arena, &mut tableau, expanded(&[b]));
+            }
+        }
+    }
+
+    debug!("interned {} distinct formulas", arena.len());
+    false
+}
+
+/// Enqueue a newly expanded branch unless it closes or the tableau already contains it.
+fn enqueue(arena: &mut FormulaArena, tableau: &mut VecDeque<Branch>, branch: Branch) {
+    if let Some(branch) = propagate_literals(arena, branch) {
+        if !tableau.contains(&branch) {
+            tableau.push_back(branch);
+        }
+    }
+}
+
+/// Simplify the non-literal formulas of the branch under the values its literals assign to their
+/// variables until nothing changes, or return `None` if the branch closes.
+fn propagate_literals(arena: &mut FormulaArena, mut branch: Branch) -> Option<Branch> {
+    loop {
+        let values = literal_values(arena, &branch)?;
+        if values.is_empty() {
+            return Some(branch);
+        }
+
+        let non_literals: Vec<FormulaId> = branch
+            .iter()
+            .copied()
+            .filter(|&id| !is_literal(arena, id))
+            .collect();
+
+        let mut memo = HashMap::new();
+        let mut changed = false;
+        for id in non_literals {
+            match substitute(arena, id, &values, &mut memo) {
+                Residual::Constant(true) => {
+                    branch.remove(&id);
+                    changed = true;
+                }
+                Residual::Constant(false) => return None,
+                Residual::Formula(residual) => {
+                    if residual != id {
+                        branch.remove(&id);
+                        branch.insert(residual);
+                        changed = true;
+                    }
+                }
+            }
+        }
+
+        if !changed {
+            return Some(branch);
+        }
+    }
+}
+
+/// What remains of a formula after substituting the values of some of its variables, see
+/// [`Residual`].
+///
+/// [`Residual`]: ../../formula/substitution/enum.Residual.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Residual {
+    Constant(bool),
+    Formula(FormulaId),
+}
+
+/// Substitute the values of the variables (by the ids of their nodes) into the formula, by the
+/// rules of [`substitute`], remembering the residuals of sub-formulas in `memo`.
+///
+/// [`substitute`]: ../../formula/substitution/fn.substitute.html
+fn substitute(
+    arena: &mut FormulaArena,
+    id: FormulaId,
+    values: &HashMap<FormulaId, bool>,
+    memo: &mut HashMap<FormulaId, Residual>,
+) -> Residual {
+    use Residual::{Constant, Formula};
+
+    if let Some(&residual) = memo.get(&id) {
+        return residual;
+    }
+
+    let mut operands = |arena: &mut FormulaArena, a, b| {
+        (
+            substitute(arena, a, values, memo),
+            substitute(arena, b, values, memo),
+        )
+    };
+
+    let residual = match arena.node(id).clone() {
+        Node::Variable(_) => match values.get(&id) {
+            Some(&value) => Constant(value),
+            None => Formula(id),
+        },
+        Node::Top => Constant(true),
+        Node::Bottom => Constant(false),
+        Node::Negation(a) => {
+            let a = substitute(arena, a, values, memo);
+            negate_residual(arena, a)
+        }
+        Node::Conjunction(a, b) => match operands(arena, a, b) {
+            (Constant(false), _) | (_, Constant(false)) => Constant(false),
+            (Constant(true), x) | (x, Constant(true)) => x,
+            (Formula(a), Formula(b)) => Formula(arena.insert(Node::Conjunction(a, b))),
+        },
+        Node::Disjunction(a, b) => match operands(arena, a, b) {
+            (Constant(true), _) | (_, Constant(true)) => Constant(true),
+            (Constant(false), x) | (x, Constant(false)) => x,
+            (Formula(a), Formula(b)) => Formula(arena.insert(Node::Disjunction(a, b))),
+        },
+        Node::Implication(a, b) => match operands(arena, a, b) {
+            (Constant(false), _) | (_, Constant(true)) => Constant(true),
+            (Constant(true), x) => x,
+            (x, Constant(false)) => negate_residual(arena, x),
+            (Formula(a), Formula(b)) => Formula(arena.insert(Node::Implication(a, b))),
+        },
+        Node::Biimplication(a, b) => match operands(arena, a, b) {
+            (Constant(true), x) | (x, Constant(true)) => x,
+            (Constant(false), x) | (x, Constant(false)) => negate_residual(arena, x),
+            (Formula(a), Formula(b)) => Formula(arena.insert(Node::Biimplication(a, b))),
+        },
+    };
+
+    memo.insert(id, residual);
+    residual
+}
+
+fn negate_residual(arena: &mut FormulaArena, residual: Residual) -> Residual {
+    match residual {
+        Residual::Constant(value) => Residual::Constant(!value),
+        Residual::Formula(id) => Residual::Formula(negate(arena, id)),
+    }
+}
+
+/// Strip (possibly nested) negations off the formula, see [`Literal::from_formula`].
+///
+/// [`Literal::from_formula`]: ../../formula/literal/struct.Literal.html#method.from_formula
+fn strip_negations(arena: &FormulaArena, mut id: FormulaId) -> (FormulaId, bool) {
+    let mut value = true;
+    while let Node::Negation(inner) = *arena.node(id) {
+        id = inner;
+        value = !value;
+    }
+
+    (id, value)
+}
+
+fn is_literal(arena: &FormulaArena, id: FormulaId) -> bool {
+    matches!(
+        arena.node(strip_negations(arena, id).0),
+        Node::Variable(_) | Node::Top | Node::Bottom
+    )
+}
+
+/// Checks if the branch contains complementary literals or a false constant.
+fn is_closed(arena: &FormulaArena, branch: &Branch) -> bool {
+    literal_values(arena, branch).is_none()
+}
+
+/// Collect the values the literals of the branch assign to their variables (by the ids of their
+/// nodes), or `None` if two of its literals are complementary or one of them is false.
+fn literal_values(arena: &FormulaArena, branch: &Branch) -> Option<HashMap<FormulaId, bool>> {
+    let mut values = HashMap::new();
+
+    for &id in branch {
+        let (base, value) = strip_negations(arena, id);
+        let consistent = match arena.node(base) {
+            Node::Variable(_) => *values.entry(base).or_insert(value) == value,
+            Node::Top => value,
+            Node::Bottom => !value,
+            _ => true,
+        };
+        if !consistent {
+            return None;
+        }
+    }
+
+    Some(values)
+}
+
+/// Negate a formula, stripping an existing outermost negation instead of stacking another one.
+fn negate(arena: &mut FormulaArena, id: FormulaId) -> FormulaId {
+    match *arena.node(id) {
+        Node::Negation(inner) => inner,
+        _ => arena.negation(id),
+    }
+}
+
+/// Expand a non-literal formula by the standard rules, see [`STANDARD_RULES`].
+///
+/// [`STANDARD_RULES`]: ../rules/constant.STANDARD_RULES.html
+fn expand(arena: &mut FormulaArena, id: FormulaId) -> Expansion {
+    if let Some(expansion) = expand_biimplication_chain(arena, id) {
+        return expansion;
+    }
+
+    match arena.node(id).clone() {
+        Node::Conjunction(a, b) => Expansion::Alpha(a, Some(b)),
+        Node::Biimplication(a, b) => Expansion::Alpha(
+            arena.insert(Node::Implication(a, b)),
+            Some(arena.insert(Node::Implication(b, a))),
+        ),
+        Node::Disjunction(a, b) => Expansion::Beta(a, b),
+        Node::Implication(a, b) => Expansion::Beta(arena.negation(a), b),
+        Node::Negation(inner) => match arena.node(inner).clone() {
+            Node::Negation(a) => Expansion::Alpha(a, None),
+            Node::Disjunction(a, b) => Expansion::Alpha(arena.negation(a), Some(arena.negation(b))),
+            Node::Conjunction(a, b) => Expansion::Beta(arena.negation(a), arena.negation(b)),
+            Node::Implication(a, b) => Expansion::Alpha(a, Some(arena.negation(b))),
+            Node::Biimplication(a, b) => {
+                let not_a = arena.negation(a);
+                let not_b = arena.negation(b);
+                Expansion::Beta(
+                    arena.insert(Node::Conjunction(a, not_b)),
+                    arena.insert(Node::Conjunction(b, not_a)),
+                )
+            }
+            Node::Variable(_) | Node::Top | Node::Bottom => unreachable!("expanded a literal"),
+        },
+        Node::Variable(_) | Node::Top | Node::Bottom => unreachable!("expanded a literal"),
+    }
+}
+
+/// The dedicated rule for chains of biimplications, see [`expand_biimplication_chain`].
+///
+/// [`expand_biimplication_chain`]: ../fn.expand_biimplication_chain.html
+fn expand_biimplication_chain(arena: &mut FormulaArena, id: FormulaId) -> Option<Expansion> {
+    fn collect(arena: &FormulaArena, id: FormulaId, operands: &mut Vec<FormulaId>) {
+        match *arena.node(id) {
+            Node::Biimplication(a, b) => {
+                collect(arena, a, operands);
+                collect(arena, b, operands);
+            }
+            _ => operands.push(id),
+        }
+    }
+
+    let (chain, negated) = match *arena.node(id) {
+        Node::Negation(inner) => (inner, true),
+        _ => (id, false),
+    };
+    if !matches!(arena.node(chain), Node::Biimplication(..)) {
+        return None;
+    }
+
+    let mut operands = Vec::new();
+    collect(arena, chain, &mut operands);
+    if operands.len() < 3 {
+        return None;
+    }
+
+    if negated {
+        // PANIC: cannot panic because a chain has at least three operands.
+        let last = operands.pop().unwrap();
+        operands.push(negate(arena, last));
+    }
+
+    let first = operands.remove(0);
+    let rest = biimplication_chain(arena, &operands);
+
+    // PANIC: cannot panic because a chain has at least three operands, and we only removed one.
+    let last = operands.pop().unwrap();
+    operands.push(negate(arena, last));
+    let negated_rest = biimplication_chain(arena, &operands);
+
+    let not_first = negate(arena, first);
+    Some(Expansion::Beta(
+        arena.insert(Node::Conjunction(first, rest)),
+        arena.insert(Node::Conjunction(not_first, negated_rest)),
+    ))
+}
+
+/// Build a right-nested biimplication chain from its (non-empty) operands.
+fn biimplication_chain(arena: &mut FormulaArena, operands: &[FormulaId]) -> FormulaId {
+    let (&last, init) = operands.split_last().unwrap();
+
+    init.iter().rev().fold(last, |chain, &operand| {
+        arena.insert(Node::Biimplication(operand, chain))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{assert_agrees_with_truth_table, formulas};
+    use assert2::check;
+
+    #[test]
+    fn test_agrees_with_tableau() {
+        assert_agrees_with_truth_table(formulas(5, 5, 25, 200), is_satisfiable);
+
+        for formula in &["0", "(-1)", "(a^(-(-(-a))))", "(-(a<->(b<->(c<->(-a)))))"] {
+            let formula = formula.parse().unwrap();
+            check!(is_satisfiable(&formula) == super::super::is_satisfiable(&formula));
+        }
+    }
+}
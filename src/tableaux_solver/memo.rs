@@ -0,0 +1,267 @@
+//! Memoization of closed theories, see [`Solver::with_theory_cache`].
+//!
+//! Formulas are canonicalized before they are expanded, and a `Theory` keeps its formulas in their
+//! `Ord` order, so a branch reached by expanding the same formulas in a different order is the
+//! very same `Theory`. Once every branch below a theory has closed, the theory is remembered in a
+//! [`TheoryCache`], and a branch reaching it again closes straight away instead of being explored
+//! again.
+//!
+//! Only closed theories are remembered: the tableau stops at the first open, fully expanded
+//! branch, so a theory known to be satisfiable is never reached again in the same run.
+//!
+//! [`Solver::with_theory_cache`]: ../struct.Solver.html#method.with_theory_cache
+//! [`TheoryCache`]: struct.TheoryCache.html
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use super::Theory;
+
+/// Theories whose branches all close, holding at most `capacity` theories however many formulas
+/// each of them has.
+///
+/// Once the cache is full, a new theory replaces an old one picked by the clock algorithm: the
+/// slots are swept in a circle, and a theory which was looked up since the last sweep is spared
+/// once. Theories are stored in full rather than by their hash alone, so that a hash collision
+/// cannot close an open branch.
+#[derive(Debug)]
+pub(super) struct TheoryCache {
+    slots: Vec<Slot>,
+    index: HashMap<Rc<Theory>, usize>,
+    hand: usize,
+    capacity: usize,
+}
+
+#[derive(Debug)]
+struct Slot {
+    theory: Rc<Theory>,
+    referenced: bool,
+}
+
+impl TheoryCache {
+    /// Construct an empty cache holding at most `capacity` theories.
+    pub(super) fn new(capacity: usize) -> Self {
+        Self {
+            slots: Vec::new(),
+            index: HashMap::new(),
+            hand: 0,
+            capacity,
+        }
+    }
+
+    /// Checks if the `theory` is cached, sparing it from the next eviction if it is.
+    pub(super) fn contains(&mut self, theory: &Theory) -> bool {
+        match self.index.get(theory) {
+            Some(&slot) => {
+                self.slots[slot].referenced = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Cache the `theory`, evicting another one if the cache is full.
+    pub(super) fn insert(&mut self, theory: Rc<Theory>) {
+        if self.capacity == 0 || self.index.contains_key(&*theory) {
+            return;
+        }
+
+        if self.slots.len() < self.capacity {
+            self.index.insert(Rc::clone(&theory), self.slots.len());
+            self.slots.push(Slot {
+                theory,
+                referenced: false,
+            });
+            return;
+        }
+
+        while self.slots[self.hand].referenced {
+            self.slots[self.hand].referenced = false;
+            self.hand = (self.hand + 1) % self.capacity;
+        }
+        let slot = Slot {
+            theory: Rc::clone(&theory),
+            referenced: false,
+        };
+        let evicted = std::mem::replace(&mut self.slots[self.hand], slot);
+        self.index.remove(&*evicted.theory);
+        self.index.insert(theory, self.hand);
+        self.hand = (self.hand + 1) % self.capacity;
+    }
+
+    /// Number of cached theories.
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.slots.len()
+    }
+}
+
+/// The theories of a run of the tableau which are queued or expanded but not closed yet, linked to
+/// the theories they were expanded from, so that a theory is cached as soon as all of its branches
+/// close.
+#[derive(Debug)]
+pub(super) struct Memo {
+    cache: TheoryCache,
+    ids: HashMap<Rc<Theory>, usize>,
+    nodes: HashMap<usize, Node>,
+    next_id: usize,
+}
+
+#[derive(Debug)]
+struct Node {
+    theory: Rc<Theory>,
+    /// Theories this one was expanded from. The same theory may be reached from several of them.
+    parents: Vec<usize>,
+    /// Number of theories expanded from this one which are not closed yet.
+    open_children: usize,
+    expanded: bool,
+}
+
+impl Memo {
+    /// Construct the bookkeeping of a run caching at most `capacity` closed theories.
+    pub(super) fn new(capacity: usize) -> Self {
+        Self {
+            cache: TheoryCache::new(capacity),
+            ids: HashMap::new(),
+            nodes: HashMap::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Checks if the `theory` is known to close.
+    pub(super) fn is_closed(&mut self, theory: &Theory) -> bool {
+        self.cache.contains(theory)
+    }
+
+    /// Track a theory the tableau starts with.
+    pub(super) fn add_root(&mut self, theory: &Theory) {
+        if !self.ids.contains_key(theory) {
+            self.add_node(theory, Vec::new());
+        }
+    }
+
+    /// Track an open `child` expanded from the `parent`, which must be tracked already. Returns
+    /// whether the child is new and has to be queued; otherwise, it is already queued or being
+    /// expanded, and the parent waits for it to close too.
+    pub(super) fn add_child(&mut self, parent: &Theory, child: &Theory) -> bool {
+        // PANIC: cannot panic because the parent is tracked until it closes, and an open child
+        // keeps it open.
+        let parent = self.ids[parent];
+        self.nodes.get_mut(&parent).unwrap().open_children += 1;
+
+        match self.ids.get(child) {
+            Some(child) => {
+                self.nodes.get_mut(child).unwrap().parents.push(parent);
+                false
+            }
+            None => {
+                self.add_node(child, vec![parent]);
+                true
+            }
+        }
+    }
+
+    /// Mark the `theory` as expanded, caching it and the theories it closes if all of its
+    /// children closed already.
+    pub(super) fn expanded(&mut self, theory: &Theory) {
+        // PANIC: cannot panic because every queued theory is tracked.
+        let id = self.ids[theory];
+        let node = self.nodes.get_mut(&id).unwrap();
+        node.expanded = true;
+        if node.open_children == 0 {
+            self.close(id);
+        }
+    }
+
+    fn add_node(&mut self, theory: &Theory, parents: Vec<usize>) {
+        let theory = Rc::new(theory.clone());
+        let id = self.next_id;
+        self.next_id += 1;
+        self.ids.insert(Rc::clone(&theory), id);
+        self.nodes.insert(
+            id,
+            Node {
+                theory,
+                parents,
+                open_children: 0,
+                expanded: false,
+            },
+        );
+    }
+
+    /// Cache the closed theory `id`, then each ancestor whose children have all closed in turn.
+    fn close(&mut self, id: usize) {
+        let mut closed = vec![id];
+        while let Some(id) = closed.pop() {
+            // PANIC: cannot panic because a theory closes once, when its last child closes.
+            let node = self.nodes.remove(&id).unwrap();
+            self.ids.remove(&*node.theory);
+            self.cache.insert(node.theory);
+
+            for parent in node.parents {
+                let parent_node = self.nodes.get_mut(&parent).unwrap();
+                parent_node.open_children -= 1;
+                if parent_node.expanded && parent_node.open_children == 0 {
+                    closed.push(parent);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tableaux_solver::{SearchStrategy, Solver};
+    use crate::testing::{assert_agrees_with_truth_table, formulas};
+    use assert2::check;
+
+    fn theory(formula: &str) -> Rc<Theory> {
+        Rc::new(Theory::from_propositional_formula(formula.parse().unwrap()))
+    }
+
+    #[test]
+    fn test_cache_evicts_unreferenced_theories() {
+        let mut cache = TheoryCache::new(2);
+        cache.insert(theory("a"));
+        cache.insert(theory("b"));
+        check!(cache.contains(&theory("a")));
+
+        // `b` is evicted rather than `a`, which was looked up since.
+        cache.insert(theory("c"));
+        check!(cache.len() == 2);
+        check!(cache.contains(&theory("a")));
+        check!(!cache.contains(&theory("b")));
+        check!(cache.contains(&theory("c")));
+
+        let mut cache = TheoryCache::new(0);
+        cache.insert(theory("a"));
+        check!(!cache.contains(&theory("a")));
+    }
+
+    #[test]
+    fn test_closes_cached_theories() {
+        // Both branches of the disjunction reach `{a, b, ...}`, which closes.
+        let formula = "(((a^(b|c))|(b^(a|c)))^((d<->e)^(d<->(-e))))"
+            .parse()
+            .unwrap();
+        for &strategy in &[SearchStrategy::DepthFirst, SearchStrategy::BreadthFirst] {
+            let solver = Solver::new().with_strategy(strategy).with_theory_cache(1);
+            check!(solver.solve(&formula, |_, _| {}) == Ok(false));
+        }
+    }
+
+    #[test]
+    fn test_agrees_with_truth_table() {
+        for &capacity in &[0, 8, 1 << 16] {
+            for &strategy in &[SearchStrategy::DepthFirst, SearchStrategy::BreadthFirst] {
+                let solver = Solver::new()
+                    .with_strategy(strategy)
+                    .with_theory_cache(capacity);
+                assert_agrees_with_truth_table(formulas(4, 5, 26, 100), |formula| {
+                    solver.solve(formula, |_, _| {}).unwrap()
+                });
+            }
+        }
+    }
+}
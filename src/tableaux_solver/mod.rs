@@ -1,14 +1,320 @@
 //! Propositional formula satisfiability solver using the Propositional Tableaux method.
 
-use crate::formula::PropositionalFormula;
+use std::time::Instant;
 
+use crate::cnf::{eliminate_blocked_clauses, eliminate_variables, CnfFormula};
+use crate::formula::{Assignment, PropositionalFormula};
+use arena::{ExpansionArena, SharedExpansion};
+use heuristic::Activity;
+use memo::Memo;
+
+mod arena;
+mod memo;
+
+pub mod closure;
+pub mod context;
+pub mod heuristic;
+pub mod interned;
+pub mod parallel;
+#[cfg(feature = "parallel")]
+pub mod pool;
+pub mod proof;
+pub mod rules;
+pub mod snapshot;
 pub mod tableau;
 pub mod theory;
-pub use tableau::Tableau;
+pub mod trace;
+pub use closure::{ClosureCheck, ComplementaryLiterals};
+pub use heuristic::BranchHeuristic;
+pub use rules::RuleSet;
+pub use tableau::{SearchStrategy, Tableau};
 pub use theory::Theory;
 
 use log::debug;
 
+/// Preprocessing passes to run over the CNF of a formula before it is handed to the tableau, see
+/// [`is_satisfiable_with_preprocessing`].
+///
+/// [`is_satisfiable_with_preprocessing`]: fn.is_satisfiable_with_preprocessing.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PreprocessingOptions {
+    /// Occurrence bound for bounded variable elimination, or `None` to skip the pass.
+    pub max_elimination_occurrences: Option<usize>,
+    /// Whether to run blocked clause elimination.
+    pub blocked_clause_elimination: bool,
+}
+
+impl Default for PreprocessingOptions {
+    /// Bounded variable elimination with an occurrence bound of `16`, without blocked clause
+    /// elimination.
+    fn default() -> Self {
+        Self {
+            max_elimination_occurrences: Some(16),
+            blocked_clause_elimination: false,
+        }
+    }
+}
+
+/// Resource usage of a run of the tableau.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TableauStats {
+    /// Number of α and β expansions.
+    pub expansions: usize,
+    /// Largest number of theories queued in the `Tableau` after an expansion.
+    pub peak_queue: usize,
+}
+
+impl TableauStats {
+    /// Update the statistics with the state of the tableau after an expansion; this is meant to be
+    /// called from the observer of [`Solver::solve`].
+    ///
+    /// [`Solver::solve`]: struct.Solver.html#method.solve
+    pub fn record(&mut self, expansions: usize, tableau: &Tableau) {
+        self.expansions = expansions;
+        self.peak_queue = self.peak_queue.max(tableau.len());
+    }
+}
+
+/// The tableau ran past its deadline before deciding the formula, see
+/// [`SharedContext::is_satisfiable_until`].
+///
+/// [`SharedContext::is_satisfiable_until`]:
+/// context/struct.SharedContext.html#method.is_satisfiable_until
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimedOut {
+    /// The literals of the most expanded open branch, i.e. the one with the fewest non-literal
+    /// formulas left (ties broken by the most literals). Every model of the formula found by
+    /// expanding that branch further extends this partial assignment.
+    pub partial: Assignment,
+}
+
+/// Resources a run of the tableau may use before giving up, see [`Solver::with_limits`].
+///
+/// [`Solver::with_limits`]: struct.Solver.html#method.with_limits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Limits {
+    /// Time at which to give up, or `None` to never run out of time.
+    pub deadline: Option<Instant>,
+    /// Number of α and β expansions after which to give up, or `None` for no limit.
+    pub max_expansions: Option<usize>,
+    /// Number of theories queued in the `Tableau` beyond which to give up, or `None` for no limit.
+    pub max_queue: Option<usize>,
+}
+
+impl Limits {
+    fn until(deadline: Option<Instant>) -> Self {
+        Self {
+            deadline,
+            ..Self::default()
+        }
+    }
+}
+
+/// One of the [`Limits`] of a run of the tableau.
+///
+/// [`Limits`]: struct.Limits.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Limit {
+    /// The `deadline` passed.
+    Deadline,
+    /// The tableau made `max_expansions` expansions.
+    Expansions,
+    /// More than `max_queue` theories were queued.
+    Queue,
+}
+
+/// The tableau reached one of its [`Limits`] before deciding the formula, see [`Solver::solve`].
+///
+/// [`Limits`]: struct.Limits.html
+/// [`Solver::solve`]: struct.Solver.html#method.solve
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LimitReached {
+    /// The limit which was reached.
+    pub limit: Limit,
+    /// The literals of the most expanded open branch, like [`TimedOut::partial`].
+    ///
+    /// [`TimedOut::partial`]: struct.TimedOut.html#structfield.partial
+    pub partial: Assignment,
+}
+
+/// Convert the outcome of a run only limited by a deadline.
+fn timed_out(result: Result<bool, LimitReached>) -> Result<bool, TimedOut> {
+    result.map_err(|LimitReached { partial, .. }| TimedOut { partial })
+}
+
+/// Convert the outcome of a run without limits.
+fn decided(result: Result<bool, LimitReached>) -> bool {
+    result.unwrap_or_else(|_| unreachable!("the tableau cannot give up without limits"))
+}
+
+/// Configurable run of the tableau, see [`is_satisfiable`] for the algorithm.
+///
+/// By default, a solver expands formulas by the standard rules with the default heuristic and
+/// strategy, only closes branches with complementary literals, and never gives up.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::tableaux_solver::{Limit, Limits, SearchStrategy, Solver};
+/// let formula = "((((a|b)^(c|d))^((e|f)^(g|h)))^(-a))".parse().unwrap();
+///
+/// let solver = Solver::new().with_strategy(SearchStrategy::DepthFirst);
+/// assert_eq!(Ok(true), solver.solve(&formula, |_, _| {}));
+///
+/// let limits = Limits {
+///     max_expansions: Some(2),
+///     ..Limits::default()
+/// };
+/// let outcome = solver.with_limits(limits).solve(&formula, |_, _| {});
+/// assert_eq!(Limit::Expansions, outcome.unwrap_err().limit);
+///
+/// let limits = Limits {
+///     max_queue: Some(2),
+///     ..Limits::default()
+/// };
+/// let outcome = solver.with_limits(limits).solve(&formula, |_, _| {});
+/// assert_eq!(Limit::Queue, outcome.unwrap_err().limit);
+/// ```
+///
+/// [`is_satisfiable`]: fn.is_satisfiable.html
+#[derive(Clone, Copy)]
+pub struct Solver<'a> {
+    heuristic: BranchHeuristic,
+    strategy: SearchStrategy,
+    check: &'a dyn ClosureCheck,
+    rules: Option<&'a RuleSet>,
+    limits: Limits,
+    theory_cache: Option<usize>,
+}
+
+impl Default for Solver<'_> {
+    fn default() -> Self {
+        Self {
+            heuristic: BranchHeuristic::default(),
+            strategy: SearchStrategy::default(),
+            check: &ComplementaryLiterals,
+            rules: None,
+            limits: Limits::default(),
+            theory_cache: None,
+        }
+    }
+}
+
+impl<'a> Solver<'a> {
+    /// Construct the default solver.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the heuristic choosing the non-literal formula to expand in each theory.
+    pub fn with_heuristic(mut self, heuristic: BranchHeuristic) -> Self {
+        self.heuristic = heuristic;
+        self
+    }
+
+    /// Set the order in which theories are retrieved from the tableau.
+    pub fn with_strategy(mut self, strategy: SearchStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Close the branches which fail the given `check` instead of only those with complementary
+    /// literals.
+    ///
+    /// Propositional reasoning still relies on complementary literals closing branches, so `check`
+    /// should extend [`ComplementaryLiterals`] rather than replace it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libprop_sat_solver::formula::{PropositionalFormula, Variable};
+    /// use libprop_sat_solver::tableaux_solver::{ComplementaryLiterals, Solver, Theory};
+    /// // The axiom `(-(a^b))`: no branch may contain both `a` and `b`.
+    /// let a = PropositionalFormula::variable(Variable::new("a"));
+    /// let b = PropositionalFormula::variable(Variable::new("b"));
+    /// let axiom = |theory: &Theory| {
+    ///     let formulas: Vec<_> = theory.formulas().collect();
+    ///     formulas.contains(&&a) && formulas.contains(&&b)
+    /// };
+    ///
+    /// let formula = PropositionalFormula::conjunction(Box::new(a.clone()), Box::new(b.clone()));
+    /// let check = (ComplementaryLiterals, axiom);
+    /// let solver = Solver::new().with_closure_check(&check);
+    /// assert_eq!(Ok(false), solver.solve(&formula, |_, _| {}));
+    /// ```
+    ///
+    /// [`ComplementaryLiterals`]: closure/struct.ComplementaryLiterals.html
+    pub fn with_closure_check(mut self, check: &'a dyn ClosureCheck) -> Self {
+        self.check = check;
+        self
+    }
+
+    /// Expand non-literal formulas by the given `rules` instead of the standard ones, see
+    /// [`RuleSet`].
+    ///
+    /// [`RuleSet`]: rules/struct.RuleSet.html
+    pub fn with_rules(mut self, rules: &'a RuleSet) -> Self {
+        self.rules = Some(rules);
+        self
+    }
+
+    /// Give up once any of the `limits` is reached.
+    pub fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Remember up to `capacity` theories whose branches all closed, so that a branch reaching one
+    /// of them again, e.g. by expanding the same formulas in a different order, closes without
+    /// being explored again.
+    ///
+    /// The `capacity` counts theories, however many formulas each of them holds; once it is
+    /// reached, new theories replace ones which were not looked up recently. Until its branches
+    /// close, each theory the queued ones were expanded from is kept as well, so that it can be
+    /// cached when they do.
+    pub fn with_theory_cache(mut self, capacity: usize) -> Self {
+        self.theory_cache = Some(capacity);
+        self
+    }
+
+    /// Checks if the given propositional formula is _satisfiable_, calling `observer` with the
+    /// number of expansions so far and the current `Tableau` after each expansion.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LimitReached`] with the limit and the partial assignment of the most promising
+    /// open branch if the formula is still undecided when a limit is reached.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no rule of a custom rule set expands some non-literal formula on a branch.
+    ///
+    /// [`LimitReached`]: struct.LimitReached.html
+    pub fn solve<F>(
+        &self,
+        propositional_formula: &PropositionalFormula,
+        observer: F,
+    ) -> Result<bool, LimitReached>
+    where
+        F: FnMut(usize, &Tableau),
+    {
+        // Expanded theories are checked before they are queued, but the starting one has to be
+        // checked here, e.g. for the formula `0`.
+        if self.check.is_closed(&Theory::from_propositional_formula(
+            propositional_formula.clone(),
+        )) {
+            return Ok(false);
+        }
+
+        // Sub-formulas and theories which only differ in the order of commutative operands are
+        // then equal, so they are expanded once and duplicate theories are pruned.
+        let tableau =
+            Tableau::from_starting_propositional_formula(propositional_formula.canonicalize())
+                .with_strategy(self.strategy);
+        run_tableau(tableau, self, observer)
+    }
+}
+
 /// Result of expansion using various rules.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ExpansionKind {
@@ -30,6 +336,9 @@ pub enum ExpansionKind {
     /// | <code>(-(A^B))</code> | `(-A)`     | `(-B)`     |
     /// | `(A->B)`              | `(-A)`     | `B`        |
     /// | `-(A<->B)`            | `(A^(-B))` | `(B^(-A))` |
+    ///
+    /// Chains of biimplications with at least three operands, e.g. `(A<->(B<->C))`, are expanded
+    /// with a dedicated β rule instead of the α rule above to avoid a doubly exponential blowup.
     Beta(Box<PropositionalFormula>, Box<PropositionalFormula>),
 }
 
@@ -94,16 +403,67 @@ pub enum ExpansionKind {
 ///
 /// Notice that the algorithm performs an optimization for early return by fusing the contradiction
 /// checking logic (i.e. determining if a branch closes) with the branch construction logic.
+///
+/// Before a new theory is enqueued, its non-literal formulas are also simplified under the truth
+/// values assigned by its literals (see [`Theory::propagate_literals`]), which drops satisfied
+/// formulas and closes branches with falsified ones without expanding them.
+///
+/// [`Theory::propagate_literals`]: theory/struct.Theory.html#method.propagate_literals
 pub fn is_satisfiable(propositional_formula: &PropositionalFormula) -> bool {
-    let mut tableau = Tableau::from_starting_propositional_formula(propositional_formula.clone());
+    decided(Solver::new().solve(propositional_formula, |_, _| {}))
+}
+
+/// Expand the branches of the `tableau` until one of them is open and fully expanded, all of them
+/// close, or one of the limits of the `solver` is reached. The branches must not be closed
+/// already.
+fn run_tableau<F>(
+    mut tableau: Tableau,
+    solver: &Solver<'_>,
+    mut observer: F,
+) -> Result<bool, LimitReached>
+where
+    F: FnMut(usize, &Tableau),
+{
     debug!("starting with tableau:\n{:#?}", &tableau);
 
+    let Solver {
+        heuristic,
+        check,
+        rules,
+        limits,
+        theory_cache,
+        ..
+    } = *solver;
+
+    let mut memo = theory_cache.map(Memo::new);
+    if let Some(memo) = &mut memo {
+        for theory in tableau.theories() {
+            memo.add_root(theory);
+        }
+    }
+
+    let mut activity = match heuristic {
+        BranchHeuristic::Arbitrary => None,
+        BranchHeuristic::Activity => Some(Activity::new()),
+    };
+    // Shares the formulas produced by expanding a formula between all the branches expanding it,
+    // and frees them all at once when the run is over.
+    let mut arena = ExpansionArena::new();
+    let mut expansions = 0;
+
     while !tableau.is_empty() {
         // PANIC: Cannot panic because a `Theory` always exists if the `Tableau` is non-empty.
-        let mut theory = tableau.pop_theory().unwrap();
+        let theory = tableau.pop_theory().unwrap();
         debug!("current_theory:\n{:#?}", &theory);
 
-        if theory.is_fully_expanded() && !theory.has_contradictions() {
+        if limits
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            return Err(give_up(Limit::Deadline, &theory, &tableau));
+        }
+
+        if theory.is_fully_expanded() && !check.is_closed(&theory) {
             // If the theory is:
             //
             // 1. fully expanded (contains only literals); and
@@ -112,17 +472,28 @@ pub fn is_satisfiable(propositional_formula: &PropositionalFormula) -> bool {
             // The branch represented by the theory remains open, and so the tableau remains open
             // too because at least one branch (this branch) remains open, hence the
             // propositional formula is indeed satisfiable.
-            return true;
+            return Ok(true);
         } else {
+            if limits.max_expansions.is_some_and(|max| expansions >= max) {
+                return Err(give_up(Limit::Expansions, &theory, &tableau));
+            }
+
             // PANIC: should never panic because we already check that the theory is _not_ fully
             // expanded, hence it must contain _non-literals_.
-            let non_literal_formula = theory.get_non_literal_formula().unwrap();
+            let non_literal_formula = match &activity {
+                Some(activity) => activity.pick(&theory),
+                None => theory.shared_non_literal_formulas().next().cloned(),
+            }
+            .unwrap();
             debug!("current non_literal: {:#?}", &non_literal_formula);
 
-            // PANIC: should never panic because we exhaustively apply expansion rules and ensure
-            // that we pass in a _non-literal_ formula.
-            match expand_non_literal_formula(&non_literal_formula).unwrap() {
-                ExpansionKind::Alpha(literal_1, optional_literal_2) => {
+            // PANIC: should never panic because the standard rules are exhaustive and we ensure
+            // that we pass in a _non-literal_ formula; custom rule sets promise to be exhaustive.
+            let expansion = arena
+                .expand(&non_literal_formula, rules)
+                .expect("no expansion rule applies to a non-literal formula");
+            let closed = match expansion {
+                SharedExpansion::Alpha(literal_1, optional_literal_2) => {
                     debug!(
                         "apply alpha expansion: [LEFT = {:#?}], [RIGHT = {:#?}]",
                         &literal_1, &optional_literal_2
@@ -137,10 +508,10 @@ pub fn is_satisfiable(propositional_formula: &PropositionalFormula) -> bool {
                         &new_theory.formulas().collect::<Vec<_>>()
                     );
 
-                    if let Some(literal_2) = optional_literal_2 {
-                        new_theory.swap_formula2(&non_literal_formula, (*literal_1, *literal_2));
-                    } else {
-                        new_theory.swap_formula(&non_literal_formula, *literal_1);
+                    match optional_literal_2 {
+                        Some(literal_2) => new_theory
+                            .swap_shared_formula(&non_literal_formula, &[literal_1, literal_2]),
+                        None => new_theory.swap_shared_formula(&non_literal_formula, &[literal_1]),
                     }
 
                     debug!(
@@ -148,25 +519,36 @@ pub fn is_satisfiable(propositional_formula: &PropositionalFormula) -> bool {
                         &new_theory.formulas().collect::<Vec<_>>()
                     );
 
-                    if !tableau.contains(&new_theory) && !new_theory.has_contradictions() {
-                        tableau.push_theory(new_theory);
-                    }
+                    enqueue_expanded(&mut tableau, new_theory, check, &theory, &mut memo)
                 }
-                ExpansionKind::Beta(literal_1, literal_2) => {
+                SharedExpansion::Beta(literal_1, literal_2) => {
                     let mut new_theory_1 = theory.clone();
                     let mut new_theory_2 = theory.clone();
 
-                    new_theory_1.swap_formula(&non_literal_formula, *literal_1);
-                    new_theory_2.swap_formula(&non_literal_formula, *literal_2);
-
-                    if !tableau.contains(&new_theory_1) && !new_theory_1.has_contradictions() {
-                        tableau.push_theory(new_theory_1);
-                    }
+                    new_theory_1.swap_shared_formula(&non_literal_formula, &[literal_1]);
+                    new_theory_2.swap_shared_formula(&non_literal_formula, &[literal_2]);
 
-                    if !tableau.contains(&new_theory_2) && !new_theory_2.has_contradictions() {
-                        tableau.push_theory(new_theory_2);
-                    }
+                    let closed_1 =
+                        enqueue_expanded(&mut tableau, new_theory_1, check, &theory, &mut memo);
+                    let closed_2 =
+                        enqueue_expanded(&mut tableau, new_theory_2, check, &theory, &mut memo);
+                    closed_1 || closed_2
                 }
+            };
+
+            if let Some(memo) = &mut memo {
+                memo.expanded(&theory);
+            }
+
+            if let (true, Some(activity)) = (closed, &mut activity) {
+                activity.bump(&non_literal_formula);
+            }
+
+            expansions += 1;
+            observer(expansions, &tableau);
+
+            if limits.max_queue.is_some_and(|max| tableau.len() > max) {
+                return Err(give_up(Limit::Queue, &theory, &tableau));
             }
         }
     }
@@ -174,89 +556,186 @@ pub fn is_satisfiable(propositional_formula: &PropositionalFormula) -> bool {
     // An empty tableau means the propositional formula is unsatisfiable, because we fully expanded
     // the propositional formula to construct all possible branches, and all branches close, hence
     // the entire tableau closes.
+    Ok(false)
+}
+
+fn give_up(limit: Limit, current: &Theory, tableau: &Tableau) -> LimitReached {
+    LimitReached {
+        limit,
+        partial: most_expanded(current, tableau),
+    }
+}
+
+/// The literal assignment of the open branch with the fewest non-literal formulas left (and the
+/// most literals among those), out of the `current` theory and those queued in the `tableau`.
+fn most_expanded(current: &Theory, tableau: &Tableau) -> Assignment {
+    // PANIC: cannot panic because there is always the current theory to pick.
+    let best = std::iter::once(current)
+        .chain(tableau.theories())
+        .min_by_key(|theory| {
+            (
+                theory.non_literal_formulas().count(),
+                std::cmp::Reverse(theory.literals().count()),
+            )
+        })
+        .unwrap();
+
+    best.literals()
+        .map(|literal| (literal.variable().clone(), !literal.is_negated()))
+        .collect()
+}
+
+/// Enqueue a newly expanded theory unless its branch closes or the tableau already contains it.
+/// Returns whether the branch closed.
+fn enqueue(tableau: &mut Tableau, mut theory: Theory, check: &dyn ClosureCheck) -> bool {
+    if !theory.propagate_literals() || check.is_closed(&theory) {
+        return true;
+    }
+
+    if !tableau.contains(&theory) {
+        tableau.push_theory(theory);
+    }
+    false
+}
+
+/// Enqueue a theory expanded from the `parent` like [`enqueue`], also closing its branch if the
+/// `memo` knows the theory closes.
+///
+/// [`enqueue`]: fn.enqueue.html
+fn enqueue_expanded(
+    tableau: &mut Tableau,
+    mut theory: Theory,
+    check: &dyn ClosureCheck,
+    parent: &Theory,
+    memo: &mut Option<Memo>,
+) -> bool {
+    let memo = match memo {
+        Some(memo) => memo,
+        None => return enqueue(tableau, theory, check),
+    };
+
+    if !theory.propagate_literals() || check.is_closed(&theory) || memo.is_closed(&theory) {
+        return true;
+    }
+
+    if memo.add_child(parent, &theory) {
+        tableau.push_theory(theory);
+    }
     false
 }
 
+/// Expand a non-literal formula by the [`STANDARD_RULES`], or return `None` for literals.
+///
+/// [`STANDARD_RULES`]: rules/constant.STANDARD_RULES.html
 fn expand_non_literal_formula(non_literal: &PropositionalFormula) -> Option<ExpansionKind> {
-    match non_literal {
-        // (A <op> B) cases:
-        //
-        // 1. (A^B) => Alpha(A, Some(B)).
-        // 2. (A<->B) => Alpha((A->B), Some((B->A))).
-        // 3. (A|B) => Beta(A, B).
-        // 4. (A->B) => Beta((-A), B).
-        PropositionalFormula::Conjunction(Some(a), Some(b)) => {
-            return Some(ExpansionKind::Alpha(a.clone(), Some(b.clone())));
-        }
-        PropositionalFormula::Biimplication(Some(a), Some(b)) => {
-            let alpha_1 = PropositionalFormula::implication(a.clone(), b.clone());
-            let alpha_2 = PropositionalFormula::implication(a.clone(), b.clone());
-            return Some(ExpansionKind::Alpha(
-                Box::new(alpha_1),
-                Some(Box::new(alpha_2)),
-            ));
-        }
-        PropositionalFormula::Disjunction(Some(a), Some(b)) => {
-            return Some(ExpansionKind::Beta(a.clone(), b.clone()));
-        }
-        PropositionalFormula::Implication(Some(a), Some(b)) => {
-            let beta_1 = PropositionalFormula::negated(a.clone());
-            return Some(ExpansionKind::Beta(Box::new(beta_1), b.clone()));
+    rules::STANDARD_RULES
+        .iter()
+        .find_map(|(_, rule)| rule(non_literal))
+}
+
+/// Dedicated expansion rule for chains of biimplications `(A1<->(A2<-> ... <->An))` with at least
+/// three operands.
+///
+/// # Why a Dedicated Rule
+///
+/// The naive α rule rewrites `(A<->B)` into `(A->B)` and `(B->A)`, which copies _both_ operands
+/// into _both_ implications. When `B` is itself a biimplication chain, every level of the chain
+/// doubles the number of copies of the remaining chain, and each copy is then β-expanded on its
+/// own, so the tableau blows up doubly exponentially in the length of the chain.
+///
+/// # The Rule
+///
+/// Biimplication is associative, so a chain (however it is parenthesized) is an equivalence-class
+/// (parity) constraint: it is true iff an even number of its operands are false. We therefore
+/// branch once on the first operand and carry the rest of the chain along as a _single_, shorter
+/// chain:
+///
+/// | Form                        | β1                          | β2                             |
+/// | --------------------------- | --------------------------- | ------------------------------ |
+/// | `(A1<->A2<-> ... <->An)`    | `(A1^(A2<-> ... <->An))`    | `((-A1)^(A2<-> ... <->(-An)))` |
+/// | `(-(A1<->A2<-> ... <->An))` | `(A1^(A2<-> ... <->(-An)))` | `((-A1)^(A2<-> ... <->An))`    |
+///
+/// using the fact that negating a chain is the same as negating any one of its operands. Each
+/// operand is copied at most once per branch, so the chain is consumed one operand per β step.
+pub(crate) fn expand_biimplication_chain(formula: &PropositionalFormula) -> Option<ExpansionKind> {
+    let mut operands = match formula {
+        PropositionalFormula::Negation(f) => {
+            let mut operands = biimplication_chain_operands(f)?;
+            // PANIC: cannot panic because a chain has at least three operands.
+            let last = operands.pop().unwrap();
+            operands.push(negate(last));
+            operands
         }
+        _ => biimplication_chain_operands(formula)?,
+    };
 
-        // (-(-A)) case:
-        //
-        // 1. (-(-A)) => Alpha(A, None).
-        //
-        // (-(A <op> B)) cases:
-        //
-        // 1. (-(A|B)) => Alpha((-A), Some((-B))).
-        // 2. (-(A^B)) => Beta((-A), (-B)).
-        // 3. (-(A->B)) => Alpha(A, Some((-B))).
-        // 4. (-(A<->B)) => Beta((A^(-B)), (B^(-A))).
-        PropositionalFormula::Negation(Some(f)) => match &**f {
-            PropositionalFormula::Negation(Some(a)) => {
-                return Some(ExpansionKind::Alpha(a.clone(), None));
-            }
-            PropositionalFormula::Disjunction(Some(a), Some(b)) => {
-                let alpha_1 = PropositionalFormula::negated(a.clone());
-                let alpha_2 = PropositionalFormula::negated(b.clone());
-                return Some(ExpansionKind::Alpha(
-                    Box::new(alpha_1),
-                    Some(Box::new(alpha_2)),
-                ));
-            }
-            PropositionalFormula::Conjunction(Some(a), Some(b)) => {
-                let beta_1 = PropositionalFormula::negated(a.clone());
-                let beta_2 = PropositionalFormula::negated(b.clone());
-                return Some(ExpansionKind::Beta(Box::new(beta_1), Box::new(beta_2)));
-            }
-            PropositionalFormula::Implication(Some(a), Some(b)) => {
-                let alpha_2 = PropositionalFormula::negated(b.clone());
-                return Some(ExpansionKind::Alpha(a.clone(), Some(Box::new(alpha_2))));
-            }
-            PropositionalFormula::Biimplication(Some(a), Some(b)) => {
-                let beta_1 = PropositionalFormula::conjunction(
-                    a.clone(),
-                    Box::new(PropositionalFormula::negated(b.clone())),
-                );
-                let beta_2 = PropositionalFormula::conjunction(
-                    b.clone(),
-                    Box::new(PropositionalFormula::negated(a.clone())),
-                );
-
-                return Some(ExpansionKind::Beta(Box::new(beta_1), Box::new(beta_2)));
+    let first = operands.remove(0);
+    let rest = biimplication_chain(&operands);
+
+    // PANIC: cannot panic because a chain has at least three operands, and we only removed one.
+    let last = operands.pop().unwrap();
+    operands.push(negate(last));
+    let negated_rest = biimplication_chain(&operands);
+
+    let beta_1 = PropositionalFormula::conjunction(Box::new(first.clone()), Box::new(rest));
+    let beta_2 = PropositionalFormula::conjunction(Box::new(negate(first)), Box::new(negated_rest));
+
+    Some(ExpansionKind::Beta(Box::new(beta_1), Box::new(beta_2)))
+}
+
+/// Collect the operands of a biimplication chain, flattening nested biimplications on either side
+/// since `((A<->B)<->C)` and `(A<->(B<->C))` are equivalent.
+///
+/// Returns `None` if the formula is not a biimplication chain with at least three operands (plain
+/// `(A<->B)` is left to the regular rule).
+fn biimplication_chain_operands(
+    formula: &PropositionalFormula,
+) -> Option<Vec<PropositionalFormula>> {
+    fn collect(formula: &PropositionalFormula, operands: &mut Vec<PropositionalFormula>) {
+        match formula {
+            PropositionalFormula::Biimplication(a, b) => {
+                collect(a, operands);
+                collect(b, operands);
             }
-            _ => {
-                return None;
+            _ => operands.push(formula.clone()),
+        }
+    }
+
+    match formula {
+        PropositionalFormula::Biimplication(_, _) => {
+            let mut operands = Vec::new();
+            collect(formula, &mut operands);
+
+            if operands.len() >= 3 {
+                Some(operands)
+            } else {
+                None
             }
-        },
-        _ => {
-            return None;
         }
+        _ => None,
     }
 }
 
+/// Rebuild a right-nested biimplication chain `(A1<->(A2<-> ... <->An))` from its operands.
+///
+/// # Panics
+///
+/// Panics if `operands` is empty.
+fn biimplication_chain(operands: &[PropositionalFormula]) -> PropositionalFormula {
+    let (last, init) = operands.split_last().unwrap();
+
+    init.iter().rev().fold(last.clone(), |chain, operand| {
+        PropositionalFormula::biimplication(Box::new(operand.clone()), Box::new(chain))
+    })
+}
+
+/// Negate a formula, stripping an existing outermost negation instead of stacking another one.
+fn negate(formula: PropositionalFormula) -> PropositionalFormula {
+    formula
+        .strip_negation()
+        .unwrap_or_else(|formula| PropositionalFormula::negated(Box::new(formula)))
+}
+
 /// Checks if a given propositional formula is _valid_.
 ///
 /// This is done by checking that the contrapositive statement: "is `-<formula>` unsatisfiable?"
@@ -265,16 +744,125 @@ pub fn is_valid(formula: &PropositionalFormula) -> bool {
     !is_satisfiable(&negated_formula)
 }
 
+/// Checks if the given propositional formula is _satisfiable_, after preprocessing it with the
+/// default [`PreprocessingOptions`].
+///
+/// [`PreprocessingOptions`]: struct.PreprocessingOptions.html
+pub fn is_satisfiable_preprocessed(propositional_formula: &PropositionalFormula) -> bool {
+    is_satisfiable_with_preprocessing(propositional_formula, &PreprocessingOptions::default())
+}
+
+/// Checks if the given propositional formula is _satisfiable_, after preprocessing it.
+///
+/// The formula is first converted into a CNF and simplified as enabled by `options` (see
+/// [`preprocess`]). Trivial results are decided right away, and the remaining clauses are handed
+/// to the tableau.
+///
+/// [`preprocess`]: fn.preprocess.html
+pub fn is_satisfiable_with_preprocessing(
+    propositional_formula: &PropositionalFormula,
+    options: &PreprocessingOptions,
+) -> bool {
+    is_satisfiable_with_preprocessing_observed(
+        propositional_formula,
+        options,
+        BranchHeuristic::default(),
+        |_, _| {},
+    )
+}
+
+/// Checks if the given propositional formula is _satisfiable_ like
+/// [`is_satisfiable_with_preprocessing`], expanding the tableau with the given `heuristic` and
+/// calling `observer` after each expansion like [`Solver::solve`].
+///
+/// [`is_satisfiable_with_preprocessing`]: fn.is_satisfiable_with_preprocessing.html
+/// [`Solver::solve`]: struct.Solver.html#method.solve
+pub fn is_satisfiable_with_preprocessing_observed<F>(
+    propositional_formula: &PropositionalFormula,
+    options: &PreprocessingOptions,
+    heuristic: BranchHeuristic,
+    observer: F,
+) -> bool
+where
+    F: FnMut(usize, &Tableau),
+{
+    let cnf = preprocess(propositional_formula, options);
+
+    if cnf.has_empty_clause() {
+        return false;
+    }
+
+    match cnf.to_formula() {
+        Some(formula) => decided(
+            Solver::new()
+                .with_heuristic(heuristic)
+                .solve(&formula, observer),
+        ),
+        // No clauses left means no constraints left.
+        None => true,
+    }
+}
+
+/// Convert the formula into an equisatisfiable CNF (see [`CnfFormula::from_formula`]), then remove
+/// blocked clauses (see [`eliminate_blocked_clauses`]) and eliminate variables with few occurrences
+/// by resolution (see [`eliminate_variables`]), as enabled by `options`.
+///
+/// [`CnfFormula::from_formula`]: ../cnf/struct.CnfFormula.html#method.from_formula
+/// [`eliminate_blocked_clauses`]: ../cnf/fn.eliminate_blocked_clauses.html
+/// [`eliminate_variables`]: ../cnf/fn.eliminate_variables.html
+pub fn preprocess(
+    propositional_formula: &PropositionalFormula,
+    options: &PreprocessingOptions,
+) -> CnfFormula {
+    let mut cnf = CnfFormula::from_formula(propositional_formula);
+
+    if options.blocked_clause_elimination {
+        let stats = eliminate_blocked_clauses(&mut cnf);
+        debug!("blocked clause elimination statistics: {:#?}", &stats);
+    }
+
+    if let Some(max_occurrences) = options.max_elimination_occurrences {
+        let stats = eliminate_variables(&mut cnf, max_occurrences);
+        debug!("variable elimination statistics: {:#?}", &stats);
+    }
+
+    cnf
+}
+
+/// Checks if a given propositional formula is _valid_, after preprocessing its negation with the
+/// default [`PreprocessingOptions`].
+///
+/// [`PreprocessingOptions`]: struct.PreprocessingOptions.html
+pub fn is_valid_preprocessed(formula: &PropositionalFormula) -> bool {
+    is_valid_with_preprocessing(formula, &PreprocessingOptions::default())
+}
+
+/// Checks if a given propositional formula is _valid_, after preprocessing its negation as in
+/// [`is_satisfiable_with_preprocessing`].
+///
+/// [`is_satisfiable_with_preprocessing`]: fn.is_satisfiable_with_preprocessing.html
+pub fn is_valid_with_preprocessing(
+    formula: &PropositionalFormula,
+    options: &PreprocessingOptions,
+) -> bool {
+    let negated_formula = PropositionalFormula::negated(Box::new(formula.clone()));
+    !is_satisfiable_with_preprocessing(&negated_formula, options)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::formula::Variable;
+    use crate::formula;
+    use crate::formula::{template, Variable};
+    use crate::testing::formulas;
+    use crate::truth_table;
     use assert2::check;
+    use std::collections::HashMap;
 
     #[test]
     fn test_propositional_variable() {
         // a
-        let formula = PropositionalFormula::variable(Variable::new("a"));
+        let formula = formula!(a);
 
         check!(is_satisfiable(&formula));
         check!(!is_valid(&formula));
@@ -283,10 +871,7 @@ mod tests {
     #[test]
     fn test_conjunction_same_variable() {
         // (a^a)
-        let formula = PropositionalFormula::conjunction(
-            Box::new(PropositionalFormula::variable(Variable::new("a"))),
-            Box::new(PropositionalFormula::variable(Variable::new("a"))),
-        );
+        let formula = formula!(a ^ a);
 
         check!(is_satisfiable(&formula));
         check!(!is_valid(&formula));
@@ -295,10 +880,7 @@ mod tests {
     #[test]
     fn test_conjunction_different_variables() {
         // (a^b)
-        let formula = PropositionalFormula::conjunction(
-            Box::new(PropositionalFormula::variable(Variable::new("a"))),
-            Box::new(PropositionalFormula::variable(Variable::new("b"))),
-        );
+        let formula = formula!(a ^ b);
 
         check!(is_satisfiable(&formula));
         check!(!is_valid(&formula));
@@ -307,10 +889,7 @@ mod tests {
     #[test]
     fn test_disjunction_same_variable() {
         // (a|a)
-        let formula = PropositionalFormula::disjunction(
-            Box::new(PropositionalFormula::variable(Variable::new("a"))),
-            Box::new(PropositionalFormula::variable(Variable::new("a"))),
-        );
+        let formula = formula!(a | a);
 
         check!(is_satisfiable(&formula));
         check!(!is_valid(&formula));
@@ -319,10 +898,7 @@ mod tests {
     #[test]
     fn test_disjunction_different_variables() {
         // (a|b)
-        let formula = PropositionalFormula::disjunction(
-            Box::new(PropositionalFormula::variable(Variable::new("a"))),
-            Box::new(PropositionalFormula::variable(Variable::new("b"))),
-        );
+        let formula = formula!(a | b);
 
         check!(is_satisfiable(&formula));
         check!(!is_valid(&formula));
@@ -331,10 +907,7 @@ mod tests {
     #[test]
     fn test_implication_different_variables() {
         // (a->b)
-        let formula = PropositionalFormula::implication(
-            Box::new(PropositionalFormula::variable(Variable::new("a"))),
-            Box::new(PropositionalFormula::variable(Variable::new("b"))),
-        );
+        let formula = formula!(a -> b);
 
         check!(is_satisfiable(&formula));
         check!(!is_valid(&formula));
@@ -343,10 +916,7 @@ mod tests {
     #[test]
     fn test_biimplication_different_variables() {
         // (a<->b)
-        let formula = PropositionalFormula::biimplication(
-            Box::new(PropositionalFormula::variable(Variable::new("a"))),
-            Box::new(PropositionalFormula::variable(Variable::new("b"))),
-        );
+        let formula = formula!(a <-> b);
 
         check!(is_satisfiable(&formula));
         check!(!is_valid(&formula));
@@ -355,12 +925,7 @@ mod tests {
     #[test]
     fn test_contradiction() {
         // (a^-a)
-        let formula = PropositionalFormula::conjunction(
-            Box::new(PropositionalFormula::variable(Variable::new("a"))),
-            Box::new(PropositionalFormula::negated(Box::new(
-                PropositionalFormula::variable(Variable::new("a")),
-            ))),
-        );
+        let formula = formula!(a ^ (-a));
 
         check!(!is_satisfiable(&formula));
         check!(!is_valid(&formula));
@@ -369,12 +934,7 @@ mod tests {
     #[test]
     fn test_tautology_disjunction() {
         // (a|(-a))
-        let formula = PropositionalFormula::disjunction(
-            Box::new(PropositionalFormula::variable(Variable::new("a"))),
-            Box::new(PropositionalFormula::negated(Box::new(
-                PropositionalFormula::variable(Variable::new("a")),
-            ))),
-        );
+        let formula = formula!(a | (-a));
 
         check!(is_satisfiable(&formula));
         check!(is_valid(&formula));
@@ -383,16 +943,7 @@ mod tests {
     #[test]
     fn test_tautology_disjunction_nested_negation() {
         // ((-a)|(-(-a)))
-        let formula = PropositionalFormula::disjunction(
-            Box::new(PropositionalFormula::negated(Box::new(
-                PropositionalFormula::variable(Variable::new("a")),
-            ))),
-            Box::new(PropositionalFormula::negated(Box::new(
-                PropositionalFormula::negated(Box::new(PropositionalFormula::variable(
-                    Variable::new("a"),
-                ))),
-            ))),
-        );
+        let formula = formula!((-a) | (-(-a)));
 
         check!(is_satisfiable(&formula));
         check!(is_valid(&formula));
@@ -401,10 +952,7 @@ mod tests {
     #[test]
     fn test_tautology_implication_literal() {
         // (a->a)
-        let formula = PropositionalFormula::implication(
-            Box::new(PropositionalFormula::variable(Variable::new("a"))),
-            Box::new(PropositionalFormula::variable(Variable::new("a"))),
-        );
+        let formula = formula!(a -> a);
 
         check!(is_satisfiable(&formula));
         check!(is_valid(&formula));
@@ -413,14 +961,7 @@ mod tests {
     #[test]
     fn test_tautology_implication_negated_literal() {
         // ((-a)->(-a))
-        let formula = PropositionalFormula::implication(
-            Box::new(PropositionalFormula::negated(Box::new(
-                PropositionalFormula::variable(Variable::new("a")),
-            ))),
-            Box::new(PropositionalFormula::negated(Box::new(
-                PropositionalFormula::variable(Variable::new("a")),
-            ))),
-        );
+        let formula = formula!((-a) -> (-a));
 
         check!(is_satisfiable(&formula));
         check!(is_valid(&formula));
@@ -429,10 +970,7 @@ mod tests {
     #[test]
     fn test_tautology_biimplication_literal() {
         // (a<->a)
-        let formula = PropositionalFormula::biimplication(
-            Box::new(PropositionalFormula::variable(Variable::new("a"))),
-            Box::new(PropositionalFormula::variable(Variable::new("a"))),
-        );
+        let formula = formula!(a <-> a);
 
         check!(is_satisfiable(&formula));
         check!(is_valid(&formula));
@@ -441,16 +979,108 @@ mod tests {
     #[test]
     fn test_tautology_biimplication_negated_literal() {
         // ((-a)<->(-a))
-        let formula = PropositionalFormula::biimplication(
-            Box::new(PropositionalFormula::negated(Box::new(
-                PropositionalFormula::variable(Variable::new("a")),
-            ))),
-            Box::new(PropositionalFormula::negated(Box::new(
-                PropositionalFormula::variable(Variable::new("a")),
-            ))),
-        );
+        let formula = formula!((-a) <-> (-a));
+
+        check!(is_satisfiable(&formula));
+        check!(is_valid(&formula));
+    }
+
+    #[test]
+    fn test_biimplication_conflicting_literals() {
+        // ((a<->b)^(a^(-b)))
+        let formula = formula!((a <-> b) ^ (a ^ (-b)));
+
+        check!(!is_satisfiable(&formula));
+        check!(!is_valid(&formula));
+    }
+
+    #[test]
+    fn test_biimplication_chain_left_nested() {
+        // ((a<->b)<->c)
+        let formula = formula!((a <-> b) <-> c);
+
+        check!(is_satisfiable(&formula));
+        check!(!is_valid(&formula));
+    }
+
+    #[test]
+    fn test_tautology_biimplication_chain() {
+        // (a<->(b<->(a<->b)))
+        let formula = formula!(a <-> (b <-> (a <-> b)));
 
         check!(is_satisfiable(&formula));
         check!(is_valid(&formula));
     }
+
+    #[test]
+    fn test_long_biimplication_chain() {
+        // (a0<->(a1<-> ... <->a5))
+        let formula = (0..5).rev().fold(
+            PropositionalFormula::variable(Variable::new("a5")),
+            |chain, i| {
+                PropositionalFormula::biimplication(
+                    Box::new(PropositionalFormula::variable(Variable::new(format!(
+                        "a{}",
+                        i
+                    )))),
+                    Box::new(chain),
+                )
+            },
+        );
+
+        check!(is_satisfiable(&formula));
+        check!(!is_valid(&formula));
+    }
+
+    #[test]
+    fn test_constants() {
+        check!(is_valid(&formula!(1)));
+        check!(!is_satisfiable(&formula!(0)));
+        check!(!is_satisfiable(&formula!(-1)));
+        check!(!is_satisfiable(&formula!(a ^ 0)));
+        check!(is_valid(&formula!(a | 1)));
+        check!(is_valid(&formula!(0 -> a)));
+        check!(!is_satisfiable(&formula!((a -> 0) ^ a)));
+        check!(!parallel::is_satisfiable(&formula!(0), 2));
+    }
+
+    #[test]
+    fn test_constants_agree_with_truth_table() {
+        let mut bindings = HashMap::new();
+        bindings.insert(Variable::new("x1"), PropositionalFormula::Top);
+        bindings.insert(Variable::new("x2"), PropositionalFormula::Bottom);
+
+        for formula in formulas(4, 4, 27, 100) {
+            let formula = template::replace(&formula, &bindings);
+            check!(is_satisfiable(&formula) == truth_table::is_satisfiable(&formula));
+            check!(parallel::is_satisfiable(&formula, 2) == truth_table::is_satisfiable(&formula));
+        }
+    }
+
+    #[test]
+    fn test_preprocessed_agrees_with_tableau() {
+        let formulas = vec![
+            formula!(a ^ (-a)),
+            formula!(a | (-a)),
+            formula!(a -> b),
+            formula!((a <-> b) ^ (a ^ (-b))),
+        ];
+
+        let with_blocked_clauses = PreprocessingOptions {
+            blocked_clause_elimination: true,
+            ..PreprocessingOptions::default()
+        };
+
+        for formula in &formulas {
+            check!(is_satisfiable_preprocessed(formula) == is_satisfiable(formula));
+            check!(is_valid_preprocessed(formula) == is_valid(formula));
+            check!(
+                is_satisfiable_with_preprocessing(formula, &with_blocked_clauses)
+                    == is_satisfiable(formula)
+            );
+            check!(
+                is_valid_with_preprocessing(formula, &with_blocked_clauses) == is_valid(formula)
+            );
+        }
+    }
 }
@@ -0,0 +1,167 @@
+//! Parallel propositional tableau, where the theories produced by β-expansions are shared among
+//! worker threads by work stealing.
+//!
+//! Each worker keeps its own LIFO deque of theories: it pushes the theories it produces to its own
+//! deque and pops from it too, so it explores its part of the tableau depth-first without touching
+//! any shared state. Only a worker which has run out of theories steals from the others (taking the
+//! oldest theories, i.e. the largest unexplored subtrees), which keeps contention low for deeply
+//! branching instances compared to a single shared `Tableau` queue.
+
+use std::iter;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
+
+use crossbeam_deque::{Injector, Stealer, Worker};
+use log::debug;
+
+use crate::formula::PropositionalFormula;
+
+use super::{expand_non_literal_formula, ExpansionKind, Theory};
+
+/// Checks if the given propositional formula is _satisfiable_ with the tableau, expanding theories
+/// on `threads` worker threads.
+///
+/// Unlike [`is_satisfiable`], theories are not checked against the other queued theories for
+/// duplicates, since there is no single queue to check against.
+///
+/// [`is_satisfiable`]: ../fn.is_satisfiable.html
+pub fn is_satisfiable(formula: &PropositionalFormula, threads: usize) -> bool {
+    // Expanded theories are checked before they are queued, but the root has to be checked here,
+    // e.g. for the formula `0`.
+    let root = Theory::from_propositional_formula(formula.clone());
+    if root.has_contradictions() {
+        return false;
+    }
+
+    let injector = Injector::new();
+    injector.push(root);
+
+    let workers: Vec<Worker<Theory>> = (0..threads.max(1)).map(|_| Worker::new_lifo()).collect();
+    let stealers: Vec<Stealer<Theory>> = workers.iter().map(Worker::stealer).collect();
+
+    let shared = Shared {
+        injector,
+        stealers,
+        // Theories which have been queued but not expanded yet, including the root.
+        pending: AtomicUsize::new(1),
+        satisfiable: AtomicBool::new(false),
+    };
+
+    thread::scope(|scope| {
+        for worker in workers {
+            let shared = &shared;
+            scope.spawn(move || shared.run(&worker));
+        }
+    });
+
+    shared.satisfiable.load(Ordering::SeqCst)
+}
+
+struct Shared {
+    injector: Injector<Theory>,
+    stealers: Vec<Stealer<Theory>>,
+    pending: AtomicUsize,
+    satisfiable: AtomicBool,
+}
+
+impl Shared {
+    fn run(&self, local: &Worker<Theory>) {
+        let mut expansions = 0;
+
+        loop {
+            // Every branch closed once no theory is pending anymore.
+            if self.satisfiable.load(Ordering::SeqCst) || self.pending.load(Ordering::SeqCst) == 0 {
+                debug!("worker stopping after {} expansions", expansions);
+                return;
+            }
+
+            let theory = match self.find_theory(local) {
+                Some(theory) => theory,
+                None => {
+                    thread::yield_now();
+                    continue;
+                }
+            };
+
+            match expand(theory) {
+                Some(successors) => {
+                    for successor in IntoIterator::into_iter(successors).flatten() {
+                        self.pending.fetch_add(1, Ordering::SeqCst);
+                        local.push(successor);
+                    }
+                }
+                None => {
+                    self.satisfiable.store(true, Ordering::SeqCst);
+                    return;
+                }
+            }
+
+            expansions += 1;
+            // Only now that its successors are pending is the expanded theory done.
+            self.pending.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    /// Take a theory from the local deque, or else steal one from the injector or another worker.
+    fn find_theory(&self, local: &Worker<Theory>) -> Option<Theory> {
+        local.pop().or_else(|| {
+            iter::repeat_with(|| {
+                self.injector
+                    .steal_batch_and_pop(local)
+                    .or_else(|| self.stealers.iter().map(Stealer::steal).collect())
+            })
+            .find(|steal| !steal.is_retry())
+            .and_then(|steal| steal.success())
+        })
+    }
+}
+
+/// Expand a theory of a parallel tableau, whose branch must be open.
+///
+/// Returns `None` if the theory is fully expanded, i.e. the formula is satisfiable, or else the
+/// theories it expands into whose branches stay open: one for an α-expansion and two for a
+/// β-expansion, unless they close.
+pub(super) fn expand(mut theory: Theory) -> Option<[Option<Theory>; 2]> {
+    if theory.is_fully_expanded() {
+        return None;
+    }
+
+    // PANIC: should never panic because the theory is not fully expanded, and we exhaustively
+    // apply expansion rules to non-literal formulas.
+    let non_literal_formula = theory.get_non_literal_formula().unwrap();
+    let successors = match expand_non_literal_formula(&non_literal_formula).unwrap() {
+        ExpansionKind::Alpha(alpha_1, alpha_2) => {
+            match alpha_2 {
+                Some(alpha_2) => theory.swap_formula2(&non_literal_formula, (*alpha_1, *alpha_2)),
+                None => theory.swap_formula(&non_literal_formula, *alpha_1),
+            }
+            [Some(theory), None]
+        }
+        ExpansionKind::Beta(beta_1, beta_2) => {
+            let mut new_theory = theory.clone();
+            new_theory.swap_formula(&non_literal_formula, *beta_1);
+            theory.swap_formula(&non_literal_formula, *beta_2);
+            [Some(new_theory), Some(theory)]
+        }
+    };
+
+    // Simplify the successors by their literals and drop those whose branches close.
+    Some(successors.map(|successor| {
+        successor.and_then(|mut theory| {
+            (theory.propagate_literals() && !theory.has_contradictions()).then_some(theory)
+        })
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{assert_agrees_with_truth_table, formulas};
+
+    #[test]
+    fn test_agrees_with_truth_table() {
+        assert_agrees_with_truth_table(formulas(4, 5, 28, 100), |formula| {
+            is_satisfiable(formula, 4)
+        });
+    }
+}
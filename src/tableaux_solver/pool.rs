@@ -0,0 +1,162 @@
+//! Parallel propositional tableau on a [`rayon`] thread pool, available with the `parallel`
+//! feature.
+//!
+//! Theories are expanded like in [`parallel`], but the two theories produced by a β-expansion are
+//! explored as two rayon tasks with [`rayon::join`], so idle threads steal whole unexplored
+//! subtrees while each thread keeps expanding its own branch depth-first. Below
+//! [`MAX_JOIN_DEPTH`] nested β-expansions, a task explores its remaining branches by itself. As
+//! soon as any task finds an open, fully expanded branch it raises a shared flag, which every task
+//! checks before each expansion, so the remaining branches are abandoned instead of being expanded
+//! to the end.
+//!
+//! [`parallel`]: ../parallel/index.html
+//! [`rayon`]: https://docs.rs/rayon
+//! [`rayon::join`]: https://docs.rs/rayon/1/rayon/fn.join.html
+//! [`MAX_JOIN_DEPTH`]: constant.MAX_JOIN_DEPTH.html
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use rayon::{ThreadPool, ThreadPoolBuilder};
+
+use crate::formula::PropositionalFormula;
+
+use super::parallel::expand;
+use super::Theory;
+
+/// Number of nested β-expansions whose theories are explored as separate rayon tasks. That makes
+/// up to `2^16` tasks, more than enough to keep any pool busy, while bounding the depth of nested
+/// [`rayon::join`] calls on each thread's stack.
+///
+/// [`rayon::join`]: https://docs.rs/rayon/1/rayon/fn.join.html
+pub const MAX_JOIN_DEPTH: usize = 16;
+
+/// Checks if the given propositional formula is _satisfiable_ with the tableau, expanding theories
+/// on a pool of `threads` threads, see [`is_satisfiable_in`].
+///
+/// A pool is spawned by the first call with each number of `threads`, and reused by all later
+/// calls with the same number.
+///
+/// # Panics
+///
+/// Panics if the threads of the pool cannot be spawned.
+///
+/// [`is_satisfiable_in`]: fn.is_satisfiable_in.html
+pub fn is_satisfiable(formula: &PropositionalFormula, threads: usize) -> bool {
+    is_satisfiable_in(formula, &pool(threads))
+}
+
+/// The pool of `threads` threads (at least one), spawning it if there is none yet.
+fn pool(threads: usize) -> Arc<ThreadPool> {
+    static POOLS: OnceLock<Mutex<HashMap<usize, Arc<ThreadPool>>>> = OnceLock::new();
+
+    let threads = threads.max(1);
+    let mut pools = POOLS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let pool = pools.entry(threads).or_insert_with(|| {
+        Arc::new(
+            ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .expect("failed to spawn the tableau threads"),
+        )
+    });
+    Arc::clone(pool)
+}
+
+/// Checks if the given propositional formula is _satisfiable_ with the tableau, expanding theories
+/// on the given thread `pool`.
+///
+/// Like [`parallel::is_satisfiable`], theories are not checked against the other queued theories
+/// for duplicates.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::tableaux_solver::pool::is_satisfiable_in;
+/// let pool = rayon::ThreadPoolBuilder::new().num_threads(2).build().unwrap();
+///
+/// assert!(is_satisfiable_in(&"(((a|b)^(c|d))^(-a))".parse().unwrap(), &pool));
+/// assert!(!is_satisfiable_in(&"((a|b)^((-a)^(-b)))".parse().unwrap(), &pool));
+/// ```
+///
+/// [`parallel::is_satisfiable`]: ../parallel/fn.is_satisfiable.html
+pub fn is_satisfiable_in(formula: &PropositionalFormula, pool: &ThreadPool) -> bool {
+    // Expanded theories are checked before they are explored, but the root has to be checked
+    // here, e.g. for the formula `0`.
+    let root = Theory::from_propositional_formula(formula.clone());
+    if root.has_contradictions() {
+        return false;
+    }
+
+    let satisfiable = AtomicBool::new(false);
+    pool.install(|| explore(root, &satisfiable, 0));
+    satisfiable.load(Ordering::SeqCst)
+}
+
+/// Expand the theory until all of its branches close, or one of them is open and fully expanded,
+/// or another task found such a branch already. `depth` is the number of nested β-expansions whose
+/// theories were explored as separate tasks so far.
+fn explore(theory: Theory, satisfiable: &AtomicBool, depth: usize) {
+    let mut theories = vec![theory];
+
+    while let Some(theory) = theories.pop() {
+        if satisfiable.load(Ordering::Relaxed) {
+            return;
+        }
+
+        match expand(theory) {
+            Some([Some(theory_1), Some(theory_2)]) if depth < MAX_JOIN_DEPTH => {
+                rayon::join(
+                    || explore(theory_1, satisfiable, depth + 1),
+                    || explore(theory_2, satisfiable, depth + 1),
+                );
+            }
+            // Carry on with the open branches on this thread.
+            Some(successors) => theories.extend(IntoIterator::into_iter(successors).flatten()),
+            None => {
+                satisfiable.store(true, Ordering::Relaxed);
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{assert_agrees_with_truth_table, formulas};
+    use assert2::check;
+
+    #[test]
+    fn test_agrees_with_truth_table() {
+        assert_agrees_with_truth_table(formulas(4, 5, 29, 100), |formula| {
+            is_satisfiable(formula, 4)
+        });
+    }
+
+    #[test]
+    fn test_pools_have_the_requested_threads() {
+        for &threads in &[1, 2, 3] {
+            check!(pool(threads).current_num_threads() == threads);
+            check!(pool(threads).install(rayon::current_num_threads) == threads);
+            check!(Arc::ptr_eq(&pool(threads), &pool(threads)));
+        }
+        check!(pool(0).current_num_threads() == 1);
+    }
+
+    #[test]
+    fn test_explores_sequentially_beyond_max_join_depth() {
+        assert_agrees_with_truth_table(formulas(4, 5, 30, 100), |formula| {
+            let root = Theory::from_propositional_formula(formula.clone());
+            let satisfiable = AtomicBool::new(false);
+            if !root.has_contradictions() {
+                explore(root, &satisfiable, MAX_JOIN_DEPTH);
+            }
+            satisfiable.into_inner()
+        });
+    }
+}
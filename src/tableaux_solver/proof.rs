@@ -0,0 +1,711 @@
+//! Machine-readable tableau proofs of unsatisfiability and validity, and an independent checker
+//! for them.
+//!
+//! # Format
+//!
+//! A proof is a JSON object naming the formula, the claim about it (`"unsatisfiable"` or
+//! `"valid"`) and the steps of a closed tableau rooted at the formula (or, for validity, its
+//! negation):
+//!
+//! ```json
+//! {
+//!   "formula": "((a|b)^((-a)^(-b)))",
+//!   "claim": "unsatisfiable",
+//!   "steps": [
+//!     { "id": 1, "parent": 0, "rule": "alpha", "formula": "((a|b)^((-a)^(-b)))", "added": ["(a|b)", "((-a)^(-b))"] },
+//!     { "id": 2, "parent": 1, "rule": "alpha", "formula": "((-a)^(-b))", "added": ["(-a)", "(-b)"] },
+//!     { "id": 3, "parent": 2, "rule": "beta1", "formula": "(a|b)", "added": ["a"] },
+//!     { "id": 4, "parent": 2, "rule": "beta2", "formula": "(a|b)", "added": ["b"] },
+//!     { "id": 5, "parent": 3, "rule": "close", "formula": "a", "added": [] },
+//!     { "id": 6, "parent": 4, "rule": "close", "formula": "b", "added": [] }
+//!   ]
+//! }
+//! ```
+//!
+//! Each step extends the branch ending at its `parent` step, where `0` is the root of the tableau
+//! holding just the formula; parents are listed before their children. The branch ending at a
+//! step holds every formula on the path from the root to it.
+//!
+//! - An `alpha` step expands a formula of the branch by the α rule, adding all of its results.
+//! - A `beta1` and a `beta2` step with the same parent expand the same formula by the β rule,
+//!   adding its first and second result respectively.
+//! - A `close` step closes the branch, which must hold both its `formula` and the negation of it,
+//!   unless the `formula` is false by itself, i.e. `0` or `(-1)`.
+//!
+//! The proof is valid if all rules are applied correctly and every branch is closed.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::formula::PropositionalFormula;
+use crate::json::{parse_json, Json};
+
+use super::{expand_non_literal_formula, ExpansionKind};
+
+/// What a [`Proof`] proves about its formula.
+///
+/// [`Proof`]: struct.Proof.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Claim {
+    /// The tableau of the formula closes.
+    Unsatisfiable,
+    /// The tableau of the negation of the formula closes.
+    Valid,
+}
+
+impl Claim {
+    fn name(self) -> &'static str {
+        match self {
+            Claim::Unsatisfiable => "unsatisfiable",
+            Claim::Valid => "valid",
+        }
+    }
+}
+
+/// Rule applied in a [`ProofStep`].
+///
+/// [`ProofStep`]: struct.ProofStep.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProofRule {
+    Alpha,
+    /// The β rule, taking its first (`1`) or second (`2`) result.
+    Beta(usize),
+    Close,
+}
+
+impl ProofRule {
+    fn name(self) -> &'static str {
+        match self {
+            ProofRule::Alpha => "alpha",
+            ProofRule::Beta(1) => "beta1",
+            ProofRule::Beta(_) => "beta2",
+            ProofRule::Close => "close",
+        }
+    }
+}
+
+/// One step of a [`Proof`], see the [module documentation](index.html) for its meaning.
+///
+/// [`Proof`]: struct.Proof.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofStep {
+    /// Identifier of the step, greater than `0`.
+    pub id: usize,
+    /// Identifier of the step whose branch this step extends, or `0` for the root.
+    pub parent: usize,
+    pub rule: ProofRule,
+    /// The expanded formula, or for `close` steps the formula whose negation is also on the branch.
+    pub formula: PropositionalFormula,
+    /// The formulas added to the branch.
+    pub added: Vec<PropositionalFormula>,
+}
+
+/// A closed tableau proving a [`Claim`] about a formula.
+///
+/// [`Claim`]: enum.Claim.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Proof {
+    pub formula: PropositionalFormula,
+    pub claim: Claim,
+    pub steps: Vec<ProofStep>,
+}
+
+impl Proof {
+    /// The formula at the root of the tableau.
+    fn root(&self) -> PropositionalFormula {
+        match self.claim {
+            Claim::Unsatisfiable => self.formula.clone(),
+            Claim::Valid => PropositionalFormula::negated(Box::new(self.formula.clone())),
+        }
+    }
+
+    /// Render the proof in the JSON format described in the [module documentation](index.html),
+    /// with one step per line.
+    pub fn to_json(&self) -> String {
+        let string = |formula: &PropositionalFormula| format!("\"{}\"", formula);
+
+        let steps: Vec<String> = self
+            .steps
+            .iter()
+            .map(|step| {
+                let added: Vec<String> = step.added.iter().map(string).collect();
+                format!(
+                    concat!(
+                        "    {{ \"id\": {}, \"parent\": {}, \"rule\": \"{}\", ",
+                        "\"formula\": {}, \"added\": [{}] }}",
+                    ),
+                    step.id,
+                    step.parent,
+                    step.rule.name(),
+                    string(&step.formula),
+                    added.join(", ")
+                )
+            })
+            .collect();
+
+        format!(
+            "{{\n  \"formula\": {},\n  \"claim\": \"{}\",\n  \"steps\": [\n{}\n  ]\n}}\n",
+            string(&self.formula),
+            self.claim.name(),
+            steps.join(",\n")
+        )
+    }
+
+    /// Render the proof as a tableau for the LaTeX package `prooftrees`, in a `prooftree`
+    /// environment. Each node holds one formula (see [`PropositionalFormula::to_latex`][latex]),
+    /// the first node added by a step is justified by its rule, and closed branches end in `close`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libprop_sat_solver::tableaux_solver::proof::prove_valid;
+    /// let proof = prove_valid(&"(a|(-a))".parse().unwrap()).unwrap();
+    ///
+    /// assert_eq!(
+    ///     r"\begin{prooftree}{}
+    /// [{\neg (a \lor \neg a)}
+    ///   [{\neg a}, just={$\alpha$}
+    ///     [{\neg \neg a}, close
+    ///     ]
+    ///   ]
+    /// ]
+    /// \end{prooftree}
+    /// ",
+    ///     proof.to_latex()
+    /// );
+    /// ```
+    ///
+    /// [latex]: ../../formula/propositional_formula/enum.PropositionalFormula.html#method.to_latex
+    pub fn to_latex(&self) -> String {
+        let mut children: HashMap<usize, Vec<&ProofStep>> = HashMap::new();
+        for step in &self.steps {
+            children.entry(step.parent).or_default().push(step);
+        }
+
+        let mut latex = "\\begin{prooftree}{}\n".to_string();
+        write_latex_nodes(&mut latex, &[self.root()], None, 0, &children, 0);
+        latex.push_str("\\end{prooftree}\n");
+        latex
+    }
+
+    /// Read a proof in the JSON format described in the [module documentation](index.html).
+    ///
+    /// # Errors
+    ///
+    /// Fails if the input is not well-formed JSON, or does not have the structure of a proof. The
+    /// proof itself is not checked, see [`check_proof`].
+    ///
+    /// [`check_proof`]: fn.check_proof.html
+    pub fn from_json(input: &str) -> Result<Self, String> {
+        let json = parse_json(input)?;
+
+        fn field<'a>(json: &'a Json, key: &str) -> Result<&'a Json, String> {
+            json.get(key)
+                .ok_or_else(|| format!("missing field {:?}", key))
+        }
+        fn formula(json: &Json) -> Result<PropositionalFormula, String> {
+            let formula = json
+                .as_str()
+                .ok_or_else(|| "formulas must be strings".to_string())?;
+            crate::parser::parse(formula).map_err(|e| format!("{}: {:?}", e, formula))
+        }
+        fn number(json: &Json, key: &str) -> Result<usize, String> {
+            field(json, key)?
+                .as_usize()
+                .ok_or_else(|| format!("{:?} must be a non-negative integer", key))
+        }
+
+        let claim = match field(&json, "claim")?.as_str() {
+            Some("unsatisfiable") => Claim::Unsatisfiable,
+            Some("valid") => Claim::Valid,
+            _ => return Err("\"claim\" must be \"unsatisfiable\" or \"valid\"".to_string()),
+        };
+
+        let steps = field(&json, "steps")?
+            .as_array()
+            .ok_or_else(|| "\"steps\" must be an array".to_string())?
+            .iter()
+            .map(|step| {
+                let rule = match field(step, "rule")?.as_str() {
+                    Some("alpha") => ProofRule::Alpha,
+                    Some("beta1") => ProofRule::Beta(1),
+                    Some("beta2") => ProofRule::Beta(2),
+                    Some("close") => ProofRule::Close,
+                    _ => return Err("unknown rule".to_string()),
+                };
+                let added = field(step, "added")?
+                    .as_array()
+                    .ok_or_else(|| "\"added\" must be an array".to_string())?
+                    .iter()
+                    .map(formula)
+                    .collect::<Result<_, _>>()?;
+
+                Ok(ProofStep {
+                    id: number(step, "id")?,
+                    parent: number(step, "parent")?,
+                    rule,
+                    formula: formula(field(step, "formula")?)?,
+                    added,
+                })
+            })
+            .collect::<Result<_, String>>()?;
+
+        Ok(Self {
+            formula: formula(field(&json, "formula")?)?,
+            claim,
+            steps,
+        })
+    }
+}
+
+/// Write the nodes of the `formulas` added by the step `id` as a chain, followed by the nodes of
+/// the steps extending its branch, where `steps` maps step ids to the steps extending them.
+fn write_latex_nodes(
+    latex: &mut String,
+    formulas: &[PropositionalFormula],
+    rule: Option<ProofRule>,
+    id: usize,
+    steps: &HashMap<usize, Vec<&ProofStep>>,
+    depth: usize,
+) {
+    let children = steps.get(&id).map_or(&[][..], Vec::as_slice);
+    let closed = children.iter().any(|step| step.rule == ProofRule::Close);
+
+    for (i, formula) in formulas.iter().enumerate() {
+        latex.push_str(&"  ".repeat(depth + i));
+        latex.push_str(&format!("[{{{}}}", formula.to_latex()));
+        match rule {
+            Some(ProofRule::Alpha) if i == 0 => latex.push_str(", just={$\\alpha$}"),
+            Some(ProofRule::Beta(_)) if i == 0 => latex.push_str(", just={$\\beta$}"),
+            _ => {}
+        }
+        if closed && i + 1 == formulas.len() {
+            latex.push_str(", close");
+        }
+        latex.push('\n');
+    }
+
+    for step in children.iter().filter(|step| step.rule != ProofRule::Close) {
+        write_latex_nodes(
+            latex,
+            &step.added,
+            Some(step.rule),
+            step.id,
+            steps,
+            depth + formulas.len(),
+        );
+    }
+
+    for i in (0..formulas.len()).rev() {
+        latex.push_str(&"  ".repeat(depth + i));
+        latex.push_str("]\n");
+    }
+}
+
+/// Checks the proof without searching for one, i.e. that every step applies its rule correctly
+/// to a formula on its branch and that every branch of the tableau is closed.
+///
+/// # Errors
+///
+/// Describes the first problem found, naming the offending step.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::tableaux_solver::proof::{check_proof, prove_unsatisfiable};
+/// let formula = "((a|b)^((-a)^(-b)))".parse().unwrap();
+///
+/// let mut proof = prove_unsatisfiable(&formula).unwrap();
+/// assert_eq!(Ok(()), check_proof(&proof));
+///
+/// // Dropping the closure of a branch leaves it open.
+/// proof.steps.pop();
+/// assert!(check_proof(&proof).is_err());
+/// ```
+pub fn check_proof(proof: &Proof) -> Result<(), String> {
+    // The formulas on the branch ending at each step, with `0` for the root.
+    let mut branches: HashMap<usize, HashSet<PropositionalFormula>> = HashMap::new();
+    branches.insert(0, std::iter::once(proof.root()).collect());
+
+    let mut children: HashMap<usize, Vec<&ProofStep>> = HashMap::new();
+    let mut closed = HashSet::new();
+
+    for step in &proof.steps {
+        let error = |message: &str| Err(format!("step {}: {}", step.id, message));
+
+        if step.id == 0 || branches.contains_key(&step.id) {
+            return error("duplicate or zero id");
+        }
+        let branch = match branches.get(&step.parent) {
+            Some(branch) => branch,
+            None => return error("parent is not an earlier step"),
+        };
+        if closed.contains(&step.parent) {
+            return error("parent branch is already closed");
+        }
+        if !branch.contains(&step.formula) {
+            return error("formula is not on the branch");
+        }
+
+        match step.rule {
+            ProofRule::Close => {
+                let negation = PropositionalFormula::negated(Box::new(step.formula.clone()));
+                if !is_false(&step.formula) && !branch.contains(&negation) {
+                    return error("negation of the formula is not on the branch");
+                }
+                if !step.added.is_empty() {
+                    return error("close steps add no formulas");
+                }
+                closed.insert(step.id);
+            }
+            rule => {
+                let expected = match (rule, expand_non_literal_formula(&step.formula)) {
+                    (ProofRule::Alpha, Some(ExpansionKind::Alpha(alpha_1, alpha_2))) => {
+                        std::iter::once(alpha_1)
+                            .chain(alpha_2)
+                            .map(|a| *a)
+                            .collect()
+                    }
+                    (ProofRule::Beta(1), Some(ExpansionKind::Beta(beta_1, _))) => vec![*beta_1],
+                    (ProofRule::Beta(_), Some(ExpansionKind::Beta(_, beta_2))) => vec![*beta_2],
+                    _ => return error("rule does not apply to the formula"),
+                };
+                if step.added != expected {
+                    return error("added formulas are not the results of the rule");
+                }
+            }
+        }
+
+        let mut extended = branch.clone();
+        extended.extend(step.added.iter().cloned());
+        branches.insert(step.id, extended);
+        children.entry(step.parent).or_default().push(step);
+    }
+
+    for id in branches.keys() {
+        if !closed.contains(id) && !children.contains_key(id) {
+            return Err(if *id == 0 {
+                "the root branch is open".to_string()
+            } else {
+                format!("step {}: branch is open", id)
+            });
+        }
+    }
+
+    // Every branch is extended, but an extension by a single β result may still leave the branch
+    // of the other result open.
+    for step in &proof.steps {
+        if let ProofRule::Beta(branch) = step.rule {
+            let other = ProofRule::Beta(3 - branch);
+            let has_sibling = children[&step.parent]
+                .iter()
+                .any(|sibling| sibling.rule == other && sibling.formula == step.formula);
+            if !has_sibling {
+                return Err(format!(
+                    "step {}: β expansion without its other branch",
+                    step.id
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Search for a proof that the formula is unsatisfiable, returning `None` if it is satisfiable.
+///
+/// Branches are expanded depth-first, applying α rules before β rules, until they close or
+/// consist of variables and their negations only.
+pub fn prove_unsatisfiable(formula: &PropositionalFormula) -> Option<Proof> {
+    Some(Proof {
+        formula: formula.clone(),
+        claim: Claim::Unsatisfiable,
+        steps: close_tableau(formula.clone())?,
+    })
+}
+
+/// Search for a proof that the formula is valid, i.e. that its negation is unsatisfiable,
+/// returning `None` if it is not valid.
+pub fn prove_valid(formula: &PropositionalFormula) -> Option<Proof> {
+    let negation = PropositionalFormula::negated(Box::new(formula.clone()));
+    Some(Proof {
+        formula: formula.clone(),
+        claim: Claim::Valid,
+        steps: close_tableau(negation)?,
+    })
+}
+
+/// Size of the complete tableau of a formula, see [`tableau_shape`].
+///
+/// [`tableau_shape`]: fn.tableau_shape.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TableauShape {
+    /// Number of branches, i.e. leaves of the tableau.
+    pub branches: usize,
+    /// Number of branches which close.
+    pub closed: usize,
+    /// Number of branches which stay open, each giving a model of the formula.
+    pub open: usize,
+    /// Number of steps of the tableau, counted like the steps of a [`Proof`]. For an
+    /// unsatisfiable formula this is the size of the proof found by [`prove_unsatisfiable`].
+    ///
+    /// [`Proof`]: struct.Proof.html
+    /// [`prove_unsatisfiable`]: fn.prove_unsatisfiable.html
+    pub size: usize,
+}
+
+/// Expand the tableau of the formula completely, without stopping at the first open branch, and
+/// measure it, e.g. to judge how hard the formula is to refute by hand.
+///
+/// The tableau is expanded like by [`prove_unsatisfiable`], so the shape is reproducible; the
+/// tableau of a valid formula is that of its negation. This takes time linear in the size of the
+/// complete tableau, which can be exponential in the size of the formula.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::tableaux_solver::proof::tableau_shape;
+/// let shape = tableau_shape(&"((a|b)^(-a))".parse().unwrap());
+/// assert_eq!((2, 1, 1), (shape.branches, shape.closed, shape.open));
+/// // α on the conjunction, β1 and β2 on the disjunction, and one close step.
+/// assert_eq!(4, shape.size);
+/// ```
+///
+/// [`prove_unsatisfiable`]: fn.prove_unsatisfiable.html
+pub fn tableau_shape(formula: &PropositionalFormula) -> TableauShape {
+    let (steps, open) = expand_tableau(formula.clone(), false);
+    let closed = steps
+        .iter()
+        .filter(|step| step.rule == ProofRule::Close)
+        .count();
+
+    TableauShape {
+        branches: closed + open,
+        closed,
+        open,
+        size: steps.len(),
+    }
+}
+
+/// A branch of the tableau being closed.
+struct Branch {
+    /// The step the branch ends at.
+    id: usize,
+    formulas: HashSet<PropositionalFormula>,
+    /// The formulas of the branch in the order they were added, for reproducible proofs.
+    order: Vec<PropositionalFormula>,
+    /// Formulas of the branch which have not been expanded yet.
+    pending: Vec<PropositionalFormula>,
+}
+
+impl Branch {
+    fn extend(&self, id: usize, added: &[PropositionalFormula]) -> Self {
+        let mut branch = Self {
+            id,
+            formulas: self.formulas.clone(),
+            order: self.order.clone(),
+            pending: self.pending.clone(),
+        };
+        for formula in added {
+            if branch.formulas.insert(formula.clone()) {
+                branch.order.push(formula.clone());
+                branch.pending.push(formula.clone());
+            }
+        }
+        branch
+    }
+}
+
+/// Checks if the formula is the constant `0` or `(-1)`, closing any branch holding it.
+fn is_false(formula: &PropositionalFormula) -> bool {
+    match formula {
+        PropositionalFormula::Bottom => true,
+        PropositionalFormula::Negation(f) => **f == PropositionalFormula::Top,
+        _ => false,
+    }
+}
+
+fn close_tableau(root: PropositionalFormula) -> Option<Vec<ProofStep>> {
+    match expand_tableau(root, true) {
+        (steps, 0) => Some(steps),
+        _ => None,
+    }
+}
+
+/// Expand the tableau of `root`, returning its steps and the number of open branches. If
+/// `stop_at_open` is set, the expansion stops at the first open branch.
+fn expand_tableau(root: PropositionalFormula, stop_at_open: bool) -> (Vec<ProofStep>, usize) {
+    let mut steps = Vec::new();
+    let mut open = 0;
+    let mut stack = vec![Branch {
+        id: 0,
+        formulas: std::iter::once(root.clone()).collect(),
+        order: vec![root.clone()],
+        pending: vec![root],
+    }];
+
+    while let Some(mut branch) = stack.pop() {
+        let contradiction = branch.order.iter().find(|formula| {
+            is_false(formula)
+                || branch
+                    .formulas
+                    .contains(&PropositionalFormula::negated(Box::new((*formula).clone())))
+        });
+        if let Some(formula) = contradiction {
+            steps.push(ProofStep {
+                id: steps.len() + 1,
+                parent: branch.id,
+                rule: ProofRule::Close,
+                formula: formula.clone(),
+                added: Vec::new(),
+            });
+            continue;
+        }
+
+        // Prefer α expansions, which do not split the branch.
+        let expansions: Vec<_> = branch
+            .pending
+            .iter()
+            .enumerate()
+            .filter_map(|(i, formula)| Some((i, expand_non_literal_formula(formula)?)))
+            .collect();
+        let (i, expansion) = match expansions
+            .iter()
+            .find(|(_, expansion)| matches!(expansion, ExpansionKind::Alpha(..)))
+            .or_else(|| expansions.first())
+        {
+            Some((i, expansion)) => (*i, expansion.clone()),
+            // Only literals are left, without complementary pairs or false constants.
+            None => {
+                open += 1;
+                if stop_at_open {
+                    break;
+                }
+                continue;
+            }
+        };
+        let formula = branch.pending.remove(i);
+
+        match expansion {
+            ExpansionKind::Alpha(alpha_1, alpha_2) => {
+                let added: Vec<_> = std::iter::once(alpha_1)
+                    .chain(alpha_2)
+                    .map(|a| *a)
+                    .collect();
+                let id = steps.len() + 1;
+                stack.push(branch.extend(id, &added));
+                steps.push(ProofStep {
+                    id,
+                    parent: branch.id,
+                    rule: ProofRule::Alpha,
+                    formula,
+                    added,
+                });
+            }
+            ExpansionKind::Beta(beta_1, beta_2) => {
+                for (rule, beta) in &[(ProofRule::Beta(1), *beta_1), (ProofRule::Beta(2), *beta_2)]
+                {
+                    let id = steps.len() + 1;
+                    stack.push(branch.extend(id, std::slice::from_ref(beta)));
+                    steps.push(ProofStep {
+                        id,
+                        parent: branch.id,
+                        rule: *rule,
+                        formula: formula.clone(),
+                        added: vec![beta.clone()],
+                    });
+                }
+            }
+        }
+    }
+
+    (steps, open)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::formulas;
+    use crate::truth_table;
+    use assert2::check;
+
+    #[test]
+    fn test_proofs_check() {
+        for formula in formulas(3, 4, 31, 100) {
+            let proof = prove_unsatisfiable(&formula);
+            check!(proof.is_some() == !truth_table::is_satisfiable(&formula));
+
+            let proof = prove_valid(&formula);
+            check!(proof.is_some() == truth_table::is_valid(&formula));
+
+            if let Some(proof) = proof {
+                check!(check_proof(&proof) == Ok(()));
+
+                let parsed = Proof::from_json(&proof.to_json());
+                check!(parsed == Ok(proof));
+            }
+        }
+    }
+
+    #[test]
+    fn test_tableau_shape() {
+        for formula in formulas(3, 4, 32, 100) {
+            let shape = tableau_shape(&formula);
+            check!(shape.branches == shape.closed + shape.open);
+            check!((shape.open > 0) == truth_table::is_satisfiable(&formula));
+
+            if let Some(proof) = prove_unsatisfiable(&formula) {
+                check!(shape.size == proof.steps.len());
+            }
+        }
+    }
+
+    #[test]
+    fn test_constants() {
+        for formula in &["((a->0)^a)", "(-1)", "(a^(-(-0)))"] {
+            let proof = prove_unsatisfiable(&formula.parse().unwrap()).unwrap();
+            check!(check_proof(&proof) == Ok(()));
+        }
+
+        let proof = prove_valid(&"(a|1)".parse().unwrap()).unwrap();
+        check!(check_proof(&proof) == Ok(()));
+        check!(prove_unsatisfiable(&"(a^1)".parse().unwrap()).is_none());
+    }
+
+    #[test]
+    fn test_invalid_proofs() {
+        let formula: PropositionalFormula = "((a|b)^((-a)^(-b)))".parse().unwrap();
+        let proof = prove_unsatisfiable(&formula).unwrap();
+
+        // Claiming validity changes the root of the tableau.
+        let mut wrong_claim = proof.clone();
+        wrong_claim.claim = Claim::Valid;
+        check!(check_proof(&wrong_claim).is_err());
+
+        // Dropping one side of a β expansion.
+        let mut one_sided = proof.clone();
+        let beta_2 = one_sided
+            .steps
+            .iter()
+            .position(|step| step.rule == ProofRule::Beta(2))
+            .unwrap();
+        let removed = one_sided.steps.remove(beta_2);
+        one_sided.steps.retain(|step| step.parent != removed.id);
+        check!(check_proof(&one_sided).is_err());
+
+        // Closing on a literal whose negation is not on the branch.
+        let mut bogus_closure = proof;
+        let close = bogus_closure
+            .steps
+            .iter_mut()
+            .find(|step| step.rule == ProofRule::Close)
+            .unwrap();
+        close.formula = "c".parse().unwrap();
+        check!(check_proof(&bogus_closure).is_err());
+    }
+
+    #[test]
+    fn test_deeply_nested_json() {
+        check!(Proof::from_json(&"[".repeat(200_000)).is_err());
+        check!(Proof::from_json(&"{\"steps\":".repeat(200_000)).is_err());
+    }
+}
@@ -0,0 +1,310 @@
+//! The α/β expansion rules of the tableau, as data.
+//!
+//! Each rule looks at a non-literal formula and returns its [`ExpansionKind`] if it applies. A
+//! [`RuleSet`] tries its rules in order and expands a formula by the first one which applies, so
+//! rules can be added or replaced (see [`Solver::with_rules`]) without touching the solver
+//! loop.
+//!
+//! [`ExpansionKind`]: ../enum.ExpansionKind.html
+//! [`RuleSet`]: struct.RuleSet.html
+//! [`Solver::with_rules`]: ../struct.Solver.html#method.with_rules
+
+use std::fmt;
+
+use crate::formula::PropositionalFormula;
+
+use super::{expand_biimplication_chain, ExpansionKind};
+
+/// An expansion rule, returning the expansion of the formula or `None` if it does not apply.
+pub type Rule = Box<dyn Fn(&PropositionalFormula) -> Option<ExpansionKind> + Send + Sync>;
+
+/// A [`Rule`] which is a plain function, like the standard rules.
+///
+/// [`Rule`]: type.Rule.html
+pub type RuleFn = fn(&PropositionalFormula) -> Option<ExpansionKind>;
+
+/// The rules of the propositional tableau, by name, in the order they are tried.
+///
+/// Chains of biimplications come first, since `(A<->(B<->C))` would otherwise be expanded by the
+/// regular `biimplication` rule, see [`ExpansionKind::Beta`].
+///
+/// [`ExpansionKind::Beta`]: ../enum.ExpansionKind.html#variant.Beta
+pub const STANDARD_RULES: &[(&str, RuleFn)] = &[
+    ("biimplication-chain", expand_biimplication_chain),
+    ("conjunction", conjunction),
+    ("biimplication", biimplication),
+    ("disjunction", disjunction),
+    ("implication", implication),
+    ("double-negation", double_negation),
+    ("negated-disjunction", negated_disjunction),
+    ("negated-conjunction", negated_conjunction),
+    ("negated-implication", negated_implication),
+    ("negated-biimplication", negated_biimplication),
+];
+
+/// An ordered, named set of expansion rules consumed by the tableau.
+///
+/// Every non-literal formula must be expanded by some rule of the set, otherwise the tableau
+/// cannot make progress and panics. Rules should also preserve satisfiability: the expanded
+/// formula must be satisfiable iff its α results are jointly satisfiable, or iff either of its β
+/// results is.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::formula::PropositionalFormula;
+/// use libprop_sat_solver::tableaux_solver::rules::RuleSet;
+/// use libprop_sat_solver::tableaux_solver::{ExpansionKind, Solver};
+/// // Expand `(A->B)` with `B` as the first branch instead of `(-A)`.
+/// let rules = RuleSet::standard().with_rule("implication", |formula: &PropositionalFormula| {
+///     match formula {
+///         PropositionalFormula::Implication(a, b) => Some(ExpansionKind::Beta(
+///             b.clone(),
+///             Box::new(PropositionalFormula::negated(a.clone())),
+///         )),
+///         _ => None,
+///     }
+/// });
+///
+/// let solver = Solver::new().with_rules(&rules);
+/// assert_eq!(Ok(true), solver.solve(&"(a->b)".parse().unwrap(), |_, _| {}));
+/// assert_eq!(Ok(false), solver.solve(&"((a->b)^(a^(-b)))".parse().unwrap(), |_, _| {}));
+/// ```
+pub struct RuleSet {
+    rules: Vec<(String, Rule)>,
+}
+
+impl RuleSet {
+    /// A rule set without any rules, expanding nothing.
+    pub fn empty() -> Self {
+        Self { rules: Vec::new() }
+    }
+
+    /// The rules of the propositional tableau, see [`STANDARD_RULES`].
+    ///
+    /// [`STANDARD_RULES`]: constant.STANDARD_RULES.html
+    pub fn standard() -> Self {
+        Self {
+            rules: STANDARD_RULES
+                .iter()
+                .map(|&(name, rule)| (name.to_string(), Box::new(rule) as Rule))
+                .collect(),
+        }
+    }
+
+    /// Replace the rule with the given name, keeping its position, or add the rule in front of
+    /// all others (i.e. with the highest priority) if there is none.
+    pub fn with_rule<F>(mut self, name: &str, rule: F) -> Self
+    where
+        F: Fn(&PropositionalFormula) -> Option<ExpansionKind> + Send + Sync + 'static,
+    {
+        match self.rules.iter_mut().find(|(existing, _)| existing == name) {
+            Some((_, existing)) => *existing = Box::new(rule),
+            None => self.rules.insert(0, (name.to_string(), Box::new(rule))),
+        }
+        self
+    }
+
+    /// Remove the rule with the given name, if any.
+    pub fn without_rule(mut self, name: &str) -> Self {
+        self.rules.retain(|(existing, _)| existing != name);
+        self
+    }
+
+    /// The names of the rules, in the order they are tried.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.rules.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Expand the formula by the first rule which applies to it, if any.
+    pub fn expand(&self, formula: &PropositionalFormula) -> Option<ExpansionKind> {
+        self.rules.iter().find_map(|(_, rule)| rule(formula))
+    }
+}
+
+impl Default for RuleSet {
+    /// The [`standard`] rules.
+    ///
+    /// [`standard`]: #method.standard
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+impl fmt::Debug for RuleSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.names()).finish()
+    }
+}
+
+/// `(A^B)` => α(`A`, `B`).
+fn conjunction(formula: &PropositionalFormula) -> Option<ExpansionKind> {
+    match formula {
+        PropositionalFormula::Conjunction(a, b) => {
+            Some(ExpansionKind::Alpha(a.clone(), Some(b.clone())))
+        }
+        _ => None,
+    }
+}
+
+/// `(A<->B)` => α(`(A->B)`, `(B->A)`).
+fn biimplication(formula: &PropositionalFormula) -> Option<ExpansionKind> {
+    match formula {
+        PropositionalFormula::Biimplication(a, b) => {
+            let alpha_1 = PropositionalFormula::implication(a.clone(), b.clone());
+            let alpha_2 = PropositionalFormula::implication(b.clone(), a.clone());
+            Some(ExpansionKind::Alpha(
+                Box::new(alpha_1),
+                Some(Box::new(alpha_2)),
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// `(A|B)` => β(`A`, `B`).
+fn disjunction(formula: &PropositionalFormula) -> Option<ExpansionKind> {
+    match formula {
+        PropositionalFormula::Disjunction(a, b) => Some(ExpansionKind::Beta(a.clone(), b.clone())),
+        _ => None,
+    }
+}
+
+/// `(A->B)` => β(`(-A)`, `B`).
+fn implication(formula: &PropositionalFormula) -> Option<ExpansionKind> {
+    match formula {
+        PropositionalFormula::Implication(a, b) => {
+            let beta_1 = PropositionalFormula::negated(a.clone());
+            Some(ExpansionKind::Beta(Box::new(beta_1), b.clone()))
+        }
+        _ => None,
+    }
+}
+
+/// The operand of a negation, if the formula is one.
+fn negated(formula: &PropositionalFormula) -> Option<&PropositionalFormula> {
+    match formula {
+        PropositionalFormula::Negation(f) => Some(f),
+        _ => None,
+    }
+}
+
+/// `(-(-A))` => α(`A`).
+fn double_negation(formula: &PropositionalFormula) -> Option<ExpansionKind> {
+    match negated(formula)? {
+        PropositionalFormula::Negation(a) => Some(ExpansionKind::Alpha(a.clone(), None)),
+        _ => None,
+    }
+}
+
+/// `(-(A|B))` => α(`(-A)`, `(-B)`).
+fn negated_disjunction(formula: &PropositionalFormula) -> Option<ExpansionKind> {
+    match negated(formula)? {
+        PropositionalFormula::Disjunction(a, b) => {
+            let alpha_1 = PropositionalFormula::negated(a.clone());
+            let alpha_2 = PropositionalFormula::negated(b.clone());
+            Some(ExpansionKind::Alpha(
+                Box::new(alpha_1),
+                Some(Box::new(alpha_2)),
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// `(-(A^B))` => β(`(-A)`, `(-B)`).
+fn negated_conjunction(formula: &PropositionalFormula) -> Option<ExpansionKind> {
+    match negated(formula)? {
+        PropositionalFormula::Conjunction(a, b) => {
+            let beta_1 = PropositionalFormula::negated(a.clone());
+            let beta_2 = PropositionalFormula::negated(b.clone());
+            Some(ExpansionKind::Beta(Box::new(beta_1), Box::new(beta_2)))
+        }
+        _ => None,
+    }
+}
+
+/// `(-(A->B))` => α(`A`, `(-B)`).
+fn negated_implication(formula: &PropositionalFormula) -> Option<ExpansionKind> {
+    match negated(formula)? {
+        PropositionalFormula::Implication(a, b) => {
+            let alpha_2 = PropositionalFormula::negated(b.clone());
+            Some(ExpansionKind::Alpha(a.clone(), Some(Box::new(alpha_2))))
+        }
+        _ => None,
+    }
+}
+
+/// `(-(A<->B))` => β(`(A^(-B))`, `(B^(-A))`).
+fn negated_biimplication(formula: &PropositionalFormula) -> Option<ExpansionKind> {
+    match negated(formula)? {
+        PropositionalFormula::Biimplication(a, b) => {
+            let beta_1 = PropositionalFormula::conjunction(
+                a.clone(),
+                Box::new(PropositionalFormula::negated(b.clone())),
+            );
+            let beta_2 = PropositionalFormula::conjunction(
+                b.clone(),
+                Box::new(PropositionalFormula::negated(a.clone())),
+            );
+            Some(ExpansionKind::Beta(Box::new(beta_1), Box::new(beta_2)))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tableaux_solver::Solver;
+    use crate::testing::{assert_agrees_with_truth_table, formulas};
+    use assert2::check;
+
+    #[test]
+    fn test_rule_set_edits() {
+        let rules = RuleSet::standard()
+            .without_rule("double-negation")
+            .with_rule("implication", implication)
+            .with_rule("custom", |_: &PropositionalFormula| None);
+
+        let names: Vec<_> = rules.names().collect();
+        check!(names.len() == STANDARD_RULES.len());
+        check!(names[0] == "custom");
+        check!(!names.contains(&"double-negation"));
+        check!(rules.expand(&"(-(-a))".parse().unwrap()).is_none());
+        check!(RuleSet::empty().expand(&"(a^b)".parse().unwrap()).is_none());
+    }
+
+    #[test]
+    fn test_standard_rules_are_exhaustive() {
+        let rules = RuleSet::standard();
+        for formula in formulas(4, 4, 33, 50) {
+            for sub_formula in formula.iter_preorder() {
+                check!(sub_formula.is_literal() || rules.expand(sub_formula).is_some());
+            }
+        }
+    }
+
+    #[test]
+    fn test_de_morgan_rules_agree_with_standard_rules() {
+        // Rewrite negated conjunctions into disjunctions instead of expanding them directly.
+        let rules = RuleSet::standard().with_rule(
+            "negated-conjunction",
+            |formula: &PropositionalFormula| match negated(formula)? {
+                PropositionalFormula::Conjunction(a, b) => Some(ExpansionKind::Alpha(
+                    Box::new(PropositionalFormula::disjunction(
+                        Box::new(PropositionalFormula::negated(a.clone())),
+                        Box::new(PropositionalFormula::negated(b.clone())),
+                    )),
+                    None,
+                )),
+                _ => None,
+            },
+        );
+
+        let solver = Solver::new().with_rules(&rules);
+        assert_agrees_with_truth_table(formulas(4, 4, 34, 100), |formula| {
+            solver.solve(formula, |_, _| {}) == Ok(true)
+        });
+    }
+}
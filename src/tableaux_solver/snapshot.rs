@@ -0,0 +1,102 @@
+//! Snapshots of a `Tableau` in JSON or Graphviz DOT format, for inspecting where a long-running
+//! search is stuck.
+//!
+//! The formulas of each `Theory` are listed in lexicographic order of their textual form, so that
+//! snapshots of the same tableau are identical.
+
+use super::{Tableau, Theory};
+
+/// Render the tableau as a JSON object of the form
+///
+/// ```json
+/// { "theories": [ ["(a|b)", "c"], ["d"] ] }
+/// ```
+///
+/// with the theories in queue order.
+pub fn to_json(tableau: &Tableau) -> String {
+    let theories: Vec<String> = tableau
+        .theories()
+        .map(|theory| {
+            let formulas: Vec<String> = sorted_formulas(theory)
+                .iter()
+                .map(|formula| json_string(formula))
+                .collect();
+            format!("[{}]", formulas.join(", "))
+        })
+        .collect();
+
+    format!("{{ \"theories\": [{}] }}\n", theories.join(", "))
+}
+
+/// Render the tableau as a Graphviz DOT graph, with one box per theory linked in queue order.
+pub fn to_dot(tableau: &Tableau) -> String {
+    let mut dot = String::from("digraph tableau {\n    rankdir=LR;\n    node [shape=box];\n");
+
+    for (i, theory) in tableau.theories().enumerate() {
+        let label: Vec<String> = sorted_formulas(theory)
+            .iter()
+            .map(|formula| escape(formula))
+            .collect();
+        dot.push_str(&format!("    t{} [label=\"{}\"];\n", i, label.join("\\n")));
+
+        if i > 0 {
+            dot.push_str(&format!("    t{} -> t{};\n", i - 1, i));
+        }
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+fn sorted_formulas(theory: &Theory) -> Vec<String> {
+    let mut formulas: Vec<String> = theory.formulas().map(|f| f.to_string()).collect();
+    formulas.sort();
+    formulas
+}
+
+fn json_string(s: &str) -> String {
+    format!("\"{}\"", escape(s))
+}
+
+/// Escape backslashes and double quotes, which is enough for both JSON and DOT strings since
+/// formulas contain no control characters.
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formula::{PropositionalFormula, Variable};
+    use assert2::check;
+
+    fn tableau() -> Tableau {
+        let mut theory =
+            Theory::from_propositional_formula(PropositionalFormula::variable(Variable::new("c")));
+        theory.add(PropositionalFormula::disjunction(
+            Box::new(PropositionalFormula::variable(Variable::new("a"))),
+            Box::new(PropositionalFormula::variable(Variable::new("b"))),
+        ));
+
+        let mut tableau = Tableau::new();
+        tableau.push_theory(theory);
+        tableau.push_theory(Theory::from_propositional_formula(
+            PropositionalFormula::variable(Variable::new("d")),
+        ));
+        tableau
+    }
+
+    #[test]
+    fn test_to_json() {
+        check!(to_json(&tableau()) == "{ \"theories\": [[\"(a|b)\", \"c\"], [\"d\"]] }\n");
+    }
+
+    #[test]
+    fn test_to_dot() {
+        check!(
+            to_dot(&tableau())
+                == "digraph tableau {\n    rankdir=LR;\n    node [shape=box];\n    \
+                    t0 [label=\"(a|b)\\nc\"];\n    t1 [label=\"d\"];\n    t0 -> t1;\n}\n"
+        );
+    }
+}
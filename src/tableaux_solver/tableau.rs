@@ -2,11 +2,55 @@
 //! tableau tree, where each `Theory` is a branch (from the root node to each leaf).
 
 use std::collections::VecDeque;
+use std::fmt;
+use std::str::FromStr;
 
 use crate::formula::PropositionalFormula;
 
 use super::Theory;
 
+/// Order in which a [`Tableau`] hands out its theories for expansion.
+///
+/// [`Tableau`]: struct.Tableau.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SearchStrategy {
+    /// First in, first out: all branches are expanded a step at a time, so the queue holds a whole
+    /// level of the tableau at once.
+    #[default]
+    BreadthFirst,
+    /// Last in, first out: a branch is expanded to the end before its siblings, so the queue only
+    /// grows with the depth of the tableau.
+    DepthFirst,
+    /// The theory with the fewest non-literal formulas left first, i.e. the branch closest to being
+    /// fully expanded, ties broken first in, first out. Every retrieval scans the whole queue.
+    BestFirst,
+}
+
+impl FromStr for SearchStrategy {
+    type Err = String;
+
+    /// Parse the name of a strategy, or one of the short names `bfs`, `dfs` and `best`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_ref() {
+            "breadth-first" | "bfs" => Ok(Self::BreadthFirst),
+            "depth-first" | "dfs" => Ok(Self::DepthFirst),
+            "best-first" | "best" => Ok(Self::BestFirst),
+            _ => Err(format!("unknown search strategy: {:?}", s)),
+        }
+    }
+}
+
+impl fmt::Display for SearchStrategy {
+    /// The name of the strategy, as accepted by [`FromStr`](#impl-FromStr).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::BreadthFirst => "breadth-first",
+            Self::DepthFirst => "depth-first",
+            Self::BestFirst => "best-first",
+        })
+    }
+}
+
 /// A `Tableau` is a collection of `Theory`-ies. This corresponds to the entire propositional
 /// tableau tree, where each `Theory` is a branch (from the root node to each leaf).
 ///
@@ -22,24 +66,34 @@ use super::Theory;
 ///
 /// 1. `{ (a^b), a }`
 /// 2. `{ (a^b), b }`
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Default)]
 pub struct Tableau {
     theories: VecDeque<Theory>,
+    strategy: SearchStrategy,
 }
 
 impl Tableau {
     /// Construct a new `Tableau` with no theories.
     pub fn new() -> Self {
-        Self {
-            theories: VecDeque::new(),
-        }
+        Self::default()
     }
 
     /// Construct a `Tableau` with the starting root node being the given propositional formula.
     pub fn from_starting_propositional_formula(formula: PropositionalFormula) -> Self {
-        let mut theories = VecDeque::new();
-        theories.push_back(Theory::from_propositional_formula(formula));
-        Self { theories }
+        let mut tableau = Self::new();
+        tableau.push_theory(Theory::from_propositional_formula(formula));
+        tableau
+    }
+
+    /// Set the order in which theories are retrieved, breadth-first by default.
+    pub fn with_strategy(mut self, strategy: SearchStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Get the order in which theories are retrieved.
+    pub fn strategy(&self) -> SearchStrategy {
+        self.strategy
     }
 
     /// Check if the `Tableau` contains no `Theory`-ies.
@@ -47,9 +101,33 @@ impl Tableau {
         self.theories.is_empty()
     }
 
-    /// Retrieve a `Theory` from the `Tableau`.
+    /// Get the theories, in the order in which they were added (which is the order in which they
+    /// will be retrieved breadth-first).
+    pub fn theories(&self) -> impl Iterator<Item = &Theory> {
+        self.theories.iter()
+    }
+
+    /// Number of theories in the `Tableau`.
+    pub fn len(&self) -> usize {
+        self.theories.len()
+    }
+
+    /// Retrieve a `Theory` from the `Tableau`, the next one by its [`SearchStrategy`].
+    ///
+    /// [`SearchStrategy`]: enum.SearchStrategy.html
     pub fn pop_theory(&mut self) -> Option<Theory> {
-        self.theories.pop_front()
+        match self.strategy {
+            SearchStrategy::BreadthFirst => self.theories.pop_front(),
+            SearchStrategy::DepthFirst => self.theories.pop_back(),
+            SearchStrategy::BestFirst => {
+                let (best, _) = self
+                    .theories
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(i, theory)| (theory.non_literal_formulas().count(), *i))?;
+                self.theories.remove(best)
+            }
+        }
     }
 
     /// Add a `Theory` to the `Tableau`.
@@ -131,6 +209,43 @@ mod tests {
         check!(tab.is_empty());
     }
 
+    #[test]
+    fn test_strategies() {
+        let var = |name| PropositionalFormula::variable(Variable::new(name));
+        let theories = [
+            Theory::from_propositional_formula(PropositionalFormula::negated(Box::new(var("a")))),
+            Theory::from_propositional_formula(PropositionalFormula::conjunction(
+                Box::new(var("b")),
+                Box::new(var("c")),
+            )),
+            Theory::from_propositional_formula(var("d")),
+        ];
+        let order = |strategy| {
+            let mut tab = Tableau::new().with_strategy(strategy);
+            for theory in &theories {
+                tab.push_theory(theory.clone());
+            }
+            std::iter::from_fn(|| tab.pop_theory()).collect::<Vec<_>>()
+        };
+
+        check!(order(SearchStrategy::BreadthFirst) == theories.to_vec());
+        check!(
+            order(SearchStrategy::DepthFirst) == theories.iter().rev().cloned().collect::<Vec<_>>()
+        );
+        check!(
+            order(SearchStrategy::BestFirst)
+                == vec![
+                    theories[0].clone(),
+                    theories[2].clone(),
+                    theories[1].clone()
+                ]
+        );
+        check!("Depth-First".parse() == Ok(SearchStrategy::DepthFirst));
+        check!("dfs".parse() == Ok(SearchStrategy::DepthFirst));
+        check!("bfs".parse() == Ok(SearchStrategy::BreadthFirst));
+        check!("best".parse() == Ok(SearchStrategy::BestFirst));
+    }
+
     #[test]
     fn test_contains_theory() {
         let tab = Tableau::from_starting_propositional_formula(PropositionalFormula::variable(
@@ -1,79 +1,95 @@
 //! A `Theory` is a set of alternative `PropositionalFormula`s, which corresponds to a branch in a
 //! tableau tree.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashSet};
+use std::sync::Arc;
 
-use crate::formula::PropositionalFormula;
+use crate::formula::literal::strip_negations;
+use crate::formula::{substitute, Assignment, Literal, PropositionalFormula, Residual};
 
 use log::debug;
 
 /// A `Theory` is a set of alternative `PropositionalFormula`s.
 ///
 /// It corresponds to one particular branch of the tableau tree.
-#[derive(Debug, PartialEq, Clone)]
+///
+/// Formulas are shared between clones of a `Theory`, so cloning one (as every expansion does) only
+/// copies pointers to its formulas rather than the formula trees themselves.
+///
+/// Formulas are kept in their `Ord` order, so that the formulas (and in particular the non-literal
+/// formula picked for expansion) are visited in the same order on every run.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Default)]
 pub struct Theory {
-	formulas: HashSet<PropositionalFormula>,
+	formulas: BTreeSet<Arc<PropositionalFormula>>,
 }
 
 impl Theory {
 	/// Construct an empty theory.
 	pub fn new() -> Self {
 		Self {
-			formulas: HashSet::new(),
+			formulas: BTreeSet::new(),
 		}
 	}
 
 	/// Construct a `Theory` from a given propositional formula.
 	pub fn from_propositional_formula(formula: PropositionalFormula) -> Self {
-		let mut formulas: HashSet<PropositionalFormula> = HashSet::new();
-		formulas.insert(formula);
+		let mut formulas = BTreeSet::new();
+		formulas.insert(Arc::new(formula));
 
 		Self { formulas }
 	}
 
 	/// Get the formulas.
 	pub fn formulas(&self) -> impl Iterator<Item = &PropositionalFormula> {
-		self.formulas.iter()
+		self.formulas.iter().map(AsRef::as_ref)
 	}
 
 	/// Add a propositional formula to the theory iff the theory does not already contain the
 	/// formula.
 	pub fn add(&mut self, formula: PropositionalFormula) {
-		self.formulas.insert(formula);
+		self.formulas.insert(Arc::new(formula));
 	}
 
 	/// Checks if the `Theory` is _fully expanded_, i.e. each propositional_formula in the given
 	/// `Theory` is a _literal_ (e.g. `p`, `-(p)`, a propositional variable or its negation).
 	pub fn is_fully_expanded(&self) -> bool {
-		self.formulas.iter().all(PropositionalFormula::is_literal)
+		self.formulas().all(PropositionalFormula::is_literal)
 	}
 
 	/// Checks if a `Theory` contains _contradictions_. That is, if the `Theory` contains a literal
-	/// `p` AND its negation `-p`.
+	/// `p` AND its negation `-p`, or the constant `0` (or `(-1)`).
 	///
 	/// # Space and Time Complexity
 	///
-	/// This function uses a [`HashMap`] (specifically, a map from some `&str` to the tuple
-	/// `(has_literal, has_negation): (bool, bool)`. As soon as we encounter the case where
-	/// `has_literal && has_negation` then we have found a _contradiction_.
+	/// This function collects the [`Literal`]s of the `Theory` into a [`HashSet`]. As soon as we
+	/// encounter a literal whose complement is already in the set then we have found a
+	/// _contradiction_.
 	///
 	/// - Worst-case time complexity: `O(n)` because we iterate through all of the formulas
 	///   for the given theory.
-	/// - Worst-case space complexity: `O(k)` for `k` propositional variables appearing in literals.
+	/// - Worst-case space complexity: `O(k)` for `k` distinct literals.
 	///
-	/// [`HashMap`]: https://doc.rust-lang.org/std/collections/struct.HashMap.html
+	/// [`Literal`]: ../../formula/literal/struct.Literal.html
+	/// [`HashSet`]: https://doc.rust-lang.org/std/collections/struct.HashSet.html
 	pub fn has_contradictions(&self) -> bool {
-		// Mapping from the variable name `&str` to `(has_literal, has_negation)`.
-		let mut literal_occurrence_map: HashMap<&str, (bool, bool)> = HashMap::new();
-
-		for formula in &self.formulas {
-			if self.check_formula(formula, &mut literal_occurrence_map) {
+		let mut literals = HashSet::new();
+
+		for formula in self.formulas() {
+			let closed = match Literal::from_formula(formula) {
+				Some(literal) => {
+					let closed = literals.contains(&literal.complement());
+					literals.insert(literal);
+					closed
+				}
+				None => is_false_constant(formula),
+			};
+			if closed {
 				return true;
 			}
 		}
 
 		debug!("for the formulas:\n{:#?}", &self.formulas);
-		debug!("construct the HashSet:\n{:#?}", &literal_occurrence_map);
+		debug!("construct the HashSet:\n{:#?}", &literals);
 		debug!("the theory contains no contradictions:\n{:#?}", &self);
 
 		// We've gone through the entire collection of formulas in the `Theory` and did not find any
@@ -81,62 +97,97 @@ impl Theory {
 		false
 	}
 
-	fn check_formula<'a>(
+	/// Get the literals of the `Theory` over propositional variables, with nested negations such as
+	/// `(-(-p))` stripped.
+	pub fn literals(&self) -> impl Iterator<Item = Literal> + '_ {
+		self.formulas().filter_map(Literal::from_formula)
+	}
+
+	/// Get a non-literal formula (not a propositional variable or its negation) from the current
+	/// `Theory`, namely the least one in the `Ord` order of formulas.
+	pub fn get_non_literal_formula(&mut self) -> Option<PropositionalFormula> {
+		self.non_literal_formulas().next().cloned()
+	}
+
+	/// Get the non-literal formulas of the `Theory`.
+	pub fn non_literal_formulas(&self) -> impl Iterator<Item = &PropositionalFormula> {
+		self.formulas().filter(|f| !f.is_literal())
+	}
+
+	/// Get the non-literal formulas of the `Theory` as the shared pointers it stores them by.
+	pub(crate) fn shared_non_literal_formulas(
 		&self,
-		formula: &'a PropositionalFormula,
-		literal_occurrence_map: &mut HashMap<&'a str, (bool, bool)>,
-	) -> bool {
-		match formula {
-			PropositionalFormula::Variable(v) => {
-				if let Some((has_literal, has_negation)) = literal_occurrence_map.get_mut(v.name())
-				{
-					if *has_negation {
-						// We've already seen the negated literal, and now we have the literal, so
-						// we've found a contradiction.
-						true
-					} else {
-						*has_literal = true;
-						false
-					}
-				} else {
-					literal_occurrence_map.insert(v.name(), (true, false));
-					false
-				}
+	) -> impl Iterator<Item = &Arc<PropositionalFormula>> {
+		self.formulas.iter().filter(|f| !f.is_literal())
+	}
+
+	/// Simplify the non-literal formulas of the `Theory` under the truth values its literals assign
+	/// to their variables, e.g. with the literal `p`, `(p|q)` is dropped and `(p->q)` becomes `q`.
+	/// Literals produced this way are propagated in turn until nothing changes.
+	///
+	/// Returns `false` if the `Theory` is found to contain contradictions, i.e. the branch closes.
+	pub fn propagate_literals(&mut self) -> bool {
+		loop {
+			let assignment = match self.literal_assignment() {
+				Some(assignment) => assignment,
+				None => return false,
+			};
+
+			if assignment.is_empty() {
+				return true;
 			}
-			PropositionalFormula::Negation(Some(f)) => match &**f {
-				PropositionalFormula::Variable(v) => {
-					if let Some((has_literal, has_negation)) =
-						literal_occurrence_map.get_mut(v.name())
-					{
-						if *has_literal {
-							// We've already seen the literal, and now we have the negation, so
-							// we've found a contradiction.
-							true
-						} else {
-							*has_negation = true;
-							false
+
+			let non_literals: Vec<Arc<PropositionalFormula>> = self
+				.formulas
+				.iter()
+				.filter(|f| !f.is_literal())
+				.cloned()
+				.collect();
+
+			let mut changed = false;
+
+			for formula in non_literals {
+				match substitute(&formula, &assignment) {
+					Residual::Constant(true) => {
+						self.formulas.remove(&formula);
+						changed = true;
+					}
+					Residual::Constant(false) => return false,
+					Residual::Formula(residual) => {
+						if residual != *formula {
+							self.swap_formula(&formula, residual);
+							changed = true;
 						}
-					} else {
-						literal_occurrence_map.insert(v.name(), (false, true));
-						false
 					}
 				}
-				PropositionalFormula::Negation(Some(ref g)) => {
-					// Now (-(-A)) == A so we've covered the base cases and we can simply
-					// recursively call `self.check_formula()` to handle the inductive cases with
-					// deeply nested negated literals.
-					self.check_formula(g, literal_occurrence_map)
-				}
-				_ => false,
-			},
-			_ => false,
+			}
+
+			if !changed {
+				return true;
+			}
 		}
 	}
 
-	/// Get a non-literal formula (not a propositional variable or its negation) from the current
-	/// `Theory`.
-	pub fn get_non_literal_formula(&mut self) -> Option<PropositionalFormula> {
-		self.formulas.iter().cloned().find(|f| !f.is_literal())
+	/// Collect the truth values assigned by the literals of the `Theory`, or `None` if two of its
+	/// literals are complementary or one of them is false.
+	fn literal_assignment(&self) -> Option<Assignment> {
+		if self.formulas().any(is_false_constant) {
+			return None;
+		}
+
+		let mut assignment = Assignment::new();
+		for literal in self.literals() {
+			let value = !literal.is_negated();
+			if *assignment
+				.entry(literal.variable().clone())
+				.or_insert(value)
+				!= value
+			{
+				return None;
+			}
+		}
+
+		Some(assignment)
 	}
 
 	/// Replace existing formula with a new formula.
@@ -146,7 +197,7 @@ impl Theory {
 		replacement: PropositionalFormula,
 	) {
 		if self.formulas.remove(existing) {
-			self.formulas.insert(replacement);
+			self.formulas.insert(Arc::new(replacement));
 		}
 	}
 
@@ -157,12 +208,32 @@ impl Theory {
 		replacements: (PropositionalFormula, PropositionalFormula),
 	) {
 		if self.formulas.remove(existing) {
-			self.formulas.insert(replacements.0);
-			self.formulas.insert(replacements.1);
+			self.formulas.insert(Arc::new(replacements.0));
+			self.formulas.insert(Arc::new(replacements.1));
+		}
+	}
+
+	/// Replace existing formula with new formulas which may be shared with other theories.
+	pub(crate) fn swap_shared_formula(
+		&mut self,
+		existing: &PropositionalFormula,
+		replacements: &[Arc<PropositionalFormula>],
+	) {
+		if self.formulas.remove(existing) {
+			self.formulas.extend(replacements.iter().cloned());
 		}
 	}
 }
 
+/// Checks if the formula is the constant `0`, possibly under an even number of negations, or the
+/// constant `1` under an odd number of them. Such a formula closes the branch by itself.
+fn is_false_constant(formula: &PropositionalFormula) -> bool {
+	matches!(
+		strip_negations(formula),
+		(PropositionalFormula::Bottom, true) | (PropositionalFormula::Top, false)
+	)
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -214,6 +285,28 @@ mod tests {
 		check!(theory.formulas().count() == 1);
 	}
 
+	#[test]
+	fn test_non_literal_formula_is_deterministic() {
+		let formulas: Vec<PropositionalFormula> = ["(c|d)", "a", "(a^b)", "(-(a->b))"]
+			.iter()
+			.map(|f| f.parse().unwrap())
+			.collect();
+
+		let mut theory = Theory::new();
+		let mut reversed = Theory::new();
+		for formula in &formulas {
+			theory.add(formula.clone());
+		}
+		for formula in formulas.iter().rev() {
+			reversed.add(formula.clone());
+		}
+
+		let expected: PropositionalFormula = "(-(a->b))".parse().unwrap();
+		check!(theory.get_non_literal_formula() == Some(expected.clone()));
+		check!(reversed.get_non_literal_formula() == Some(expected));
+		check!(theory.formulas().eq(reversed.formulas()));
+	}
+
 	#[test]
 	fn test_all_fully_expanded() {
 		let formula_1 = PropositionalFormula::variable(Variable::new("a"));
@@ -392,4 +485,77 @@ mod tests {
 
 		check!(theory.has_contradictions());
 	}
+
+	#[test]
+	fn test_propagate_literals() {
+		let p = PropositionalFormula::variable(Variable::new("p"));
+		let q = PropositionalFormula::variable(Variable::new("q"));
+		let r = PropositionalFormula::variable(Variable::new("r"));
+
+		let mut theory = Theory::new();
+		theory.add(p.clone());
+		theory.add(PropositionalFormula::disjunction(
+			Box::new(p.clone()),
+			Box::new(r.clone()),
+		));
+		theory.add(PropositionalFormula::implication(
+			Box::new(p.clone()),
+			Box::new(q.clone()),
+		));
+		theory.add(PropositionalFormula::implication(
+			Box::new(q.clone()),
+			Box::new(PropositionalFormula::conjunction(
+				Box::new(r.clone()),
+				Box::new(q.clone()),
+			)),
+		));
+
+		check!(theory.propagate_literals());
+
+		let mut expected = Theory::new();
+		expected.add(p);
+		expected.add(q);
+		expected.add(r);
+
+		check!(theory == expected);
+	}
+
+	#[test]
+	fn test_propagate_literals_closes_branch() {
+		let p = PropositionalFormula::variable(Variable::new("p"));
+		let q = PropositionalFormula::variable(Variable::new("q"));
+
+		let mut theory = Theory::new();
+		theory.add(PropositionalFormula::negated(Box::new(q.clone())));
+		theory.add(PropositionalFormula::implication(
+			Box::new(p.clone()),
+			Box::new(q.clone()),
+		));
+		theory.add(p);
+
+		check!(!theory.propagate_literals());
+	}
+
+	#[test]
+	fn test_constants() {
+		let a = PropositionalFormula::variable(Variable::new("a"));
+
+		let theory = Theory::from_propositional_formula(PropositionalFormula::Top);
+		check!(theory.is_fully_expanded());
+		check!(!theory.has_contradictions());
+
+		let mut theory = Theory::new();
+		theory.add(a.clone());
+		theory.add(PropositionalFormula::Bottom);
+		check!(theory.has_contradictions());
+		check!(!theory.propagate_literals());
+
+		let mut theory = Theory::new();
+		theory.add(a);
+		theory.add(PropositionalFormula::negated(Box::new(
+			PropositionalFormula::Top,
+		)));
+		check!(theory.has_contradictions());
+		check!(!theory.propagate_literals());
+	}
 }
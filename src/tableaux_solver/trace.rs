@@ -0,0 +1,226 @@
+//! Explanations of satisfiable formulas as the sequence of rule applications along an open branch
+//! of the tableau.
+
+use std::fmt;
+
+use crate::formula::PropositionalFormula;
+
+use super::{expand_non_literal_formula, ExpansionKind, Theory};
+
+/// Tableau rule applied in a [`TraceStep`].
+///
+/// [`TraceStep`]: struct.TraceStep.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+    /// The α rule, adding all of its results to the branch.
+    Alpha,
+    /// The β rule, continuing the branch with its first (`1`) or second (`2`) result.
+    Beta(usize),
+}
+
+/// One rule application along an open branch, see [`explain_satisfiable`].
+///
+/// [`explain_satisfiable`]: fn.explain_satisfiable.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceStep {
+    /// The expanded formula.
+    pub formula: PropositionalFormula,
+    /// The applied rule.
+    pub rule: Rule,
+    /// The formulas the expansion added to the branch.
+    pub added: Vec<PropositionalFormula>,
+    /// The literals on the branch after the expansion, in the order they were added.
+    pub literals: Vec<PropositionalFormula>,
+}
+
+impl fmt::Display for TraceStep {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let join = |formulas: &[PropositionalFormula]| {
+            formulas
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        match self.rule {
+            Rule::Alpha => write!(f, "alpha on {} gives {}", self.formula, join(&self.added))?,
+            Rule::Beta(branch) => write!(
+                f,
+                "beta on {} takes branch {}: {}",
+                self.formula,
+                branch,
+                join(&self.added)
+            )?,
+        }
+
+        if self.literals.is_empty() {
+            write!(f, "; no literals yet")
+        } else {
+            write!(f, "; literals: {}", join(&self.literals))
+        }
+    }
+}
+
+/// A branch of the tableau being explored, along with the steps that led to it.
+struct Branch {
+    theory: Theory,
+    steps: Vec<TraceStep>,
+    literals: Vec<PropositionalFormula>,
+}
+
+impl Branch {
+    /// Continue the branch by replacing `formula` with `added`, recording the step.
+    fn extend(
+        &self,
+        formula: &PropositionalFormula,
+        rule: Rule,
+        added: Vec<PropositionalFormula>,
+    ) -> Self {
+        let mut theory = self.theory.clone();
+        match &added[..] {
+            [a] => theory.swap_formula(formula, a.clone()),
+            [a, b] => theory.swap_formula2(formula, (a.clone(), b.clone())),
+            _ => unreachable!("expansions add one or two formulas"),
+        }
+
+        let mut literals = self.literals.clone();
+        for literal in added.iter().filter(|added| added.is_literal()) {
+            if !literals.contains(literal) {
+                literals.push(literal.clone());
+            }
+        }
+
+        let mut steps = self.steps.clone();
+        steps.push(TraceStep {
+            formula: formula.clone(),
+            rule,
+            added,
+            literals: literals.clone(),
+        });
+
+        Self {
+            theory,
+            steps,
+            literals,
+        }
+    }
+}
+
+/// Find an open branch of the tableau of the given formula and return the rule applications along
+/// it, or `None` if the formula is unsatisfiable.
+///
+/// Only the steps of the open branch are returned, not the whole tableau: the literals on its last
+/// step form a (partial) model of the formula, and the steps show how each literal was derived
+/// from the formula. Branches are explored depth-first, taking the first result of β rules first,
+/// and without the literal propagation of [`is_satisfiable`] so that every step is a plain rule
+/// application.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::tableaux_solver::trace::{explain_satisfiable, Rule};
+/// let formula = "(a^((-a)|b))".parse().unwrap();
+///
+/// let steps = explain_satisfiable(&formula).unwrap();
+/// assert_eq!(2, steps.len());
+/// assert_eq!(Rule::Beta(2), steps[1].rule);
+/// let literals: Vec<_> = steps[1].literals.iter().map(ToString::to_string).collect();
+/// assert_eq!("a, b", literals.join(", "));
+///
+/// assert!(explain_satisfiable(&"(a^(-a))".parse().unwrap()).is_none());
+/// ```
+///
+/// [`is_satisfiable`]: ../fn.is_satisfiable.html
+pub fn explain_satisfiable(formula: &PropositionalFormula) -> Option<Vec<TraceStep>> {
+    let literals = if formula.is_literal() {
+        vec![formula.clone()]
+    } else {
+        Vec::new()
+    };
+    let mut stack = vec![Branch {
+        theory: Theory::from_propositional_formula(formula.clone()),
+        steps: Vec::new(),
+        literals,
+    }];
+
+    while let Some(mut branch) = stack.pop() {
+        if branch.theory.has_contradictions() {
+            continue;
+        }
+
+        let non_literal_formula = match branch.theory.get_non_literal_formula() {
+            Some(non_literal_formula) => non_literal_formula,
+            None => return Some(branch.steps),
+        };
+
+        // PANIC: cannot panic because the formula is a non-literal.
+        match expand_non_literal_formula(&non_literal_formula).unwrap() {
+            ExpansionKind::Alpha(alpha_1, alpha_2) => {
+                let added = std::iter::once(alpha_1)
+                    .chain(alpha_2)
+                    .map(|alpha| *alpha)
+                    .collect();
+                stack.push(branch.extend(&non_literal_formula, Rule::Alpha, added));
+            }
+            ExpansionKind::Beta(beta_1, beta_2) => {
+                // Push the second branch first, so that the first one is explored first.
+                stack.push(branch.extend(&non_literal_formula, Rule::Beta(2), vec![*beta_2]));
+                stack.push(branch.extend(&non_literal_formula, Rule::Beta(1), vec![*beta_1]));
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formula;
+    use crate::formula::{substitute, Residual};
+    use crate::testing::formulas;
+    use crate::truth_table;
+    use assert2::check;
+
+    #[test]
+    fn test_open_branch_literals_are_a_model() {
+        for formula in formulas(4, 4, 35, 100) {
+            let steps = explain_satisfiable(&formula);
+            check!(steps.is_some() == truth_table::is_satisfiable(&formula));
+
+            if let Some(steps) = steps {
+                let literals = match steps.last() {
+                    Some(step) => step.literals.clone(),
+                    None => vec![formula.clone()],
+                };
+
+                // Unmentioned variables may take any value, so fix them to false.
+                let mut assignment: formula::Assignment = formula
+                    .variables()
+                    .into_iter()
+                    .map(|variable| (variable.clone(), false))
+                    .collect();
+                for literal in literals.iter().filter_map(formula::Literal::from_formula) {
+                    assignment.insert(literal.variable().clone(), !literal.is_negated());
+                }
+
+                check!(substitute(&formula, &assignment) == Residual::Constant(true));
+            }
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        let steps = explain_satisfiable(&formula!((a -> b) ^ a)).unwrap();
+        let lines: Vec<_> = steps.iter().map(ToString::to_string).collect();
+
+        check!(
+            lines
+                == vec![
+                    "alpha on ((a->b)^a) gives (a->b), a; literals: a",
+                    "beta on (a->b) takes branch 2: b; literals: a, b",
+                ]
+        );
+    }
+}
@@ -0,0 +1,33 @@
+//! Helpers shared by the unit tests.
+
+use assert2::check;
+
+use crate::formula::{FormulaGenerator, PropositionalFormula};
+use crate::truth_table;
+
+/// Generate `count` pseudo-random formulas over the variables `x1, ..., xn`, for `n` the number of
+/// `variables`, whose connectives are nested at most `depth` deep. Tests pick distinct `seed`s so
+/// that they do not all check the same formulas.
+pub(crate) fn formulas(
+    variables: usize,
+    depth: usize,
+    seed: u64,
+    count: usize,
+) -> impl Iterator<Item = PropositionalFormula> {
+    FormulaGenerator::new(variables, depth, seed).take(count)
+}
+
+/// Check that `is_satisfiable` decides the given `formulas` like the truth table does.
+pub(crate) fn assert_agrees_with_truth_table<I, F>(formulas: I, mut is_satisfiable: F)
+where
+    I: IntoIterator<Item = PropositionalFormula>,
+    F: FnMut(&PropositionalFormula) -> bool,
+{
+    for formula in formulas {
+        check!(
+            is_satisfiable(&formula) == truth_table::is_satisfiable(&formula),
+            "{}",
+            formula
+        );
+    }
+}
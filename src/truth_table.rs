@@ -0,0 +1,132 @@
+//! Brute-force satisfiability and validity checking by enumerating the truth table of a formula.
+//!
+//! Each of the `2^n` assignments to the `n` variables of a formula is evaluated, so this is only
+//! practical for formulas over few variables, where it often beats branching on a tableau.
+
+use std::collections::HashMap;
+
+use crate::formula::{PropositionalFormula, Variable};
+
+/// Checks if the given propositional formula is _satisfiable_ by trying every assignment to its
+/// variables.
+///
+/// # Example
+///
+/// ```
+/// use libprop_sat_solver::formula::{PropositionalFormula, Variable};
+/// use libprop_sat_solver::truth_table::is_satisfiable;
+/// let formula = PropositionalFormula::conjunction(
+///     Box::new(PropositionalFormula::variable(Variable::new("a"))),
+///     Box::new(PropositionalFormula::negated(Box::new(PropositionalFormula::variable(
+///         Variable::new("a"),
+///     )))),
+/// );
+/// assert!(!is_satisfiable(&formula));
+/// ```
+pub fn is_satisfiable(formula: &PropositionalFormula) -> bool {
+    any_assignment(formula, |value| value == Some(true))
+}
+
+/// Checks if the given propositional formula is _valid_ by trying every assignment to its
+/// variables.
+pub fn is_valid(formula: &PropositionalFormula) -> bool {
+    !any_assignment(formula, |value| value != Some(true))
+}
+
+/// Checks if `predicate` holds for the value of the formula under any assignment to its variables.
+fn any_assignment<P>(formula: &PropositionalFormula, predicate: P) -> bool
+where
+    P: Fn(Option<bool>) -> bool,
+{
+    let variables = formula.variables();
+    let mut assignment: HashMap<&Variable, bool> = variables
+        .iter()
+        .map(|&variable| (variable, false))
+        .collect();
+
+    loop {
+        if predicate(evaluate(formula, &assignment)) {
+            return true;
+        }
+
+        // Advance to the next assignment by counting up in binary, stopping after all ones.
+        let mut carry = true;
+        for variable in &variables {
+            // PANIC: every variable of the formula has been assigned above.
+            let value = assignment.get_mut(variable).unwrap();
+            *value = !*value;
+            if *value {
+                carry = false;
+                break;
+            }
+        }
+
+        if carry {
+            return false;
+        }
+    }
+}
+
+/// Evaluates the formula under the assignment, or `None` if it leaves a variable unassigned.
+fn evaluate(formula: &PropositionalFormula, assignment: &HashMap<&Variable, bool>) -> Option<bool> {
+    match formula {
+        PropositionalFormula::Variable(v) => assignment.get(v).copied(),
+        PropositionalFormula::Top => Some(true),
+        PropositionalFormula::Bottom => Some(false),
+        PropositionalFormula::Negation(a) => Some(!evaluate(a, assignment)?),
+        PropositionalFormula::Conjunction(a, b) => {
+            Some(evaluate(a, assignment)? && evaluate(b, assignment)?)
+        }
+        PropositionalFormula::Disjunction(a, b) => {
+            Some(evaluate(a, assignment)? || evaluate(b, assignment)?)
+        }
+        PropositionalFormula::Implication(a, b) => {
+            Some(!evaluate(a, assignment)? || evaluate(b, assignment)?)
+        }
+        PropositionalFormula::Biimplication(a, b) => {
+            Some(evaluate(a, assignment)? == evaluate(b, assignment)?)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tableaux_solver;
+    use crate::testing::formulas;
+    use assert2::check;
+
+    #[test]
+    fn test_excluded_middle() {
+        // (a|(-a))
+        let formula = PropositionalFormula::disjunction(
+            Box::new(PropositionalFormula::variable(Variable::new("a"))),
+            Box::new(PropositionalFormula::negated(Box::new(
+                PropositionalFormula::variable(Variable::new("a")),
+            ))),
+        );
+
+        check!(is_satisfiable(&formula));
+        check!(is_valid(&formula));
+    }
+
+    #[test]
+    fn test_contingent_formula() {
+        // (a->b)
+        let formula = PropositionalFormula::implication(
+            Box::new(PropositionalFormula::variable(Variable::new("a"))),
+            Box::new(PropositionalFormula::variable(Variable::new("b"))),
+        );
+
+        check!(is_satisfiable(&formula));
+        check!(!is_valid(&formula));
+    }
+
+    #[test]
+    fn test_agrees_with_tableau() {
+        for formula in formulas(4, 4, 36, 100) {
+            check!(is_satisfiable(&formula) == tableaux_solver::is_satisfiable(&formula));
+            check!(is_valid(&formula) == tableaux_solver::is_valid(&formula));
+        }
+    }
+}
@@ -0,0 +1,154 @@
+//! Watch mode: re-solve the input file whenever it changes, see `--watch`.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, prelude::*};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use log::{info, warn};
+
+use libprop_sat_solver::formula::PropositionalFormula;
+
+use libprop_sat_solver::parser;
+
+use crate::header;
+
+/// How often the modification time of the watched file is polled.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The label and result of the formula on a line, or `None` if the line is ill-formed.
+type Verdict = Option<(Option<String>, bool)>;
+
+/// Solve the formulas of the file at `path` with `solve`, then poll the file and re-solve it
+/// whenever it is modified, until the program is interrupted.
+///
+/// Results are cached by the content of their line, so only lines whose content changed since the
+/// previous run are solved again (and lines moved around are not solved at all). Each run prints
+/// the results of the changed lines, prefixed by their line number (and the label of the formula,
+/// if it has one), followed by a summary of how many lines changed. Ill-formed lines are
+/// reported in place of a result instead of aborting.
+pub fn run<F>(path: &Path, solve: F) -> io::Result<()>
+where
+    F: Fn(usize, &PropositionalFormula) -> bool,
+{
+    let mut last_modified: Option<SystemTime> = None;
+    let mut previous_lines: Vec<String> = Vec::new();
+    let mut cache: HashMap<String, Verdict> = HashMap::new();
+
+    loop {
+        // Editors often replace files instead of writing them in place, so the file may briefly
+        // be missing; just try again on the next poll.
+        let modified = match fs::metadata(path).and_then(|metadata| metadata.modified()) {
+            Ok(modified) => modified,
+            Err(e) => {
+                warn!("failed to stat {:?}: {}", path, e);
+                thread::sleep(POLL_INTERVAL);
+                continue;
+            }
+        };
+
+        if last_modified != Some(modified) {
+            last_modified = Some(modified);
+
+            match fs::read_to_string(path) {
+                Ok(input) => {
+                    let lines: Vec<String> = input.lines().map(str::to_string).collect();
+                    resolve(&lines, &previous_lines, &mut cache, &solve)?;
+                    previous_lines = lines;
+                }
+                Err(e) => warn!("failed to read {:?}: {}", path, e),
+            }
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Solve the changed `lines`, reusing the `cache` for the others, and print the results.
+fn resolve<F>(
+    lines: &[String],
+    previous_lines: &[String],
+    cache: &mut HashMap<String, Verdict>,
+    solve: &F,
+) -> io::Result<()>
+where
+    F: Fn(usize, &PropositionalFormula) -> bool,
+{
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+
+    let mut changed = 0;
+    let mut solved = 0;
+    let header_lines = lines
+        .first()
+        .map_or(0, |line| usize::from(header::is_header(0, line)));
+
+    for (index, line) in lines.iter().enumerate() {
+        if previous_lines.get(index) == Some(line) || header::is_header(index, line) {
+            continue;
+        }
+        changed += 1;
+
+        let verdict = match cache.get(line) {
+            Some(verdict) => verdict.clone(),
+            None => {
+                solved += 1;
+                let verdict = parser::parse_labeled(line)
+                    .ok()
+                    .map(|(label, formula)| (label, solve(index, &formula)));
+                cache.insert(line.clone(), verdict.clone());
+                verdict
+            }
+        };
+
+        match verdict {
+            Some((Some(label), result)) => {
+                stdout.write_fmt(format_args!("{}\t{}: {:?}\n", index + 1, label, result))?
+            }
+            Some((None, result)) => {
+                stdout.write_fmt(format_args!("{}\t{:?}\n", index + 1, result))?
+            }
+            None => stdout.write_fmt(format_args!("{}\till-formed\n", index + 1))?,
+        }
+    }
+
+    // Forget the results of lines which are gone.
+    cache.retain(|line, _| lines.contains(line));
+
+    info!("solved {} formulas", solved);
+    stdout.write_fmt(format_args!(
+        "summary: {} changed, {} unchanged\n",
+        changed,
+        lines.len() - changed - header_lines
+    ))?;
+    stdout.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert2::check;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_only_changed_lines_are_solved() {
+        let solved = Cell::new(0);
+        let solve = |_: usize, _: &PropositionalFormula| {
+            solved.set(solved.get() + 1);
+            true
+        };
+        let mut cache = HashMap::new();
+
+        let lines: Vec<String> = vec!["a".into(), "(a|b)".into(), "(-c)".into()];
+        resolve(&lines, &[], &mut cache, &solve).unwrap();
+        check!(solved.get() == 3);
+
+        // Editing one line and swapping the others only solves the edited line.
+        let edited: Vec<String> = vec!["(-c)".into(), "(a|b)".into(), "(a^d)".into()];
+        resolve(&edited, &lines, &mut cache, &solve).unwrap();
+        check!(solved.get() == 4);
+        check!(cache.len() == 3);
+    }
+}